@@ -7,6 +7,11 @@ fn main() {
     let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
 
     let kernel = build_kernel();
+    let kernel = if std::env::var_os("CARGO_FEATURE_KSYMS").is_some() {
+        embed_symbols(&kernel, &out_dir.join("kernel-ksyms"))
+    } else {
+        kernel
+    };
 
     // Prefer OVMF firmware already installed on the system (e.g. the
     // `edk2-ovmf` Arch package) — no network required. Only fall back to
@@ -383,6 +388,12 @@ fn build_kernel() -> PathBuf {
     if profile == "release" {
         cmd.arg("--release");
     }
+    if std::env::var_os("CARGO_FEATURE_KSYMS").is_some() {
+        cmd.arg("--features").arg("ksyms");
+    }
+    if std::env::var_os("CARGO_FEATURE_BOOT_YIELD_PAIR").is_some() {
+        cmd.arg("--features").arg("boot-yield-pair");
+    }
 
     let status = cmd.status().expect("Failed to spawn cargo for kernel build");
     assert!(status.success(), "Kernel build failed");
@@ -393,6 +404,114 @@ fn build_kernel() -> PathBuf {
         .join("kernel")
 }
 
+/// Copies the kernel ELF at `kernel` to `out`, with its `.ksymtab` section
+/// (reserved by the kernel's `ksyms` feature) filled in from its own
+/// `.symtab`: every sized function symbol, demangled, sorted by address.
+/// The layout is documented in `kernel/src/symbols.rs`, which reads it.
+///
+/// Patching in place keeps every address in the image unchanged — the
+/// table is exactly what the linker already saw, so there's no second link
+/// whose layout could drift from the first. A kernel with no `.symtab`
+/// (stripped) keeps an empty table, with a warning.
+fn embed_symbols(kernel: &std::path::Path, out: &std::path::Path) -> PathBuf {
+    const MAGIC: &[u8; 8] = b"KSYMTAB\0";
+    const SHT_SYMTAB: u32 = 2;
+    const STT_FUNC: u8 = 2;
+    /// Longer demangled names (deep generic instantiations) are cut here.
+    const MAX_NAME: usize = 160;
+
+    let mut elf = std::fs::read(kernel).expect("Failed to read kernel ELF");
+    let u16_at = |b: &[u8], o: usize| u16::from_le_bytes(b[o..o + 2].try_into().unwrap()) as usize;
+    let u32_at = |b: &[u8], o: usize| u32::from_le_bytes(b[o..o + 4].try_into().unwrap());
+    let u64_at = |b: &[u8], o: usize| u64::from_le_bytes(b[o..o + 8].try_into().unwrap());
+
+    // Section headers: (name offset, type, addr, file offset, size, link).
+    let (shoff, shentsize, shnum, shstrndx) =
+        (u64_at(&elf, 0x28) as usize, u16_at(&elf, 0x3A), u16_at(&elf, 0x3C), u16_at(&elf, 0x3E));
+    let sections: Vec<(usize, u32, u64, usize, usize, usize)> = (0..shnum)
+        .map(|i| {
+            let h = shoff + i * shentsize;
+            (
+                u32_at(&elf, h) as usize,
+                u32_at(&elf, h + 4),
+                u64_at(&elf, h + 16),
+                u64_at(&elf, h + 24) as usize,
+                u64_at(&elf, h + 32) as usize,
+                u32_at(&elf, h + 40) as usize,
+            )
+        })
+        .collect();
+    let shstrtab = sections[shstrndx].3;
+    let section_name = |name: usize| {
+        let start = shstrtab + name;
+        let end = start + elf[start..].iter().position(|&b| b == 0).unwrap();
+        String::from_utf8_lossy(&elf[start..end]).into_owned()
+    };
+
+    let (_, _, ksym_addr, ksym_off, ksym_size, _) = *sections
+        .iter()
+        .find(|s| section_name(s.0) == ".ksymtab")
+        .expect("kernel built with `ksyms` has no .ksymtab section");
+    assert_eq!(&elf[ksym_off..ksym_off + 8], MAGIC, ".ksymtab doesn't start with the expected magic");
+
+    let mut funcs: Vec<(u64, u32, String)> = Vec::new();
+    match sections.iter().find(|s| s.1 == SHT_SYMTAB) {
+        Some(&(_, _, _, off, size, link)) => {
+            let strtab = sections[link].3;
+            for sym in (off..off + size).step_by(24) {
+                let (value, size) = (u64_at(&elf, sym + 8), u64_at(&elf, sym + 16));
+                if elf[sym + 4] & 0xF != STT_FUNC || value == 0 || size == 0 {
+                    continue;
+                }
+                let start = strtab + u32_at(&elf, sym) as usize;
+                let end = start + elf[start..].iter().position(|&b| b == 0).unwrap();
+                let raw = String::from_utf8_lossy(&elf[start..end]);
+                let mut name = format!("{:#}", rustc_demangle::demangle(&raw));
+                if name.len() > MAX_NAME {
+                    let mut cut = MAX_NAME;
+                    while !name.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    name.truncate(cut);
+                }
+                funcs.push((value, size.min(u32::MAX as u64) as u32, name));
+            }
+        }
+        None => println!("cargo:warning=kernel ELF has no .symtab — backtraces stay unsymbolized"),
+    }
+    funcs.sort_by_key(|f| f.0);
+    funcs.dedup_by_key(|f| f.0);
+
+    let names_at = 24 + funcs.len() * 16;
+    let mut table = Vec::with_capacity(ksym_size);
+    table.extend_from_slice(MAGIC);
+    table.extend_from_slice(&ksym_addr.to_le_bytes());
+    table.extend_from_slice(&(funcs.len() as u32).to_le_bytes());
+    table.extend_from_slice(&(names_at as u32).to_le_bytes());
+    let mut names = Vec::new();
+    for (addr, size, name) in &funcs {
+        table.extend_from_slice(&addr.to_le_bytes());
+        table.extend_from_slice(&size.to_le_bytes());
+        table.extend_from_slice(&(names.len() as u32).to_le_bytes());
+        names.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        names.extend_from_slice(name.as_bytes());
+    }
+    table.extend_from_slice(&names);
+    assert!(
+        table.len() <= ksym_size,
+        "symbol table is {} bytes but .ksymtab is only {} — raise KSYMTAB_SIZE in kernel/src/symbols.rs",
+        table.len(), ksym_size,
+    );
+
+    elf[ksym_off..ksym_off + table.len()].copy_from_slice(&table);
+    std::fs::write(out, &elf).expect("Failed to write symbolized kernel ELF");
+    println!(
+        "cargo:warning=embedded {} kernel symbols ({} of {} KiB)",
+        funcs.len(), table.len() / 1024, ksym_size / 1024,
+    );
+    out.to_path_buf()
+}
+
 /// Looks for OVMF_CODE/OVMF_VARS in the usual distro install locations.
 fn find_system_ovmf() -> Option<(PathBuf, PathBuf)> {
     const CANDIDATES: &[(&str, &str)] = &[
@@ -38,6 +38,20 @@ pub struct RawKey {
     pub pressed: bool,
 }
 
+/// A console-scrollback action, for keys the kernel's framebuffer console
+/// consumes itself instead of passing on as input. Shift+PgUp / Shift+PgDn
+/// page through scrollback (plain PgUp/PgDn still reach the foreground
+/// program as `\x1b[5~`/`\x1b[6~` — `less` and `vi` need them); releasing
+/// whichever paging key started it snaps the view back to live output, so
+/// scrolling back is a hold-to-peek gesture (typematic repeat of the held
+/// key keeps paging further).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleScroll {
+    PageUp,
+    PageDown,
+    Live,
+}
+
 /// Maximum chars a single `process()` call can emit — the longest sequence
 /// is 4 (`\x1b`, `[`, `5`, `~` for PgUp/PgDn).
 const MAX_CHARS: usize = 4;
@@ -54,13 +68,16 @@ pub struct KeyOutput {
     /// raw event carries the extended marker instead, matching the
     /// original code exactly).
     pub raw: Option<RawKey>,
+    /// Set instead of any chars when this scancode is a console-scrollback
+    /// key (see `ConsoleScroll`).
+    pub scroll: Option<ConsoleScroll>,
     chars: [char; MAX_CHARS],
     nchars: usize,
 }
 
 impl KeyOutput {
     fn empty() -> Self {
-        KeyOutput { raw: None, chars: ['\0'; MAX_CHARS], nchars: 0 }
+        KeyOutput { raw: None, scroll: None, chars: ['\0'; MAX_CHARS], nchars: 0 }
     }
 
     fn push_char(&mut self, c: char) {
@@ -92,11 +109,14 @@ pub struct KeyDecoder {
     ctrl: bool,
     caps: bool,
     ext: bool,
+    /// A Shift+PgUp/PgDn press has scrolled the console back and the
+    /// matching key release hasn't snapped it back to live yet.
+    scrolling: bool,
 }
 
 impl KeyDecoder {
     pub const fn new() -> Self {
-        KeyDecoder { shift: false, ctrl: false, caps: false, ext: false }
+        KeyDecoder { shift: false, ctrl: false, caps: false, ext: false, scrolling: false }
     }
 
     /// Reproduces `process_scancode` exactly, decision-for-decision, but
@@ -110,11 +130,12 @@ impl KeyDecoder {
     ///    `RAW_KEY_EVENTS.push` (a client reading raw events wants every
     ///    transition, whether or not it also produces a character).
     /// 3. Release (`scancode >= 0x80`): update Shift/Ctrl modifier state
-    ///    only (matching the original's `(ext, base)` match), emit no
-    ///    chars.
-    /// 4. Press, extended (`ext`): arrow keys / Home / End / PgUp / PgDn /
-    ///    Delete → ANSI sequences; Right Ctrl sets the modifier and emits
-    ///    nothing.
+    ///    (matching the original's `(ext, base)` match), emit no chars —
+    ///    except a PgUp/PgDn release while `scrolling`, which emits
+    ///    `ConsoleScroll::Live`.
+    /// 4. Press, extended (`ext`): Shift+PgUp/PgDn → `ConsoleScroll` and
+    ///    no chars; arrow keys / Home / End / PgUp / PgDn / Delete → ANSI
+    ///    sequences; Right Ctrl sets the modifier and emits nothing.
     /// 5. Press, non-extended: Shift/Left-Ctrl/CapsLock update modifier
     ///    state and emit nothing; anything else decodes through
     ///    `scancode_to_char` using the modifier state read *before* this
@@ -142,6 +163,10 @@ impl KeyDecoder {
             match (ext, base) {
                 (false, 0x2A) | (false, 0x36) => self.shift = false,
                 (_, 0x1D) => self.ctrl = false, // Ctrl (left or right)
+                (true, 0x49) | (true, 0x51) if self.scrolling => {
+                    self.scrolling = false;
+                    out.scroll = Some(ConsoleScroll::Live);
+                }
                 _ => {}
             }
             return out;
@@ -154,6 +179,11 @@ impl KeyDecoder {
 
         // Extended (0xE0-prefixed) codes — arrow keys, right Ctrl, extras.
         if ext {
+            if shifted && (scancode == 0x49 || scancode == 0x51) {
+                self.scrolling = true;
+                out.scroll = Some(if scancode == 0x49 { ConsoleScroll::PageUp } else { ConsoleScroll::PageDown });
+                return out;
+            }
             match scancode {
                 0x1D => { self.ctrl = true; return out; } // Right Ctrl
                 0x48 => out.push_chars(&['\x1b', '[', 'A']), // Up
@@ -366,6 +396,35 @@ mod tests {
         assert_eq!(d.process(0x51).chars(), &['\x1b', '[', '6', '~']); // PgDn
     }
 
+    #[test]
+    fn shift_pgup_scrolls_console_instead_of_emitting_chars() {
+        let mut d = KeyDecoder::new();
+        d.process(0x2A); // Shift press
+        d.process(0xE0);
+        let out = d.process(0x49); // PgUp
+        assert!(out.chars().is_empty());
+        assert_eq!(out.scroll, Some(ConsoleScroll::PageUp));
+        // Still a real key transition for raw-event readers.
+        assert_eq!(out.raw, Some(RawKey { keycode: 0x80 | 0x49, pressed: true }));
+
+        d.process(0xE0);
+        assert_eq!(d.process(0x51).scroll, Some(ConsoleScroll::PageDown));
+
+        // Releasing the paging key snaps back, even after Shift went up.
+        d.process(0xAA); // Shift release
+        d.process(0xE0);
+        assert_eq!(d.process(0xD1).scroll, Some(ConsoleScroll::Live)); // PgDn release
+    }
+
+    #[test]
+    fn plain_pgup_release_does_not_snap_console() {
+        let mut d = KeyDecoder::new();
+        d.process(0xE0);
+        assert_eq!(d.process(0x49).scroll, None);
+        d.process(0xE0);
+        assert_eq!(d.process(0xC9).scroll, None);
+    }
+
     #[test]
     fn shift_release_clears_modifier() {
         let mut d = KeyDecoder::new();
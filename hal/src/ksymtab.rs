@@ -0,0 +1,191 @@
+//! `.ksymtab` symbol-table lookup — the pure half of the kernel's
+//! `symbols.rs`, host-tested with `cargo test`.
+//!
+//! The kernel owns the section (a fixed-size static the root `build.rs`
+//! fills post-link) and knows where it was loaded; everything that only
+//! reads bytes lives here, so the table format and the binary search can
+//! be checked against a hand-built table instead of only by booting a
+//! `--features ksyms` kernel and reading a backtrace.
+//!
+//! LAYOUT (little-endian, offsets from the start of the section):
+//!   0   magic    b"KSYMTAB\0"
+//!   8   base     u64  link-time address of the section itself
+//!   16  count    u32  number of entries
+//!   20  names    u32  offset of the name area
+//!   24  entries  count × { addr: u64, size: u32, name: u32 }, sorted by
+//!                addr; `name` is relative to the name area
+//!   ..  names    each a u16 length followed by that many UTF-8 bytes
+//!
+//! Every read is bounds-checked and nothing allocates or locks: the kernel
+//! calls this from the panic handler and from NMI/#MC.
+
+/// First 8 bytes of a table, filled in or not.
+pub const MAGIC: [u8; 8] = *b"KSYMTAB\0";
+const HEADER_LEN: usize = 24;
+const ENTRY_LEN: usize = 16;
+
+fn u16_at(table: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(table.get(off..off.checked_add(2)?)?.try_into().ok()?))
+}
+
+fn u32_at(table: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(table.get(off..off.checked_add(4)?)?.try_into().ok()?))
+}
+
+fn u64_at(table: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(table.get(off..off.checked_add(8)?)?.try_into().ok()?))
+}
+
+/// Number of symbols in `table` (0 without the magic, i.e. a section
+/// `build.rs` never filled in, or no table at all).
+pub fn count(table: &[u8]) -> usize {
+    if table.get(..MAGIC.len()) != Some(&MAGIC[..]) {
+        return 0;
+    }
+    u32_at(table, 16).unwrap_or(0) as usize
+}
+
+/// The function containing `addr` and `addr`'s offset into it. `loaded_at`
+/// is where `table` actually sits in memory: the image was placed at a
+/// base of the bootloader's choosing, and comparing that with the
+/// link-time `base` in the header gives the slide to undo.
+pub fn resolve(table: &[u8], loaded_at: u64, addr: u64) -> Option<(&str, usize)> {
+    let count = count(table);
+    if count == 0 {
+        return None;
+    }
+    let base = u64_at(table, 8)?;
+    let names = u32_at(table, 20)? as usize;
+    let slide = loaded_at.wrapping_sub(base);
+    let target = addr.wrapping_sub(slide);
+
+    let entry = |i: usize| HEADER_LEN + i * ENTRY_LEN;
+
+    // Last entry starting at or below `target`.
+    let (mut lo, mut hi) = (0, count);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if u64_at(table, entry(mid))? <= target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let at = entry(lo.checked_sub(1)?);
+    let start = u64_at(table, at)?;
+    let size = u32_at(table, at + 8)? as u64;
+    if target - start >= size {
+        return None;
+    }
+
+    let name_at = names.checked_add(u32_at(table, at + 12)? as usize)?;
+    let len = u16_at(table, name_at)? as usize;
+    let name = core::str::from_utf8(table.get(name_at + 2..name_at + 2 + len)?).ok()?;
+    Some((name, (target - start) as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lay `funcs` (addr, size, name; sorted by addr) out the way
+    /// `build.rs`'s `embed_symbols` does, for a section linked at `base`.
+    fn table(base: u64, funcs: &[(u64, u32, &str)]) -> Vec<u8> {
+        let names_at = HEADER_LEN + funcs.len() * ENTRY_LEN;
+        let mut t = Vec::from(MAGIC);
+        t.extend_from_slice(&base.to_le_bytes());
+        t.extend_from_slice(&(funcs.len() as u32).to_le_bytes());
+        t.extend_from_slice(&(names_at as u32).to_le_bytes());
+        let mut names = Vec::new();
+        for &(addr, size, name) in funcs {
+            t.extend_from_slice(&addr.to_le_bytes());
+            t.extend_from_slice(&size.to_le_bytes());
+            t.extend_from_slice(&(names.len() as u32).to_le_bytes());
+            names.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            names.extend_from_slice(name.as_bytes());
+        }
+        t.extend_from_slice(&names);
+        t
+    }
+
+    const BASE: u64 = 0xFFFF_8000_0020_0000;
+    const FUNCS: &[(u64, u32, &str)] = &[
+        (0xFFFF_8000_0000_1000, 0x40, "kernel::main"),
+        (0xFFFF_8000_0000_1040, 0x10, "kernel::panic::rust_begin_unwind"),
+        // Ends at 0x1100; nothing covers 0x1100..0x1200.
+        (0xFFFF_8000_0000_1080, 0x80, "core::fmt::write"),
+        (0xFFFF_8000_0000_1200, 0x20, "<alloc::vec::Vec<T> as Drop>::drop"),
+    ];
+
+    #[test]
+    fn empty_or_unfilled_table_resolves_nothing() {
+        assert_eq!(count(&[]), 0);
+        assert_eq!(resolve(&[], 0, 0x1000), None);
+        // What the kernel ships before `build.rs` patches it: magic, zeros.
+        let mut unfilled = vec![0u8; 64];
+        unfilled[..8].copy_from_slice(&MAGIC);
+        assert_eq!(count(&unfilled), 0);
+        assert_eq!(resolve(&unfilled, BASE, FUNCS[0].0), None);
+        // No magic at all.
+        let mut t = table(BASE, FUNCS);
+        t[0] = b'X';
+        assert_eq!(count(&t), 0);
+        assert_eq!(resolve(&t, BASE, FUNCS[0].0), None);
+    }
+
+    #[test]
+    fn hits_and_misses_at_size_boundaries() {
+        let t = table(BASE, FUNCS);
+        assert_eq!(count(&t), FUNCS.len());
+
+        // First byte, last byte, one past the end of each function.
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_1000), Some(("kernel::main", 0)));
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_103F), Some(("kernel::main", 0x3F)));
+        assert_eq!(
+            resolve(&t, BASE, 0xFFFF_8000_0000_1040),
+            Some(("kernel::panic::rust_begin_unwind", 0)),
+        );
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_1050), None, "between two functions");
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_10FF), Some(("core::fmt::write", 0x7F)));
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_1100), None, "past the end, in a gap");
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_121F).map(|(_, off)| off), Some(0x1F));
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_1220), None, "past the last function");
+        assert_eq!(resolve(&t, BASE, 0xFFFF_8000_0000_0FFF), None, "below the first function");
+    }
+
+    #[test]
+    fn undoes_the_load_slide() {
+        let t = table(BASE, FUNCS);
+        // Loaded 0x40_0000 higher than linked: every address moves with it.
+        let slide = 0x40_0000;
+        assert_eq!(resolve(&t, BASE + slide, 0xFFFF_8000_0000_1008 + slide), Some(("kernel::main", 8)));
+        assert_eq!(resolve(&t, BASE + slide, 0xFFFF_8000_0000_1008), None);
+        // And lower (the subtraction wraps).
+        assert_eq!(
+            resolve(&t, BASE - slide, 0xFFFF_8000_0000_1090 - slide),
+            Some(("core::fmt::write", 0x10)),
+        );
+    }
+
+    #[test]
+    fn decodes_names_and_rejects_truncated_tables() {
+        let t = table(BASE, FUNCS);
+        assert_eq!(
+            resolve(&t, BASE, 0xFFFF_8000_0000_1200).map(|(name, _)| name),
+            Some("<alloc::vec::Vec<T> as Drop>::drop"),
+        );
+        // A name running past the end of the table is a miss, not a panic.
+        assert_eq!(resolve(&t[..t.len() - 1], BASE, 0xFFFF_8000_0000_1200), None);
+        // Invalid UTF-8 in a name too.
+        let mut bad = t.clone();
+        let last = bad.len() - 1;
+        bad[last] = 0xFF;
+        assert_eq!(resolve(&bad, BASE, 0xFFFF_8000_0000_1200), None);
+        // The other names are unaffected.
+        assert_eq!(resolve(&bad, BASE, 0xFFFF_8000_0000_1000), Some(("kernel::main", 0)));
+        // A count claiming more entries than the bytes hold fails cleanly.
+        let mut long = t;
+        long[16..20].copy_from_slice(&1000u32.to_le_bytes());
+        assert_eq!(resolve(&long, BASE, 0xFFFF_8000_0000_1000), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Local APIC timer — register protocol and calibration math, host-tested
+//! with `cargo test`.
+//!
+//! The LAPIC is memory-mapped (xAPIC mode: a 4 KiB page of 32-bit registers
+//! at 16-byte strides), not port I/O, so it can't go through `PortIo`. The
+//! seam here is [`LapicMmio`] — read/write a 32-bit register by its offset
+//! from the LAPIC base — which the kernel implements over its physmap (see
+//! `kernel/src/lapic.rs`) and tests back with [`MockLapic`]. Everything that
+//! decides *what* to write — the spurious-vector enable bit, the LVT timer
+//! mode/mask encoding, the divide configuration, and turning a calibration
+//! sample into an initial count for a target rate — lives here.
+
+use alloc::vec::Vec;
+
+// Register offsets from the LAPIC base (Intel SDM Vol. 3A, Table 11-1).
+pub const REG_ID: u32 = 0x020;
+pub const REG_EOI: u32 = 0x0B0;
+pub const REG_SPURIOUS: u32 = 0x0F0;
+pub const REG_LVT_TIMER: u32 = 0x320;
+pub const REG_TIMER_INITIAL: u32 = 0x380;
+pub const REG_TIMER_CURRENT: u32 = 0x390;
+pub const REG_TIMER_DIVIDE: u32 = 0x3E0;
+
+/// Spurious-interrupt vector register bit 8: APIC software enable.
+const SPURIOUS_APIC_ENABLE: u32 = 1 << 8;
+/// LVT bit 16: interrupt masked.
+const LVT_MASKED: u32 = 1 << 16;
+/// LVT timer bits 17-18 = 0b01: periodic (0b00 is one-shot).
+const LVT_TIMER_PERIODIC: u32 = 1 << 17;
+/// Divide configuration `0b0011`: divide the bus clock by 16. Fixed rather
+/// than configurable: calibration and programming must agree on it, and
+/// /16 keeps a 100 Hz initial count comfortably inside 32 bits on any
+/// bus clock up to ~400 GHz while still leaving plenty of resolution.
+const DIVIDE_BY_16: u32 = 0b0011;
+
+/// 32-bit MMIO register access by offset from the LAPIC base.
+pub trait LapicMmio {
+    fn read(&self, offset: u32) -> u32;
+    fn write(&self, offset: u32, val: u32);
+}
+
+impl<T: LapicMmio + ?Sized> LapicMmio for &T {
+    fn read(&self, offset: u32) -> u32 {
+        (**self).read(offset)
+    }
+    fn write(&self, offset: u32, val: u32) {
+        (**self).write(offset, val)
+    }
+}
+
+/// Reasons a calibration sample can't be turned into a periodic rate — the
+/// kernel adapter logs which one and falls back to the PIT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LapicError {
+    /// `target_hz == 0` or `sample_hz == 0`.
+    ZeroFrequency,
+    /// The timer didn't count at all during the calibration window — a
+    /// broken or absent timer (or an emulator that doesn't implement it).
+    NotCounting,
+    /// The timer is too slow for `target_hz`: less than one count per
+    /// period.
+    FrequencyTooHigh,
+    /// The initial count for `target_hz` doesn't fit the 32-bit register.
+    FrequencyTooLow,
+}
+
+/// Initial count for a periodic timer at `target_hz`, given that the timer
+/// (at the /16 divide this module always uses) counted down `elapsed`
+/// ticks during one calibration window of `1 / sample_hz` seconds — i.e.
+/// one PIT period, for the kernel's PIT-based calibration.
+pub fn initial_count_for_hz(elapsed: u32, sample_hz: u32, target_hz: u32) -> Result<u32, LapicError> {
+    if target_hz == 0 || sample_hz == 0 {
+        return Err(LapicError::ZeroFrequency);
+    }
+    if elapsed == 0 {
+        return Err(LapicError::NotCounting);
+    }
+    let per_second = elapsed as u64 * sample_hz as u64;
+    let count = per_second / target_hz as u64;
+    if count == 0 {
+        return Err(LapicError::FrequencyTooHigh);
+    }
+    u32::try_from(count).map_err(|_| LapicError::FrequencyTooLow)
+}
+
+/// The LAPIC register protocol, generic over the [`LapicMmio`] seam.
+pub struct Lapic<M: LapicMmio> {
+    mmio: M,
+}
+
+impl<M: LapicMmio> Lapic<M> {
+    pub fn new(mmio: M) -> Self {
+        Lapic { mmio }
+    }
+
+    /// APIC ID of this CPU (bits 24-31 of the ID register).
+    pub fn id(&self) -> u8 {
+        (self.mmio.read(REG_ID) >> 24) as u8
+    }
+
+    /// Software-enable the LAPIC, delivering spurious interrupts on
+    /// `spurious_vector`. Keeps the register's other bits (focus checking,
+    /// EOI broadcast suppression) as firmware left them.
+    pub fn enable(&self, spurious_vector: u8) {
+        let svr = self.mmio.read(REG_SPURIOUS) & !0xFF;
+        self.mmio.write(REG_SPURIOUS, svr | SPURIOUS_APIC_ENABLE | spurious_vector as u32);
+    }
+
+    /// Start a masked one-shot countdown from `u32::MAX`, for calibration:
+    /// no interrupt fires, the current-count register just runs down.
+    pub fn start_calibration(&self) {
+        self.mmio.write(REG_TIMER_DIVIDE, DIVIDE_BY_16);
+        self.mmio.write(REG_LVT_TIMER, LVT_MASKED);
+        self.mmio.write(REG_TIMER_INITIAL, u32::MAX);
+    }
+
+    /// Ticks counted since `start_calibration`.
+    pub fn calibration_elapsed(&self) -> u32 {
+        u32::MAX - self.mmio.read(REG_TIMER_CURRENT)
+    }
+
+    /// Stop the timer (an initial count of 0 halts it) and mask it.
+    pub fn stop(&self) {
+        self.mmio.write(REG_LVT_TIMER, LVT_MASKED);
+        self.mmio.write(REG_TIMER_INITIAL, 0);
+    }
+
+    /// Periodic mode on `vector`, reloading from `initial_count` (see
+    /// [`initial_count_for_hz`]). The initial-count write goes last: it's
+    /// what starts the countdown.
+    pub fn start_periodic(&self, vector: u8, initial_count: u32) {
+        self.mmio.write(REG_TIMER_DIVIDE, DIVIDE_BY_16);
+        self.mmio.write(REG_LVT_TIMER, LVT_TIMER_PERIODIC | vector as u32);
+        self.mmio.write(REG_TIMER_INITIAL, initial_count);
+    }
+
+    /// Signal end-of-interrupt. Any value works; 0 is conventional.
+    pub fn eoi(&self) {
+        self.mmio.write(REG_EOI, 0);
+    }
+}
+
+/// A `LapicMmio` mock: reads are served from a register map (default 0),
+/// writes are logged in order and also update the map, so a
+/// read-modify-write (`enable`) sees the value a test seeded.
+pub struct MockLapic {
+    regs: spin::Mutex<alloc::collections::BTreeMap<u32, u32>>,
+    writes: spin::Mutex<Vec<(u32, u32)>>,
+}
+
+impl MockLapic {
+    pub fn new() -> Self {
+        MockLapic {
+            regs: spin::Mutex::new(alloc::collections::BTreeMap::new()),
+            writes: spin::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Seed a register's value without logging a write.
+    pub fn set(&self, offset: u32, val: u32) {
+        self.regs.lock().insert(offset, val);
+    }
+
+    /// Every `(offset, value)` write so far, in order.
+    pub fn writes(&self) -> Vec<(u32, u32)> {
+        self.writes.lock().clone()
+    }
+}
+
+impl Default for MockLapic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LapicMmio for MockLapic {
+    fn read(&self, offset: u32) -> u32 {
+        *self.regs.lock().get(&offset).unwrap_or(&0)
+    }
+    fn write(&self, offset: u32, val: u32) {
+        self.regs.lock().insert(offset, val);
+        self.writes.lock().push((offset, val));
+    }
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Pure calibration math ────────────────────────────────────────────
+
+    #[test]
+    fn count_for_100hz_from_one_100hz_pit_period_is_the_sample_itself() {
+        // The kernel's actual usage: calibrate over one 10 ms PIT period,
+        // then tick at the same 100 Hz — the count *is* what was measured.
+        assert_eq!(initial_count_for_hz(62_500, 100, 100), Ok(62_500));
+    }
+
+    #[test]
+    fn count_scales_inversely_with_target_rate() {
+        assert_eq!(initial_count_for_hz(62_500, 100, 1000), Ok(6_250));
+        assert_eq!(initial_count_for_hz(62_500, 100, 50), Ok(125_000));
+    }
+
+    #[test]
+    fn zero_frequencies_are_rejected() {
+        assert_eq!(initial_count_for_hz(1000, 100, 0), Err(LapicError::ZeroFrequency));
+        assert_eq!(initial_count_for_hz(1000, 0, 100), Err(LapicError::ZeroFrequency));
+    }
+
+    #[test]
+    fn a_timer_that_never_counted_is_rejected() {
+        assert_eq!(initial_count_for_hz(0, 100, 100), Err(LapicError::NotCounting));
+    }
+
+    #[test]
+    fn target_faster_than_the_timer_is_rejected() {
+        // 10 ticks per 10 ms = 1000 ticks/s; 2000 Hz would be half a tick.
+        assert_eq!(initial_count_for_hz(10, 100, 2000), Err(LapicError::FrequencyTooHigh));
+    }
+
+    #[test]
+    fn count_overflowing_32_bits_is_rejected_not_truncated() {
+        // ~429 M ticks per 10 ms at 1 Hz is ~42.9 G — past u32::MAX.
+        assert_eq!(initial_count_for_hz(u32::MAX / 10, 100, 1), Err(LapicError::FrequencyTooLow));
+    }
+
+    // ── Register writes (MockLapic) ─────────────────────────────────────
+
+    #[test]
+    fn enable_sets_software_enable_and_vector_keeping_other_bits() {
+        let mmio = MockLapic::new();
+        // Firmware left bit 12 (EOI-broadcast suppression) set and some
+        // other vector in the low byte.
+        mmio.set(REG_SPURIOUS, (1 << 12) | 0x0F);
+        Lapic::new(&mmio).enable(0xFF);
+        assert_eq!(mmio.writes(), alloc::vec![(REG_SPURIOUS, (1 << 12) | (1 << 8) | 0xFF)]);
+    }
+
+    #[test]
+    fn start_periodic_writes_divide_then_lvt_then_initial_count() {
+        let mmio = MockLapic::new();
+        Lapic::new(&mmio).start_periodic(32, 62_500);
+        assert_eq!(
+            mmio.writes(),
+            alloc::vec![
+                (REG_TIMER_DIVIDE, 0b0011),
+                (REG_LVT_TIMER, (1 << 17) | 32),
+                (REG_TIMER_INITIAL, 62_500),
+            ]
+        );
+    }
+
+    #[test]
+    fn calibration_runs_masked_from_max_and_reports_elapsed() {
+        let mmio = MockLapic::new();
+        let lapic = Lapic::new(&mmio);
+        lapic.start_calibration();
+        assert_eq!(
+            mmio.writes(),
+            alloc::vec![
+                (REG_TIMER_DIVIDE, 0b0011),
+                (REG_LVT_TIMER, 1 << 16),
+                (REG_TIMER_INITIAL, u32::MAX),
+            ]
+        );
+        mmio.set(REG_TIMER_CURRENT, u32::MAX - 62_500);
+        assert_eq!(lapic.calibration_elapsed(), 62_500);
+    }
+
+    #[test]
+    fn stop_masks_then_zeroes_the_initial_count() {
+        let mmio = MockLapic::new();
+        Lapic::new(&mmio).stop();
+        assert_eq!(mmio.writes(), alloc::vec![(REG_LVT_TIMER, 1 << 16), (REG_TIMER_INITIAL, 0)]);
+    }
+
+    #[test]
+    fn eoi_writes_zero_to_the_eoi_register() {
+        let mmio = MockLapic::new();
+        Lapic::new(&mmio).eoi();
+        assert_eq!(mmio.writes(), alloc::vec![(REG_EOI, 0)]);
+    }
+
+    #[test]
+    fn id_is_the_top_byte_of_the_id_register() {
+        let mmio = MockLapic::new();
+        mmio.set(REG_ID, 0x0300_0000);
+        assert_eq!(Lapic::new(&mmio).id(), 3);
+    }
+}
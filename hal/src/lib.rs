@@ -24,8 +24,12 @@ pub mod acpi;
 pub mod ac97;
 pub mod block;
 pub mod keyboard;
+pub mod ksymtab;
+pub mod lapic;
 pub mod mouse;
+pub mod pcspeaker;
 pub mod pit;
+pub mod power;
 pub mod rtc;
 
 /// Legacy x86 port I/O seam. The production implementation (kernel side)
@@ -33,6 +33,23 @@ pub struct MouseEvent {
     pub buttons: u8, // bit0=left, bit1=right, bit2=middle
 }
 
+impl MouseEvent {
+    /// Re-encodes this event as a standard 3-byte PS/2 packet — the format
+    /// `/dev/mouse` hands out, same as Linux's `/dev/input/mice`. Inverse
+    /// of [`PacketDecoder::push_byte`]: bit 3 always set (so a reader can
+    /// resync on it the same way the decoder does), 9-bit two's-complement
+    /// deltas with the sign in byte 0, overflow bits never set. Deltas
+    /// outside the 9-bit range `-256..=255` are clamped.
+    pub fn to_packet(&self) -> [u8; 3] {
+        let dx = self.dx.clamp(-256, 255);
+        let dy = self.dy.clamp(-256, 255);
+        let mut b0 = 0x08 | (self.buttons & 0x07);
+        if dx < 0 { b0 |= 0x10; }
+        if dy < 0 { b0 |= 0x20; }
+        [b0, dx as u8, dy as u8]
+    }
+}
+
 /// In-progress 3-byte packet assembly state, extracted verbatim from the
 /// original `kernel/src/mouse.rs`'s `PacketState` (there backed by an
 /// `UnsafeCell`+`AtomicUsize` for ISR-safety — that trust model stays in
@@ -281,6 +298,33 @@ mod tests {
         assert_eq!(ev.buttons, 0b111);
     }
 
+    #[test]
+    fn to_packet_round_trips_through_the_decoder() {
+        for ev in [
+            MouseEvent { dx: 10, dy: 20, buttons: 0b011 },
+            MouseEvent { dx: -255, dy: -1, buttons: 0b100 },
+            MouseEvent { dx: 0, dy: -256, buttons: 0 },
+        ] {
+            let mut d = PacketDecoder::new();
+            let [b0, b1, b2] = ev.to_packet();
+            assert_eq!(b0 & 0x08, 0x08);
+            assert_eq!(d.push_byte(b0), None);
+            assert_eq!(d.push_byte(b1), None);
+            assert_eq!(d.push_byte(b2), Some(ev));
+        }
+    }
+
+    #[test]
+    fn to_packet_clamps_out_of_range_deltas() {
+        let ev = MouseEvent { dx: 1000, dy: -1000, buttons: 0 };
+        let mut d = PacketDecoder::new();
+        let [b0, b1, b2] = ev.to_packet();
+        assert_eq!(b0 & 0xC0, 0); // never claims overflow
+        d.push_byte(b0);
+        d.push_byte(b1);
+        assert_eq!(d.push_byte(b2), Some(MouseEvent { dx: 255, dy: -256, buttons: 0 }));
+    }
+
     // ── 8042 enable sequence ─────────────────────────────────────────────
 
     #[test]
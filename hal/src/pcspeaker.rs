@@ -0,0 +1,126 @@
+//! PC speaker — PIT channel 2 square wave gated onto the speaker through
+//! port 0x61. Pure register protocol generic over `PortIo`, host-tested
+//! with `cargo test`; the kernel adapter (`kernel/src/pcspeaker.rs`)
+//! owns timing and the saved port-0x61 state.
+//!
+//! Channel 2 is independent of channel 0 (the scheduler tick programmed by
+//! [`crate::pit::Pit::set_rate`]): separate data port, and the command
+//! byte here selects channel 2 only, so starting or stopping a tone never
+//! touches the tick rate. Divisor math is shared with channel 0
+//! ([`crate::pit::divisor_for_hz`]), including its rejection of 0 Hz and
+//! of frequencies the 16-bit divisor can't express.
+
+use crate::pit::{divisor_for_hz, divisor_to_wire, PitError};
+use crate::PortIo;
+
+const PIT_CHANNEL_2_DATA: u16 = 0x42;
+const PIT_COMMAND: u16 = 0x43;
+/// "Port B" of the old 8255 PPI: bit 0 gates PIT channel 2, bit 1 connects
+/// its output to the speaker. The other bits belong to other hardware
+/// (NMI/parity status and masks), so only these two are ever changed.
+const SPEAKER_PORT: u16 = 0x61;
+const SPEAKER_GATE_BITS: u8 = 0x03;
+
+/// Channel 2, lobyte/hibyte access, mode 3 (square wave), binary mode.
+const CMD_CHANNEL2_MODE3: u8 = 0xB6;
+
+pub struct PcSpeaker<IO: PortIo> {
+    io: IO,
+}
+
+impl<IO: PortIo> PcSpeaker<IO> {
+    pub fn new(io: IO) -> Self {
+        PcSpeaker { io }
+    }
+
+    /// Program channel 2 to `hz` and open the speaker gate. Returns port
+    /// 0x61's value from *before* the gate was opened, for [`Self::restore`].
+    /// Writes nothing if `hz` can't be programmed.
+    pub fn start(&self, hz: u32) -> Result<u8, PitError> {
+        let wire = divisor_to_wire(divisor_for_hz(hz)?);
+        self.io.outb(PIT_COMMAND, CMD_CHANNEL2_MODE3);
+        self.io.outb(PIT_CHANNEL_2_DATA, (wire & 0xFF) as u8);
+        self.io.outb(PIT_CHANNEL_2_DATA, (wire >> 8) as u8);
+
+        let prev = self.io.inb(SPEAKER_PORT);
+        self.io.outb(SPEAKER_PORT, prev | SPEAKER_GATE_BITS);
+        Ok(prev)
+    }
+
+    /// Put port 0x61 back exactly as [`Self::start`] found it.
+    pub fn restore(&self, prev: u8) {
+        self.io.outb(SPEAKER_PORT, prev);
+    }
+
+    /// Close the speaker gate without a saved value — clears just the two
+    /// gate bits, leaving the rest of port 0x61 alone.
+    pub fn silence(&self) {
+        let cur = self.io.inb(SPEAKER_PORT);
+        self.io.outb(SPEAKER_PORT, cur & !SPEAKER_GATE_BITS);
+    }
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScriptedIo;
+
+    #[test]
+    fn start_programs_channel2_then_opens_gate_preserving_other_bits() {
+        let io = ScriptedIo::new();
+        io.queue_read(SPEAKER_PORT, 0xB0);
+        let spk = PcSpeaker::new(&io);
+        assert_eq!(spk.start(1000), Ok(0xB0));
+
+        // divisor 1193 = 0x04A9 -> low 0xA9, high 0x04.
+        assert_eq!(
+            io.writes(),
+            alloc::vec![
+                (PIT_COMMAND, CMD_CHANNEL2_MODE3 as u32),
+                (PIT_CHANNEL_2_DATA, 0xA9),
+                (PIT_CHANNEL_2_DATA, 0x04),
+                (SPEAKER_PORT, 0xB3),
+            ]
+        );
+    }
+
+    #[test]
+    fn start_never_touches_channel0() {
+        let io = ScriptedIo::new();
+        let spk = PcSpeaker::new(&io);
+        spk.start(440).unwrap();
+        assert!(io.writes().iter().all(|&(port, _)| port != 0x40));
+        // Command byte selects channel 2 (bits 7:6 = 0b10), not channel 0.
+        assert_eq!(io.writes()[0], (PIT_COMMAND, 0xB6));
+    }
+
+    #[test]
+    fn invalid_frequency_errors_and_writes_nothing() {
+        let io = ScriptedIo::new();
+        let spk = PcSpeaker::new(&io);
+        assert_eq!(spk.start(0), Err(PitError::ZeroFrequency));
+        assert_eq!(spk.start(5), Err(PitError::FrequencyTooLow));
+        assert!(io.writes().is_empty());
+    }
+
+    #[test]
+    fn restore_writes_back_the_saved_value() {
+        let io = ScriptedIo::new();
+        io.queue_read(SPEAKER_PORT, 0x01); // gate bit 0 already on before us
+        let spk = PcSpeaker::new(&io);
+        let prev = spk.start(880).unwrap();
+        spk.restore(prev);
+        assert_eq!(io.writes().last(), Some(&(SPEAKER_PORT, 0x01)));
+    }
+
+    #[test]
+    fn silence_clears_only_the_gate_bits() {
+        let io = ScriptedIo::new();
+        io.queue_read(SPEAKER_PORT, 0xF3);
+        let spk = PcSpeaker::new(&io);
+        spk.silence();
+        assert_eq!(io.writes(), alloc::vec![(SPEAKER_PORT, 0xF0)]);
+    }
+}
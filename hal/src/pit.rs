@@ -21,6 +21,11 @@ const PIT_COMMAND: u16 = 0x43;
 /// Channel 0, lobyte/hibyte access, mode 2 (rate generator), binary mode.
 const CMD_CHANNEL0_MODE2: u8 = 0x34;
 
+/// 8254 read-back command: latch channel 0's status byte only (bits 7:6 =
+/// read-back, bit 5 = don't latch the count, bit 4 = latch status, bit 1 =
+/// channel 0). The next read of the channel-0 data port returns it.
+const CMD_READBACK_STATUS_CH0: u8 = 0xE2;
+
 /// The PIT's base input clock (Hz) — fixed by the hardware, not configurable.
 pub const PIT_FREQUENCY_HZ: u32 = 1_193_182;
 
@@ -57,7 +62,7 @@ pub enum PitError {
 /// rounding-behavior change — but returns `None` instead of panicking or
 /// silently truncating for the two out-of-range cases documented on
 /// [`PitError`].
-pub fn divisor_for_hz(hz: u32) -> Result<u32, PitError> {
+pub const fn divisor_for_hz(hz: u32) -> Result<u32, PitError> {
     if hz == 0 {
         return Err(PitError::ZeroFrequency);
     }
@@ -76,6 +81,26 @@ pub fn divisor_for_hz(hz: u32) -> Result<u32, PitError> {
     Ok(divisor)
 }
 
+/// The rate a divisor really produces, rounded to the nearest Hz — what a
+/// request for `hz` actually gets once [`divisor_for_hz`] has floored it
+/// (`1193182 / 11931` = 100.007 Hz for a 100 Hz request, so `100`; a
+/// 1000 Hz request gets divisor 1193 and 1000.15 Hz, so `1000`; 7000 Hz
+/// gets divisor 170 and 7018.7 Hz, so `7019`).
+pub const fn achieved_hz(divisor: u32) -> u32 {
+    (PIT_FREQUENCY_HZ + divisor / 2) / divisor
+}
+
+/// Whether a read-back status byte (see [`Pit::read_status`]) says the
+/// channel is set up the way [`Pit::set_rate`] leaves it: lobyte/hibyte
+/// access (bits 5:4 = `11`), mode 2 (bits 3:1), binary counting (bit 0 =
+/// 0). Mode 2 may read back as `110` — bit 3 is a don't-care for modes 2
+/// and 3, and some chipsets report it set — so only bits 2:1 are compared.
+pub const fn is_rate_generator_status(status: u8) -> bool {
+    let access = (status >> 4) & 0b11;
+    let mode = (status >> 1) & 0b011;
+    access == 0b11 && mode == 0b010 && status & 1 == 0
+}
+
 /// Encodes a divisor in `1..=65536` (see [`divisor_for_hz`]) as the raw
 /// 16-bit value the PIT's reload register actually gets: every value
 /// `1..=65535` is written as-is; `65536` — one past what 16 bits can
@@ -87,7 +112,7 @@ pub fn divisor_for_hz(hz: u32) -> Result<u32, PitError> {
 /// integer-Hz divisors — the 0-encoding only matters if a caller ever
 /// requests the divisor directly rather than always going through
 /// `divisor_for_hz`).
-fn divisor_to_wire(divisor: u32) -> u16 {
+pub(crate) fn divisor_to_wire(divisor: u32) -> u16 {
     if divisor == 65536 {
         0
     } else {
@@ -110,8 +135,10 @@ impl<IO: PortIo> Pit<IO> {
     /// Programs channel 0 into mode 2 (rate generator) at `hz`. Writes the
     /// command byte (`0x34`) to the command port, then the divisor's low
     /// byte, then its high byte, to the channel-0 data port — same order,
-    /// same three ports, as the original inline `asm!` sequence.
-    pub fn set_rate(&self, hz: u32) -> Result<(), PitError> {
+    /// same three ports, as the original inline `asm!` sequence. Returns
+    /// the rate actually programmed ([`achieved_hz`]), which the divisor's
+    /// floor division can leave slightly off `hz`.
+    pub fn set_rate(&self, hz: u32) -> Result<u32, PitError> {
         let divisor = divisor_for_hz(hz)?;
         let wire = divisor_to_wire(divisor);
         let l = (wire & 0xFF) as u8;
@@ -120,7 +147,17 @@ impl<IO: PortIo> Pit<IO> {
         self.io.outb(PIT_COMMAND, CMD_CHANNEL0_MODE2);
         self.io.outb(PIT_CHANNEL_0_DATA, l);
         self.io.outb(PIT_CHANNEL_0_DATA, h);
-        Ok(())
+        Ok(achieved_hz(divisor))
+    }
+
+    /// Channel 0's status byte via the 8254 read-back command — the mode
+    /// and access bits as the chip actually holds them, for checking
+    /// against [`is_rate_generator_status`]. Latching status leaves the
+    /// count alone, so this doesn't disturb a running period. (The older
+    /// 8253 has no read-back command; what it returns here is undefined.)
+    pub fn read_status(&self) -> u8 {
+        self.io.outb(PIT_COMMAND, CMD_READBACK_STATUS_CH0);
+        self.io.inb(PIT_CHANNEL_0_DATA)
     }
 }
 
@@ -135,9 +172,9 @@ mod tests {
 
     #[test]
     fn divisor_for_100hz_matches_kernels_actual_usage() {
-        // kernel/src/init/devices.rs calls pit::init(100) — the only real
-        // caller today. 1193182 / 100 = 11931 (floor), same as the
-        // original's plain integer division.
+        // kernel/src/init/devices.rs calls pit::init(TICK_HZ), which is
+        // 100 — the only real caller today. 1193182 / 100 = 11931
+        // (floor), same as the original's plain integer division.
         assert_eq!(divisor_for_hz(100), Ok(11931));
     }
 
@@ -182,13 +219,37 @@ mod tests {
         assert_eq!(divisor_to_wire(65535), 0xFFFF);
     }
 
+    #[test]
+    fn achieved_hz_rounds_the_floored_divisor_back_to_a_rate() {
+        assert_eq!(achieved_hz(11931), 100);
+        assert_eq!(achieved_hz(1193), 1000);
+        // 1193182 / 7000 = 170 -> 7018.7 Hz: the request isn't what you get.
+        assert_eq!(divisor_for_hz(7000), Ok(170));
+        assert_eq!(achieved_hz(170), 7019);
+        // Divisor 1 is the input clock itself.
+        assert_eq!(achieved_hz(1), PIT_FREQUENCY_HZ);
+    }
+
+    #[test]
+    fn rate_generator_status_accepts_mode_2_and_its_alias() {
+        // What set_rate's command byte leaves: 0x34 in bits 5:0.
+        assert!(is_rate_generator_status(0x34));
+        // Mode 2 read back as 110, plus OUTPUT/NULL COUNT bits set.
+        assert!(is_rate_generator_status(0x3C | 0xC0));
+        // Mode 3 (square wave), mode 0, BCD, and lobyte-only access.
+        assert!(!is_rate_generator_status(0x36));
+        assert!(!is_rate_generator_status(0x30));
+        assert!(!is_rate_generator_status(0x35));
+        assert!(!is_rate_generator_status(0x14));
+    }
+
     // ── Register writes (ScriptedIo) ────────────────────────────────────
 
     #[test]
     fn set_rate_100hz_writes_command_then_low_then_high_byte() {
         let io = ScriptedIo::new();
         let pit = Pit::new(&io);
-        assert_eq!(pit.set_rate(100), Ok(()));
+        assert_eq!(pit.set_rate(100), Ok(100));
 
         // divisor 11931 = 0x2E9B -> low 0x9B, high 0x2E.
         assert_eq!(
@@ -205,7 +266,7 @@ mod tests {
     fn set_rate_1000hz_writes_expected_divisor_bytes() {
         let io = ScriptedIo::new();
         let pit = Pit::new(&io);
-        assert_eq!(pit.set_rate(1000), Ok(()));
+        assert_eq!(pit.set_rate(1000), Ok(1000));
 
         // divisor 1193 = 0x04A9 -> low 0xA9, high 0x04.
         assert_eq!(
@@ -218,6 +279,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_status_issues_read_back_then_reads_channel_0() {
+        let io = ScriptedIo::new();
+        io.queue_read(PIT_CHANNEL_0_DATA, 0x34);
+        let pit = Pit::new(&io);
+        assert_eq!(pit.read_status(), 0x34);
+        assert_eq!(io.writes(), alloc::vec![(PIT_COMMAND, CMD_READBACK_STATUS_CH0 as u32)]);
+    }
+
     #[test]
     fn set_rate_zero_frequency_errors_and_writes_nothing() {
         let io = ScriptedIo::new();
@@ -242,7 +312,7 @@ mod tests {
         // a normal, non-zero divisor rather than erroring.
         let io = ScriptedIo::new();
         let pit = Pit::new(&io);
-        assert_eq!(pit.set_rate(19), Ok(()));
+        assert_eq!(pit.set_rate(19), Ok(19));
         let writes = io.writes();
         assert_eq!(writes[0], (PIT_COMMAND, CMD_CHANNEL0_MODE2 as u32));
         // divisor 62799 = 0xF54F -> low 0x4F, high 0xF5. Definitely nonzero.
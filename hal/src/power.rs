@@ -0,0 +1,85 @@
+//! Power off / reset — the two port writes QEMU (and real PCs, for reset)
+//! honour, generic over `PortIo` and host-tested with `cargo test`. The
+//! kernel adapter (`kernel/src/power.rs`) decides what to do when a write
+//! doesn't take effect; nothing here can tell, because on success the
+//! machine is simply gone.
+//!
+//! Shutdown is NOT real ACPI: a proper S5 transition means finding the
+//! FADT's PM1a control block and evaluating `\_S5` from the DSDT (AML),
+//! and `hal::acpi` only parses the MADT. Instead it writes SLP_EN|SLP_TYP=0
+//! straight to the PM1a_CNT ports QEMU's chipsets are known to put there:
+//! 0x604 (the Q35/PIIX4 ACPI PM block at its default base) and 0xB004
+//! (older QEMU's and Bochs' hardcoded one). Harmless on hardware that has
+//! nothing at either port — the writes just fall on the floor.
+
+use crate::PortIo;
+
+/// PM1a_CNT on current QEMU (`-machine pc` / `q35`).
+pub const QEMU_PM1A_CNT: u16 = 0x604;
+/// PM1a_CNT on older QEMU / Bochs.
+pub const BOCHS_PM1A_CNT: u16 = 0xB004;
+/// SLP_EN (bit 13) with SLP_TYP = 0, which is S5 on both of the above.
+const SLP_EN_S5: u16 = 0x2000;
+
+const KBC_STATUS_CMD_PORT: u16 = 0x64;
+const KBC_STATUS_INPUT_FULL: u8 = 1 << 1;
+/// 8042 "pulse output port": bit 0 of the output port is the CPU reset
+/// line, so pulsing it low resets the machine.
+const KBC_CMD_PULSE_RESET: u8 = 0xFE;
+
+/// Same bounded-poll budget as `mouse::TIMEOUT_POLLS`.
+pub const TIMEOUT_POLLS: u32 = 100_000;
+
+/// Write the S5 request to the current QEMU port, then the legacy one.
+/// Only returns if neither took effect.
+pub fn request_shutdown<IO: PortIo>(io: &IO) {
+    io.outw(QEMU_PM1A_CNT, SLP_EN_S5);
+    io.outw(BOCHS_PM1A_CNT, SLP_EN_S5);
+}
+
+/// Ask the 8042 to pulse the CPU reset line. Waits (bounded) for the
+/// controller's input buffer to drain first, since a command written while
+/// it's still busy is dropped; sends the pulse anyway if it never drains —
+/// there's no better option left at that point. Returns `false` if the
+/// controller never became ready. Only returns at all if the reset didn't
+/// happen.
+pub fn request_reset<IO: PortIo>(io: &IO) -> bool {
+    let ready = (0..TIMEOUT_POLLS)
+        .any(|_| io.inb(KBC_STATUS_CMD_PORT) & KBC_STATUS_INPUT_FULL == 0);
+    io.outb(KBC_STATUS_CMD_PORT, KBC_CMD_PULSE_RESET);
+    ready
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScriptedIo;
+
+    #[test]
+    fn shutdown_writes_qemu_port_then_bochs_fallback() {
+        let io = ScriptedIo::new();
+        request_shutdown(&io);
+        assert_eq!(
+            io.writes(),
+            alloc::vec![(QEMU_PM1A_CNT, 0x2000), (BOCHS_PM1A_CNT, 0x2000)]
+        );
+    }
+
+    #[test]
+    fn reset_waits_for_input_buffer_then_pulses() {
+        let io = ScriptedIo::new();
+        io.queue_reads(KBC_STATUS_CMD_PORT, &[0x02, 0x02, 0x00]);
+        assert!(request_reset(&io));
+        assert_eq!(io.writes(), alloc::vec![(KBC_STATUS_CMD_PORT, 0xFE)]);
+    }
+
+    #[test]
+    fn reset_still_pulses_when_controller_never_drains() {
+        let io = ScriptedIo::new();
+        io.queue_read(KBC_STATUS_CMD_PORT, 0x02); // sticky: always busy
+        assert!(!request_reset(&io));
+        assert_eq!(io.writes(), alloc::vec![(KBC_STATUS_CMD_PORT, 0xFE)]);
+    }
+}
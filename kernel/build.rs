@@ -74,6 +74,19 @@ const DISK_C_PROGRAMS: &[&str] = &[
     "jobctl_test",
     "ext2_robust_test",
     "fpu_test",
+    "beep",
+    "shutdown",
+    "reboot",
+    "mousedemo",
+    "sda_test",
+    "lspci",
+    "shm_test",
+    "font",
+    "faultbench",
+    "cpuinfo",
+    "serial_test",
+    "memmap",
+    "iov_test",
 ];
 
 /// Not built here at all — see the busybox.elf handling below, which
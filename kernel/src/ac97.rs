@@ -82,14 +82,14 @@ impl Driver for Ac97Driver {
 
     fn init(&mut self) -> Result<(), DriverError> {
         let Some(dev) = crate::pci::find_device(VENDOR_INTEL, DEVICE_AC97) else {
-            crate::serial_println!("ac97: no AC97 PCI device found — /dev/dsp will discard writes");
+            crate::info!("ac97: no AC97 PCI device found — /dev/dsp will discard writes");
             return Err(DriverError::NotFound);
         };
         crate::pci::enable_bus_master_and_io(&dev);
 
         let nam_base = dev.bar0 as u16;
         let nabm_base = dev.bar1 as u16;
-        crate::serial_println!(
+        crate::info!(
             "ac97: found at {:02x}:{:02x}.{} (NAM={:#x} NABM={:#x} irq={})",
             dev.bus, dev.device, dev.function, nam_base, nabm_base, dev.interrupt_line
         );
@@ -98,13 +98,13 @@ impl Driver for Ac97Driver {
 
         // Cold reset, then wait for the codec-ready bit.
         if regs.cold_reset().is_err() {
-            crate::serial_println!("ac97: codec never became ready — giving up");
+            crate::warn!("ac97: codec never became ready — giving up");
             return Err(DriverError::NotFound);
         }
 
         // Reset the PCM-out stream's registers, wait for RR to self-clear.
         if regs.reset_pcm_stream().is_err() {
-            crate::serial_println!("ac97: PCM-out register reset never completed — giving up");
+            crate::warn!("ac97: PCM-out register reset never completed — giving up");
             return Err(DriverError::NotFound);
         }
 
@@ -114,7 +114,7 @@ impl Driver for Ac97Driver {
         // 4KiB frame is generous but simplest (matches the alignment the
         // hardware wants for BDBAR anyway).
         let Some(bdl_phys) = (unsafe { crate::allocator::phys_alloc(12) }) else {
-            crate::serial_println!("ac97: BDL allocation failed — giving up");
+            crate::warn!("ac97: BDL allocation failed — giving up");
             return Err(DriverError::NotFound);
         };
         let bdl_virt = (crate::memory::physical_memory_offset() + bdl_phys.as_u64()).as_mut_ptr::<BdlEntry>();
@@ -123,7 +123,7 @@ impl Driver for Ac97Driver {
         let mut slot_phys = [0u64; RING_SLOTS];
         for i in 0..RING_SLOTS {
             let Some(phys) = (unsafe { crate::allocator::phys_alloc(SLOT_ORDER) }) else {
-                crate::serial_println!("ac97: ring buffer allocation failed — giving up");
+                crate::warn!("ac97: ring buffer allocation failed — giving up");
                 return Err(DriverError::NotFound);
             };
             let virt = (crate::memory::physical_memory_offset() + phys.as_u64()).as_mut_ptr::<u8>();
@@ -149,7 +149,7 @@ impl Driver for Ac97Driver {
 
         *AC97.lock() = Some(Ac97 { regs, slot_virt, next_fill: AtomicUsize::new(0) });
         READY.store(1, Ordering::Release);
-        crate::serial_println!(
+        crate::info!(
             "ac97: PCM-out running (48000 Hz stereo s16le, {} physical buffers x {}B, {} BDL entries)",
             RING_SLOTS,
             SLOT_BYTES,
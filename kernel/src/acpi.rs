@@ -56,7 +56,7 @@ impl Driver for AcpiDriver {
 
     fn init(&mut self) -> Result<(), DriverError> {
         let Some(rsdp_pa) = self.rsdp_addr else {
-            serial_println!("[acpi] no RSDP address from bootloader — skipping ACPI parse");
+            crate::info!("[acpi] no RSDP address from bootloader — skipping ACPI parse");
             return Err(DriverError::NotFound);
         };
 
@@ -118,10 +118,10 @@ pub fn selftest_ok(topo: &AcpiTopology) -> bool {
 /// original inline implementation logged at each of its early returns.
 fn log_error(e: AcpiError) {
     match e {
-        AcpiError::BadSignature => serial_println!("[acpi] RSDP signature mismatch — skipping ACPI parse"),
-        AcpiError::BadChecksum => serial_println!("[acpi] checksum validation failed — skipping ACPI parse"),
-        AcpiError::NoRootTable => serial_println!("[acpi] no usable RSDT/XSDT address — skipping ACPI parse"),
-        AcpiError::NoMadt => serial_println!("[acpi] no MADT (APIC) table found — skipping ACPI parse"),
+        AcpiError::BadSignature => crate::warn!("[acpi] RSDP signature mismatch — skipping ACPI parse"),
+        AcpiError::BadChecksum => crate::warn!("[acpi] checksum validation failed — skipping ACPI parse"),
+        AcpiError::NoRootTable => crate::warn!("[acpi] no usable RSDT/XSDT address — skipping ACPI parse"),
+        AcpiError::NoMadt => crate::warn!("[acpi] no MADT (APIC) table found — skipping ACPI parse"),
     }
 }
 
@@ -131,17 +131,17 @@ fn log_error(e: AcpiError) {
 fn log_summary(topo: &AcpiTopology) {
     use alloc::vec::Vec;
 
-    serial_println!("[acpi] Local APIC @ {:#010x}", topo.local_apic_addr);
+    crate::info!("[acpi] Local APIC @ {:#010x}", topo.local_apic_addr);
     let enabled_ids: Vec<u8> = topo.cpus.iter().map(|c| c.apic_id).collect();
-    serial_println!("[acpi] CPUs: {} (apic_id {:?} enabled)", topo.cpus.len(), enabled_ids);
+    crate::info!("[acpi] CPUs: {} (apic_id {:?} enabled)", topo.cpus.len(), enabled_ids);
     for io in &topo.io_apics {
-        serial_println!(
+        crate::info!(
             "[acpi] I/O APIC {} @ {:#010x} gsi_base={}",
             io.id, io.address, io.gsi_base
         );
     }
     for iso in &topo.overrides {
-        serial_println!(
+        crate::info!(
             "[acpi] override: bus {} IRQ {} -> GSI {} (flags {:#x})",
             iso.bus, iso.source, iso.gsi, iso.flags
         );
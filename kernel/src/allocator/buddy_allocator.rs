@@ -6,6 +6,8 @@
 //   - Removed dangerous `remove_block` (assumed addr==head without check).
 //   - Unified raw print helpers into serial_println_raw! (fmt::Write).
 //   - Replaced O(n) `is_free` linked-list scan with O(1) bitmap lookup.
+//   - Added `reserve` (drop a physical range from the pool) and `claim`
+//     (allocate one specific block), both built on `carve`.
 //
 // BITMAP DESIGN:
 //   One bit per possible block at each order level.  A set bit means the
@@ -137,7 +139,7 @@ impl BuddyAllocator {
     fn bitmap_set(&mut self, order: usize, addr: PhysAddr) {
         if let Some((byte, mask)) = Self::bitmap_pos(order, addr) {
             if self.bitmap[byte] & mask != 0 {
-                crate::serial_println_raw!(
+                crate::error!(
                     "[BUDDY] DOUBLE-FREE: block {:#x} order {} already marked free!",
                     addr.as_u64(), order
                 );
@@ -276,7 +278,7 @@ impl BuddyAllocator {
             None => {
                 // Phantom bitmap entry — free list is completely empty.
                 // Clear the phantom bit so future coalescing won't see it again.
-                crate::serial_println_raw!(
+                crate::warn!(
                     "[BUDDY] phantom: block {:#x} order {} in bitmap but free_list[{}] EMPTY — clearing",
                     addr.as_u64(), order, idx
                 );
@@ -290,7 +292,7 @@ impl BuddyAllocator {
             iters += 1;
             if iters > 4096 {
                 // Pathological list — treat as phantom, clear bitmap, abort.
-                crate::serial_println_raw!(
+                crate::warn!(
                     "[BUDDY] phantom: infinite loop free_list[{}] for {:#x} — clearing",
                     idx, addr.as_u64()
                 );
@@ -314,7 +316,7 @@ impl BuddyAllocator {
                 }
                 None => {
                     // Block not found in list — phantom entry. Clear and abort.
-                    crate::serial_println_raw!(
+                    crate::warn!(
                         "[BUDDY] phantom: {:#x} NOT FOUND in free_list[{}] — clearing",
                         addr.as_u64(), order
                     );
@@ -382,7 +384,7 @@ impl BuddyAllocator {
             }
         }
 
-        crate::serial_println_raw!("Buddy: OOM for order {}", order);
+        crate::warn!("Buddy: OOM for order {}", order);
         None
     }
 
@@ -429,6 +431,132 @@ impl BuddyAllocator {
         self.add_block(current_order, current_addr);
     }
 
+    // ====================================================================
+    // Reservation — carving specific physical ranges out of the pool
+    // ====================================================================
+
+    /// Remove every free frame in `[start, end)` from the pool for good, so
+    /// it is never handed out — for MMIO windows, ACPI tables or firmware
+    /// areas that overlap a region `add_region` was given as usable RAM.
+    /// `start`/`end` are widened to 4 KiB frame boundaries. Free blocks
+    /// straddling either edge are split: the parts outside the range go
+    /// back on the free lists, the parts inside are dropped (and no longer
+    /// counted in `total_bytes`).
+    ///
+    /// Only FREE frames are affected. A frame in the range that is
+    /// allocated right now stays its owner's, and returns to the pool on
+    /// `deallocate` like any other — reserve during device init, before
+    /// anything could have been handed that memory.
+    ///
+    /// Returns the number of bytes actually removed from the pool.
+    ///
+    /// No caller yet — this (and `claim`) is the API PCI/MMIO drivers
+    /// reserve through; exercised by `hw_tests::buddy_reserve_and_claim`.
+    #[allow(dead_code)]
+    pub unsafe fn reserve(&mut self, start: u64, end: u64) -> u64 {
+        let frame = 1u64 << MIN_ORDER;
+        let start = start & !(frame - 1);
+        let end = end.saturating_add(frame - 1) & !(frame - 1);
+        if start >= end {
+            return 0;
+        }
+
+        let mut reserved = 0;
+        while let Some((order, addr)) = self.find_free_overlapping(start, end) {
+            // If the unlink gives up (its scan bound, a broken link), the
+            // block is still on the list and `find_free_overlapping` would
+            // hand it back forever with BUDDY held — stop instead and leave
+            // the rest of the range in the pool.
+            if !self.remove_arbitrary_block(order, addr) {
+                debug_assert!(false, "Buddy: reserve couldn't unlink {:#x} order {}", addr.as_u64(), order);
+                break;
+            }
+            reserved += self.carve(order, addr.as_u64(), start, end);
+        }
+        self.total_memory -= reserved;
+        reserved
+    }
+
+    /// Allocate the specific block `[addr, addr + 2^order)` — e.g. a known
+    /// MMIO window that happens to sit inside usable RAM. `addr` must be
+    /// aligned to `2^order`. Succeeds only if the whole block is currently
+    /// free (as itself or as part of a bigger free block, which is split
+    /// around it); returns `None` otherwise, without touching anything.
+    /// The block is owned like any `allocate(order)` result: give it back
+    /// with `deallocate(addr, order)`.
+    #[allow(dead_code)]
+    pub unsafe fn claim(&mut self, addr: PhysAddr, order: usize) -> Option<PhysAddr> {
+        debug_assert!(order >= MIN_ORDER, "Order {} below MIN_ORDER {}", order, MIN_ORDER);
+        debug_assert!(order <= MAX_ORDER, "Order {} exceeds MAX_ORDER {}", order, MAX_ORDER);
+        if addr.as_u64() & ((1u64 << order) - 1) != 0 {
+            return None;
+        }
+
+        // A free block containing `addr` at `order` or above: there's at
+        // most one, since free blocks never overlap.
+        let (parent_order, parent) = (order..=MAX_ORDER)
+            .map(|o| (o, PhysAddr::new(addr.as_u64() & !((1u64 << o) - 1))))
+            .find(|&(o, block)| self.free_list_contains(o, block))?;
+
+        if !self.remove_arbitrary_block(parent_order, parent) {
+            return None;
+        }
+        let end = addr.as_u64() + (1u64 << order);
+        self.carve(parent_order, parent.as_u64(), addr.as_u64(), end);
+        Some(addr)
+    }
+
+    /// Split the (already unlinked) block `[addr, addr + 2^order)` so every
+    /// part outside `[start, end)` goes back on a free list and every part
+    /// inside is left out of them. Returns how many bytes were left out.
+    unsafe fn carve(&mut self, order: usize, addr: u64, start: u64, end: u64) -> u64 {
+        let size = 1u64 << order;
+        if addr >= end || addr + size <= start {
+            self.add_block(order, PhysAddr::new(addr));
+            return 0;
+        }
+        if start <= addr && addr + size <= end {
+            return size;
+        }
+        // Straddles an edge. Can't be a single frame: the range is
+        // frame-aligned, so a frame is always entirely in or out.
+        let half = order - 1;
+        self.carve(half, addr, start, end) + self.carve(half, addr + (size >> 1), start, end)
+    }
+
+    /// First free block (any order) overlapping `[start, end)`. Walks the
+    /// free lists rather than the bitmap so it also sees blocks above
+    /// `MAX_PHYS_ADDR` — O(free blocks), fine for init-time reservations.
+    fn find_free_overlapping(&self, start: u64, end: u64) -> Option<(usize, PhysAddr)> {
+        (MIN_ORDER..=MAX_ORDER).find_map(|order| {
+            self.free_blocks(order).find(|addr| {
+                let a = addr.as_u64();
+                a < end && a + (1u64 << order) > start
+            }).map(|addr| (order, addr))
+        })
+    }
+
+    /// Whether `addr` is on `order`'s free list: the O(1) bitmap test where
+    /// the bitmap tracks it, a list walk above `MAX_PHYS_ADDR`.
+    fn free_list_contains(&self, order: usize, addr: PhysAddr) -> bool {
+        if addr.as_u64() < MAX_PHYS_ADDR {
+            return self.is_free(order, addr);
+        }
+        self.free_blocks(order).any(|a| a == addr)
+    }
+
+    /// Iterate `order`'s free list.
+    fn free_blocks(&self, order: usize) -> impl Iterator<Item = PhysAddr> + '_ {
+        let phys_offset = crate::memory::physical_memory_offset();
+        let mut current = self.free_lists[self.order_to_index(order)].head;
+        core::iter::from_fn(move || {
+            let addr = current?;
+            let virt = phys_offset + addr.as_u64();
+            current = unsafe { (*virt.as_ptr::<FreeBlock>()).next };
+            Some(addr)
+        })
+    }
+
     // ====================================================================
     // Debug
     // ====================================================================
@@ -464,6 +592,22 @@ impl BuddyAllocator {
         self.total_memory
     }
 
+    /// Number of free blocks on `order`'s list (hw_tests).
+    #[cfg(test)]
+    pub(crate) fn free_count(&self, order: usize) -> usize {
+        self.free_blocks(order).count()
+    }
+
+    /// Forget every region and free block, back to `new()`'s state —
+    /// in place, since a whole `BuddyAllocator` (bitmap included) is too
+    /// big to build on a kernel stack (hw_tests).
+    #[cfg(test)]
+    pub(crate) fn reset(&mut self) {
+        self.free_lists = [FreeList::new(); NUM_ORDERS];
+        self.bitmap.fill(0);
+        self.total_memory = 0;
+    }
+
     /// Debug: print statistics (lock-free, no allocation).
     pub fn debug_print_stats(&self) {
         crate::serial_println_raw!("Buddy Allocator Stats:");
@@ -503,5 +647,47 @@ impl BuddyAllocator {
     }
 }
 
-// Global instance
-pub static BUDDY: Mutex<BuddyAllocator> = Mutex::new(BuddyAllocator::new());
\ No newline at end of file
+// Global instance — only reachable through `lock_buddy`/`try_lock_buddy`.
+static BUDDY: Mutex<BuddyAllocator> = Mutex::new(BuddyAllocator::new());
+
+/// The global Buddy, locked. Interrupts stay masked for the guard's whole
+/// lifetime, so no ISR on this core can find it held by the code it
+/// interrupted — and BUDDY is the leaf of `lock_order`'s hierarchy, so
+/// nothing else may be locked until this is dropped.
+///
+/// Field order matters, as in `SchedGuard`: the lock is released before
+/// `_irq` restores IF.
+pub struct BuddyGuard {
+    buddy: spin::MutexGuard<'static, BuddyAllocator>,
+    _irq: crate::interrupts::InterruptGuard,
+}
+
+impl core::ops::Deref for BuddyGuard {
+    type Target = BuddyAllocator;
+    fn deref(&self) -> &BuddyAllocator { &self.buddy }
+}
+
+impl core::ops::DerefMut for BuddyGuard {
+    fn deref_mut(&mut self) -> &mut BuddyAllocator { &mut self.buddy }
+}
+
+impl Drop for BuddyGuard {
+    fn drop(&mut self) {
+        crate::lock_order::release(crate::lock_order::LockClass::Buddy);
+    }
+}
+
+#[track_caller]
+pub fn lock_buddy() -> BuddyGuard {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    crate::lock_order::acquire(crate::lock_order::LockClass::Buddy);
+    BuddyGuard { buddy: BUDDY.lock(), _irq }
+}
+
+/// Non-blocking `lock_buddy` for ISR/tick context: `None` if it's held.
+pub fn try_lock_buddy() -> Option<BuddyGuard> {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    let buddy = BUDDY.try_lock()?;
+    crate::lock_order::acquired_nonblocking(crate::lock_order::LockClass::Buddy);
+    Some(BuddyGuard { buddy, _irq })
+}
\ No newline at end of file
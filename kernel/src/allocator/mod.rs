@@ -11,8 +11,15 @@
 //
 // Current design:
 //   - Buddy allocator is the SOLE owner of physical memory after init.
-//   - BootInfoFrameAllocator exists as a type (for potential early-boot use)
-//     but is NOT stored globally.
+//   - BootInfoFrameAllocator is gone entirely (it used to survive as an
+//     unused type "for potential early-boot use"). Nothing in boot needs
+//     frames before `memory::init_core` seeds the Buddy, and a second
+//     allocator over the same usable regions is exactly the double-
+//     allocation hazard above — an old `expand_heap` mapped heap pages with
+//     its frames. The heap never grows by mapping pages now: the slab takes
+//     pages straight from the Buddy (`SlabCache::expand`, `allocate_large`).
+//     Frame allocators for page tables are `BuddyFrameAllocator` /
+//     `ZeroedBuddyFrameAllocator` (page_table_manager.rs).
 //   - Page table operations go through OwnedPageTable (page_table_manager.rs).
 
 pub mod buddy_allocator;
@@ -22,10 +29,25 @@ use x86_64::PhysAddr;
 
 /// Allocate 2^order bytes of physical memory from the global buddy allocator.
 pub unsafe fn phys_alloc(order: usize) -> Option<PhysAddr> {
-    buddy_allocator::BUDDY.lock().allocate(order)
+    buddy_allocator::lock_buddy().allocate(order)
+}
+
+/// Like `phys_alloc`, but the block comes back zero-filled through the
+/// physical-memory offset mapping — for anything handed to user space or
+/// used as a page table, where leftover contents of a previous owner
+/// would leak (or be misread as entries). Doing it here means a call site
+/// can't forget the separate `write_bytes` pass. The memset runs after
+/// `BUDDY`'s lock is released, so a 2 MiB clear doesn't stall every
+/// other allocation; still, prefer plain `phys_alloc` for blocks that are
+/// about to be fully overwritten anyway.
+pub unsafe fn phys_alloc_zeroed(order: usize) -> Option<PhysAddr> {
+    let addr = phys_alloc(order)?;
+    let virt = crate::memory::physical_memory_offset() + addr.as_u64();
+    core::ptr::write_bytes(virt.as_mut_ptr::<u8>(), 0, 1usize << order);
+    Some(addr)
 }
 
 /// Return 2^order bytes of physical memory to the buddy allocator.
 pub unsafe fn phys_free(addr: PhysAddr, order: usize) {
-    buddy_allocator::BUDDY.lock().deallocate(addr, order);
+    buddy_allocator::lock_buddy().deallocate(addr, order);
 }
\ No newline at end of file
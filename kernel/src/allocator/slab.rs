@@ -8,9 +8,29 @@ use x86_64::{PhysAddr, VirtAddr};
 
 // Tamaños de slab: 8, 16, 32, 64, 128, 256, 512, 1024, 2048 bytes
 const SLAB_SIZES: &[usize] = &[8, 16, 32, 64, 128, 256, 512, 1024, 2048];
-const NUM_SLABS: usize = SLAB_SIZES.len();
+pub const NUM_SLABS: usize = SLAB_SIZES.len();
 const MAX_SLAB_SIZE: usize = 2048;
 
+/// Debug builds only: bytes appended after every allocation and filled
+/// with `REDZONE_BYTE`, checked on free to catch writes past the end of
+/// the object (the 0xAA/0xDD poisoning only catches use-after-free). The
+/// redzone is counted into the size that picks the size class, so an
+/// object may land one class up (and one of 2041-2048 bytes goes to the
+/// Buddy instead); release builds lay objects out exactly as before.
+#[cfg(debug_assertions)]
+const REDZONE: usize = 8;
+#[cfg(debug_assertions)]
+const REDZONE_BYTE: u8 = 0xFE;
+
+/// Bytes a `layout` actually takes — what picks the slab class (or the
+/// Buddy order). Used by BOTH allocate and deallocate, which must agree.
+fn effective_size(layout: Layout) -> usize {
+    let size = layout.size().max(layout.align());
+    #[cfg(debug_assertions)]
+    let size = size + REDZONE;
+    size
+}
+
 // ✅ Constantes para cálculo de order
 const PAGE_SIZE: usize = 4096;
 const PAGE_ORDER: usize = 12; // log2(4096)
@@ -29,6 +49,19 @@ fn size_to_buddy_order(size: usize) -> usize {
     }
 }
 
+/// El heap no existe hasta `memory::init_core` (offset de memoria física y
+/// Buddy sembrado). Usarlo antes (p.ej. un `Vec` en código de arranque
+/// movido por encima de esa línea) no fallaba de forma legible: `phys_alloc`
+/// sobre un Buddy vacío devolvía `None` y el resultado era un "memory
+/// allocation failed" genérico, o peor, con offset 0, punteros a la
+/// dirección física cruda. Se chequea solo donde el slab va al Buddy
+/// (`expand`, `allocate_large`), no en el camino rápido.
+fn assert_heap_ready() {
+    if crate::memory::physical_memory_offset().as_u64() == 0 {
+        panic!("heap used before memory::init_core(): no physical memory offset, Buddy not seeded");
+    }
+}
+
 // Compile-time checks
 const _: () = {
     // ✅ Todas las size classes deben ser potencia de 2
@@ -70,7 +103,7 @@ impl SlabAllocator {
 
     /// Allocate usando slab o buddy
     pub unsafe fn allocate(&mut self, layout: Layout) -> *mut u8 {
-        let size = layout.size().max(layout.align());
+        let size = effective_size(layout);
 
         if size > MAX_SLAB_SIZE {
             // Usar Buddy directamente para allocaciones grandes
@@ -84,7 +117,12 @@ impl SlabAllocator {
                 "Slab size must be power of 2");
             debug_assert!(SLAB_SIZES[idx] <= PAGE_SIZE,
                 "Slab size must fit in page");
-            self.caches[idx].allocate(SLAB_SIZES[idx])
+            let ptr = self.caches[idx].allocate(SLAB_SIZES[idx], layout.size());
+            #[cfg(debug_assertions)]
+            if !ptr.is_null() {
+                core::ptr::write_bytes(ptr.add(layout.size()), REDZONE_BYTE, REDZONE);
+            }
+            ptr
         } else {
             null_mut()
         }
@@ -96,10 +134,10 @@ impl SlabAllocator {
             return;
         }
 
-        let size = layout.size().max(layout.align());
+        let size = effective_size(layout);
 
         if size > MAX_SLAB_SIZE {
-            crate::serial_println_raw!(">>> Slab: Large dealloc");
+            crate::trace!(">>> Slab: Large dealloc");
             self.deallocate_large(ptr, size);
             return;
         }
@@ -112,7 +150,19 @@ impl SlabAllocator {
                 "Free must use same size class as alloc"
             );
 
-            self.caches[idx].deallocate(ptr, SLAB_SIZES[idx]);
+            #[cfg(debug_assertions)]
+            {
+                // ✅ Redzone intacta = nadie escribió más allá del objeto
+                let redzone = core::slice::from_raw_parts(ptr.add(layout.size()), REDZONE);
+                if let Some(off) = redzone.iter().position(|&b| b != REDZONE_BYTE) {
+                    panic!(
+                        "Slab overrun: object {:#x} ({} bytes, {}B class) redzone byte {} is {:#04x}",
+                        ptr as u64, layout.size(), SLAB_SIZES[idx], off, redzone[off]
+                    );
+                }
+            }
+
+            self.caches[idx].deallocate(ptr, SLAB_SIZES[idx], layout.size());
         }
     }
 
@@ -123,8 +173,9 @@ impl SlabAllocator {
         
         // ✅ USAR FUNCIÓN CENTRALIZADA
         let order = size_to_buddy_order(total_size);
-        
-        crate::serial_println_raw!(">>> allocate_large: size={} order={}", total_size, order);
+
+        assert_heap_ready();
+        crate::trace!(">>> allocate_large: size={} order={}", total_size, order);
 
         let result = crate::allocator::phys_alloc(order)
             .map(|phys_addr| {
@@ -135,9 +186,9 @@ impl SlabAllocator {
             .unwrap_or(null_mut());
         
         if result.is_null() {
-            crate::serial_println_raw!(">>> allocate_large: FAILED");
+            crate::warn!(">>> allocate_large: FAILED");
         } else {
-            crate::serial_println_raw!(">>> allocate_large: OK at {:#x}", result as u64);
+            crate::trace!(">>> allocate_large: OK at {:#x}", result as u64);
         }
         
         result
@@ -151,13 +202,10 @@ impl SlabAllocator {
         let virt = VirtAddr::new(ptr as u64);
         let phys = PhysAddr::new(virt.as_u64() - phys_offset.as_u64());
 
-        crate::serial_println_raw!(
+        crate::trace!(
             "[SLAB] deallocate_large: virt={:#x} phys={:#x} size={} order={}",
             ptr as u64, phys.as_u64(), size, order
         );
-        if (phys.as_u64() & !0x3FFF) == 0x1ecb0000 {
-            crate::serial_println_raw!("[SLAB]   ^^^ THIS IS IN THE HOT RANGE!");
-        }
 
         crate::allocator::phys_free(phys, order);
     }
@@ -175,6 +223,33 @@ impl SlabAllocator {
             }
         }
     }
+
+    /// Debug builds only: print every size class that has live objects —
+    /// or, given a `baseline` from `slab_usage()`, only the classes that
+    /// have MORE live objects now than then — and return how many objects
+    /// that is in total. Taking the baseline before some self-contained
+    /// piece of work (a test, a process run) and reporting after it turns
+    /// "the heap grew" into "these size classes kept N objects / M bytes".
+    ///
+    /// Same no-allocation rule as `stats()`: runs under `SLAB_ALLOCATOR`'s
+    /// lock, so it prints straight to serial.
+    #[cfg(debug_assertions)]
+    pub fn report_leaks(&self, baseline: Option<&[SlabClassUsage; NUM_SLABS]>) -> usize {
+        let mut leaked = 0;
+        for (idx, cache) in self.caches.iter().enumerate() {
+            let before = baseline.map_or(0, |b| b[idx].used_objects);
+            if cache.used_objects <= before {
+                continue;
+            }
+            let extra = cache.used_objects - before;
+            leaked += extra;
+            crate::serial_println_raw!(
+                "[SLAB] {}B: {} live object(s) ({} since baseline), {} bytes requested",
+                SLAB_SIZES[idx], cache.used_objects, extra, cache.live_bytes
+            );
+        }
+        leaked
+    }
 }
 
 /// Un slab cache para objetos de un tamaño fijo
@@ -182,6 +257,10 @@ struct SlabCache {
     free_list: Option<NonNull<FreeObject>>,
     total_objects: usize,
     used_objects: usize,
+    /// Suma de los `layout.size()` pedidos por los objetos vivos (no del
+    /// tamaño de la clase), para `report_leaks`. Solo en debug.
+    #[cfg(debug_assertions)]
+    live_bytes: usize,
 }
 
 impl SlabCache {
@@ -190,11 +269,17 @@ impl SlabCache {
             free_list: None,
             total_objects: 0,
             used_objects: 0,
+            #[cfg(debug_assertions)]
+            live_bytes: 0,
         }
     }
 
-    /// Allocate un objeto del slab
-    unsafe fn allocate(&mut self, object_size: usize) -> *mut u8 {
+    /// Allocate un objeto del slab. `requested` es el tamaño que pidió el
+    /// llamador (`<= object_size`), solo para la contabilidad de debug.
+    unsafe fn allocate(&mut self, object_size: usize, requested: usize) -> *mut u8 {
+        #[cfg(not(debug_assertions))]
+        let _ = requested;
+
         // Si no hay objetos libres, expandir el cache
         if self.free_list.is_none() {
             if !self.expand(object_size) {
@@ -225,6 +310,7 @@ impl SlabCache {
 
         #[cfg(debug_assertions)]
         {
+            self.live_bytes += requested;
             // ✅ Poison con patrón de "allocated"
             core::ptr::write_bytes(ptr, 0xAA, object_size.min(256));
         }
@@ -233,10 +319,23 @@ impl SlabCache {
     }
 
     /// Deallocate un objeto
-    unsafe fn deallocate(&mut self, ptr: *mut u8, object_size: usize) {
+    unsafe fn deallocate(&mut self, ptr: *mut u8, object_size: usize, requested: usize) {
+        #[cfg(not(debug_assertions))]
+        let _ = requested;
 
         #[cfg(debug_assertions)]
         {
+            // ✅ Más frees que allocs en esta clase: doble free (o un free
+            // con el Layout de otra clase). No se puede hacer panic acá —
+            // el panic handler tomaría este mismo lock — así que se avisa
+            // y se sigue, como antes (el contador ya saturaba en 0).
+            if self.used_objects == 0 {
+                crate::error!(
+                    "[SLAB] DOUBLE-FREE? {:#x} freed to the {}B cache with no live objects",
+                    ptr as u64, object_size
+                );
+            }
+            self.live_bytes = self.live_bytes.saturating_sub(requested);
             // ✅ Poison con patrón de "freed"
             core::ptr::write_bytes(ptr, 0xDD, object_size.min(256));
         }
@@ -253,11 +352,13 @@ impl SlabCache {
 
     /// Expandir el cache allocando una nueva página del Buddy
     unsafe fn expand(&mut self, object_size: usize) -> bool {
+        assert_heap_ready();
+
         // Allocar una página de 4KB del Buddy
         let page_phys = match crate::allocator::phys_alloc(12) {
             Some(addr) => addr,
             None => {
-                crate::serial_println_raw!("Slab: Failed to expand {}B cache (OOM)", object_size);
+                crate::warn!("Slab: Failed to expand {}B cache (OOM)", object_size);
                 return false;
             }
         };
@@ -283,7 +384,7 @@ impl SlabCache {
 
         self.total_objects += objects_per_page;
 
-        crate::serial_println_raw!(
+        crate::trace!(
             "Slab: Expanded {}B cache (+{} objects, total {})",
             object_size, objects_per_page, self.total_objects
         );
@@ -324,4 +425,35 @@ static GLOBAL_ALLOCATOR: SlabGlobalAlloc = SlabGlobalAlloc;
 // Función pública para stats
 pub fn slab_stats() {
     SLAB_ALLOCATOR.lock().stats();
+}
+
+/// One size class's occupancy, as reported by `slab_usage`.
+#[derive(Clone, Copy)]
+pub struct SlabClassUsage {
+    pub object_size: usize,
+    pub total_objects: usize,
+    pub used_objects: usize,
+}
+
+/// `SlabAllocator::report_leaks` on the global allocator — see there.
+/// Debug builds only. Called from `hw_tests`
+/// (`slab_report_leaks_against_baseline`); drop it in anywhere else a
+/// leak is suspected.
+#[cfg(debug_assertions)]
+#[allow(dead_code)]
+pub fn slab_report_leaks(baseline: Option<&[SlabClassUsage; NUM_SLABS]>) -> usize {
+    SLAB_ALLOCATOR.lock().report_leaks(baseline)
+}
+
+/// Per-size-class object counts, copied out under the lock into a fixed
+/// array. Deliberately allocation-free: the caller formats this into a
+/// `String` (`/proc/meminfo`), and that allocation goes through this same
+/// `SLAB_ALLOCATOR` lock — formatting while still holding it would
+/// self-deadlock, same reason `stats()` above prints straight to serial.
+pub fn slab_usage() -> [SlabClassUsage; NUM_SLABS] {
+    let slab = SLAB_ALLOCATOR.lock();
+    core::array::from_fn(|i| {
+        let (total, used) = slab.caches[i].stats();
+        SlabClassUsage { object_size: SLAB_SIZES[i], total_objects: total, used_objects: used }
+    })
 }
\ No newline at end of file
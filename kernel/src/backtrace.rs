@@ -0,0 +1,118 @@
+// kernel/src/backtrace.rs
+//
+// Frame-pointer backtraces for panics and exception dumps.
+//
+// The kernel is built with `-C force-frame-pointers=yes` (see
+// `kernel/.cargo/config.toml`), so every Rust function starts with
+// `push rbp; mov rbp, rsp`: [rbp] is the caller's rbp and [rbp + 8] the
+// return address into the caller. Following that chain gives raw return
+// addresses — no DWARF, no unwinding tables, no allocation, so it's safe
+// from the panic handler and from NMI/#MC.
+//
+// STOPPING:
+//   At `MAX_FRAMES`, at a zero return address, when the next rbp doesn't
+//   move up the stack (callers' frames are at higher addresses — this is
+//   what ends a corrupt or looping chain; see below for the one exception),
+//   or when rbp stops looking like
+//   a kernel frame: misaligned, or not on a present, supervisor-only page
+//   per a lock-free walk of the live page table (`page_table_manager::
+//   walk`). Reading an unmapped rbp from inside the panic handler would
+//   fault again, so nothing is dereferenced without that check — and a
+//   panic before `memory::init` has set the physmap offset gets no
+//   backtrace at all.
+//
+// With `--features ksyms` each address is also printed as function+offset
+// (`symbols::resolve`).
+//
+// Through an exception handler the chain continues into the interrupted
+// code: the handler's own frame saved the interrupted rbp. The slot above
+// it holds the CPU-pushed RIP — or, for vectors with an error code, the
+// error code — so that one entry can be off; the rest are real callers.
+// A handler on an IST stack (#DF, #PF, NMI, #MC — `process::tss`) saved
+// an rbp on a different stack, usually at a *lower* address, so leaving
+// an IST stack is exempt from the "moves up" rule: once per IST slot
+// (`tss::ist_slot_of`), which still ends a chain that loops back onto one.
+// Monotonicity is otherwise enforced within each stack.
+
+use x86_64::structures::paging::PageTableFlags;
+use x86_64::VirtAddr;
+
+/// Deepest backtrace printed.
+pub const MAX_FRAMES: usize = 32;
+
+/// Return addresses collected by `capture`, innermost first.
+pub struct Backtrace {
+    frames: [u64; MAX_FRAMES],
+    len: usize,
+}
+
+impl Backtrace {
+    pub fn frames(&self) -> &[u64] {
+        &self.frames[..self.len]
+    }
+}
+
+/// This function's caller-visible rbp — where a walk of "here" starts.
+#[inline(always)]
+pub fn current_rbp() -> u64 {
+    let rbp: u64;
+    unsafe { core::arch::asm!("mov {}, rbp", out(reg) rbp, options(nomem, nostack, preserves_flags)); }
+    rbp
+}
+
+/// Whether the 16-byte frame record at `rbp` can be read: 8-aligned, and
+/// both words on present kernel-only pages.
+fn is_kernel_frame(rbp: u64) -> bool {
+    if rbp == 0 || !rbp.is_multiple_of(8) {
+        return false;
+    }
+    let Some(ret_slot) = rbp.checked_add(8) else { return false };
+    [rbp, ret_slot].into_iter().all(|addr| {
+        let Ok(addr) = VirtAddr::try_new(addr) else { return false };
+        let walk = unsafe { crate::memory::page_table_manager::walk(addr) };
+        walk.phys.is_some()
+            && walk.levels().last().is_some_and(|leaf| !leaf.flags().contains(PageTableFlags::USER_ACCESSIBLE))
+    })
+}
+
+/// Walk the frame-pointer chain starting at `rbp`.
+pub fn capture(mut rbp: u64) -> Backtrace {
+    let mut bt = Backtrace { frames: [0; MAX_FRAMES], len: 0 };
+    if crate::memory::physical_memory_offset().as_u64() == 0 {
+        return bt;
+    }
+    // IST slots already hopped out of (bit per slot).
+    let mut left_ist = 0u8;
+    while bt.len < MAX_FRAMES && is_kernel_frame(rbp) {
+        let (next, ret) = unsafe { (*(rbp as *const u64), *((rbp + 8) as *const u64)) };
+        if ret == 0 {
+            break;
+        }
+        bt.frames[bt.len] = ret;
+        bt.len += 1;
+        if next <= rbp {
+            match crate::process::tss::ist_slot_of(rbp) {
+                Some(slot) if left_ist & (1 << slot) == 0 && crate::process::tss::ist_slot_of(next) != Some(slot) => {
+                    left_ist |= 1 << slot;
+                }
+                _ => break,
+            }
+        }
+        rbp = next;
+    }
+    bt
+}
+
+/// Print the chain from `rbp` to serial, lock-free (`serial_println_raw!`).
+pub fn print_from(rbp: u64) {
+    let bt = capture(rbp);
+    crate::serial_println_raw!("  backtrace ({} frames):", bt.len);
+    for (i, &ret) in bt.frames().iter().enumerate() {
+        // Look up `ret - 1`: a call that never returns can be a function's
+        // last instruction, leaving `ret` at the start of the next one.
+        match crate::symbols::resolve(ret - 1) {
+            Some((name, off)) => crate::serial_println_raw!("    #{:<2} {:#018x} {}+{:#x}", i, ret, name, off + 1),
+            None => crate::serial_println_raw!("    #{:<2} {:#018x}", i, ret),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 // kernel/src/block/ata.rs
 //
 // ATA PIO driver, polling mode (no IRQ), LBA28, secondary channel, master
-// drive only. Just enough to back a read-only ext2 mount (fs::ext2).
+// drive only. Backs the ext2 mount (fs::ext2) and the raw /dev/sda node
+// (drivers::dev_sda).
 //
 // Deliberately targets the SECONDARY IDE channel (0x170/0x376), not the
 // primary (0x1F0/0x3F6) the UEFI boot disk sits on — see src/main.rs, which
@@ -32,6 +33,7 @@ const STATUS_BSY: u8 = 1 << 7;
 const CMD_READ_SECTORS: u8 = 0x20;
 const CMD_WRITE_SECTORS: u8 = 0x30;
 const CMD_CACHE_FLUSH: u8 = 0xE7;
+const CMD_IDENTIFY: u8 = 0xEC;
 
 pub const SECTOR_SIZE: usize = 512;
 
@@ -195,3 +197,48 @@ pub fn present() -> bool {
     let mut status: Port<u8> = Port::new(COMMAND_STATUS);
     unsafe { status.read() != 0xFF }
 }
+
+/// Addressable size of the drive in sectors, from IDENTIFY DEVICE's
+/// words 60-61 (the LBA28 total — this driver can't address past it
+/// anyway). `None` if nothing answers, the device isn't plain ATA (an
+/// ATAPI drive aborts IDENTIFY and leaves its signature in
+/// LBA_MID/LBA_HIGH), or the drive errors out. Only `/dev/sda`
+/// (`drivers::dev_sda`) needs this, for SEEK_END and end-of-disk short
+/// reads — `fs::ext2` gets its size from its own superblock.
+pub fn sector_count() -> Option<u32> {
+    if !present() {
+        return None;
+    }
+    let _guard = ATA_LOCK.lock();
+
+    unsafe {
+        let mut drive_head: Port<u8> = Port::new(DRIVE_HEAD);
+        let mut sector_count: Port<u8> = Port::new(SECTOR_COUNT);
+        let mut lba_low: Port<u8> = Port::new(LBA_LOW);
+        let mut lba_mid: Port<u8> = Port::new(LBA_MID);
+        let mut lba_high: Port<u8> = Port::new(LBA_HIGH);
+        let mut command: Port<u8> = Port::new(COMMAND_STATUS);
+        let mut data: Port<u16> = Port::new(DATA);
+
+        drive_head.write(0xA0); // master; IDENTIFY ignores the LBA bits
+        wait_400ns();
+        sector_count.write(0);
+        lba_low.write(0);
+        lba_mid.write(0);
+        lba_high.write(0);
+        command.write(CMD_IDENTIFY);
+        wait_400ns();
+
+        wait_not_busy().ok()?;
+        if lba_mid.read() != 0 || lba_high.read() != 0 {
+            return None;
+        }
+        wait_drq().ok()?;
+
+        let mut words = [0u16; 256];
+        for w in words.iter_mut() {
+            *w = data.read();
+        }
+        Some(words[60] as u32 | (words[61] as u32) << 16)
+    }
+}
@@ -0,0 +1,164 @@
+// kernel/src/cpu/features.rs
+//
+// CPUID feature detection — queried once, cached, read from anywhere.
+//
+// Until this existed every optional CPU feature was either assumed
+// (SSE/FXSR for `process::fpu`, the TSC for `cpu::tsc`) or avoided
+// outright because nothing could check for it (NX — see the note at the
+// top of `memory/page_table_manager.rs`). `features()` is the one place
+// that answers "does this CPU have X": code that turns an optional feature
+// on gates on it (`lapic::init_timer` on `apic`/`x2apic`), and
+// `/proc/cpuinfo` (the `cpuinfo` program) prints it.
+//
+// USAGE:
+//   cpu::features::init()     — run CPUID (boot, before anything gates on it)
+//   cpu::features::features() — the cached `CpuFeatures` (runs init if needed)
+
+use core::arch::x86_64::{__cpuid, CpuidResult};
+
+use spin::Once;
+
+/// The subset of CPUID this kernel cares about, decoded from leaves 0, 1,
+/// 0x8000_0001, 0x8000_0002..=4 (brand string) and 0x8000_0007
+/// (invariant TSC). A leaf the CPU doesn't implement leaves its bits false.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuFeatures {
+    /// Leaf 0 vendor string, e.g. `GenuineIntel`, `AuthenticAMD`, `TCGTCGTCGTCG`.
+    pub vendor: [u8; 12],
+    /// Leaves 0x8000_0002..=4 brand string, NUL-padded (leading spaces
+    /// trimmed); empty if the extended leaves aren't there.
+    pub brand: [u8; 48],
+    pub fpu: bool,
+    pub tsc: bool,
+    /// Machine-check exception (#MC, CR4.MCE).
+    pub mce: bool,
+    pub apic: bool,
+    /// Machine-check architecture (MCG_CAP and the MCi_* bank MSRs).
+    pub mca: bool,
+    pub sse: bool,
+    pub sse2: bool,
+    pub x2apic: bool,
+    pub rdrand: bool,
+    /// Execute-disable (EFER.NXE can be set).
+    pub nx: bool,
+    /// TSC ticks at a constant rate across P-/C-states.
+    pub invariant_tsc: bool,
+}
+
+impl CpuFeatures {
+    fn detect() -> Self {
+        let leaf0 = cpuid(0);
+        let max_basic = leaf0.eax;
+        let mut vendor = [0u8; 12];
+        vendor[0..4].copy_from_slice(&leaf0.ebx.to_le_bytes());
+        vendor[4..8].copy_from_slice(&leaf0.edx.to_le_bytes());
+        vendor[8..12].copy_from_slice(&leaf0.ecx.to_le_bytes());
+
+        let (ecx1, edx1) = if max_basic >= 1 {
+            let l = cpuid(1);
+            (l.ecx, l.edx)
+        } else {
+            (0, 0)
+        };
+
+        let max_ext = cpuid(0x8000_0000).eax;
+        let edx_ext1 = if max_ext >= 0x8000_0001 { cpuid(0x8000_0001).edx } else { 0 };
+        let edx_ext7 = if max_ext >= 0x8000_0007 { cpuid(0x8000_0007).edx } else { 0 };
+
+        let mut brand = [0u8; 48];
+        if max_ext >= 0x8000_0004 {
+            for (i, leaf) in (0x8000_0002u32..=0x8000_0004).enumerate() {
+                let r = cpuid(leaf);
+                for (j, reg) in [r.eax, r.ebx, r.ecx, r.edx].iter().enumerate() {
+                    let at = i * 16 + j * 4;
+                    brand[at..at + 4].copy_from_slice(&reg.to_le_bytes());
+                }
+            }
+            // Intel pads the brand string on the left with spaces.
+            let lead = brand.iter().take_while(|&&b| b == b' ').count();
+            brand.copy_within(lead.., 0);
+            brand[48 - lead..].fill(0);
+        }
+
+        Self {
+            vendor,
+            brand,
+            fpu: edx1 & (1 << 0) != 0,
+            tsc: edx1 & (1 << 4) != 0,
+            mce: edx1 & (1 << 7) != 0,
+            apic: edx1 & (1 << 9) != 0,
+            mca: edx1 & (1 << 14) != 0,
+            sse: edx1 & (1 << 25) != 0,
+            sse2: edx1 & (1 << 26) != 0,
+            x2apic: ecx1 & (1 << 21) != 0,
+            rdrand: ecx1 & (1 << 30) != 0,
+            nx: edx_ext1 & (1 << 20) != 0,
+            invariant_tsc: edx_ext7 & (1 << 8) != 0,
+        }
+    }
+
+    pub fn vendor_str(&self) -> &str {
+        core::str::from_utf8(&self.vendor).unwrap_or("?")
+    }
+
+    pub fn brand_str(&self) -> &str {
+        let len = self.brand.iter().position(|&b| b == 0).unwrap_or(48);
+        core::str::from_utf8(&self.brand[..len]).unwrap_or("?").trim_end()
+    }
+
+    /// `(name, present)` for every flag, in `/proc/cpuinfo` order — the
+    /// names are Linux's `/proc/cpuinfo` flag spellings.
+    pub fn flags(&self) -> [(&'static str, bool); 11] {
+        [
+            ("fpu", self.fpu),
+            ("tsc", self.tsc),
+            ("mce", self.mce),
+            ("apic", self.apic),
+            ("mca", self.mca),
+            ("sse", self.sse),
+            ("sse2", self.sse2),
+            ("x2apic", self.x2apic),
+            ("rdrand", self.rdrand),
+            ("nx", self.nx),
+            ("constant_tsc", self.invariant_tsc),
+        ]
+    }
+}
+
+fn cpuid(leaf: u32) -> CpuidResult {
+    __cpuid(leaf)
+}
+
+static FEATURES: Once<CpuFeatures> = Once::new();
+
+/// Run CPUID and cache the result. Called early in boot so the log shows
+/// what was found; later calls are no-ops.
+pub fn init() {
+    let f = features();
+    crate::info!("CPU: {} \"{}\"", f.vendor_str(), f.brand_str());
+}
+
+/// The cached CPU features (detected on first use).
+pub fn features() -> &'static CpuFeatures {
+    FEATURES.call_once(CpuFeatures::detect)
+}
+
+/// `/proc/cpuinfo` contents: vendor, model name, and the flags present
+/// (space-separated, Linux spelling), plus the calibrated TSC frequency.
+pub fn render_cpuinfo() -> alloc::string::String {
+    use core::fmt::Write;
+
+    let f = features();
+    let mut out = alloc::string::String::new();
+    let _ = writeln!(out, "vendor_id\t: {}", f.vendor_str());
+    let _ = writeln!(out, "model name\t: {}", f.brand_str());
+    let _ = writeln!(out, "tsc MHz\t\t: {}", super::tsc::freq_hz() / 1_000_000);
+    let _ = write!(out, "flags\t\t:");
+    for (name, present) in f.flags() {
+        if present {
+            let _ = write!(out, " {}", name);
+        }
+    }
+    out.push('\n');
+    out
+}
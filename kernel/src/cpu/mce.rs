@@ -0,0 +1,92 @@
+// kernel/src/cpu/mce.rs
+//
+// Machine-check enablement and bank dump.
+//
+// Without CR4.MCE a machine check doesn't raise #MC at all — the CPU goes
+// straight to shutdown (a triple-fault-style reset), which from the outside
+// looks exactly like any other mysterious hang-then-reboot. `init()` sets
+// CR4.MCE so it arrives as vector 18 instead, and the handler in
+// `init/devices.rs` can say *what* went wrong from the MCA banks before
+// halting. Nothing here tries to recover: a corrected error never raises
+// #MC (it's only logged in a bank, and nothing polls them), and an
+// uncorrected one is fatal for a kernel with no page-offlining or
+// process-kill-on-poison machinery.
+//
+// USAGE:
+//   cpu::mce::init()        — boot, after `cpu::features::init()`
+//   cpu::mce::dump_banks()  — allocation-free, from the #MC handler
+
+use x86_64::registers::control::{Cr4, Cr4Flags};
+use x86_64::registers::model_specific::Msr;
+
+const IA32_MCG_CAP: u32 = 0x179;
+const IA32_MCG_STATUS: u32 = 0x17A;
+/// MCi_STATUS for bank `i` is `IA32_MC0_STATUS + 4 * i`, MCi_ADDR is one past it.
+const IA32_MC0_STATUS: u32 = 0x401;
+
+const MCI_STATUS_VAL: u64 = 1 << 63;
+const MCI_STATUS_UC: u64 = 1 << 61;
+const MCI_STATUS_ADDRV: u64 = 1 << 58;
+
+/// Never look at more banks than this, whatever MCG_CAP claims.
+const MAX_BANKS: u32 = 32;
+
+fn bank_count() -> u32 {
+    if !super::features::features().mca {
+        return 0;
+    }
+    let cap = unsafe { Msr::new(IA32_MCG_CAP).read() };
+    ((cap & 0xFF) as u32).min(MAX_BANKS)
+}
+
+/// Turn #MC delivery on (CR4.MCE) if CPUID has it, reporting — then
+/// clearing — any bank a previous boot left valid: a machine check that
+/// reset the machine before the handler existed is only visible here.
+pub fn init() {
+    if !super::features::features().mce {
+        crate::info!("mce: not supported (CPUID), machine checks will reset the CPU");
+        return;
+    }
+    let banks = bank_count();
+    for bank in 0..banks {
+        let mut status_msr = Msr::new(IA32_MC0_STATUS + 4 * bank);
+        let status = unsafe { status_msr.read() };
+        if status & MCI_STATUS_VAL != 0 {
+            crate::warn!("mce: bank {} left over from before boot: status={:#018x}", bank, status);
+            unsafe { status_msr.write(0); }
+        }
+    }
+    unsafe { Cr4::update(|f| f.insert(Cr4Flags::MACHINE_CHECK_EXCEPTION)); }
+    crate::info!("mce: #MC enabled, {} MCA banks", banks);
+}
+
+/// Print MCG_STATUS and every valid bank through `serial_println_raw!`
+/// (no locks, no allocation — called from the #MC handler).
+pub fn dump_banks() {
+    if !super::features::features().mca {
+        crate::serial_println_raw!("  (no MCA banks — CPUID.1:EDX.MCA clear)");
+        return;
+    }
+    let mcg = unsafe { Msr::new(IA32_MCG_STATUS).read() };
+    crate::serial_println_raw!(
+        "  MCG_STATUS={:#x} (RIPV={} EIPV={} MCIP={})",
+        mcg, mcg & 1, (mcg >> 1) & 1, (mcg >> 2) & 1
+    );
+    for bank in 0..bank_count() {
+        let status = unsafe { Msr::new(IA32_MC0_STATUS + 4 * bank).read() };
+        if status & MCI_STATUS_VAL == 0 {
+            continue;
+        }
+        let addr = if status & MCI_STATUS_ADDRV != 0 {
+            unsafe { Msr::new(IA32_MC0_STATUS + 4 * bank + 1).read() }
+        } else {
+            0
+        };
+        crate::serial_println_raw!(
+            "  bank {}: status={:#018x} {} addr={:#x}",
+            bank, status,
+            if status & MCI_STATUS_UC != 0 { "uncorrected" } else { "corrected" },
+            addr
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // kernel/src/cpu/mod.rs
 // CPU topology — today single-CPU, tomorrow SMP.
 
+pub mod features;
+pub mod mce;
 pub mod tsc;
 
 /// Maximum number of CPUs this kernel supports.
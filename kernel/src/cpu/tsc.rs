@@ -12,6 +12,18 @@
 //   cpu::tsc::freq_hz()     — calibrated Hz (0 before init)
 //   cpu::tsc::uptime_ns()   — nanoseconds since init
 //   cpu::tsc::uptime_ms()   — milliseconds since init
+//   cpu::tsc::now()         — timestamp for `elapsed_ns`
+//   cpu::tsc::elapsed_ns(t) — ns since `now()` returned `t`, if convertible
+//
+// CONVERTING DELTAS: a TSC delta only means a fixed amount of wall time if
+// the counter ticks at a constant rate — CPUID.80000007h:EDX[8] ("invariant
+// TSC", `cpu::features().invariant_tsc`). Without it the rate follows
+// P-states and the calibrated frequency is only right at whatever speed the
+// CPU happened to run during calibration, so `tsc_to_ns`/`elapsed_ns` return
+// `None` and callers fall back to the raw count: still monotonic, good for
+// ordering and relative comparisons, not for units. `uptime_ns` keeps
+// converting unconditionally — the clocksource needs *some* nanosecond
+// value, and on QEMU without `-cpu host,+invtsc` the bit is clear anyway.
 
 use core::arch::asm;
 use core::sync::atomic::{AtomicU64, Ordering};
@@ -73,17 +85,23 @@ fn read_pit_count() -> u16 {
 
 // ── Calibration ────────────────────────────────────────────────────────────
 
-/// Measure TSC ticks in exactly one PIT period (10 ms at 100 Hz).
+/// PIT channel-0 rate: what `pit::init` achieved for the tick rate
+/// `init_hardware_interrupts` asked for (0 if it failed — calibration then
+/// comes out as 0, i.e. uncalibrated).
+pub fn pit_hz() -> u64 {
+    crate::pit::frequency() as u64
+}
+
+/// Call `mark` at two consecutive PIT period boundaries, exactly one PIT
+/// period (1 / `pit_hz()` s) apart — the measurement window both this
+/// module's TSC calibration and `crate::lapic`'s timer calibration use.
 ///
 /// Algorithm:
 ///   1. Sync to a period boundary by waiting for the counter to wrap
 ///      (cur > prev means the down-count rolled over from 0 to divisor).
-///   2. Record `t0` at that boundary.
-///   3. Busy-poll a second wrap; when it happens record `t1`.
-///   4. `freq = (t1 - t0) * PIT_HZ`
-fn calibrate() -> u64 {
-    const PIT_HZ: u64 = 100; // must match pit::init(100) in init/devices.rs
-
+///   2. `mark()` at that boundary.
+///   3. Busy-poll a second wrap; when it happens `mark()` again.
+pub fn across_one_pit_period(mut mark: impl FnMut()) {
     // ── Phase 1: synchronize to a period boundary ──────────────────────────
     let mut prev = read_pit_count();
     loop {
@@ -96,7 +114,7 @@ fn calibrate() -> u64 {
     }
 
     // ── Phase 2: measure one full period ──────────────────────────────────
-    let t0 = read();
+    mark();
     let start = read_pit_count();
 
     // We need to detect the next wrap: count must first descend below `start`
@@ -110,12 +128,24 @@ fn calibrate() -> u64 {
         }
         if seen_below && cur >= start {
             // Second wrap — one full period elapsed.
-            let t1 = read();
-            return (t1 - t0) * PIT_HZ;
+            mark();
+            return;
         }
     }
 }
 
+/// Measure TSC ticks in exactly one PIT period (10 ms at 100 Hz):
+/// `freq = (t1 - t0) * pit_hz()`.
+fn calibrate() -> u64 {
+    let mut t = [0u64; 2];
+    let mut i = 0;
+    across_one_pit_period(|| {
+        t[i] = read();
+        i += 1;
+    });
+    (t[1] - t[0]) * pit_hz()
+}
+
 // ── Public API ─────────────────────────────────────────────────────────────
 
 /// Calibrate the TSC and record the boot timestamp.
@@ -152,3 +182,27 @@ pub fn uptime_ns() -> u64 {
 pub fn uptime_ms() -> u64 {
     uptime_ns() / 1_000_000
 }
+
+/// A timestamp for [`elapsed_ns`] — just the raw TSC, under a name that
+/// reads right at call sites (`let t = tsc::now(); ...; tsc::elapsed_ns(t)`).
+#[inline]
+pub fn now() -> u64 {
+    read()
+}
+
+/// Convert a TSC delta to nanoseconds. `None` if the TSC isn't invariant
+/// or hasn't been calibrated yet — see "CONVERTING DELTAS" above.
+pub fn tsc_to_ns(delta: u64) -> Option<u64> {
+    let freq = TSC_FREQ_HZ.load(Ordering::Relaxed);
+    if freq == 0 || !crate::cpu::features::features().invariant_tsc {
+        return None;
+    }
+    Some(((delta as u128 * 1_000_000_000) / freq as u128) as u64)
+}
+
+/// Nanoseconds since `start` (a value from [`now`]), or `None` where
+/// [`tsc_to_ns`] can't convert; `now().wrapping_sub(start)` is the raw
+/// fallback.
+pub fn elapsed_ns(start: u64) -> Option<u64> {
+    tsc_to_ns(now().wrapping_sub(start))
+}
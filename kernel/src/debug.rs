@@ -277,6 +277,30 @@ static ORPHAN_INODES_RECLAIMED: AtomicU64 = AtomicU64::new(0);
 /// instrumentation around instead of deleting it, useful for the next
 /// scheduler investigation too).
 static SWITCHES_TOTAL: AtomicU64 = AtomicU64::new(0);
+/// Why the CPU changed hands, counted once per switch to a *different*
+/// process (`Scheduler::note_switch_out`): the outgoing one blocked,
+/// yielded, stopped or exited (voluntary), or its slice ran out
+/// (preemption). Alongside them, how often a Ready process changed run
+/// queue — decay, aging, a priority-inheritance boost — which the `cfs`
+/// build never does. All three back `kdebug schedstat`.
+static VOLUNTARY_SWITCHES: AtomicU64 = AtomicU64::new(0);
+static PREEMPTIONS:        AtomicU64 = AtomicU64::new(0);
+static QUEUE_MIGRATIONS:   AtomicU64 = AtomicU64::new(0);
+/// Not-present faults resolved by `memory::demand_paging::map_demand_page`
+/// (COW faults are counted separately above), plus the extra neighbour
+/// pages its fault-around cluster and `madvise(MADV_WILLNEED)` mapped
+/// eagerly. `userspace/c/faultbench.c` diffs these around a 256 KiB
+/// zeroing loop to measure what fault-around actually saves.
+static DEMAND_FAULTS:    AtomicU64 = AtomicU64::new(0);
+static PREFAULTED_PAGES: AtomicU64 = AtomicU64::new(0);
+/// NMIs handled (and survived) by `init::devices::nmi_handler`.
+static NMIS_TOTAL:       AtomicU64 = AtomicU64::new(0);
+/// Starvation reports from the scheduler watchdog (`Scheduler::watchdog_check`).
+static WATCHDOG_WARNINGS: AtomicU64 = AtomicU64::new(0);
+/// Timer ticks skipped because the scheduler lock was already held — see
+/// `process/timer_preempt.rs`'s LOCK ALREADY HELD. Nonzero means something
+/// re-enabled interrupts while holding it.
+static SCHED_LOCK_BUSY_TICKS: AtomicU64 = AtomicU64::new(0);
 
 pub fn inc_forks()         { FORKS_TOTAL.fetch_add(1, Ordering::Relaxed); }
 pub fn inc_execs()         { EXECS_TOTAL.fetch_add(1, Ordering::Relaxed); }
@@ -284,11 +308,29 @@ pub fn inc_reaps()         { REAPS_TOTAL.fetch_add(1, Ordering::Relaxed); }
 pub fn inc_cow_resolved()  { COW_FAULTS_RESOLVED.fetch_add(1, Ordering::Relaxed); }
 pub fn inc_cow_failed()    { COW_FAULTS_FAILED.fetch_add(1, Ordering::Relaxed); }
 pub fn inc_switches()      { SWITCHES_TOTAL.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_voluntary_switches() { VOLUNTARY_SWITCHES.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_preemptions()   { PREEMPTIONS.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_queue_migrations() { QUEUE_MIGRATIONS.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_demand_faults() { DEMAND_FAULTS.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_nmis()          { NMIS_TOTAL.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_watchdog_warnings() { WATCHDOG_WARNINGS.fetch_add(1, Ordering::Relaxed); }
+pub fn inc_sched_lock_busy_ticks() { SCHED_LOCK_BUSY_TICKS.fetch_add(1, Ordering::Relaxed); }
+pub fn add_prefaulted(pages: u64) { PREFAULTED_PAGES.fetch_add(pages, Ordering::Relaxed); }
 pub fn add_orphans_reclaimed(blocks: u64, inodes: u64) {
     ORPHAN_BLOCKS_RECLAIMED.fetch_add(blocks, Ordering::Relaxed);
     ORPHAN_INODES_RECLAIMED.fetch_add(inodes, Ordering::Relaxed);
 }
 
+/// `(voluntary_switches, preemptions, migrations_between_queues)`, for
+/// `scheduler::schedstat_report`.
+pub fn sched_counters() -> (u64, u64, u64) {
+    (
+        VOLUNTARY_SWITCHES.load(Ordering::Relaxed),
+        PREEMPTIONS.load(Ordering::Relaxed),
+        QUEUE_MIGRATIONS.load(Ordering::Relaxed),
+    )
+}
+
 /// Render the current state for `/proc/kdebug`: enabled subsystems (by
 /// name, not just the raw mask) plus every counter above.
 pub fn render_report() -> alloc::string::String {
@@ -307,8 +349,12 @@ pub fn render_report() -> alloc::string::String {
         enabled.push_str("(none)");
     }
 
+    let level = crate::log::level();
+    let level_name = crate::log::Level::from_u8(level).map_or("off", |l| l.name());
+
     format!(
         "trace_mask: {:#x} ({})\n\
+         log_level: {} ({})\n\
          forks_total: {}\n\
          execs_total: {}\n\
          reaps_total: {}\n\
@@ -317,8 +363,18 @@ pub fn render_report() -> alloc::string::String {
          orphan_blocks_reclaimed: {}\n\
          orphan_inodes_reclaimed: {}\n\
          switches_total: {}\n\
+         voluntary_switches: {}\n\
+         preemptions: {}\n\
+         migrations_between_queues: {}\n\
+         demand_faults: {}\n\
+         prefaulted_pages: {}\n\
+         nmis_total: {}\n\
+         watchdog_warnings: {}\n\
+         sched_lock_busy_ticks: {}\n\
+         spurious_irqs: {}\n\
          {}{}",
         mask, enabled,
+        level, level_name,
         FORKS_TOTAL.load(Ordering::Relaxed),
         EXECS_TOTAL.load(Ordering::Relaxed),
         REAPS_TOTAL.load(Ordering::Relaxed),
@@ -327,6 +383,15 @@ pub fn render_report() -> alloc::string::String {
         ORPHAN_BLOCKS_RECLAIMED.load(Ordering::Relaxed),
         ORPHAN_INODES_RECLAIMED.load(Ordering::Relaxed),
         SWITCHES_TOTAL.load(Ordering::Relaxed),
+        VOLUNTARY_SWITCHES.load(Ordering::Relaxed),
+        PREEMPTIONS.load(Ordering::Relaxed),
+        QUEUE_MIGRATIONS.load(Ordering::Relaxed),
+        DEMAND_FAULTS.load(Ordering::Relaxed),
+        PREFAULTED_PAGES.load(Ordering::Relaxed),
+        NMIS_TOTAL.load(Ordering::Relaxed),
+        WATCHDOG_WARNINGS.load(Ordering::Relaxed),
+        SCHED_LOCK_BUSY_TICKS.load(Ordering::Relaxed),
+        crate::interrupts::irq::spurious_count(),
         SCHEDULER_LOCK.render("scheduler"),
         alloc::format!(
             "{}{}{}{}",
@@ -352,6 +417,14 @@ pub fn print_panic_snapshot() {
     crate::serial_println_raw!("  cow_faults_resolved: {}", COW_FAULTS_RESOLVED.load(Ordering::Relaxed));
     crate::serial_println_raw!("  cow_faults_failed: {}", COW_FAULTS_FAILED.load(Ordering::Relaxed));
     crate::serial_println_raw!("  switches_total: {}", SWITCHES_TOTAL.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  voluntary_switches: {}", VOLUNTARY_SWITCHES.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  preemptions: {}", PREEMPTIONS.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  migrations_between_queues: {}", QUEUE_MIGRATIONS.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  demand_faults: {}", DEMAND_FAULTS.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  prefaulted_pages: {}", PREFAULTED_PAGES.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  nmis_total: {}", NMIS_TOTAL.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  watchdog_warnings: {}", WATCHDOG_WARNINGS.load(Ordering::Relaxed));
+    crate::serial_println_raw!("  sched_lock_busy_ticks: {}", SCHED_LOCK_BUSY_TICKS.load(Ordering::Relaxed));
     let acq = SCHEDULER_LOCK.acquires.load(Ordering::Relaxed);
     let rel = SCHEDULER_LOCK.releases.load(Ordering::Relaxed);
     crate::serial_println_raw!("  scheduler_lock: acquires={} releases={} outstanding={}", acq, rel, acq.saturating_sub(rel));
@@ -11,7 +11,7 @@
 
 use alloc::boxed::Box;
 use crate::fs::types::Stat;
-use crate::process::file::{FileHandle, FileResult};
+use crate::process::file::{FileHandle, FileResult, Readiness};
 
 pub struct KbdDevice;
 
@@ -37,6 +37,12 @@ impl FileHandle for KbdDevice {
         Some(Stat::chardev(0))
     }
 
+    /// Readable once a key is buffered, so a game loop can poll() instead
+    /// of spinning on 0-byte reads.
+    fn poll(&self) -> Readiness {
+        Readiness { readable: crate::keyboard::read_key_peek(), ..Readiness::ALWAYS }
+    }
+
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
         Some(Box::new(KbdDevice))
     }
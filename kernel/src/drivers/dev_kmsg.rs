@@ -0,0 +1,44 @@
+// kernel/src/drivers/dev_kmsg.rs
+//
+// /dev/kmsg — read-only view of the kernel message ring (`crate::log`).
+//
+// Each open file has its own cursor, starting at the oldest message still
+// in the ring, so every reader gets the full backlog once and then only
+// what's new. Reads return whole lines where possible and never block:
+// `Ok(0)` once caught up, so `cat /dev/kmsg` prints the backlog and exits
+// instead of hanging.
+
+use alloc::boxed::Box;
+use crate::fs::types::Stat;
+use crate::process::file::{FileError, FileHandle, FileResult};
+
+pub struct KmsgDevice {
+    /// Absolute offset into the message stream — see `log::read_from`.
+    pos: u64,
+}
+
+impl FileHandle for KmsgDevice {
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        Ok(crate::log::read_from(&mut self.pos, buf))
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> FileResult<usize> {
+        Err(FileError::NotSupported)
+    }
+
+    fn stat(&self) -> Option<Stat> {
+        Some(Stat::chardev(0))
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(Box::new(KmsgDevice { pos: self.pos }))
+    }
+
+    fn name(&self) -> &str {
+        "/dev/kmsg"
+    }
+}
+
+pub fn open() -> Box<dyn FileHandle> {
+    Box::new(KmsgDevice { pos: 0 })
+}
@@ -0,0 +1,58 @@
+// kernel/src/drivers/dev_mouse.rs
+//
+// /dev/mouse — the PS/2 mouse as a plain stream of 3-byte PS/2 packets,
+// the same packed format Linux's `/dev/input/mice` serves: byte 0 is
+// buttons (bits 0-2) + always-set bit 3 + dx/dy sign bits, bytes 1/2 are
+// the low 8 bits of dx/dy (Y positive-up). Simpler to consume than the
+// evdev records on /dev/input/event1 when all a client wants is "where did
+// the pointer go and which buttons are down" — see `hal::mouse::
+// MouseEvent::to_packet` for the exact encoding.
+//
+// Non-blocking: `read()` returns as many whole packets as fit in `buf`
+// (never a partial one, so a reader never has to resync mid-stream), or 0
+// if nothing is queued.
+//
+// Both this and /dev/input/event1 drain the one shared `mouse::read_event`
+// queue — with both open at once, each packet goes to whichever reads
+// first. Same single-consumer model as the keyboard's /dev/kbd vs
+// /dev/input/event0.
+
+use alloc::boxed::Box;
+use crate::fs::types::Stat;
+use crate::process::file::{FileHandle, FileResult};
+
+const PACKET_SIZE: usize = 3;
+
+pub struct MouseDevice;
+
+impl FileHandle for MouseDevice {
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        let mut n = 0;
+        while n + PACKET_SIZE <= buf.len() {
+            let Some(ev) = crate::mouse::read_event() else { break };
+            buf[n..n + PACKET_SIZE].copy_from_slice(&ev.to_packet());
+            n += PACKET_SIZE;
+        }
+        Ok(n)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        Ok(buf.len()) // writes are ignored
+    }
+
+    fn stat(&self) -> Option<Stat> {
+        Some(Stat::chardev(0))
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(Box::new(MouseDevice))
+    }
+
+    fn name(&self) -> &str {
+        "/dev/mouse"
+    }
+}
+
+pub fn open() -> Box<dyn FileHandle> {
+    Box::new(MouseDevice)
+}
@@ -0,0 +1,94 @@
+// kernel/src/drivers/dev_random.rs
+//
+// /dev/random — pseudo-random bytes, seeded from the TSC.
+//
+// NOT cryptographic. There's no entropy pool: the generator is xorshift64*
+// over one global state word, seeded on first use from `cpu::tsc::now()`
+// (plus RDRAND when CPUID has it), and every read() mixes in the TSC again
+// so two processes reading back-to-back don't see the same stream. Good
+// enough for seeding srand(), picking temp names, shuffling — which is all
+// anything here uses it for. Reads never block; writes are discarded
+// (Linux would mix them into the pool). `getrandom(2)` calls `fill`
+// directly, so there is one stream whichever way a program asks.
+
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::fs::types::Stat;
+use crate::process::file::{FileHandle, FileResult};
+
+/// Generator state; 0 = not seeded yet (xorshift never reaches 0 from a
+/// non-zero state, so it doubles as the flag).
+static STATE: AtomicU64 = AtomicU64::new(0);
+
+fn rdrand() -> Option<u64> {
+    if !crate::cpu::features::features().rdrand {
+        return None;
+    }
+    let mut v = 0u64;
+    // Retry a few times: RDRAND may transiently report no data (CF=0).
+    for _ in 0..10 {
+        if unsafe { core::arch::x86_64::_rdrand64_step(&mut v) } == 1 {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// splitmix64 finaliser — spreads the few low-order bits that actually
+/// vary between TSC reads across the whole word.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Fill `buf`. Runs with interrupts off (syscall path), so the
+/// load/store pair on `STATE` can't interleave with another reader.
+pub fn fill(buf: &mut [u8]) {
+    let mut s = STATE.load(Ordering::Relaxed);
+    if s == 0 {
+        s = crate::cpu::tsc::now() ^ rdrand().unwrap_or(0);
+    }
+    s = mix(s ^ crate::cpu::tsc::now());
+    if s == 0 {
+        s = 0x9E37_79B9_7F4A_7C15;
+    }
+    for chunk in buf.chunks_mut(8) {
+        s ^= s >> 12;
+        s ^= s << 25;
+        s ^= s >> 27;
+        let out = s.wrapping_mul(0x2545_F491_4F6C_DD1D).to_le_bytes();
+        chunk.copy_from_slice(&out[..chunk.len()]);
+    }
+    STATE.store(s, Ordering::Relaxed);
+}
+
+pub struct DevRandom;
+
+impl FileHandle for DevRandom {
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        fill(buf);
+        Ok(buf.len())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> Option<Stat> {
+        Some(Stat::chardev(0))
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(Box::new(DevRandom))
+    }
+
+    fn name(&self) -> &str {
+        "/dev/random"
+    }
+}
+
+pub fn open() -> Box<dyn FileHandle> {
+    Box::new(DevRandom)
+}
@@ -0,0 +1,106 @@
+// kernel/src/drivers/dev_sda.rs
+//
+// /dev/sda — the raw ATA disk (`block::ata`), sector-granular.
+//
+// This is the same drive `fs::ext2` mounts at /mnt: the secondary-channel
+// master, not the primary channel the UEFI boot disk sits on (see
+// `block/ata.rs`'s header for why the driver only ever talks to that one).
+// Writing here goes underneath a mounted filesystem, exactly like writing
+// to a mounted /dev/sda on Linux — fine for reading, a round-trip test on
+// sectors ext2 doesn't use, or inspecting on-disk structures; corrupting
+// for anything else.
+//
+// Every `read`/`write` must start on a sector boundary and cover whole
+// sectors (`EINVAL` otherwise) — no read-modify-write for partial sectors,
+// the caller does that if it wants it. A transfer that runs into the end
+// of the disk is cut short there (0 at the end, like a regular file for
+// reads; `ENOSPC` for writes). `seek` is plain byte-offset `lseek` against
+// the disk's size from IDENTIFY; the alignment check happens at transfer
+// time.
+
+use alloc::boxed::Box;
+use crate::block::ata::{self, SECTOR_SIZE};
+use crate::fs::types::Stat;
+use crate::process::file::{compute_seek, FileError, FileHandle, FileResult};
+
+/// Sectors per ATA command. `ata::*_sectors` take a `u8` count (0 means
+/// 256), so keep each chunk well inside it.
+const MAX_SECTORS_PER_CMD: usize = 128;
+
+pub struct AtaDiskDevice {
+    pos: u64,
+    sectors: u64,
+}
+
+impl AtaDiskDevice {
+    /// Validates alignment and clips a `len`-byte transfer at `self.pos` to
+    /// the end of the disk. Returns `(first_lba, sector_count)`.
+    fn span(&self, len: usize) -> FileResult<(u64, usize)> {
+        if !self.pos.is_multiple_of(SECTOR_SIZE as u64) || !len.is_multiple_of(SECTOR_SIZE) {
+            return Err(FileError::InvalidArgument);
+        }
+        let lba = self.pos / SECTOR_SIZE as u64;
+        let left = self.sectors.saturating_sub(lba);
+        Ok((lba, (len / SECTOR_SIZE).min(left as usize)))
+    }
+}
+
+impl FileHandle for AtaDiskDevice {
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        let (lba, count) = self.span(buf.len())?;
+        let mut done = 0;
+        while done < count {
+            let n = (count - done).min(MAX_SECTORS_PER_CMD);
+            let chunk = &mut buf[done * SECTOR_SIZE..(done + n) * SECTOR_SIZE];
+            ata::read_sectors((lba + done as u64) as u32, n as u8, chunk)
+                .map_err(|_| FileError::IOError)?;
+            done += n;
+        }
+        self.pos += (count * SECTOR_SIZE) as u64;
+        Ok(count * SECTOR_SIZE)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        let (lba, count) = self.span(buf.len())?;
+        if count == 0 && !buf.is_empty() {
+            return Err(FileError::NoSpace);
+        }
+        let mut done = 0;
+        while done < count {
+            let n = (count - done).min(MAX_SECTORS_PER_CMD);
+            let chunk = &buf[done * SECTOR_SIZE..(done + n) * SECTOR_SIZE];
+            ata::write_sectors((lba + done as u64) as u32, n as u8, chunk)
+                .map_err(|_| FileError::IOError)?;
+            done += n;
+        }
+        self.pos += (count * SECTOR_SIZE) as u64;
+        Ok(count * SECTOR_SIZE)
+    }
+
+    fn seek(&mut self, offset: i64, whence: i32) -> FileResult<i64> {
+        let size = (self.sectors * SECTOR_SIZE as u64) as i64;
+        let pos = compute_seek(self.pos as i64, size, offset, whence)?;
+        self.pos = pos as u64;
+        Ok(pos)
+    }
+
+    fn stat(&self) -> Option<Stat> {
+        Some(Stat::blockdev(0, (self.sectors * SECTOR_SIZE as u64) as i64))
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(Box::new(AtaDiskDevice { pos: self.pos, sectors: self.sectors }))
+    }
+
+    fn name(&self) -> &str {
+        "/dev/sda"
+    }
+}
+
+/// No disk attached opens fine and behaves as a zero-length device (every
+/// read returns 0, every write `ENOSPC`) — `open_device` has no way to
+/// fail, and an empty device is the honest description anyway.
+pub fn open() -> Box<dyn FileHandle> {
+    let sectors = ata::sector_count().unwrap_or(0) as u64;
+    Box::new(AtaDiskDevice { pos: 0, sectors })
+}
@@ -0,0 +1,91 @@
+// kernel/src/drivers/dev_tty.rs
+//
+// /dev/tty — "the terminal" as one fd: writes go to the framebuffer
+// console, the serial console, or both; reads take typed input from the
+// shared keyboard/UART ring, same as `/dev/console`. Every process's fds
+// 0/1/2 start out on it (`FileDescriptorTable::new_with_stdio`), and
+// `sys_ioctl` treats it as a tty (termios, job control) and as the
+// framebuffer (`FBIO_*`).
+//
+// OUTPUTS (`set_outputs`, `kdebug tty fb|serial|both`), one global setting
+// for every open /dev/tty, like the one `tty.rs` termios:
+//   OUT_BOTH   — `FramebufferConsole::write`: the screen plus its `[fb] `-
+//                tagged serial mirror. The boot default, so serial.log reads
+//                exactly as it did when stdout was `/dev/fb`.
+//   OUT_FB     — the screen only (`FramebufferConsole::draw`).
+//   OUT_SERIAL — the console UART only, untagged — for driving the system
+//                from a serial terminal.
+// Cooked-mode echo (`tty::feed_input`) doesn't look at this; it always
+// goes to the framebuffer console and its mirror.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use alloc::boxed::Box;
+use crate::fs::types::Stat;
+use crate::process::file::{FileHandle, FileResult, Readiness};
+use super::framebuffer_console::FramebufferConsole;
+use super::serial_console::SerialConsole;
+
+/// Output bits for `set_outputs`.
+pub const OUT_FB: u8 = 1;
+pub const OUT_SERIAL: u8 = 2;
+pub const OUT_BOTH: u8 = OUT_FB | OUT_SERIAL;
+
+static OUTPUTS: AtomicU8 = AtomicU8::new(OUT_BOTH);
+
+/// Where /dev/tty writes currently go (`OUT_*` bits).
+pub fn outputs() -> u8 {
+    OUTPUTS.load(Ordering::Relaxed)
+}
+
+/// Send /dev/tty writes to `outputs` (`OUT_FB`, `OUT_SERIAL` or
+/// `OUT_BOTH`). Returns the previous setting, or `None` (nothing changed)
+/// for anything else — including 0, which would make the terminal a sink.
+pub fn set_outputs(outputs: u8) -> Option<u8> {
+    if !(OUT_FB..=OUT_BOTH).contains(&outputs) {
+        return None;
+    }
+    Some(OUTPUTS.swap(outputs, Ordering::Relaxed))
+}
+
+pub struct Tty;
+
+impl FileHandle for Tty {
+    /// `SerialConsole::read`: non-blocking drain of the keyboard/UART ring.
+    /// fd 0 on a /dev/tty takes `sys_read`'s blocking keyboard path
+    /// instead, exactly as it does for `/dev/console`.
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        SerialConsole.read(buf)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        match outputs() {
+            OUT_FB => FramebufferConsole.draw(buf),
+            OUT_SERIAL => crate::serial::console_write(buf),
+            _ => return FramebufferConsole.write(buf),
+        }
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> Option<Stat> {
+        Some(Stat::chardev(0))
+    }
+
+    fn poll(&self) -> Readiness {
+        SerialConsole.poll()
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(Box::new(Tty))
+    }
+
+    fn name(&self) -> &str {
+        "tty"
+    }
+}
+
+pub fn open() -> Box<dyn FileHandle> {
+    // Same one-time screen clear opening `/dev/fb` does.
+    FramebufferConsole::new();
+    Box::new(Tty)
+}
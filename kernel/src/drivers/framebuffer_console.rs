@@ -2,15 +2,38 @@
 //
 // Framebuffer text console with ANSI escape code support.
 //
+// The parser (`AnsiState`) lives in FB_STATE, so a sequence split across
+// two writes picks up where it stopped. Handled: SGR colors (16, 256 and
+// 24-bit, `ESC[0m` reset, `ESC[7m`/`ESC[27m` reverse video), cursor
+// movement (`H`/`f`, `A`-`D`, `G`, `d`), erase (`J`, `K`), save/restore
+// cursor (`ESC 7`/`ESC 8`, `ESC[s`/`ESC[u`) and DECTCEM. Anything else is
+// swallowed, never drawn.
+//
 // All instances share a single global cursor position (FB_STATE) so
 // that parent/child processes after fork() see a consistent cursor.
+//
+// Every cell drawn is also recorded in `FbState::screen`, a shadow of the
+// visible text grid, and each row that scrolls off the top is kept as a
+// `String` in a bounded scrollback ring — see `Screen`. Shift+PgUp/PgDn
+// (`try_scroll`, driven by the keyboard ISR) re-render an earlier window
+// from those; releasing the paging key, or any new output, redraws the
+// live grid.
+//
+// The cursor is a blinking inverse-video block over the cell at
+// (`row`, `col`) — see `Cursor`. It's erased before and redrawn after
+// every change to the screen, and toggled by the timer tick (`blink_tick`).
+//
+// Output is decoded as UTF-8 (`Utf8Decoder`) and drawn one cell per
+// `char`: printable ASCII and Latin-1 get real glyphs, anything the font
+// lacks (and malformed input, as U+FFFD) a box — see `font::Font::glyph`.
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 use spin::Mutex;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use crate::{
-    framebuffer::{FRAMEBUFFER, Color, Framebuffer},
+    font::{Font, FONTS, LEGACY_8X8},
+    framebuffer::{FRAMEBUFFER, Color, Framebuffer, GlyphStyle},
     fs::types::Stat,
     process::file::{FileHandle, FileError, FileResult},
 };
@@ -22,39 +45,45 @@ const MARGIN_Y: usize = 4;
 
 /// Every `draw_char`/`draw_text` call in this file uses this scale — one
 /// constant instead of a literal `1` repeated at each call site, so
-/// `CHAR_W`/`CHAR_H` (and every cols/rows computation derived from them)
+/// `char_w`/`char_h` (and every cols/rows computation derived from them)
 /// can never drift out of sync with what's actually drawn.
 const SCALE: usize = 1;
 const LINE_GAP: usize = 1; // extra px of line spacing below each glyph row
 
-const CHAR_W: usize = crate::framebuffer::GLYPH_W * SCALE;
-const CHAR_H: usize = crate::framebuffer::GLYPH_H * SCALE + LINE_GAP;
+/// Cell size in px for the console's current font (`FbState::font`).
+fn char_w(font: &Font) -> usize { font.width * SCALE }
+fn char_h(font: &Font) -> usize { font.height * SCALE + LINE_GAP }
+
+/// How a cell is drawn: its colors, at `SCALE`, in `font`.
+fn style(fg: Color, bg: Color, font: &Font) -> GlyphStyle<'_> {
+    GlyphStyle { fg, bg, scale: SCALE, font }
+}
 
 const DEFAULT_FG: Color = Color::rgb(220, 220, 220);
-const DEFAULT_BG: Color = Color::rgb(0, 0, 0);
+const DEFAULT_BG: Color = Color::BLACK;
 
 // ── ANSI color palette ────────────────────────────────────────────────────────
 
 const ANSI_COLORS: [Color; 8] = [
-    Color::rgb(0,   0,   0  ), // 0: black
-    Color::rgb(170, 0,   0  ), // 1: red
-    Color::rgb(0,   170, 0  ), // 2: green
-    Color::rgb(170, 85,  0  ), // 3: yellow (dark)
-    Color::rgb(0,   0,   170), // 4: blue
-    Color::rgb(170, 0,   170), // 5: magenta
-    Color::rgb(0,   170, 170), // 6: cyan
-    Color::rgb(170, 170, 170), // 7: white (light gray)
+    Color::BLACK,      // 0: black
+    Color::RED,        // 1: red
+    Color::GREEN,      // 2: green
+    Color::BROWN,      // 3: yellow (dark)
+    Color::BLUE,       // 4: blue
+    Color::MAGENTA,    // 5: magenta
+    Color::CYAN,       // 6: cyan
+    Color::LIGHT_GRAY, // 7: white (light gray)
 ];
 
 const ANSI_BRIGHT: [Color; 8] = [
-    Color::rgb(85,  85,  85 ), // 0: bright black (dark gray)
-    Color::rgb(255, 85,  85 ), // 1: bright red
-    Color::rgb(85,  255, 85 ), // 2: bright green
-    Color::rgb(255, 255, 85 ), // 3: bright yellow
-    Color::rgb(85,  85,  255), // 4: bright blue
-    Color::rgb(255, 85,  255), // 5: bright magenta
-    Color::rgb(85,  255, 255), // 6: bright cyan
-    Color::rgb(255, 255, 255), // 7: bright white
+    Color::DARK_GRAY,     // 0: bright black (dark gray)
+    Color::LIGHT_RED,     // 1: bright red
+    Color::LIGHT_GREEN,   // 2: bright green
+    Color::YELLOW,        // 3: bright yellow
+    Color::LIGHT_BLUE,    // 4: bright blue
+    Color::LIGHT_MAGENTA, // 5: bright magenta
+    Color::LIGHT_CYAN,    // 6: bright cyan
+    Color::WHITE,         // 7: bright white
 ];
 
 fn ansi_color(idx: u8, bright: bool) -> Color {
@@ -91,6 +120,299 @@ enum AnsiState {
     Csi { buf: [u8; 32], len: usize },
 }
 
+// ── Shadow grid + scrollback ──────────────────────────────────────────────────
+
+/// Scrollback depth, in lines. Bounds memory: every slot is preallocated
+/// at `SCROLLBACK_LINE_MAX` bytes, ~128 KiB in total.
+const SCROLLBACK_LINES: usize = 512;
+/// Longer lines are truncated on the way into scrollback. A row can't
+/// exceed `cols` anyway; this only matters on very wide framebuffers.
+const SCROLLBACK_LINE_MAX: usize = 256;
+
+#[derive(Clone, Copy)]
+struct Cell {
+    ch: char,
+    fg: Color,
+    bg: Color,
+}
+
+/// What a freshly scrolled-in row holds — `Framebuffer::scroll_up` zero-fills
+/// it, i.e. black, which is `DEFAULT_BG`.
+const BLANK: Cell = Cell { ch: ' ', fg: DEFAULT_FG, bg: DEFAULT_BG };
+
+/// The text behind the pixels: the live grid (`cells`, row-major) and a
+/// ring of the `SCROLLBACK_LINES` most recent rows that scrolled off it.
+///
+/// Everything is allocated once, by `ensure_sized` from
+/// `FramebufferConsole::new` (process context), never on the write path:
+/// `try_echo` and `try_scroll` run in the keyboard ISR, which may have
+/// interrupted a holder of the allocator's lock. Until then (or if the
+/// framebuffer's size ever disagrees with the grid) nothing is recorded and
+/// paging is a no-op — the console itself draws exactly as before.
+///
+/// Scrollback lines are plain text (UTF-8, so `SCROLLBACK_LINE_MAX` counts
+/// bytes, and a line is cut at a char boundary); colors are only kept for the live grid
+/// (which has to be restored exactly on snap-back), so history re-renders
+/// in the default colors.
+struct Screen {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    /// Ring storage; slot capacities never grow past `SCROLLBACK_LINE_MAX`.
+    history: Vec<String>,
+    /// Index of the oldest line in `history`.
+    head: usize,
+    len: usize,
+    /// How many lines back the display currently is; 0 = live.
+    view: usize,
+}
+
+impl Screen {
+    const fn new() -> Self {
+        Screen { cols: 0, rows: 0, cells: Vec::new(), history: Vec::new(), head: 0, len: 0, view: 0 }
+    }
+
+    fn ensure_sized(&mut self, cols: usize, rows: usize) {
+        if self.cols != cols || self.rows != rows {
+            self.cols = cols;
+            self.rows = rows;
+            self.cells = vec![BLANK; cols * rows];
+        }
+        if self.history.is_empty() {
+            self.history = (0..SCROLLBACK_LINES)
+                .map(|_| String::with_capacity(SCROLLBACK_LINE_MAX))
+                .collect();
+        }
+    }
+
+    fn ready(&self, cols: usize, rows: usize) -> bool {
+        self.cols == cols && self.rows == rows && !self.history.is_empty()
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        (row < self.rows && col < self.cols).then(|| self.cells[row * self.cols + col])
+    }
+
+    fn set(&mut self, row: usize, col: usize, cell: Cell) {
+        if row < self.rows && col < self.cols {
+            self.cells[row * self.cols + col] = cell;
+        }
+    }
+
+    fn clear(&mut self, bg: Color) {
+        self.cells.fill(Cell { bg, ..BLANK });
+    }
+
+    /// Mirror of `Framebuffer::scroll_up(char_h(font))`: row 0 goes to scrollback.
+    fn scroll(&mut self) {
+        if self.rows == 0 || self.history.is_empty() {
+            return;
+        }
+        let slot = (self.head + self.len) % SCROLLBACK_LINES;
+        if self.len < SCROLLBACK_LINES {
+            self.len += 1;
+        } else {
+            self.head = (self.head + 1) % SCROLLBACK_LINES;
+        }
+        let line = &mut self.history[slot];
+        line.clear();
+        for cell in &self.cells[..self.cols] {
+            if line.len() + cell.ch.len_utf8() > SCROLLBACK_LINE_MAX {
+                break;
+            }
+            line.push(cell.ch);
+        }
+        let trimmed = line.trim_end().len();
+        line.truncate(trimmed);
+
+        self.cells.copy_within(self.cols.., 0);
+        let last = (self.rows - 1) * self.cols;
+        self.cells[last..].fill(BLANK);
+    }
+
+    /// `i` counts from the oldest retained line.
+    fn history_line(&self, i: usize) -> &str {
+        &self.history[(self.head + i) % SCROLLBACK_LINES]
+    }
+
+    /// Redraw the whole text area for the current `view`: the last `view`
+    /// scrollback lines on top, then the live grid's first `rows - view`
+    /// rows below them. `view == 0` redraws the live grid as-is.
+    fn render(&self, fb: &mut Framebuffer, font: &Font) {
+        let view = self.view.min(self.len);
+        for r in 0..self.rows {
+            let py = MARGIN_Y + r * char_h(font);
+            if r < view {
+                let mut line = self.history_line(self.len - view + r).chars();
+                for c in 0..self.cols {
+                    let ch = line.next().unwrap_or(' ');
+                    fb.draw_char(MARGIN_X + c * char_w(font), py, ch, &style(DEFAULT_FG, DEFAULT_BG, font));
+                }
+            } else {
+                let row = &self.cells[(r - view) * self.cols..][..self.cols];
+                for (c, cell) in row.iter().enumerate() {
+                    fb.draw_char(MARGIN_X + c * char_w(font), py, cell.ch, &style(cell.fg, cell.bg, font));
+                }
+            }
+        }
+    }
+}
+
+// ── UTF-8 decoding ────────────────────────────────────────────────────────────
+
+/// Incremental UTF-8 decoder for `FramebufferConsole::write`. Lives in
+/// `FbState`, not on the stack, because a multi-byte char can be split
+/// across two `write()` calls — stdio flushes at buffer boundaries, not
+/// char boundaries.
+///
+/// Malformed input (a stray continuation byte, an invalid lead byte, a
+/// sequence cut short by a non-continuation byte, an overlong or surrogate
+/// encoding) comes out as U+FFFD, one per bad sequence, like any terminal.
+struct Utf8Decoder {
+    buf: [u8; 4],
+    len: usize,
+    need: usize,
+}
+
+impl Utf8Decoder {
+    const fn new() -> Self {
+        Utf8Decoder { buf: [0; 4], len: 0, need: 0 }
+    }
+
+    fn pending(&self) -> bool {
+        self.len != 0
+    }
+
+    /// Drop a partial sequence (the caller reports it as U+FFFD).
+    fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    /// Feed one non-ASCII byte. `None` while a sequence is still
+    /// incomplete.
+    fn push(&mut self, byte: u8) -> Option<char> {
+        if self.len == 0 {
+            self.need = match byte {
+                0xC2..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF4 => 4,
+                _ => return Some(char::REPLACEMENT_CHARACTER),
+            };
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+        if self.len < self.need {
+            return None;
+        }
+        let bytes = &self.buf[..self.len];
+        self.len = 0;
+        Some(core::str::from_utf8(bytes).ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+// ── Cursor ────────────────────────────────────────────────────────────────────
+
+/// Half-period of the cursor blink.
+const CURSOR_BLINK_MS: u64 = 500;
+
+/// The visible text cursor: the cell at `FbState`'s (`row`, `col`) drawn
+/// with fg/bg swapped, on for `CURSOR_BLINK_MS`, off for as long.
+///
+/// What's under it doesn't need a separate pixel save: the cell it covers
+/// is always in the shadow grid (`Screen::cells`), so erasing it is just
+/// redrawing that cell — and that stays right across a scroll, which moves
+/// the grid along with the pixels. Every path that draws or scrolls calls
+/// `cursor_hide` first and `cursor_show` after, so the block is never
+/// scrolled, recorded into scrollback, or left behind at an old position.
+/// Without a ready shadow grid (before `FramebufferConsole::new`) there is
+/// nothing to restore from, and no cursor.
+///
+/// Off until the console is first opened (`FramebufferConsole::new`), so
+/// the non-interactive boot screen never shows one. A program can also
+/// hide it with DECTCEM (`ESC[?25l`, back with `ESC[?25h`), like a real
+/// terminal, and it's never drawn over a raw-pixel client (`FB_RAW_DIRTY`)
+/// or while paged back through scrollback.
+struct Cursor {
+    /// Set once by the first `FramebufferConsole::new`.
+    enabled: bool,
+    /// DECTCEM.
+    visible: bool,
+    /// Blink phase; forced on by every `write()`, so typing shows it solid.
+    phase_on: bool,
+    /// The cell currently drawn inverted, if any.
+    drawn: Option<(usize, usize)>,
+}
+
+impl Cursor {
+    const fn new() -> Self {
+        Cursor { enabled: false, visible: true, phase_on: true, drawn: None }
+    }
+}
+
+/// Next `uptime_ms` at which `blink_tick` toggles the phase.
+static NEXT_BLINK_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Redraw the cell under the cursor normally, if the cursor is drawn.
+fn cursor_hide(fb: &mut Framebuffer, state: &mut FbState) {
+    let Some((row, col)) = state.cursor.drawn.take() else { return };
+    if FB_RAW_DIRTY.load(Ordering::SeqCst) || state.screen.view != 0 {
+        return; // already painted over
+    }
+    if let Some(cell) = state.screen.get(row, col) {
+        let font = state.font;
+        fb.draw_char(MARGIN_X + col * char_w(font), MARGIN_Y + row * char_h(font), cell.ch, &style(cell.fg, cell.bg, font));
+    }
+}
+
+/// Draw the cursor at (`row`, `col`) if it should be visible right now.
+fn cursor_show(fb: &mut Framebuffer, state: &mut FbState, cols: usize, rows: usize) {
+    let c = &state.cursor;
+    if !c.enabled || !c.visible || !c.phase_on || c.drawn.is_some()
+        || state.screen.view != 0 || FB_RAW_DIRTY.load(Ordering::SeqCst)
+        || !state.screen.ready(cols, rows) || cols == 0 || rows == 0
+    {
+        return;
+    }
+    let (row, col) = (state.row.min(rows - 1), state.col.min(cols - 1));
+    let Some(cell) = state.screen.get(row, col) else { return };
+    let font = state.font;
+    fb.draw_char(MARGIN_X + col * char_w(font), MARGIN_Y + row * char_h(font), cell.ch, &style(cell.bg, cell.fg, font));
+    state.cursor.drawn = Some((row, col));
+}
+
+/// Blink the cursor. Called from the timer ISR on every tick; does nothing
+/// until `CURSOR_BLINK_MS` has passed since the last toggle (or the last
+/// `write()`), and — like `try_echo` — only try-locks FB_STATE/FRAMEBUFFER,
+/// skipping the toggle if the interrupted code holds either.
+pub fn blink_tick() {
+    let now = crate::cpu::tsc::uptime_ms();
+    if now < NEXT_BLINK_MS.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(mut state) = FB_STATE.try_lock() else { return };
+    if !state.cursor.enabled {
+        return;
+    }
+    let Some(mut fb_guard) = FRAMEBUFFER.try_lock() else { return };
+    let Some(fb) = fb_guard.as_mut() else { return };
+    NEXT_BLINK_MS.store(now + CURSOR_BLINK_MS, Ordering::Relaxed);
+
+    let (cols, rows) = grid_size(fb, state.font);
+    state.cursor.phase_on = !state.cursor.phase_on;
+    if state.cursor.phase_on {
+        cursor_show(fb, &mut state, cols, rows);
+    } else {
+        cursor_hide(fb, &mut state);
+    }
+    fb.present_dirty();
+}
+
 // ── Global cursor + color state ───────────────────────────────────────────────
 
 struct FbState {
@@ -99,6 +421,16 @@ struct FbState {
     fg:   Color,
     bg:   Color,
     ansi: AnsiState,
+    utf8: Utf8Decoder,
+    screen: Screen,
+    /// `LEGACY_8X8` until `set_font` picks another one.
+    font: &'static Font,
+    cursor: Cursor,
+    /// SGR 7 (reverse video) is on: `fg`/`bg` are held swapped, so every
+    /// draw path picks it up without knowing about it.
+    reverse: bool,
+    /// (`row`, `col`) as of the last DECSC (`ESC 7`) / `ESC[s`.
+    saved: (usize, usize),
 }
 
 static FB_STATE: Mutex<FbState> = Mutex::new(FbState {
@@ -107,7 +439,102 @@ static FB_STATE: Mutex<FbState> = Mutex::new(FbState {
     fg: DEFAULT_FG,
     bg: DEFAULT_BG,
     ansi: AnsiState::Normal,
+    utf8: Utf8Decoder::new(),
+    screen: Screen::new(),
+    font: &LEGACY_8X8,
+    cursor: Cursor::new(),
+    reverse: false,
+    saved: (0, 0),
 });
+
+/// Draw one cell and record it in the shadow grid.
+fn put_cell(fb: &mut Framebuffer, state: &mut FbState, row: usize, col: usize, ch: char, fg: Color, bg: Color) {
+    let font = state.font;
+    fb.draw_char(MARGIN_X + col * char_w(font), MARGIN_Y + row * char_h(font), ch, &style(fg, bg, font));
+    state.screen.set(row, col, Cell { ch, fg, bg });
+}
+
+fn clear_screen(fb: &mut Framebuffer, state: &mut FbState, bg: Color) {
+    fb.clear(bg);
+    state.screen.clear(bg);
+    state.cursor.drawn = None;
+}
+
+fn scroll_screen(fb: &mut Framebuffer, state: &mut FbState) {
+    fb.scroll_up(char_h(state.font));
+    state.screen.scroll();
+}
+
+/// Draw `c` at the cursor and advance it, wrapping (and scrolling) at the
+/// right edge.
+fn print_char(fb: &mut Framebuffer, state: &mut FbState, c: char, cols: usize, rows: usize) {
+    let (row, col, fg, bg) = (state.row, state.col, state.fg, state.bg);
+    put_cell(fb, state, row, col, c, fg, bg);
+    state.col += 1;
+    if state.col >= cols {
+        state.col = 0;
+        state.row += 1;
+        if state.row >= rows {
+            scroll_screen(fb, state);
+            state.row = rows - 1;
+        }
+    }
+}
+
+/// Console side of Shift+PgUp/PgDn (see `hal::keyboard::ConsoleScroll`).
+/// Called from the keyboard ISR, so — like `try_echo` — both locks are
+/// only try-locked, and a key that arrives while either is held is simply
+/// dropped (the next repeat, or the release, will get through).
+pub fn try_scroll(action: hal::keyboard::ConsoleScroll) {
+    use hal::keyboard::ConsoleScroll;
+    let Some(mut state) = FB_STATE.try_lock() else { return };
+    let Some(mut fb_guard) = FRAMEBUFFER.try_lock() else { return };
+    let Some(fb) = fb_guard.as_mut() else { return };
+    let font = state.font;
+    let (cols, rows) = grid_size(fb, font);
+    if !state.screen.ready(cols, rows) || FB_RAW_DIRTY.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let screen = &mut state.screen;
+    let view = match action {
+        ConsoleScroll::PageUp => (screen.view + rows).min(screen.len),
+        ConsoleScroll::PageDown => screen.view.saturating_sub(rows),
+        ConsoleScroll::Live => 0,
+    };
+    if view != screen.view {
+        screen.view = view;
+        screen.render(fb, font);
+        // `render` repainted every cell, including the one under the cursor.
+        state.cursor.drawn = None;
+        cursor_show(fb, &mut state, cols, rows);
+        fb.present_dirty();
+    }
+}
+
+/// Switch the console to `FONTS[index]` (`FBIO_SETFONT`). The text grid
+/// changes size with the cell, so this starts over on a cleared screen
+/// with the cursor home — whatever was on it is already in scrollback or
+/// gone, exactly as if it had been `ESC[2J`-cleared. Scrollback itself is
+/// kept (it's plain text, font-independent). Programs that sized
+/// themselves from `TIOCGWINSZ` before the switch keep their old idea of
+/// the grid until they ask again — there's no `SIGWINCH`.
+pub fn set_font(index: usize) -> Result<(), ()> {
+    let font = *FONTS.get(index).ok_or(())?;
+    let mut state = FB_STATE.lock();
+    let mut fb_guard = FRAMEBUFFER.lock();
+    state.font = font;
+    let Some(fb) = fb_guard.as_mut() else { return Ok(()) };
+    let (cols, rows) = grid_size(fb, font);
+    state.screen.ensure_sized(cols, rows);
+    state.screen.view = 0;
+    clear_screen(fb, &mut state, DEFAULT_BG);
+    state.col = 0;
+    state.row = 0;
+    cursor_show(fb, &mut state, cols, rows);
+    fb.present_dirty();
+    Ok(())
+}
 static FB_CLEARED: AtomicBool = AtomicBool::new(false);
 
 /// Set by `FBIO_BLIT` (`sys_ioctl`) every time a raw-pixel client (e.g. the
@@ -131,27 +558,50 @@ pub fn mark_raw_dirty() {
 // User-process stdout *and* stderr (fds 1 and 2) are both bound to this
 // driver, so they're only ever visible on the framebuffer — invisible in
 // headless runs (`-display none`) short of a `screendump`. Mirror every byte
-// written here out over COM1 too (raw port I/O, same as
-// SerialConsole::write — no shared lock, so no deadlock risk against the
-// FB_STATE/FRAMEBUFFER locks already held by the caller), tagged with a
-// `[fb] ` prefix at the start of each line so it's greppable/distinguishable
-// from the kernel's own `serial_println!` diagnostics in the same log.
+// written here out over the serial console port too (COM1, unless moved to
+// COM2 — see serial.rs), tagged with a `[fb] ` prefix at the start
+// of each line so it's greppable/distinguishable from the kernel's own
+// `serial_println!` diagnostics in the same log.
+//
+// The whole buffer goes out under one `serial::with_console` call. This used
+// to be raw port I/O, and a user `write(1, ...)` interleaved byte-by-byte
+// with concurrent kernel logging (and with other processes' writes). Taking
+// the UART lock here is deadlock-free even from `try_echo`'s ISR path: it is
+// only ever held with interrupts masked, so the ISR can't have interrupted
+// a holder on this CPU. Lock order is FB_STATE/FRAMEBUFFER → UART, and
+// nothing takes them the other way round.
 static STDOUT_AT_LINE_START: AtomicBool = AtomicBool::new(true);
 
 fn mirror_to_serial(buf: &[u8]) {
-    use x86_64::instructions::port::Port;
-    let mut port = Port::<u8>::new(0x3F8);
-    for &byte in buf {
-        if STDOUT_AT_LINE_START.load(Ordering::Relaxed) {
-            for &b in b"[fb] " {
-                unsafe { port.write(b); }
+    crate::serial::with_console(|serial| {
+        for &byte in buf {
+            if STDOUT_AT_LINE_START.load(Ordering::Relaxed) {
+                for &b in b"[fb] " {
+                    serial.write_byte(b);
+                }
+                STDOUT_AT_LINE_START.store(false, Ordering::Relaxed);
+            }
+            serial.write_byte(byte);
+            if byte == b'\n' {
+                STDOUT_AT_LINE_START.store(true, Ordering::Relaxed);
             }
-            STDOUT_AT_LINE_START.store(false, Ordering::Relaxed);
-        }
-        unsafe { port.write(byte); }
-        if byte == b'\n' {
-            STDOUT_AT_LINE_START.store(true, Ordering::Relaxed);
         }
+    });
+}
+
+/// Tty echo (`tty::feed_input`, cooked mode): draw `buf` like a write to
+/// fd 1 would. Runs in the keyboard/serial ISR, which may have interrupted
+/// a holder of FB_STATE/FRAMEBUFFER (a raw blit, `text_dimensions`) — so
+/// both are only try-locked, and the echo falls back to the serial mirror
+/// alone rather than deadlocking on them.
+pub fn try_echo(buf: &[u8]) {
+    // Nothing can take either lock between this probe and the write below:
+    // we're in an ISR, interrupts are off.
+    let free = FB_STATE.try_lock().is_some() && FRAMEBUFFER.try_lock().is_some();
+    if free {
+        let _ = FramebufferConsole.write(buf);
+    } else {
+        mirror_to_serial(buf);
     }
 }
 
@@ -194,7 +644,10 @@ fn apply_sgr(params: &[u32], state: &mut FbState) {
             0 => {
                 state.fg = DEFAULT_FG;
                 state.bg = DEFAULT_BG;
+                state.reverse = false;
             }
+            7 => set_reverse(state, true),
+            27 => set_reverse(state, false),
             1..=29 => {}  // bold, italic, underline etc — ignore
             30..=37 => state.fg = ansi_color((params[i] - 30) as u8, false),
             38 => {
@@ -234,19 +687,36 @@ fn apply_sgr(params: &[u32], state: &mut FbState) {
     }
 }
 
+/// Reverse video on or off. Colors set while it's on land in the swapped
+/// slots (`ESC[7;31m` is red *background*) — close enough for what uses
+/// it: BusyBox `vi`'s and `top`'s highlighted lines.
+fn set_reverse(state: &mut FbState, on: bool) {
+    if state.reverse != on {
+        core::mem::swap(&mut state.fg, &mut state.bg);
+        state.reverse = on;
+    }
+}
+
 /// Overwrite `row`'s cells in `[start_col, end_col)` with blanks in the
 /// current background color. Shared by `ESC[J`'s partial-screen-clear
 /// cases (0 and 1), which need to blank a range of whole rows plus one
 /// partial row, the same way `ESC[K` already blanks a range within a
 /// single row.
-fn clear_row_from(fb: &mut Framebuffer, state: &FbState, row: usize, start_col: usize, end_col: usize) {
+fn clear_row_from(fb: &mut Framebuffer, state: &mut FbState, row: usize, start_col: usize, end_col: usize) {
+    let bg = state.bg;
     for c in start_col..end_col {
-        let px = MARGIN_X + c * CHAR_W;
-        let py = MARGIN_Y + row * CHAR_H;
-        fb.draw_char(px, py, b' ', DEFAULT_FG, state.bg, SCALE);
+        put_cell(fb, state, row, c, ' ', DEFAULT_FG, bg);
     }
 }
 
+/// DECRC (`ESC 8`) / `ESC[u`: back to the saved position, clamped in case
+/// the grid shrank since (a font change).
+fn restore_cursor(state: &mut FbState, cols: usize, rows: usize) {
+    let (row, col) = state.saved;
+    state.row = row.min(rows - 1);
+    state.col = col.min(cols - 1);
+}
+
 // ── CSI dispatcher ────────────────────────────────────────────────────────────
 
 fn dispatch_csi(
@@ -259,6 +729,15 @@ fn dispatch_csi(
 ) {
     let (params, nparams) = parse_params(param_buf);
 
+    // DEC private modes (`ESC[?...h`/`l`): only DECTCEM (25, cursor
+    // visibility) is implemented; the rest are ignored.
+    if param_buf.first() == Some(&b'?') {
+        if matches!(final_byte, b'h' | b'l') && params[0] == 25 {
+            state.cursor.visible = final_byte == b'h';
+        }
+        return;
+    }
+
     match final_byte {
         b'm' => {
             apply_sgr(&params[..nparams], state);
@@ -286,6 +765,18 @@ fn dispatch_csi(
             let n = if params[0] == 0 { 1 } else { params[0] as usize };
             state.col = state.col.saturating_sub(n);
         }
+        b'G' => {
+            // ESC[nG — cursor to column n of this row (1-based)
+            let c = if params[0] == 0 { 1 } else { params[0] as usize };
+            state.col = (c - 1).min(cols - 1);
+        }
+        b'd' => {
+            // ESC[nd — cursor to row n, same column (1-based)
+            let r = if params[0] == 0 { 1 } else { params[0] as usize };
+            state.row = (r - 1).min(rows - 1);
+        }
+        b's' => state.saved = (state.row, state.col),
+        b'u' => restore_cursor(state, cols, rows),
         b'J' => {
             // ESC[J with no explicit parameter means ESC[0J ("clear from
             // cursor to end of screen"), not "do nothing" — `parse_params`
@@ -300,21 +791,23 @@ fn dispatch_csi(
             // through everywhere else (visible as e.g. the boot banner's
             // "BusyBox..." still on screen with just its leading "B"
             // overwritten by vi's "~" column).
+            let (row, col) = (state.row, state.col);
             match params[0] {
                 0 => {
-                    clear_row_from(fb, state, state.row, state.col, cols);
-                    for r in (state.row + 1)..rows {
+                    clear_row_from(fb, state, row, col, cols);
+                    for r in (row + 1)..rows {
                         clear_row_from(fb, state, r, 0, cols);
                     }
                 }
                 1 => {
-                    for r in 0..state.row {
+                    for r in 0..row {
                         clear_row_from(fb, state, r, 0, cols);
                     }
-                    clear_row_from(fb, state, state.row, 0, state.col + 1);
+                    clear_row_from(fb, state, row, 0, col + 1);
                 }
                 2 | 3 => {
-                    fb.clear(state.bg);
+                    let bg = state.bg;
+                    clear_screen(fb, state, bg);
                     state.col = 0;
                     state.row = 0;
                 }
@@ -322,28 +815,11 @@ fn dispatch_csi(
             }
         }
         b'K' => {
+            let (row, col) = (state.row, state.col);
             match params[0] {
-                0 => {
-                    for c in state.col..cols {
-                        let px = MARGIN_X + c * CHAR_W;
-                        let py = MARGIN_Y + state.row * CHAR_H;
-                        fb.draw_char(px, py, b' ', DEFAULT_FG, state.bg, SCALE);
-                    }
-                }
-                1 => {
-                    for c in 0..=state.col {
-                        let px = MARGIN_X + c * CHAR_W;
-                        let py = MARGIN_Y + state.row * CHAR_H;
-                        fb.draw_char(px, py, b' ', DEFAULT_FG, state.bg, SCALE);
-                    }
-                }
-                2 => {
-                    for c in 0..cols {
-                        let px = MARGIN_X + c * CHAR_W;
-                        let py = MARGIN_Y + state.row * CHAR_H;
-                        fb.draw_char(px, py, b' ', DEFAULT_FG, state.bg, SCALE);
-                    }
-                }
+                0 => clear_row_from(fb, state, row, col, cols),
+                1 => clear_row_from(fb, state, row, 0, col + 1),
+                2 => clear_row_from(fb, state, row, 0, cols),
                 _ => {}
             }
         }
@@ -358,38 +834,49 @@ pub struct FramebufferConsole;
 impl FramebufferConsole {
     pub fn new() -> Self {
         if !FB_CLEARED.swap(true, Ordering::SeqCst) {
+            let mut state = FB_STATE.lock();
             if let Some(fb) = FRAMEBUFFER.lock().as_mut() {
-                fb.clear(DEFAULT_BG);
+                let (cols, rows) = grid_size(fb, state.font);
+                state.screen.ensure_sized(cols, rows);
+                clear_screen(fb, &mut state, DEFAULT_BG);
+                state.cursor.enabled = true;
+                cursor_show(fb, &mut state, cols, rows);
+                fb.present_dirty();
             }
         }
         Self
     }
-}
-
-impl FileHandle for FramebufferConsole {
-    fn read(&mut self, _buf: &mut [u8]) -> FileResult<usize> {
-        Err(FileError::NotSupported)
-    }
-
-    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
-        mirror_to_serial(buf);
 
+    /// Render `buf` on the screen — `write` minus the serial mirror, which
+    /// is what `/dev/tty` uses when its output is set to the framebuffer
+    /// alone (`dev_tty::set_outputs`). A no-op without a framebuffer.
+    pub fn draw(&self, buf: &[u8]) {
         let mut state = FB_STATE.lock();
         let mut fb_guard = FRAMEBUFFER.lock();
-        let Some(fb) = fb_guard.as_mut() else { return Ok(buf.len()); };
+        let Some(fb) = fb_guard.as_mut() else { return };
 
         if FB_RAW_DIRTY.swap(false, Ordering::SeqCst) {
-            fb.clear(DEFAULT_BG);
+            clear_screen(fb, &mut state, DEFAULT_BG);
             state.col = 0;
             state.row = 0;
             state.fg = DEFAULT_FG;
             state.bg = DEFAULT_BG;
+            state.reverse = false;
             state.ansi = AnsiState::Normal;
+            state.utf8.reset();
         }
 
-        let (w, h) = fb.dimensions();
-        let cols = (w.saturating_sub(MARGIN_X)) / CHAR_W;
-        let rows = (h.saturating_sub(MARGIN_Y)) / CHAR_H;
+        let font = state.font;
+        let (cols, rows) = grid_size(fb, font);
+
+        // Before anything can draw over it or scroll it.
+        cursor_hide(fb, &mut state);
+
+        // New output always lands on the live screen.
+        if state.screen.view != 0 {
+            state.screen.view = 0;
+            state.screen.render(fb, font);
+        }
 
         for &byte in buf {
             // Replace state.ansi with Normal, taking ownership of the old value.
@@ -397,6 +884,12 @@ impl FileHandle for FramebufferConsole {
             let ansi = core::mem::replace(&mut state.ansi, AnsiState::Normal);
             match ansi {
                 AnsiState::Normal => {
+                    // A sequence cut short: report it, then handle `byte`
+                    // on its own.
+                    if state.utf8.pending() && !is_continuation(byte) {
+                        state.utf8.reset();
+                        print_char(fb, &mut state, char::REPLACEMENT_CHARACTER, cols, rows);
+                    }
                     match byte {
                         0x1B => {
                             state.ansi = AnsiState::Escape;
@@ -405,7 +898,7 @@ impl FileHandle for FramebufferConsole {
                             state.col = 0;
                             state.row += 1;
                             if state.row >= rows {
-                                fb.scroll_up(CHAR_H);
+                                scroll_screen(fb, &mut state);
                                 state.row = rows - 1;
                             }
                         }
@@ -415,34 +908,30 @@ impl FileHandle for FramebufferConsole {
                         0x08 | 0x7f => {
                             if state.col > 0 {
                                 state.col -= 1;
-                                let px = MARGIN_X + state.col * CHAR_W;
-                                let py = MARGIN_Y + state.row * CHAR_H;
-                                fb.draw_char(px, py, b' ', state.fg, state.bg, SCALE);
+                                let (row, col, fg, bg) = (state.row, state.col, state.fg, state.bg);
+                                put_cell(fb, &mut state, row, col, ' ', fg, bg);
                             }
                         }
                         b if b >= 0x20 && b < 0x7f => {
-                            let px = MARGIN_X + state.col * CHAR_W;
-                            let py = MARGIN_Y + state.row * CHAR_H;
-                            fb.draw_char(px, py, b, state.fg, state.bg, SCALE);
-                            state.col += 1;
-                            if state.col >= cols {
-                                state.col = 0;
-                                state.row += 1;
-                                if state.row >= rows {
-                                    fb.scroll_up(CHAR_H);
-                                    state.row = rows - 1;
-                                }
+                            print_char(fb, &mut state, b as char, cols, rows);
+                        }
+                        b if b >= 0x80 => {
+                            // C1 controls (U+0080-U+009F) are dropped, like
+                            // the C0 ones below.
+                            if let Some(c) = state.utf8.push(b).filter(|c| !c.is_control()) {
+                                print_char(fb, &mut state, c, cols, rows);
                             }
                         }
                         _ => {}
                     }
                 }
-                AnsiState::Escape => {
-                    if byte == b'[' {
-                        state.ansi = AnsiState::Csi { buf: [0u8; 32], len: 0 };
-                    }
-                    // else: unrecognised escape — state.ansi stays Normal
-                }
+                AnsiState::Escape => match byte {
+                    b'[' => state.ansi = AnsiState::Csi { buf: [0u8; 32], len: 0 },
+                    b'7' => state.saved = (state.row, state.col),
+                    b'8' => restore_cursor(&mut state, cols, rows),
+                    // Unrecognised escape — state.ansi stays Normal.
+                    _ => {}
+                },
                 AnsiState::Csi { mut buf, mut len } => {
                     if byte >= 0x40 && byte <= 0x7E {
                         // Final byte — dispatch and return to Normal
@@ -461,6 +950,26 @@ impl FileHandle for FramebufferConsole {
             }
         }
 
+        // Solid while output keeps coming; blinking resumes a full period
+        // after the last write.
+        state.cursor.phase_on = true;
+        NEXT_BLINK_MS.store(crate::cpu::tsc::uptime_ms() + CURSOR_BLINK_MS, Ordering::Relaxed);
+        cursor_show(fb, &mut state, cols, rows);
+
+        // One flush per write() — however many cells and scrolls `buf`
+        // caused — instead of one per glyph.
+        fb.present_dirty();
+    }
+}
+
+impl FileHandle for FramebufferConsole {
+    fn read(&mut self, _buf: &mut [u8]) -> FileResult<usize> {
+        Err(FileError::NotSupported)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        mirror_to_serial(buf);
+        self.draw(buf);
         Ok(buf.len())
     }
 
@@ -485,14 +994,19 @@ impl FileHandle for FramebufferConsole {
 /// set up (headless/serial-only boot) — same default the ioctl used to
 /// hardcode unconditionally.
 pub fn text_dimensions() -> (usize, usize) {
+    let font = FB_STATE.lock().font;
     let fb_guard = FRAMEBUFFER.lock();
     let Some(fb) = fb_guard.as_ref() else { return (80, 25); };
-    let (w, h) = fb.dimensions();
-    let cols = (w.saturating_sub(MARGIN_X)) / CHAR_W;
-    let rows = (h.saturating_sub(MARGIN_Y)) / CHAR_H;
+    let (cols, rows) = grid_size(fb, font);
     (cols.max(1), rows.max(1))
 }
 
+/// Text grid that fits `fb` at `font`'s cell size inside the margins.
+fn grid_size(fb: &Framebuffer, font: &Font) -> (usize, usize) {
+    let (w, h) = fb.dimensions();
+    ((w.saturating_sub(MARGIN_X)) / char_w(font), (h.saturating_sub(MARGIN_Y)) / char_h(font))
+}
+
 pub fn open() -> Box<dyn FileHandle> {
     Box::new(FramebufferConsole::new())
 }
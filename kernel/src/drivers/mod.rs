@@ -12,8 +12,13 @@ mod evdev;
 pub mod dev_dsp;
 pub mod dev_input_event;
 pub mod dev_kbd;
+pub mod dev_kmsg;
+pub mod dev_mouse;
 pub mod dev_mouse_event;
 pub mod dev_null;
+pub mod dev_random;
+pub mod dev_sda;
+pub mod dev_tty;
 pub mod dev_zero;
 pub mod serial_console;
 pub mod framebuffer_console;
@@ -35,12 +40,17 @@ static DEVICES: &[DeviceEntry] = &[
     DeviceEntry { path: "/dev/zero",    open: dev_zero::open },
     DeviceEntry { path: "/dev/console", open: serial_console::open },
     DeviceEntry { path: "/dev/fb",      open: framebuffer_console::open },
+    DeviceEntry { path: "/dev/tty",     open: dev_tty::open }, // fb and/or serial out, keyboard in
     // Nested under /dev/input/, same layout real Linux uses for evdev
     // devices — see fs/devfs.rs's InputDirInode for the one-level
     // subdirectory support this needs (devfs is otherwise flat).
     DeviceEntry { path: "/dev/input/event0", open: dev_input_event::open }, // keyboard
     DeviceEntry { path: "/dev/input/event1", open: dev_mouse_event::open }, // mouse
+    DeviceEntry { path: "/dev/mouse", open: dev_mouse::open }, // same mouse, raw 3-byte PS/2 packets
     DeviceEntry { path: "/dev/dsp", open: dev_dsp::open }, // AC97 PCM output, see ac97.rs
+    DeviceEntry { path: "/dev/kmsg", open: dev_kmsg::open }, // kernel log ring, see log.rs
+    DeviceEntry { path: "/dev/sda", open: dev_sda::open }, // raw ATA disk (the ext2 one), see block/ata.rs
+    DeviceEntry { path: "/dev/random", open: dev_random::open }, // TSC-seeded PRNG, not crypto
 ];
 
 /// Open a device by path.  Returns `None` if no driver matches.
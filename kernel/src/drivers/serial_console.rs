@@ -1,10 +1,10 @@
 // kernel/src/drivers/serial_console.rs
 //
-// Serial console (COM1, 0x3F8).
+// Serial console (the console port — COM1, or COM2 if selected; see serial.rs).
 
 use alloc::boxed::Box;
 use crate::fs::types::Stat;
-use crate::process::file::{FileHandle, FileResult};
+use crate::process::file::{FileHandle, FileResult, Readiness};
 
 pub struct SerialConsole;
 
@@ -32,16 +32,13 @@ impl FileHandle for SerialConsole {
         Ok(n)
     }
 
+    /// Goes to the console port (`serial::console_port`, COM1 unless moved
+    /// to COM2). One `write()` is one run on the wire: the whole buffer
+    /// goes out under that UART's lock, so a concurrent `serial_println!`
+    /// or another process's write lands before or after it, never in the
+    /// middle.
     fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
-        use x86_64::instructions::port::Port;
-
-        unsafe {
-            let mut port = Port::<u8>::new(0x3F8);
-            for &byte in buf {
-                port.write(byte);
-            }
-        }
-
+        crate::serial::console_write(buf);
         Ok(buf.len())
     }
 
@@ -49,6 +46,13 @@ impl FileHandle for SerialConsole {
         Some(Stat::chardev(0))
     }
 
+    /// Readable when `KEYBOARD_BUFFER` has a byte — what both this
+    /// handle's `read` and `sys_read`'s fd-0 path consume. This is what
+    /// ash's line editor polls stdin with.
+    fn poll(&self) -> Readiness {
+        Readiness { readable: crate::keyboard::read_key_peek(), ..Readiness::ALWAYS }
+    }
+
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
         Some(Box::new(SerialConsole))
     }
@@ -0,0 +1,269 @@
+// kernel/src/font/dejavu8x16.rs
+//
+// GENERATED by scripts/gen-font8x16.py from DejaVu Sans Mono — do not
+// edit by hand. 8x16 cells for U+0000-U+00FF, one byte per row, bit 0 =
+// leftmost column (font8x8's layout). DejaVu is derived from Bitstream
+// Vera, whose license (DejaVu's LICENSE file) permits redistributing
+// modified versions, including rasterized bitmaps like these, with
+// its notice: Copyright (c) 2003 by Bitstream, Inc. All Rights
+// Reserved. Bitstream Vera is a trademark of Bitstream, Inc. DejaVu
+// changes are in the public domain.
+
+pub const DEJAVU_8X16: [[u8; 16]; 256] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0000
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0001
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0002
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0003
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0004
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0005
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0006
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0007
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0008
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0009
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000A
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000B
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000C
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000D
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000E
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+000F
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0010
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0011
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0012
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0013
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0014
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0015
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0016
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0017
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0018
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0019
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001A
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001B
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001C
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001D
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001E
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+001F
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0020
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // !
+    [0x00, 0x00, 0x34, 0x34, 0x34, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // "
+    [0x00, 0x00, 0x48, 0x48, 0x68, 0xFE, 0x24, 0x24, 0x7F, 0x16, 0x12, 0x12, 0x00, 0x00, 0x00, 0x00], // #
+    [0x00, 0x00, 0x10, 0x10, 0x7C, 0x16, 0x16, 0x3C, 0x70, 0xD0, 0x50, 0x7C, 0x10, 0x10, 0x00, 0x00], // $
+    [0x00, 0x00, 0x00, 0x07, 0x09, 0x09, 0x66, 0x1C, 0x73, 0x48, 0x48, 0x70, 0x00, 0x00, 0x00, 0x00], // %
+    [0x00, 0x00, 0x38, 0x04, 0x04, 0x0C, 0x0C, 0x92, 0xB2, 0xE2, 0xE6, 0xBC, 0x00, 0x00, 0x00, 0x00], // &
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '
+    [0x00, 0x00, 0x10, 0x10, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0x10, 0x00, 0x00, 0x00], // (
+    [0x00, 0x00, 0x08, 0x08, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x18, 0x08, 0x00, 0x00, 0x00], // )
+    [0x00, 0x00, 0x08, 0x2A, 0x1C, 0x1C, 0x2A, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // *
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x08, 0x7F, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x08, 0x08, 0x00, 0x00], // ,
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // .
+    [0x00, 0x00, 0x40, 0x60, 0x20, 0x30, 0x10, 0x18, 0x08, 0x0C, 0x04, 0x04, 0x02, 0x00, 0x00, 0x00], // /
+    [0x00, 0x00, 0x3C, 0x64, 0x46, 0x42, 0x5A, 0x5A, 0x42, 0x46, 0x64, 0x3C, 0x00, 0x00, 0x00, 0x00], // 0
+    [0x00, 0x00, 0x18, 0x1E, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7C, 0x00, 0x00, 0x00, 0x00], // 1
+    [0x00, 0x00, 0x3C, 0x66, 0x40, 0x40, 0x60, 0x30, 0x18, 0x08, 0x04, 0x7E, 0x00, 0x00, 0x00, 0x00], // 2
+    [0x00, 0x00, 0x3C, 0x60, 0x40, 0x60, 0x38, 0x60, 0x40, 0x40, 0x62, 0x3E, 0x00, 0x00, 0x00, 0x00], // 3
+    [0x00, 0x00, 0x00, 0x30, 0x38, 0x28, 0x24, 0x26, 0x22, 0x7E, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00], // 4
+    [0x00, 0x00, 0x3E, 0x02, 0x02, 0x1E, 0x32, 0x60, 0x60, 0x60, 0x32, 0x1E, 0x00, 0x00, 0x00, 0x00], // 5
+    [0x00, 0x00, 0x78, 0x04, 0x06, 0x3A, 0x7E, 0x46, 0x46, 0x46, 0x64, 0x3C, 0x00, 0x00, 0x00, 0x00], // 6
+    [0x00, 0x00, 0x7E, 0x60, 0x20, 0x30, 0x30, 0x10, 0x18, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00], // 7
+    [0x00, 0x00, 0x3C, 0x66, 0x66, 0x66, 0x3C, 0x66, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // 8
+    [0x00, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x66, 0x7C, 0x48, 0x40, 0x20, 0x1C, 0x00, 0x00, 0x00, 0x00], // 9
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // :
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x18, 0x18, 0x08, 0x08, 0x00, 0x00], // ;
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x70, 0x1C, 0x03, 0x0E, 0x78, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00], // <
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x00, 0x7E, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // =
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x38, 0xC0, 0x70, 0x1E, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00], // >
+    [0x00, 0x00, 0x3E, 0x20, 0x20, 0x30, 0x18, 0x08, 0x08, 0x00, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // ?
+    [0x00, 0x00, 0x00, 0x78, 0xC4, 0x82, 0xF2, 0x8B, 0x8B, 0x8A, 0xF2, 0x02, 0x04, 0x78, 0x00, 0x00], // @
+    [0x00, 0x00, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // A
+    [0x00, 0x00, 0x3E, 0x66, 0x62, 0x62, 0x3E, 0x62, 0x42, 0x42, 0x66, 0x3E, 0x00, 0x00, 0x00, 0x00], // B
+    [0x00, 0x00, 0x78, 0x4C, 0x06, 0x02, 0x02, 0x02, 0x02, 0x06, 0x4C, 0x78, 0x00, 0x00, 0x00, 0x00], // C
+    [0x00, 0x00, 0x00, 0x3E, 0x62, 0x42, 0x42, 0x42, 0x42, 0x42, 0x62, 0x3E, 0x00, 0x00, 0x00, 0x00], // D
+    [0x00, 0x00, 0x7E, 0x02, 0x02, 0x02, 0x7E, 0x02, 0x02, 0x02, 0x3E, 0x3E, 0x00, 0x00, 0x00, 0x00], // E
+    [0x00, 0x00, 0x7C, 0x04, 0x04, 0x04, 0x7C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // F
+    [0x00, 0x00, 0x78, 0x4C, 0x06, 0x02, 0x02, 0x62, 0x42, 0x46, 0x44, 0x78, 0x00, 0x00, 0x00, 0x00], // G
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x7E, 0x42, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00, 0x00, 0x00], // H
+    [0x00, 0x00, 0x3E, 0x3E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x3E, 0x00, 0x00, 0x00, 0x00], // I
+    [0x00, 0x00, 0x3C, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x33, 0x1E, 0x00, 0x00, 0x00, 0x00], // J
+    [0x00, 0x00, 0x42, 0x62, 0x32, 0x1A, 0x0E, 0x1E, 0x32, 0x22, 0x62, 0xC2, 0x00, 0x00, 0x00, 0x00], // K
+    [0x00, 0x00, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x7E, 0x00, 0x00, 0x00, 0x00], // L
+    [0x00, 0x00, 0x63, 0x67, 0x77, 0x57, 0x5F, 0x4B, 0x43, 0x43, 0x43, 0x40, 0x00, 0x00, 0x00, 0x00], // M
+    [0x00, 0x00, 0x46, 0x46, 0x4E, 0x4A, 0x4A, 0x52, 0x72, 0x72, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // N
+    [0x00, 0x00, 0x3C, 0x66, 0x62, 0x42, 0x42, 0x42, 0x42, 0x62, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // O
+    [0x00, 0x00, 0x3E, 0x62, 0x42, 0x42, 0x76, 0x3E, 0x02, 0x02, 0x02, 0x02, 0x00, 0x00, 0x00, 0x00], // P
+    [0x00, 0x00, 0x3C, 0x66, 0x62, 0x42, 0x42, 0x42, 0x42, 0x62, 0x66, 0x3C, 0x20, 0x00, 0x00, 0x00], // Q
+    [0x00, 0x00, 0x00, 0x3E, 0x62, 0x42, 0x62, 0x3E, 0x36, 0x62, 0x42, 0xC2, 0x00, 0x00, 0x00, 0x00], // R
+    [0x00, 0x00, 0x3C, 0x06, 0x02, 0x06, 0x1C, 0x70, 0x40, 0x40, 0x62, 0x3E, 0x00, 0x00, 0x00, 0x00], // S
+    [0x00, 0x00, 0x7F, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // T
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // U
+    [0x00, 0x00, 0x00, 0x42, 0x42, 0x66, 0x66, 0x24, 0x24, 0x3C, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // V
+    [0x00, 0x00, 0x01, 0xC1, 0x41, 0x49, 0x59, 0x5F, 0x57, 0x76, 0x76, 0x26, 0x00, 0x00, 0x00, 0x00], // W
+    [0x00, 0x00, 0x42, 0x66, 0x24, 0x18, 0x18, 0x1C, 0x34, 0x66, 0x42, 0x40, 0x00, 0x00, 0x00, 0x00], // X
+    [0x00, 0x00, 0x43, 0x62, 0x26, 0x14, 0x1C, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // Y
+    [0x00, 0x00, 0x7E, 0x60, 0x20, 0x30, 0x10, 0x08, 0x0C, 0x04, 0x06, 0x7E, 0x00, 0x00, 0x00, 0x00], // Z
+    [0x00, 0x00, 0x38, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x38, 0x00, 0x00], // [
+    [0x00, 0x00, 0x02, 0x04, 0x04, 0x0C, 0x08, 0x18, 0x10, 0x30, 0x20, 0x60, 0x40, 0x00, 0x00, 0x00], // \
+    [0x00, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1C, 0x00, 0x00], // ]
+    [0x00, 0x00, 0x08, 0x1C, 0x24, 0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x00], // _
+    [0x00, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x64, 0x40, 0x7C, 0x46, 0x42, 0x66, 0x5C, 0x00, 0x00, 0x00, 0x00], // a
+    [0x00, 0x00, 0x02, 0x02, 0x02, 0x3E, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3E, 0x00, 0x00, 0x00, 0x00], // b
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x78, 0x0C, 0x04, 0x04, 0x04, 0x0C, 0x78, 0x00, 0x00, 0x00, 0x00], // c
+    [0x00, 0x00, 0x40, 0x40, 0x40, 0x7C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x7C, 0x00, 0x00, 0x00, 0x00], // d
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x66, 0x42, 0x7E, 0x02, 0x06, 0x7C, 0x10, 0x00, 0x00, 0x00], // e
+    [0x00, 0x00, 0x70, 0x18, 0x08, 0x7E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // f
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x7C, 0x40, 0x60, 0x3C, 0x00], // g
+    [0x00, 0x00, 0x02, 0x02, 0x02, 0x3E, 0x26, 0x62, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // h
+    [0x00, 0x00, 0x10, 0x10, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0xFE, 0x00, 0x00, 0x00, 0x00], // i
+    [0x00, 0x00, 0x10, 0x10, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x18, 0x0E, 0x00], // j
+    [0x00, 0x00, 0x04, 0x04, 0x04, 0x64, 0x34, 0x1C, 0x3C, 0x24, 0x44, 0xC4, 0x00, 0x00, 0x00, 0x00], // k
+    [0x00, 0x00, 0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x78, 0x00, 0x00, 0x00, 0x00], // l
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x7E, 0xD2, 0x92, 0x92, 0x92, 0x92, 0x92, 0x00, 0x00, 0x00, 0x00], // m
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x26, 0x62, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // n
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // o
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3E, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3E, 0x02, 0x02, 0x02, 0x00], // p
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x7C, 0x40, 0x40, 0x40, 0x00], // q
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // r
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3E, 0x02, 0x06, 0x3C, 0x20, 0x20, 0x3E, 0x00, 0x00, 0x00, 0x00], // s
+    [0x00, 0x00, 0x00, 0x08, 0x08, 0x7E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x78, 0x00, 0x00, 0x00, 0x00], // t
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x64, 0x7C, 0x00, 0x00, 0x00, 0x00], // u
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x66, 0x24, 0x3C, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // v
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x41, 0x49, 0x5B, 0x76, 0x36, 0x26, 0x00, 0x00, 0x00, 0x00], // w
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x62, 0x24, 0x1C, 0x18, 0x1C, 0x24, 0x62, 0x00, 0x00, 0x00, 0x00], // x
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x62, 0x26, 0x24, 0x3C, 0x18, 0x18, 0x18, 0x08, 0x06, 0x00], // y
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x3E, 0x20, 0x10, 0x08, 0x0C, 0x06, 0x3E, 0x00, 0x00, 0x00, 0x00], // z
+    [0x00, 0x00, 0x70, 0x10, 0x10, 0x10, 0x10, 0x18, 0x0C, 0x10, 0x10, 0x10, 0x10, 0x70, 0x00, 0x00], // {
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00], // |
+    [0x00, 0x00, 0x0E, 0x08, 0x08, 0x08, 0x08, 0x18, 0x30, 0x08, 0x08, 0x08, 0x08, 0x0E, 0x00, 0x00], // }
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4F, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ~
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+007F
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0080
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0081
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0082
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0083
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0084
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0085
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0086
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0087
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0088
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0089
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008A
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008B
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008C
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008D
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008E
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+008F
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0090
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0091
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0092
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0093
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0094
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0095
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0096
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0097
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0098
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0099
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009A
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009B
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009C
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009D
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009E
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+009F
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00A0
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00], // ¡
+    [0x00, 0x00, 0x00, 0x10, 0x10, 0x7C, 0x14, 0x16, 0x16, 0x16, 0x16, 0x7C, 0x10, 0x10, 0x00, 0x00], // ¢
+    [0x00, 0x00, 0x70, 0x18, 0x08, 0x08, 0x08, 0x7E, 0x08, 0x08, 0x08, 0xFE, 0x00, 0x00, 0x00, 0x00], // £
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x3C, 0x24, 0x24, 0x3C, 0x42, 0x00, 0x00, 0x00, 0x00, 0x00], // ¤
+    [0x00, 0x00, 0x00, 0x42, 0x66, 0x24, 0x7E, 0x18, 0x7E, 0x18, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // ¥
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00], // ¦
+    [0x00, 0x00, 0x38, 0x04, 0x0C, 0x1C, 0x64, 0x44, 0x4C, 0x30, 0x60, 0x60, 0x3C, 0x00, 0x00, 0x00], // §
+    [0x00, 0x00, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ¨
+    [0x00, 0x00, 0x00, 0x3C, 0x5A, 0x85, 0x85, 0x85, 0x85, 0x42, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00], // ©
+    [0x00, 0x00, 0x3C, 0x20, 0x3C, 0x24, 0x3C, 0x00, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ª
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x48, 0x24, 0x12, 0x24, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00], // «
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x40, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ¬
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ­
+    [0x00, 0x00, 0x00, 0x3C, 0x42, 0xBD, 0xAD, 0x9D, 0xA5, 0x42, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00], // ®
+    [0x00, 0x00, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ¯
+    [0x00, 0x00, 0x18, 0x24, 0x24, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // °
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x18, 0x7F, 0x08, 0x08, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x00], // ±
+    [0x00, 0x00, 0x18, 0x20, 0x20, 0x10, 0x08, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ²
+    [0x00, 0x00, 0x38, 0x20, 0x38, 0x20, 0x20, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ³
+    [0x00, 0x20, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ´
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x62, 0x62, 0x62, 0x62, 0x62, 0x62, 0x7E, 0x02, 0x02, 0x02, 0x00], // µ
+    [0x00, 0x00, 0x7C, 0x5E, 0x5E, 0x5E, 0x5C, 0x50, 0x50, 0x50, 0x50, 0x50, 0x50, 0x00, 0x00, 0x00], // ¶
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ·
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x1C, 0x00], // ¸
+    [0x00, 0x00, 0x18, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ¹
+    [0x00, 0x00, 0x1C, 0x22, 0x22, 0x22, 0x1C, 0x00, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // º
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12, 0x24, 0x48, 0x24, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00], // »
+    [0x00, 0x04, 0x06, 0x04, 0x04, 0x04, 0x0E, 0x78, 0x07, 0x20, 0x30, 0x28, 0x78, 0x20, 0x00, 0x00], // ¼
+    [0x00, 0x06, 0x04, 0x04, 0x04, 0x04, 0x4E, 0x3C, 0x33, 0x40, 0x40, 0x20, 0x10, 0x70, 0x00, 0x00], // ½
+    [0x00, 0x06, 0x08, 0x0C, 0x0C, 0x08, 0x0F, 0x38, 0x23, 0x30, 0x28, 0x28, 0x78, 0x20, 0x00, 0x00], // ¾
+    [0x00, 0x00, 0x00, 0x00, 0x10, 0x10, 0x00, 0x10, 0x10, 0x18, 0x0C, 0x04, 0x04, 0x7C, 0x00, 0x00], // ¿
+    [0x18, 0x00, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // À
+    [0x08, 0x00, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // Á
+    [0x34, 0x00, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // Â
+    [0x34, 0x00, 0x18, 0x18, 0x3C, 0x2C, 0x24, 0x64, 0x7E, 0x42, 0xC2, 0x00, 0x00, 0x00, 0x00, 0x00], // Ã
+    [0x24, 0x00, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // Ä
+    [0x34, 0x24, 0x18, 0x18, 0x3C, 0x34, 0x24, 0x26, 0x7E, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00], // Å
+    [0x00, 0x00, 0x7C, 0x14, 0x14, 0x16, 0x72, 0x12, 0x1E, 0x13, 0x71, 0x71, 0x00, 0x00, 0x00, 0x00], // Æ
+    [0x00, 0x00, 0x78, 0x44, 0x06, 0x02, 0x02, 0x02, 0x06, 0x06, 0x4C, 0x78, 0x20, 0x30, 0x00, 0x00], // Ç
+    [0x08, 0x00, 0x7E, 0x02, 0x02, 0x02, 0x7E, 0x02, 0x02, 0x02, 0x3E, 0x3E, 0x00, 0x00, 0x00, 0x00], // È
+    [0x08, 0x00, 0x7E, 0x02, 0x02, 0x02, 0x7E, 0x02, 0x02, 0x02, 0x3E, 0x3E, 0x00, 0x00, 0x00, 0x00], // É
+    [0x14, 0x00, 0x7E, 0x02, 0x02, 0x02, 0x7E, 0x02, 0x02, 0x02, 0x3E, 0x3E, 0x00, 0x00, 0x00, 0x00], // Ê
+    [0x34, 0x00, 0x7E, 0x06, 0x02, 0x02, 0x7E, 0x06, 0x02, 0x02, 0x06, 0x7E, 0x00, 0x00, 0x00, 0x00], // Ë
+    [0x08, 0x00, 0x3E, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0x3E, 0x00, 0x00, 0x00, 0x00], // Ì
+    [0x10, 0x00, 0x7C, 0x18, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x18, 0x7C, 0x00, 0x00, 0x00, 0x00], // Í
+    [0x14, 0x00, 0x3E, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0x3E, 0x00, 0x00, 0x00, 0x00], // Î
+    [0x34, 0x00, 0x3E, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0x3E, 0x00, 0x00, 0x00, 0x00], // Ï
+    [0x00, 0x00, 0x3E, 0x62, 0x42, 0x42, 0x4F, 0x42, 0x42, 0x62, 0x3E, 0x1E, 0x00, 0x00, 0x00, 0x00], // Ð
+    [0x34, 0x00, 0x46, 0x46, 0x4E, 0x4A, 0x4A, 0x52, 0x72, 0x72, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // Ñ
+    [0x08, 0x00, 0x3C, 0x66, 0x62, 0x42, 0x42, 0x42, 0x42, 0x62, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ò
+    [0x10, 0x00, 0x3C, 0x66, 0x46, 0x42, 0x42, 0x42, 0x42, 0x46, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ó
+    [0x1C, 0x00, 0x3C, 0x66, 0x62, 0x42, 0x42, 0x42, 0x42, 0x62, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ô
+    [0x3C, 0x00, 0x3C, 0x66, 0x46, 0x42, 0x42, 0x42, 0x42, 0x46, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Õ
+    [0x34, 0x00, 0x3C, 0x66, 0x62, 0x42, 0x42, 0x42, 0x42, 0x62, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ö
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x22, 0x36, 0x1C, 0x1C, 0x36, 0x22, 0x00, 0x00, 0x00, 0x00, 0x00], // ×
+    [0x00, 0x00, 0xBC, 0x66, 0x66, 0x62, 0x52, 0x4A, 0x4E, 0x46, 0x66, 0x3F, 0x00, 0x00, 0x00, 0x00], // Ø
+    [0x08, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ù
+    [0x10, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ú
+    [0x18, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Û
+    [0x3C, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // Ü
+    [0x08, 0x00, 0x43, 0x62, 0x26, 0x14, 0x1C, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // Ý
+    [0x00, 0x00, 0x02, 0x06, 0x3E, 0x62, 0x42, 0x62, 0x7E, 0x06, 0x02, 0x02, 0x00, 0x00, 0x00, 0x00], // Þ
+    [0x00, 0x00, 0x1C, 0x26, 0x32, 0x1A, 0x0A, 0x1A, 0x62, 0x42, 0x42, 0x3E, 0x00, 0x00, 0x00, 0x00], // ß
+    [0x00, 0x00, 0x08, 0x10, 0x00, 0x3C, 0x60, 0x40, 0x7C, 0x46, 0x62, 0x7E, 0x18, 0x00, 0x00, 0x00], // à
+    [0x00, 0x20, 0x10, 0x00, 0x3C, 0x64, 0x40, 0x7C, 0x46, 0x42, 0x66, 0x5C, 0x00, 0x00, 0x00, 0x00], // á
+    [0x00, 0x18, 0x28, 0x00, 0x3C, 0x64, 0x40, 0x7C, 0x46, 0x42, 0x66, 0x5C, 0x00, 0x00, 0x00, 0x00], // â
+    [0x00, 0x00, 0x2C, 0x34, 0x00, 0x3C, 0x60, 0x60, 0x7C, 0x46, 0x62, 0x7E, 0x0C, 0x00, 0x00, 0x00], // ã
+    [0x00, 0x00, 0x2C, 0x00, 0x3C, 0x64, 0x40, 0x7C, 0x46, 0x42, 0x66, 0x5C, 0x00, 0x00, 0x00, 0x00], // ä
+    [0x00, 0x38, 0x24, 0x38, 0x00, 0x3C, 0x60, 0x40, 0x7C, 0x46, 0x62, 0x7E, 0x18, 0x00, 0x00, 0x00], // å
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0xFE, 0x90, 0x90, 0xFE, 0x12, 0x12, 0xFE, 0x44, 0x00, 0x00, 0x00], // æ
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x78, 0x0C, 0x04, 0x04, 0x04, 0x0C, 0x78, 0x20, 0x20, 0x30, 0x00], // ç
+    [0x00, 0x04, 0x08, 0x10, 0x00, 0x3C, 0x46, 0x42, 0x7E, 0x02, 0x06, 0x7C, 0x10, 0x00, 0x00, 0x00], // è
+    [0x00, 0x20, 0x30, 0x10, 0x00, 0x3C, 0x46, 0x42, 0x7E, 0x02, 0x06, 0x7C, 0x10, 0x00, 0x00, 0x00], // é
+    [0x00, 0x00, 0x18, 0x24, 0x00, 0x3C, 0x66, 0x42, 0x7E, 0x02, 0x06, 0x7C, 0x10, 0x00, 0x00, 0x00], // ê
+    [0x00, 0x00, 0x24, 0x20, 0x00, 0x3C, 0x66, 0x42, 0x7E, 0x02, 0x06, 0x7C, 0x10, 0x00, 0x00, 0x00], // ë
+    [0x00, 0x0C, 0x08, 0x10, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0xFE, 0x00, 0x00, 0x00, 0x00], // ì
+    [0x00, 0x20, 0x30, 0x10, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0xFE, 0x00, 0x00, 0x00, 0x00], // í
+    [0x00, 0x10, 0x38, 0x20, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0xFE, 0x00, 0x00, 0x00, 0x00], // î
+    [0x00, 0x00, 0x6C, 0x00, 0x00, 0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0xFE, 0x00, 0x00, 0x00, 0x00], // ï
+    [0x00, 0x00, 0x2C, 0x1C, 0x30, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // ð
+    [0x00, 0x00, 0x2C, 0x12, 0x00, 0x3A, 0x26, 0x62, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // ñ
+    [0x00, 0x04, 0x08, 0x18, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // ò
+    [0x00, 0x20, 0x10, 0x18, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // ó
+    [0x00, 0x00, 0x18, 0x24, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // ô
+    [0x00, 0x00, 0x2C, 0x10, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x62, 0x3C, 0x08, 0x00, 0x00, 0x00], // õ
+    [0x00, 0x00, 0x3C, 0x00, 0x00, 0x3C, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3C, 0x00, 0x00, 0x00, 0x00], // ö
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x00, 0x7F, 0x00, 0x18, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00], // ÷
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x66, 0x72, 0x5A, 0x4E, 0x66, 0x3E, 0x00, 0x00, 0x00, 0x00], // ø
+    [0x00, 0x00, 0x08, 0x10, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x64, 0x7C, 0x00, 0x00, 0x00, 0x00], // ù
+    [0x00, 0x20, 0x30, 0x10, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x64, 0x7C, 0x00, 0x00, 0x00, 0x00], // ú
+    [0x00, 0x10, 0x28, 0x00, 0x40, 0x44, 0x44, 0x44, 0x44, 0x44, 0x64, 0x5C, 0x00, 0x00, 0x00, 0x00], // û
+    [0x00, 0x00, 0x2C, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x64, 0x7C, 0x00, 0x00, 0x00, 0x00], // ü
+    [0x00, 0x20, 0x10, 0x08, 0x00, 0x42, 0x62, 0x26, 0x24, 0x34, 0x18, 0x18, 0x08, 0x0C, 0x06, 0x00], // ý
+    [0x00, 0x00, 0x02, 0x02, 0x02, 0x3E, 0x66, 0x42, 0x42, 0x42, 0x66, 0x3E, 0x02, 0x02, 0x02, 0x00], // þ
+    [0x00, 0x00, 0x24, 0x04, 0x00, 0x42, 0x62, 0x26, 0x24, 0x3C, 0x18, 0x18, 0x18, 0x08, 0x06, 0x00], // ÿ
+];
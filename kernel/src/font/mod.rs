@@ -0,0 +1,82 @@
+// kernel/src/font/mod.rs
+//
+// Bitmap fonts for `Framebuffer::draw_char`/`draw_text`. A `Font` is just
+// a glyph size plus a lookup from code point to row bitmap — one byte per pixel
+// row, bit 0 = leftmost column (font8x8's layout, which every font here
+// shares, so glyphs are at most 8 px wide). Integer upscaling stays the
+// draw calls' `scale` parameter; a font only picks the base cell.
+//
+// Two are built in:
+// - `LEGACY_8X8`: font8x8's `BASIC_LEGACY` + `LATIN_LEGACY`, the original
+//   (and still the default) font — also what the boot banner and the
+//   panic screen use.
+// - `DEJAVU_8X16`: DejaVu Sans Mono rasterized to 8x16 by
+//   `scripts/gen-font8x16.py` (`dejavu8x16.rs`, generated). Twice the
+//   vertical resolution reads a lot better at `scale == 1` than 8x8 does,
+//   and better than 8x8 at `scale == 2`, whose pixel-doubled glyphs look
+//   blocky.
+//
+// Both cover printable ASCII and Latin-1 (U+00A0-U+00FF) — enough for the
+// accented Spanish text this project prints. Anything else (C0/C1
+// controls, `→`, emoji, ...) draws as the font's `missing` box, one cell
+// wide, so a string's layout never depends on which of its chars the font
+// happens to have.
+//
+// The console's current font is switched at runtime with the `/dev/fb`
+// `FBIO_SETFONT` ioctl (see `framebuffer_console::set_font`) by its index
+// in `FONTS`.
+
+mod dejavu8x16;
+
+use font8x8::legacy::{BASIC_LEGACY, LATIN_LEGACY};
+
+pub struct Font {
+    pub width: usize,
+    pub height: usize,
+    /// `None` for anything without a glyph — only ever asked about
+    /// printable code points (see `printable`).
+    glyph: fn(char) -> Option<&'static [u8]>,
+    /// Hollow box drawn in place of a missing glyph.
+    missing: &'static [u8],
+}
+
+impl Font {
+    /// Row bitmap for `c` (`height` rows): its glyph, or the `missing` box
+    /// if the font doesn't have one (or `c` is a control character).
+    pub fn glyph(&self, c: char) -> &'static [u8] {
+        if !printable(c) {
+            return self.missing;
+        }
+        (self.glyph)(c).unwrap_or(self.missing)
+    }
+}
+
+/// Not a C0/C1 control or DEL — the code points a glyph table's slot is
+/// actually meaningful for.
+fn printable(c: char) -> bool {
+    !c.is_control()
+}
+
+pub static LEGACY_8X8: Font = Font {
+    width: 8,
+    height: 8,
+    glyph: |c| match c as u32 {
+        0..=0x7F => Some(&BASIC_LEGACY[c as usize][..]),
+        0xA0..=0xFF => Some(&LATIN_LEGACY[c as usize - 0xA0][..]),
+        _ => None,
+    },
+    missing: &[0x00, 0x7E, 0x42, 0x42, 0x42, 0x42, 0x7E, 0x00],
+};
+
+pub static DEJAVU_8X16: Font = Font {
+    width: 8,
+    height: 16,
+    glyph: |c| dejavu8x16::DEJAVU_8X16.get(c as usize).map(|g| &g[..]),
+    missing: &[
+        0x00, 0x00, 0x00, 0x7E, 0x42, 0x42, 0x42, 0x42,
+        0x42, 0x42, 0x42, 0x42, 0x7E, 0x00, 0x00, 0x00,
+    ],
+};
+
+/// Every built-in font, indexed by `FBIO_SETFONT`'s argument.
+pub static FONTS: [&Font; 2] = [&LEGACY_8X8, &DEJAVU_8X16];
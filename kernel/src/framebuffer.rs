@@ -1,20 +1,55 @@
-use font8x8::legacy::BASIC_LEGACY;
 use spin::Mutex;
 use core::ptr::NonNull;
+use alloc::{boxed::Box, vec};
 
-/// Glyph pixel size at `scale == 1`, derived from `BASIC_LEGACY` itself
-/// (one bit per pixel column, one `u8` row per pixel row) instead of a
-/// hand-typed guess living separately in the console driver — if the font
-/// ever changes, this can't silently drift out of sync with `draw_char`.
-pub const GLYPH_W: usize = u8::BITS as usize;
-pub const GLYPH_H: usize = BASIC_LEGACY[0].len();
+use crate::font::{Font, LEGACY_8X8};
 
+/// El framebuffer puede dibujarse de dos maneras:
+///
+/// - Directo (el modo de arranque, y el único hasta que hay heap): cada
+///   `draw_*` escribe en la memoria de video (`buffer`) y se ve al instante.
+/// - Con back buffer (`enable_back_buffer`, en cuanto el heap está listo):
+///   todo se dibuja en una copia en RAM (`back`) y se acumula en `dirty` el
+///   rectángulo tocado; `present_dirty()` copia a la memoria de video solo
+///   ese rectángulo, fila por fila. La memoria de video es lenta de tocar
+///   píxel a píxel (y leerla, como hace `scroll_up` con `copy_within`, es
+///   lentísimo: no está en caché), así que un eco de teclado pasa de
+///   escribir una celda desperdigada en VRAM a copiar 8x16 px contiguos, y
+///   un scroll deja de leer VRAM del todo.
+///
+/// En modo directo `dirty` se lleva igual y `present_dirty()` solo lo
+/// resetea, de modo que los llamadores no tienen que saber en qué modo
+/// están: dibujan y después llaman a `present_dirty()`.
 pub struct Framebuffer {
     buffer: NonNull<u8>,
+    back: Option<Box<[u8]>>,
+    dirty: Option<Rect>,
     width: usize,
     height: usize,
     stride: usize,
     bytes_per_pixel: usize,
+    format: PixelFormat,
+}
+
+/// Rectángulo en píxeles, `[x0, x1) x [y0, y1)`.
+#[derive(Clone, Copy)]
+struct Rect {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+impl Rect {
+    /// Caja mínima que contiene a ambos.
+    fn union(self, other: Rect) -> Rect {
+        Rect {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
 }
 
 // SAFETY: El framebuffer es solo memoria de video, podemos compartirlo
@@ -28,27 +63,116 @@ impl Framebuffer {
         height: usize,
         stride: usize,
         bytes_per_pixel: usize,
+        format: PixelFormat,
     ) -> Self {
         Self {
             buffer: NonNull::new(buffer.as_mut_ptr()).unwrap(),
+            back: None,
+            dirty: None,
             width,
             height,
             stride,
             bytes_per_pixel,
+            format,
+        }
+    }
+
+    /// Pasa a dibujar en un back buffer en RAM (ver el comentario de
+    /// `Framebuffer`). Necesita el heap: son `height * stride * bpp` bytes
+    /// (4 MB a 1280x800x4), que van directo al buddy allocator. Arranca con
+    /// una copia de lo que ya hay en pantalla. Llamarla dos veces no hace
+    /// nada.
+    pub fn enable_back_buffer(&mut self) {
+        if self.back.is_some() {
+            return;
+        }
+        let len = self.byte_len();
+        let mut back = vec![0u8; len].into_boxed_slice();
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.buffer.as_ptr(), back.as_mut_ptr(), len);
+        }
+        self.back = Some(back);
+    }
+
+    /// Copia a la memoria de video la parte de la pantalla dibujada desde
+    /// el último `present_dirty()` y la da por limpia. Sin back buffer no
+    /// copia nada (ya se dibujó directo en video).
+    pub fn present_dirty(&mut self) {
+        let Some(rect) = self.dirty.take() else { return };
+        let Some(back) = self.back.as_ref() else { return };
+
+        let row_bytes = self.stride * self.bytes_per_pixel;
+        let start = rect.x0 * self.bytes_per_pixel;
+        let len = (rect.x1 - rect.x0) * self.bytes_per_pixel;
+        for y in rect.y0..rect.y1 {
+            let offset = y * row_bytes + start;
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    back.as_ptr().add(offset),
+                    self.buffer.as_ptr().add(offset),
+                    len,
+                );
+            }
+        }
+    }
+
+    /// Marca toda la pantalla como sucia — para lo que cambia (casi) todos
+    /// los píxeles, como `clear` o `scroll_up`.
+    pub fn mark_all_dirty(&mut self) {
+        self.mark_dirty(0, 0, self.width, self.height);
+    }
+
+    /// Agrega `w`x`h` px en `(x, y)`, recortado a la pantalla, al
+    /// rectángulo sucio.
+    fn mark_dirty(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        let rect = Rect {
+            x0: x.min(self.width),
+            y0: y.min(self.height),
+            x1: x.saturating_add(w).min(self.width),
+            y1: y.saturating_add(h).min(self.height),
+        };
+        if rect.x0 == rect.x1 || rect.y0 == rect.y1 {
+            return;
         }
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(rect),
+            None => rect,
+        });
+    }
+
+    fn byte_len(&self) -> usize {
+        self.height * self.stride * self.bytes_per_pixel
+    }
+
+    /// Donde dibujan los `draw_*`: el back buffer si lo hay, si no la
+    /// memoria de video. El `'static` es el mismo atajo que se usaba con la
+    /// memoria de video: deja llamar a `draw_pixel(&self, ...)` mientras se
+    /// tiene el slice. Vale porque `back` no se reemplaza una vez creado.
+    fn draw_target(&mut self) -> &'static mut [u8] {
+        let len = self.byte_len();
+        let ptr = match self.back.as_mut() {
+            Some(back) => back.as_mut_ptr(),
+            None => self.buffer.as_ptr(),
+        };
+        unsafe { core::slice::from_raw_parts_mut(ptr, len) }
     }
 
     /// Limpia toda la pantalla con el color especificado
     pub fn clear(&mut self, color: Color) {
-        let buffer = unsafe {
-            core::slice::from_raw_parts_mut(self.buffer.as_ptr(), self.height * self.stride * self.bytes_per_pixel)
-        };
+        self.fill_rect(0, 0, self.width, self.height, color);
+    }
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.draw_pixel(buffer, x, y, color);
+    /// Rellena `w`x`h` px en `(x, y)` con `color` (recortado a la pantalla).
+    pub fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: Color) {
+        let buffer = self.draw_target();
+        let x1 = x.saturating_add(w).min(self.width);
+        let y1 = y.saturating_add(h).min(self.height);
+        for py in y..y1 {
+            for px in x..x1 {
+                self.draw_pixel(buffer, px, py, color);
             }
         }
+        self.mark_dirty(x, y, w, h);
     }
 
     fn draw_pixel(&self, buffer: &mut [u8], x: usize, y: usize, color: Color) {
@@ -57,32 +181,25 @@ impl Framebuffer {
         }
 
         let offset = (y * self.stride + x) * self.bytes_per_pixel;
-        if offset + self.bytes_per_pixel <= buffer.len() {
-            buffer[offset] = color.b;
-            buffer[offset + 1] = color.g;
-            buffer[offset + 2] = color.r;
-            // buffer[offset + 3] = 0xFF; // Alpha si es necesario.
+        let bpp = self.bytes_per_pixel;
+        if offset + bpp <= buffer.len() {
+            let bytes = color.to_bytes(self.format);
+            buffer[offset..offset + bpp].copy_from_slice(&bytes[..bpp.min(4)]);
         }
     }
 
-    /// Dibuja un carácter en las coordenadas especificadas
-    pub fn draw_char(
-        &mut self,
-        x: usize,
-        y: usize,
-        ascii: u8,
-        fg_color: Color,
-        bg_color: Color,
-        scale: usize,
-    ) {
-        let buffer = unsafe {
-            core::slice::from_raw_parts_mut(self.buffer.as_ptr(), self.height * self.stride * self.bytes_per_pixel)
-        };
+    /// Dibuja un carácter en las coordenadas especificadas, con los
+    /// colores, la escala y la fuente de `style` (celda de
+    /// `font.width * scale` x `font.height * scale` px). Un carácter que
+    /// la fuente no tiene sale como una cajita (ver `Font::glyph`).
+    pub fn draw_char(&mut self, x: usize, y: usize, c: char, style: &GlyphStyle) {
+        let GlyphStyle { fg: fg_color, bg: bg_color, scale, font } = *style;
+        let buffer = self.draw_target();
+
+        let glyph = font.glyph(c);
 
-        let glyph: [u8; 8] = BASIC_LEGACY[ascii as usize];
-        
         for (row, &bits) in glyph.iter().enumerate() {
-            for col in 0..8 {
+            for col in 0..font.width {
                 let bit_set = (bits >> col) & 1 != 0;
                 let color = if bit_set { fg_color } else { bg_color };
                 
@@ -96,9 +213,12 @@ impl Framebuffer {
                 }
             }
         }
+        self.mark_dirty(x, y, font.width * scale, glyph.len() * scale);
     }
 
-    /// Dibuja texto en las coordenadas especificadas
+    /// Dibuja texto en las coordenadas especificadas con la fuente por
+    /// defecto (`LEGACY_8X8`) — ver `draw_text_at`. Las líneas que no
+    /// caben vuelven a empezar en `x`.
     pub fn draw_text(
         &mut self,
         x: usize,
@@ -107,13 +227,50 @@ impl Framebuffer {
         fg_color: Color,
         bg_color: Color,
         scale: usize,
-    ) {
-        let char_width = 8 * scale;
-        
-        for (i, &byte) in text.as_bytes().iter().enumerate() {
-            let char_x = x + i * char_width;
-            self.draw_char(char_x, y, byte, fg_color, bg_color, scale);
+    ) -> TextCursor {
+        self.draw_text_at(TextCursor::new(x, y), text, fg_color, bg_color, scale, &LEGACY_8X8)
+    }
+
+    /// Dibuja `text` desde `cursor` con la fuente `font` y devuelve dónde
+    /// iría el siguiente carácter, para que quien escribe por partes (p.ej.
+    /// `core::fmt::Write`, que entrega cada `writeln!` en varios trozos)
+    /// siga donde quedó en vez de llevar su propia aritmética de `x += ...`.
+    ///
+    /// Recorre `text` por `char` (no por byte: un carácter UTF-8 multibyte
+    /// es un solo glifo) y avanza una celda por carácter. Un carácter que
+    /// no cabe entero antes del borde derecho pasa a la línea siguiente, y
+    /// `\n` también; ambos vuelven a `cursor.left`. Al llegar abajo (la
+    /// línea siguiente no cabe entera) deja de dibujar — el resto del texto
+    /// se descarta, y llamadas posteriores con el cursor devuelto tampoco
+    /// dibujan nada. Hacer scroll es cosa del llamador (ver `scroll_up`).
+    pub fn draw_text_at(
+        &mut self,
+        mut cursor: TextCursor,
+        text: &str,
+        fg_color: Color,
+        bg_color: Color,
+        scale: usize,
+        font: &Font,
+    ) -> TextCursor {
+        let style = GlyphStyle { fg: fg_color, bg: bg_color, scale, font };
+        let char_width = font.width * scale;
+        let char_height = font.height * scale;
+
+        for c in text.chars() {
+            if c == '\n' {
+                cursor.newline(char_height);
+                continue;
+            }
+            if cursor.x + char_width > self.width && cursor.x > cursor.left {
+                cursor.newline(char_height);
+            }
+            if cursor.y + char_height > self.height {
+                break;
+            }
+            self.draw_char(cursor.x, cursor.y, c, &style);
+            cursor.x += char_width;
         }
+        cursor
     }
 
     /// Desplaza el contenido de la pantalla `line_height` píxeles hacia arriba.
@@ -123,11 +280,10 @@ impl Framebuffer {
         let total = self.height * row_bytes;
         let skip = line_height * row_bytes;
         if skip >= total { return; }
-        let buffer = unsafe {
-            core::slice::from_raw_parts_mut(self.buffer.as_ptr(), total)
-        };
+        let buffer = self.draw_target();
         buffer.copy_within(skip..total, 0);
         for byte in &mut buffer[(total - skip)..] { *byte = 0; }
+        self.mark_all_dirty();
     }
 
     /// Obtiene las dimensiones del framebuffer
@@ -152,36 +308,92 @@ impl Framebuffer {
         let off_x = (self.width.saturating_sub(dst_w)) / 2;
         let off_y = (self.height.saturating_sub(dst_h)) / 2;
 
-        let buffer = unsafe {
-            core::slice::from_raw_parts_mut(self.buffer.as_ptr(), self.height * self.stride * self.bytes_per_pixel)
-        };
+        let buffer = self.draw_target();
+        let bpp = self.bytes_per_pixel;
 
         for sy in 0..src_h {
             let src_row = sy * src_w;
             for sx in 0..src_w {
-                let p = src[src_row + sx];
-                let r = ((p >> 16) & 0xFF) as u8;
-                let g = ((p >> 8) & 0xFF) as u8;
-                let b = (p & 0xFF) as u8;
+                let bytes = Color::from_u32(src[src_row + sx]).to_bytes(self.format);
                 for oy in 0..scale {
                     let dy = off_y + sy * scale + oy;
                     let row_off = dy * self.stride * self.bytes_per_pixel;
                     for ox in 0..scale {
                         let dx = off_x + sx * scale + ox;
-                        let offset = row_off + dx * self.bytes_per_pixel;
-                        if offset + self.bytes_per_pixel <= buffer.len() {
-                            buffer[offset] = b;
-                            buffer[offset + 1] = g;
-                            buffer[offset + 2] = r;
+                        let offset = row_off + dx * bpp;
+                        if offset + bpp <= buffer.len() {
+                            buffer[offset..offset + bpp].copy_from_slice(&bytes[..bpp.min(4)]);
                         }
                     }
                 }
             }
         }
+        self.mark_dirty(off_x, off_y, dst_w, dst_h);
     }
 }
 
+/// Cómo dibuja `draw_char` un glifo: color de primer plano y de fondo,
+/// factor de escala entero y fuente.
+#[derive(Clone, Copy)]
+pub struct GlyphStyle<'a> {
+    pub fg: Color,
+    pub bg: Color,
+    pub scale: usize,
+    pub font: &'a Font,
+}
+
+/// Posición de "pluma" para `draw_text_at`: dónde va el siguiente glifo
+/// (`x`, `y`, en píxeles) y la columna `left` a la que vuelve una línea
+/// nueva.
 #[derive(Clone, Copy)]
+pub struct TextCursor {
+    pub x: usize,
+    pub y: usize,
+    pub left: usize,
+}
+
+impl TextCursor {
+    /// Cursor en `(x, y)` cuyas líneas nuevas empiezan en `x`.
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y, left: x }
+    }
+
+    pub fn newline(&mut self, line_height: usize) {
+        self.x = self.left;
+        self.y += line_height;
+    }
+}
+
+/// Orden de los bytes de un píxel en la memoria de video, según lo que
+/// reporta el bootloader (`bootloader_api::info::PixelFormat`). Antes se
+/// asumía BGR en todos lados; ahora solo `Color::to_bytes` sabe del orden.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PixelFormat {
+    /// Rojo en el byte más bajo.
+    Rgb,
+    /// Azul en el byte más bajo — lo que dan OVMF/QEMU.
+    Bgr,
+    /// Un solo byte de intensidad por píxel.
+    Gray,
+}
+
+impl PixelFormat {
+    /// `Unknown` (máscaras de bits arbitrarias) no se soporta de verdad:
+    /// cae a BGR, que es lo que se asumía antes de que existiera este tipo.
+    pub fn from_bootloader(format: bootloader_api::info::PixelFormat) -> Self {
+        use bootloader_api::info::PixelFormat as Boot;
+        match format {
+            Boot::Rgb => PixelFormat::Rgb,
+            Boot::U8 => PixelFormat::Gray,
+            _ => PixelFormat::Bgr,
+        }
+    }
+}
+
+/// Color RGB de 8 bits por canal, independiente del formato de la
+/// pantalla. Los nombrados son la paleta VGA de 16 colores (la misma que
+/// usa la consola para los códigos ANSI 30-37/90-97).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -189,9 +401,46 @@ pub struct Color {
 }
 
 impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const BLUE: Color = Color::rgb(0, 0, 170);
+    pub const GREEN: Color = Color::rgb(0, 170, 0);
+    pub const CYAN: Color = Color::rgb(0, 170, 170);
+    pub const RED: Color = Color::rgb(170, 0, 0);
+    pub const MAGENTA: Color = Color::rgb(170, 0, 170);
+    pub const BROWN: Color = Color::rgb(170, 85, 0);
+    pub const LIGHT_GRAY: Color = Color::rgb(170, 170, 170);
+    pub const DARK_GRAY: Color = Color::rgb(85, 85, 85);
+    pub const LIGHT_BLUE: Color = Color::rgb(85, 85, 255);
+    pub const LIGHT_GREEN: Color = Color::rgb(85, 255, 85);
+    pub const LIGHT_CYAN: Color = Color::rgb(85, 255, 255);
+    pub const LIGHT_RED: Color = Color::rgb(255, 85, 85);
+    pub const LIGHT_MAGENTA: Color = Color::rgb(255, 85, 255);
+    pub const YELLOW: Color = Color::rgb(255, 255, 85);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
     pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
+
+    /// Desde `0x00RRGGBB` (el formato de `FBIO_BLIT`); el byte alto se ignora.
+    pub const fn from_u32(p: u32) -> Self {
+        Self::rgb((p >> 16) as u8, (p >> 8) as u8, p as u8)
+    }
+
+    /// Los bytes del píxel en el orden de `format`, listos para copiar los
+    /// primeros `bytes_per_pixel` a la memoria de video. El cuarto byte
+    /// (relleno en los modos de 32 bits) queda en 0.
+    pub const fn to_bytes(self, format: PixelFormat) -> [u8; 4] {
+        match format {
+            PixelFormat::Rgb => [self.r, self.g, self.b, 0],
+            PixelFormat::Bgr => [self.b, self.g, self.r, 0],
+            PixelFormat::Gray => {
+                // Luma BT.601 en enteros: (77 R + 150 G + 29 B) / 256.
+                let y = (77 * self.r as u32 + 150 * self.g as u32 + 29 * self.b as u32) >> 8;
+                [y as u8, 0, 0, 0]
+            }
+        }
+    }
 }
 
 // Global framebuffer
@@ -200,4 +449,11 @@ pub static FRAMEBUFFER: Mutex<Option<Framebuffer>> = Mutex::new(None);
 // Helper para inicializar
 pub fn init_global_framebuffer(framebuffer: Framebuffer) {
     *FRAMEBUFFER.lock() = Some(framebuffer);
+}
+
+/// `enable_back_buffer` sobre el framebuffer global, si lo hay.
+pub fn enable_global_back_buffer() {
+    if let Some(fb) = FRAMEBUFFER.lock().as_mut() {
+        fb.enable_back_buffer();
+    }
 }
\ No newline at end of file
@@ -8,8 +8,10 @@
 //   ├── console
 //   ├── null
 //   ├── zero
+//   ├── random
 //   ├── fb
 //   ├── kbd
+//   ├── kmsg
 //   └── input/   (InputDirInode — one hardcoded level of nesting)
 //       └── event0
 //
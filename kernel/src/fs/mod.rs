@@ -46,9 +46,9 @@ pub fn init() {
     match ext2::init() {
         Ok(()) => {
             vfs::mount("/mnt", Arc::new(ext2::Ext2FsHandle));
-            crate::serial_println!("ext2: mounted /mnt");
+            crate::info!("ext2: mounted /mnt");
         }
-        Err(e) => crate::serial_println!("ext2: not mounted ({})", e),
+        Err(e) => crate::info!("ext2: not mounted ({})", e),
     }
     // /proc — synthetic, read-only (meminfo today)
     vfs::mount("/proc", Arc::new(procfs::ProcFs));
@@ -11,9 +11,14 @@
 // ──────
 //   /proc/           (ProcDirInode)
 //   ├── meminfo
+//   ├── uptime
+//   ├── memmap       (bootloader memory map, see `memory::memmap`)
+//   ├── timers       (pending hrtimers, see `time::hrtimer`)
 //   ├── self         → symlink to /proc/<own pid>
 //   └── <pid>/       (ProcPidDirInode, only for a pid that actually exists)
-//       └── exe      → symlink to whatever ELF path that process is running
+//       ├── exe      → symlink to whatever ELF path that process is running
+//       ├── stat     (one-line, what BusyBox `ps`/`top` parse)
+//       └── status   (key: value lines, for people)
 //
 // Real Linux's /proc/<pid> has dozens of entries (cmdline, status, fd/,
 // maps, ...) — only `exe` exists here, since that's the one thing
@@ -23,7 +28,9 @@
 // show every process; direct lookup (`cat /proc/3/exe`, `cd /proc/3`)
 // still works for any pid that's actually alive.
 //
-// Inode numbers: 200 = /proc directory, 201 = meminfo, 202 = self.
+// Inode numbers: 200 = /proc directory, 201 = meminfo, 202 = self,
+// 203 = kdebug, 204 = acpi, 205 = pci, 206 = uptime, 207 = cpuinfo,
+// 208 = memmap, 209 = timers.
 // Per-pid inodes are derived from the pid (see `pid_dir_ino`/`pid_exe_ino`).
 
 use alloc::{boxed::Box, format, string::String, sync::Arc, vec::Vec};
@@ -34,9 +41,10 @@ use crate::fs::{
 };
 use crate::process::file::{FileError, FileHandle, FileResult};
 
-fn pid_dir_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 3 }
-fn pid_exe_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 3 + 1 }
-fn pid_stat_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 3 + 2 }
+fn pid_dir_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 4 }
+fn pid_exe_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 4 + 1 }
+fn pid_stat_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 4 + 2 }
+fn pid_status_ino(pid: usize) -> u64 { 1000 + (pid as u64) * 4 + 3 }
 
 // ── Filesystem ───────────────────────────────────────────────────────────────
 
@@ -51,19 +59,48 @@ impl Filesystem for ProcFs {
 }
 
 /// Renders `/proc/meminfo` content as of right now — `MemTotal`/`MemFree`
-/// only (no `MemAvailable`/`Buffers`/`Cached`: this kernel has no page
-/// cache or reclaimable memory concept to report). Matches real
-/// `/proc/meminfo`'s `"%-13s%8lu kB\n"` shape closely enough for tools
-/// that grep/awk specific field names, which is the only thing that
-/// actually matters for compatibility.
+/// from the buddy allocator (`MemAvailable` is just `MemFree` again: no
+/// `Buffers`/`Cached`, this kernel has no page cache or reclaimable memory
+/// concept to report), then `Slab:` (bytes the slab caches hold, in use or
+/// not — what Linux reports under that name too) followed by one
+/// non-standard `SlabNNNN:` line per size class as `used/total` objects.
+/// Matches real `/proc/meminfo`'s `"%-13s%8lu kB\n"` shape closely enough
+/// for tools that grep/awk specific field names, which is the only thing
+/// that actually matters for compatibility.
+///
+/// Both allocators' numbers are copied out and their locks dropped before
+/// any formatting: `format!` allocates through the slab, which can in turn
+/// take `BUDDY` to grow a cache.
 fn render_meminfo() -> String {
-    let buddy = crate::allocator::buddy_allocator::BUDDY.lock();
-    let total_kb = buddy.total_bytes() / 1024;
-    let free_kb = buddy.free_bytes() / 1024;
-    format!(
-        "MemTotal:       {:>8} kB\nMemFree:        {:>8} kB\nMemAvailable:   {:>8} kB\n",
-        total_kb, free_kb, free_kb
-    )
+    let (total_kb, free_kb) = {
+        let buddy = crate::allocator::buddy_allocator::lock_buddy();
+        (buddy.total_bytes() / 1024, buddy.free_bytes() / 1024)
+    };
+    let slabs = crate::allocator::slab::slab_usage();
+    let slab_kb: usize = slabs.iter().map(|c| c.total_objects * c.object_size).sum::<usize>() / 1024;
+    let mut out = format!(
+        "MemTotal:       {:>8} kB\nMemFree:        {:>8} kB\nMemAvailable:   {:>8} kB\nSlab:           {:>8} kB\n",
+        total_kb, free_kb, free_kb, slab_kb
+    );
+    for c in slabs.iter() {
+        let label = format!("Slab{}:", c.object_size);
+        out.push_str(&format!("{:<16}{:>8}/{}\n", label, c.used_objects, c.total_objects));
+    }
+    out
+}
+
+/// Renders `/proc/uptime`: seconds since boot and seconds the idle process
+/// (pid 0) has been charged, both with two decimals — Linux's exact
+/// format, which BusyBox `uptime` parses. Idle time comes from idle's
+/// `cpu_ticks` (timer ticks at `TICK_HZ`, see `Scheduler::tick`), so it
+/// moves in whole tick periods while uptime comes from the finer-grained
+/// clocksource.
+fn render_uptime() -> String {
+    let up_cs = crate::time::ktime_get() / 10_000_000;
+    let idle_cs = crate::process::scheduler::proc_stat_snapshot(0)
+        .map(|s| crate::time::clockevent::jiffies_to_ns(s.cpu_ticks) / 10_000_000)
+        .unwrap_or(0);
+    format!("{}.{:02} {}.{:02}\n", up_cs / 100, up_cs % 100, idle_cs / 100, idle_cs % 100)
 }
 
 /// Renders `/proc/acpi` — a human-readable dump of `crate::acpi::topology()`
@@ -105,25 +142,63 @@ fn render_acpi() -> String {
 /// `ps`/`top` (`libbb/procps.c::procps_scan`) actually parses: split on the
 /// last `)` to pull `comm` out (so it's safe even if `comm` itself
 /// contained spaces, though ours never does), then a fixed-position
-/// `sscanf` over everything after. Fields this kernel has no real data for
-/// (page fault counts, per-process cpu ticks, start time, memory size) are
-/// reported as `0` — enough for `ps`/`top` to run and show real pid/name/
-/// state/ppid/pgid/priority without crashing on a short field list, not
-/// enough for their CPU%/MEM%/VSZ/RSS columns to mean anything yet.
+/// `sscanf` over everything after. `utime`/`cutime` are real
+/// (`Process::cpu_ticks`/`children_cpu_ticks` in `USER_HZ` units, the
+/// same values `times()` reports — `stime`/`cstime` stay `0`, there's no
+/// user/system split).
+/// Fields this kernel has no real data for (page fault counts, start time,
+/// memory size) are reported as `0` — enough for `ps`/`top` to run and
+/// show real pid/name/state/ppid/pgid/priority/TIME without crashing on a
+/// short field list, not enough for their MEM%/VSZ/RSS columns to mean
+/// anything yet.
 fn render_proc_stat(pid: usize, snap: &crate::process::scheduler::ProcStatSnapshot) -> String {
+    let comm = comm_of(snap);
+    let (state, _) = state_of(snap);
+    format!(
+        "{pid} ({comm}) {state} {ppid} {pgid} {pgid} 0 -1 0 0 0 0 0 {utime} 0 {cutime} 0 {priority} 0 0 0 0 0 0\n",
+        pid = pid, comm = comm, state = state,
+        ppid = snap.ppid, pgid = snap.pgid, priority = snap.priority,
+        utime = crate::time::clockevent::ticks_to_clock_t(snap.cpu_ticks),
+        cutime = crate::time::clockevent::ticks_to_clock_t(snap.children_cpu_ticks),
+    )
+}
+
+/// `Process::name` up to its NUL, `"?"` if empty.
+fn comm_of(snap: &crate::process::scheduler::ProcStatSnapshot) -> String {
     let end = snap.name.iter().position(|&b| b == 0).unwrap_or(snap.name.len());
     let comm = String::from_utf8_lossy(&snap.name[..end]);
-    let comm = if comm.is_empty() { "?" } else { comm.as_ref() };
-    let state = match snap.state {
-        crate::process::ProcessState::Ready | crate::process::ProcessState::Running => 'R',
-        crate::process::ProcessState::Blocked => 'S',
-        crate::process::ProcessState::Zombie => 'Z',
-        crate::process::ProcessState::Stopped => 'T',
+    if comm.is_empty() { String::from("?") } else { comm.into_owned() }
+}
+
+/// Linux's one-letter state code and its long name, as `stat` and `status`
+/// print them respectively.
+fn state_of(snap: &crate::process::scheduler::ProcStatSnapshot) -> (char, &'static str) {
+    match snap.state {
+        crate::process::ProcessState::Ready | crate::process::ProcessState::Running => ('R', "running"),
+        crate::process::ProcessState::Blocked => ('S', "sleeping"),
+        crate::process::ProcessState::Zombie => ('Z', "zombie"),
+        crate::process::ProcessState::Stopped => ('T', "stopped"),
+    }
+}
+
+/// Renders `/proc/<pid>/status` — the same snapshot as `stat`, as the
+/// human-readable `Key:\tvalue` lines Linux uses there (`grep State
+/// /proc/3/status`). `Tgid:` is what `getpid()` returns in that process,
+/// `Pid:` its own id (`gettid()`) — they differ only for a `clone()`d
+/// thread. Only the fields this kernel really has; `Priority:`
+/// isn't a Linux key (there it's only in `stat`), kept here because it's
+/// the scheduler's main knob and the point of this file is debugging.
+fn render_proc_status(pid: usize, snap: &crate::process::scheduler::ProcStatSnapshot) -> String {
+    let (code, long) = state_of(snap);
+    let fds = match snap.fds {
+        Some(n) => format!("{}", n),
+        None => String::from("?"),
     };
     format!(
-        "{pid} ({comm}) {state} {ppid} {pgid} {pgid} 0 -1 0 0 0 0 0 0 0 0 0 {priority} 0 0 0 0 0 0\n",
-        pid = pid, comm = comm, state = state,
-        ppid = snap.ppid, pgid = snap.pgid, priority = snap.priority,
+        "Name:\t{}\nState:\t{} ({})\nTgid:\t{}\nPid:\t{}\nPPid:\t{}\nPgid:\t{}\nPriority:\t{}\nCpuTicks:\t{}\nFDSize:\t{}\nFDs:\t{}\nCpus_allowed:\t{:x}\nCpus_allowed_list:\t{}\nvoluntary_ctxt_switches:\t{}\nnonvoluntary_ctxt_switches:\t{}\n",
+        comm_of(snap), code, long, snap.tgid, pid, snap.ppid, snap.pgid, snap.priority, snap.cpu_ticks,
+        crate::process::file::MAX_FILES, fds, snap.affinity.bits(), snap.affinity.to_list(),
+        snap.nvcsw, snap.nivcsw,
     )
 }
 
@@ -147,6 +222,11 @@ impl Inode for ProcDirInode {
             "meminfo" => Ok(Arc::new(MeminfoInode)),
             "kdebug" => Ok(Arc::new(KdebugInode)),
             "acpi" => Ok(Arc::new(AcpiInode)),
+            "pci" => Ok(Arc::new(PciInode)),
+            "uptime" => Ok(Arc::new(UptimeInode)),
+            "cpuinfo" => Ok(Arc::new(CpuinfoInode)),
+            "memmap" => Ok(Arc::new(MemmapInode)),
+            "timers" => Ok(Arc::new(TimersInode)),
             "self" => Ok(Arc::new(SelfInode)),
             _ => {
                 let pid: usize = name.parse().map_err(|_| Errno::ENOENT)?;
@@ -167,13 +247,18 @@ impl Inode for ProcDirInode {
             3 => Ok(Some(DirEntry::new(202, FileType::Symlink, b"self"))),
             4 => Ok(Some(DirEntry::new(203, FileType::Regular, b"kdebug"))),
             5 => Ok(Some(DirEntry::new(204, FileType::Regular, b"acpi"))),
+            6 => Ok(Some(DirEntry::new(205, FileType::Regular, b"pci"))),
+            7 => Ok(Some(DirEntry::new(206, FileType::Regular, b"uptime"))),
+            8 => Ok(Some(DirEntry::new(207, FileType::Regular, b"cpuinfo"))),
+            9 => Ok(Some(DirEntry::new(208, FileType::Regular, b"memmap"))),
+            10 => Ok(Some(DirEntry::new(209, FileType::Regular, b"timers"))),
             n => {
                 // Live pids, appended after the always-present entries above
                 // — this is what makes `ls /proc` / BusyBox `ps`'s
                 // `opendir("/proc")` scan see every process (previously
                 // direct lookup like `cat /proc/3/exe` worked but nothing
                 // enumerated them, see this module's top doc comment).
-                let idx = (n - 6) as usize;
+                let idx = (n - 11) as usize;
                 let pids = crate::process::scheduler::all_pids();
                 let Some(&pid) = pids.get(idx) else { return Ok(None); };
                 let name = format!("{}", pid);
@@ -198,7 +283,7 @@ impl Inode for MeminfoInode {
         if flags.is_write() {
             return Err(Errno::EROFS);
         }
-        Ok(Box::new(ProcFile { data: render_meminfo().into_bytes(), offset: 0 }))
+        Ok(Box::new(ProcFile::new(201, "procfs/meminfo", render_meminfo())))
     }
 }
 
@@ -221,7 +306,7 @@ impl Inode for KdebugInode {
         if flags.is_write() {
             return Err(Errno::EROFS);
         }
-        Ok(Box::new(ProcFile { data: crate::debug::render_report().into_bytes(), offset: 0 }))
+        Ok(Box::new(ProcFile::new(203, "procfs/kdebug", crate::debug::render_report())))
     }
 }
 
@@ -243,7 +328,114 @@ impl Inode for AcpiInode {
         if flags.is_write() {
             return Err(Errno::EROFS);
         }
-        Ok(Box::new(ProcFile { data: render_acpi().into_bytes(), offset: 0 }))
+        Ok(Box::new(ProcFile::new(204, "procfs/acpi", render_acpi())))
+    }
+}
+
+// ── pci file inode ───────────────────────────────────────────────────────────
+//
+// Read-only `lspci`-style listing of every PCI function (`crate::pci::
+// enumerate`), re-scanned on every open() — config space is cheap to read
+// and this way it can't go stale, same convention as `/proc/acpi`.
+struct PciInode;
+
+fn render_pci() -> String {
+    crate::pci::render_list(&crate::pci::enumerate())
+}
+
+impl Inode for PciInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        Stat::regular(205, render_pci().len() as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        Ok(Box::new(ProcFile::new(205, "procfs/pci", render_pci())))
+    }
+}
+
+// ── cpuinfo file inode ───────────────────────────────────────────────────────
+//
+// Vendor, brand string and feature flags from `cpu::features` (CPUID,
+// detected once at boot) — backs the `cpuinfo` program.
+struct CpuinfoInode;
+
+impl Inode for CpuinfoInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        Stat::regular(207, crate::cpu::features::render_cpuinfo().len() as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        Ok(Box::new(ProcFile::new(207, "procfs/cpuinfo", crate::cpu::features::render_cpuinfo())))
+    }
+}
+
+// ── memmap file inode ────────────────────────────────────────────────────────
+//
+// The bootloader's physical memory regions, snapshotted at boot by
+// `memory::memmap::record` — backs the `memmap` program.
+struct MemmapInode;
+
+impl Inode for MemmapInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        Stat::regular(208, crate::memory::memmap::render_memmap().len() as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        Ok(Box::new(ProcFile::new(208, "procfs/memmap", crate::memory::memmap::render_memmap())))
+    }
+}
+
+// ── timers file inode ────────────────────────────────────────────────────────
+//
+// Every pending sleep/poll/epoll timeout in `time::hrtimer`'s queue.
+struct TimersInode;
+
+impl Inode for TimersInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        Stat::regular(209, crate::time::hrtimer::render_timers().len() as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        Ok(Box::new(ProcFile::new(209, "procfs/timers", crate::time::hrtimer::render_timers())))
+    }
+}
+
+// ── uptime file inode ────────────────────────────────────────────────────────
+
+struct UptimeInode;
+
+impl Inode for UptimeInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        Stat::regular(206, render_uptime().len() as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        Ok(Box::new(ProcFile::new(206, "procfs/uptime", render_uptime())))
     }
 }
 
@@ -298,6 +490,7 @@ impl Inode for ProcPidDirInode {
         match name {
             "exe" => Ok(Arc::new(ProcExeInode { pid: self.pid })),
             "stat" => Ok(Arc::new(ProcStatInode { pid: self.pid })),
+            "status" => Ok(Arc::new(ProcStatusInode { pid: self.pid })),
             _ => Err(Errno::ENOENT),
         }
     }
@@ -309,6 +502,7 @@ impl Inode for ProcPidDirInode {
             1 => Ok(Some(DirEntry::new(ino, FileType::Directory, b".."))),
             2 => Ok(Some(DirEntry::new(pid_exe_ino(self.pid), FileType::Symlink, b"exe"))),
             3 => Ok(Some(DirEntry::new(pid_stat_ino(self.pid), FileType::Regular, b"stat"))),
+            4 => Ok(Some(DirEntry::new(pid_status_ino(self.pid), FileType::Regular, b"status"))),
             _ => Ok(None),
         }
     }
@@ -337,8 +531,36 @@ impl Inode for ProcStatInode {
         }
         let snap = crate::process::scheduler::proc_stat_snapshot(self.pid)
             .ok_or(Errno::ENOENT)?;
-        let data = render_proc_stat(self.pid, &snap).into_bytes();
-        Ok(Box::new(ProcFile { data, offset: 0 }))
+        let data = render_proc_stat(self.pid, &snap);
+        Ok(Box::new(ProcFile::new(pid_stat_ino(self.pid), "procfs/pid-stat", data)))
+    }
+}
+
+// ── /proc/<pid>/status file inode ────────────────────────────────────────────
+
+/// See `render_proc_status`.
+struct ProcStatusInode {
+    pid: usize,
+}
+
+impl Inode for ProcStatusInode {
+    fn as_any(&self) -> &dyn core::any::Any { self }
+
+    fn stat(&self) -> Stat {
+        let len = crate::process::scheduler::proc_stat_snapshot(self.pid)
+            .map(|s| render_proc_status(self.pid, &s).len())
+            .unwrap_or(0);
+        Stat::regular(pid_status_ino(self.pid), len as i64)
+    }
+
+    fn open(&self, flags: OpenFlags) -> Result<Box<dyn FileHandle>, Errno> {
+        if flags.is_write() {
+            return Err(Errno::EROFS);
+        }
+        let snap = crate::process::scheduler::proc_stat_snapshot(self.pid)
+            .ok_or(Errno::ENOENT)?;
+        let data = render_proc_status(self.pid, &snap);
+        Ok(Box::new(ProcFile::new(pid_status_ino(self.pid), "procfs/pid-status", data)))
     }
 }
 
@@ -403,15 +625,26 @@ impl Inode for ProcExeInode {
 
 // ── Open file handles ────────────────────────────────────────────────────────
 
-/// Read-only handle over a snapshot generated at `open()` time.
+/// Read-only handle over a snapshot generated at `open()` time. Seekable
+/// within that snapshot (`lseek` back to 0 rereads the same text, it does
+/// not regenerate it — reopen for fresh numbers, as on Linux).
 struct ProcFile {
+    ino:    u64,
+    name:   &'static str,
     data:   Vec<u8>,
     offset: usize,
 }
 
+impl ProcFile {
+    fn new(ino: u64, name: &'static str, text: String) -> Self {
+        ProcFile { ino, name, data: text.into_bytes(), offset: 0 }
+    }
+}
+
 impl FileHandle for ProcFile {
     fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
-        let remaining = &self.data[self.offset..];
+        let start = self.offset.min(self.data.len());
+        let remaining = &self.data[start..];
         if remaining.is_empty() {
             return Ok(0); // EOF
         }
@@ -425,11 +658,19 @@ impl FileHandle for ProcFile {
         Err(FileError::NotSupported)
     }
 
+    fn seek(&mut self, offset: i64, whence: i32) -> FileResult<i64> {
+        let pos = crate::process::file::compute_seek(
+            self.offset as i64, self.data.len() as i64, offset, whence,
+        )?;
+        self.offset = pos as usize;
+        Ok(pos)
+    }
+
     fn stat(&self) -> Option<crate::fs::types::Stat> {
-        Some(Stat::regular(201, self.data.len() as i64))
+        Some(Stat::regular(self.ino, self.data.len() as i64))
     }
 
-    fn name(&self) -> &str { "procfs/meminfo" }
+    fn name(&self) -> &str { self.name }
 }
 
 /// Directory handle: keeps a readdir cursor and serves `getdents64`.
@@ -17,7 +17,7 @@
 // scratch fs nobody expects strict live-mutation semantics from.
 
 use alloc::{boxed::Box, collections::BTreeMap, string::String, string::ToString, sync::Arc, vec::Vec};
-use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use spin::Mutex;
 
 use crate::fs::{
@@ -109,7 +109,7 @@ impl Inode for RamDirNode {
         for (name, node) in entries.iter() {
             snapshot.push(DirEntry::new(node.stat().st_ino, node.file_type(), name.as_bytes()));
         }
-        Ok(Box::new(RamDirHandle { snapshot, offset: 0 }))
+        Ok(Box::new(RamDirHandle { ino: self.ino, snapshot, offset: 0 }))
     }
 
     fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Errno> {
@@ -143,6 +143,7 @@ impl Inode for RamDirNode {
             ino: alloc_ino(),
             data: Arc::new(Mutex::new(Vec::new())),
             mode: Arc::new(AtomicU32::new(0o644)),
+            linked: Arc::new(AtomicBool::new(true)),
         });
         entries.insert(name.to_string(), node.clone() as Arc<dyn Inode>);
         Ok(node as Arc<dyn Inode>)
@@ -163,7 +164,14 @@ impl Inode for RamDirNode {
         match entries.get(name) {
             None => Err(Errno::ENOENT),
             Some(node) if node.file_type() == FileType::Directory => Err(Errno::EISDIR),
-            Some(_) => { entries.remove(name); Ok(()) }
+            Some(_) => {
+                if let Some(node) = entries.remove(name) {
+                    if let Some(file) = node.as_any().downcast_ref::<RamFileNode>() {
+                        file.linked.store(false, Ordering::Relaxed);
+                    }
+                }
+                Ok(())
+            }
         }
     }
 
@@ -216,6 +224,7 @@ impl Inode for RamDirNode {
 
 /// Directory handle: serves `getdents64` off the open-time snapshot.
 struct RamDirHandle {
+    ino: u64,
     snapshot: Vec<DirEntry>,
     offset: usize,
 }
@@ -234,7 +243,10 @@ impl FileHandle for RamDirHandle {
     }
 
     fn stat(&self) -> Option<Stat> {
-        Some(Stat::dir(ROOT_INO))
+        // This directory's own inode, not the mount root's — `fstat` on an
+        // opendir()'d subdirectory has to agree with `stat` on its path for
+        // tools that compare (dev, ino) pairs to detect directory cycles.
+        Some(Stat::dir(self.ino))
     }
 
     fn name(&self) -> &str { "ramfs/dir" }
@@ -252,6 +264,15 @@ struct RamFileNode {
     // handle) agree on one true value — same reasoning as `data` itself
     // being `Arc`-shared below.
     mode: Arc<AtomicU32>,
+    // Cleared by `RamDirNode::unlink`. The data itself needs no help to
+    // outlive its name — every open `RamFileHandle` holds its own `Arc` on
+    // it, so it's freed on the last close — but `fstat` on one of those
+    // handles should then say `st_nlink == 0`, as Linux does, not claim the
+    // file still has a name. `Arc`-shared with the handles for the same
+    // reason as `mode`. (`rename` moves the same node between directories
+    // via `take_child`/`insert_child`, so it never touches this; a file
+    // it replaces goes through `unlink`, which does.)
+    linked: Arc<AtomicBool>,
 }
 
 impl Inode for RamFileNode {
@@ -276,6 +297,7 @@ impl Inode for RamFileNode {
             data: self.data.clone(),
             offset: Arc::new(Mutex::new(offset)),
             mode: self.mode.clone(),
+            linked: self.linked.clone(),
         }))
     }
 
@@ -329,6 +351,8 @@ struct RamFileHandle {
     // Shared with the owning `RamFileNode` (and every other open handle on
     // it) — see that struct's doc comment on this same field.
     mode: Arc<AtomicU32>,
+    // Same sharing, for `stat`'s link count once the file is unlinked.
+    linked: Arc<AtomicBool>,
 }
 
 impl FileHandle for RamFileHandle {
@@ -357,8 +381,10 @@ impl FileHandle for RamFileHandle {
     }
 
     fn stat(&self) -> Option<Stat> {
+        let nlink = if self.linked.load(Ordering::Relaxed) { 1 } else { 0 };
         Some(Stat::regular_writable(self.ino, self.data.lock().len() as i64)
-            .with_perm_bits(self.mode.load(Ordering::Relaxed)))
+            .with_perm_bits(self.mode.load(Ordering::Relaxed))
+            .with_nlink(nlink))
     }
 
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
@@ -367,6 +393,7 @@ impl FileHandle for RamFileHandle {
             data: self.data.clone(),
             offset: self.offset.clone(),
             mode: self.mode.clone(),
+            linked: self.linked.clone(),
         }))
     }
 
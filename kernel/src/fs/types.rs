@@ -92,6 +92,7 @@ impl OpenFlags {
     pub const TRUNC:     Self = Self(0o1000);
     pub const APPEND:    Self = Self(0o2000);
     pub const DIRECTORY: Self = Self(0o200000);
+    pub const CLOEXEC:   Self = Self(0o2000000);
 
     /// True if the file is opened for writing.
     #[inline]
@@ -104,6 +105,12 @@ impl OpenFlags {
     pub fn is_directory(self) -> bool {
         self.0 & 0o200000 != 0
     }
+
+    /// True if O_CLOEXEC is set (the new fd starts with FD_CLOEXEC).
+    #[inline]
+    pub fn is_cloexec(self) -> bool {
+        self.0 & Self::CLOEXEC.0 != 0
+    }
 }
 
 // ── Stat ─────────────────────────────────────────────────────────────────────
@@ -224,6 +231,13 @@ impl Stat {
     pub fn chardev(ino: u64) -> Self {
         Self::base(ino, FileType::CharDevice.as_mode_bits() | 0o666, 1, 0, 0)
     }
+
+    /// Construct a block-device stat. `size` is the device's capacity in
+    /// bytes — Linux reports 0 here and makes callers ask BLKGETSIZE64,
+    /// but with no such ioctl a real size is the only way to learn it.
+    pub fn blockdev(ino: u64, size: i64) -> Self {
+        Self::base(ino, FileType::BlockDevice.as_mode_bits() | 0o660, 1, size, (size + 511) / 512)
+    }
 }
 
 // ── DirEntry ─────────────────────────────────────────────────────────────────
@@ -113,7 +113,7 @@ pub trait Inode: Send + Sync {
 
     /// Insert an already-existing inode under a new name — the "attach"
     /// half of a rename. Fails with `EEXIST` if `name` is already taken
-    /// (this VFS doesn't support rename-clobbering an existing target).
+    /// (`rename` removes a target it replaces before calling this).
     fn insert_child(&self, _name: &str, _node: Arc<dyn Inode>) -> Result<(), Errno> {
         Err(Errno::EROFS)
     }
@@ -461,12 +461,34 @@ pub fn rmdir(path: &str) -> Result<(), Errno> {
 /// Move/rename `old_path` to `new_path`. Both must resolve to directories
 /// on the same mounted filesystem (no cross-filesystem support — the
 /// target parent's `insert_child` will fail with `EROFS`/`ENOSYS` if not).
+///
+/// An existing `new_path` is replaced, as with `rename(2)`: a non-directory
+/// by a non-directory, an empty directory by a directory (`EISDIR` /
+/// `ENOTDIR` for a mismatch). It goes through the parent's own
+/// `unlink`/`rmdir`, so fds still open on a replaced file see it deleted
+/// (ramfs: `st_nlink` 0), exactly as after a plain `unlink`. Not atomic:
+/// if the final `insert_child` fails, the source is put back but the
+/// replaced target is already gone.
 pub fn rename(old_path: &str, new_path: &str) -> Result<(), Errno> {
     let (old_dir, old_leaf) = split_parent(old_path)?;
     let (new_dir, new_leaf) = split_parent(new_path)?;
     let old_parent = resolve(old_dir)?;
     let new_parent = resolve(new_dir)?;
 
+    let source = old_parent.lookup(old_leaf)?;
+    if let Ok(target) = new_parent.lookup(new_leaf) {
+        if old_path == new_path || Arc::ptr_eq(&source, &target) {
+            return Ok(());
+        }
+        let source_is_dir = source.file_type() == FileType::Directory;
+        match (source_is_dir, target.file_type() == FileType::Directory) {
+            (false, true) => return Err(Errno::EISDIR),
+            (true, false) => return Err(Errno::ENOTDIR),
+            (true, true) => new_parent.rmdir(new_leaf)?,
+            (false, false) => new_parent.unlink(new_leaf)?,
+        }
+    }
+
     let node = old_parent.take_child(old_leaf)?;
     if let Err(e) = new_parent.insert_child(new_leaf, node.clone()) {
         // Best-effort rollback so a failed rename doesn't just lose the file.
@@ -483,6 +505,11 @@ pub fn rename(old_path: &str, new_path: &str) -> Result<(), Errno> {
 // from* differs, which is why this is two helpers, not one. Before these
 // existed, seven directory handles (devfs x2, initramfs, procfs x2, ramfs,
 // ext2) each hand-rolled an identical packing loop.
+//
+// Both return `EINVAL` when entries remain but not even the next one fits
+// in `buf`, as Linux does. Returning 0 there would read as end-of-directory
+// and silently truncate the listing for a caller with a small buffer (a
+// 255-byte name needs a 280-byte record).
 
 /// Walk `dir.readdir(offset)` one entry at a time, packing each into `buf`
 /// as a `linux_dirent64` record, until either the directory is exhausted
@@ -500,6 +527,9 @@ pub fn getdents64_via_readdir(dir: &dyn Inode, offset: &mut u64, buf: &mut [u8])
         };
         let needed = entry.dirent64_size();
         if written + needed > buf.len() {
+            if written == 0 {
+                return Errno::EINVAL.as_i64();
+            }
             break;
         }
         let next_off = *offset as i64 + 1;
@@ -519,6 +549,9 @@ pub fn getdents64_from_snapshot(entries: &[crate::fs::types::DirEntry], offset:
         let entry = &entries[*offset];
         let needed = entry.dirent64_size();
         if written + needed > buf.len() {
+            if written == 0 {
+                return Errno::EINVAL.as_i64();
+            }
             break;
         }
         let next_off = *offset as i64 + 1;
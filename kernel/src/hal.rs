@@ -101,8 +101,8 @@ pub enum DriverError {
 pub fn run_all(drivers: &mut [&mut dyn Driver]) {
     for drv in drivers.iter_mut() {
         match drv.init() {
-            Ok(()) => crate::serial_println!("[hal] driver '{}' init: OK", drv.name()),
-            Err(e) => crate::serial_println!("[hal] driver '{}' init: FAILED ({:?})", drv.name(), e),
+            Ok(()) => crate::info!("[hal] driver '{}' init: OK", drv.name()),
+            Err(e) => crate::warn!("[hal] driver '{}' init: FAILED ({:?})", drv.name(), e),
         }
     }
 }
@@ -12,16 +12,17 @@
 //     Previously it only overwrote the 5-field ExceptionStackFrame,
 //     leaking RAX..R15 from the killed process into the next one.
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use spin::Once;
 
 use crate::{
     framebuffer::{self, Color},
     interrupts::{
         exception::ExceptionStackFrame,
-        idt::InterruptDescriptorTable,
+        idt::{ExceptionHandler, ExceptionHandlerWithErrCode, InterruptDescriptorTable},
     },
     keyboard,
-    serial_println,
 };
 
 // ============================================================================
@@ -32,9 +33,23 @@ static IDT: Once<InterruptDescriptorTable> = Once::new();
 
 pub fn init_idt() {
     IDT.call_once(|| {
-        let mut idt = InterruptDescriptorTable::new();
+        // Built from our own GDT's layout (constructed here if `tss::init`
+        // hasn't run yet — it's loaded later, in boot step 9). Until then
+        // the bootloader's GDT is live, so a fault in that window only
+        // dispatches if its kernel CS matches ours (0x08 — it always has,
+        // or the old hardcoded selector wouldn't have worked either).
+        let mut idt = InterruptDescriptorTable::new(crate::process::tss::kernel_code_selector());
         idt.add_handler(0, divide_by_zero_handler);
+        // #DB: single-step tracing (`process::single_step`), with the full
+        // register set, hence an asm entry like the timer's.
+        idt.add_handler_addr(1, crate::process::single_step::debug_trap_entry as *const () as u64);
+        idt.add_handler_with_ist(
+            2,
+            nmi_handler as ExceptionHandler,
+            crate::process::tss::NMI_IST_INDEX + 1,
+        );
         idt.add_handler(6, invalid_opcode_handler);
+        idt.add_handler(7, device_not_available_handler);
         // IST index is 1-based in the IDT entry.  TSS defines
         // DOUBLE_FAULT_IST_INDEX = 0 (array index), so CPU IST = 0 + 1 = 1.
         idt.add_double_fault_handler(
@@ -43,11 +58,27 @@ pub fn init_idt() {
             (crate::process::tss::DOUBLE_FAULT_IST_INDEX + 1) as u16,
         );
         idt.add_handler_with_error(13, general_protection_fault_handler);
-        idt.add_handler_with_error(14, page_fault_handler);
-        idt.entries[32].set_handler_addr(crate::process::timer_preempt::timer_interrupt_entry as u64);
-        idt.add_handler(33, keyboard_interrupt_handler);
-        idt.add_handler(36, serial_interrupt_handler);
-        idt.add_handler(44, mouse_interrupt_handler);
+        idt.add_handler(16, x87_floating_point_handler);
+        idt.add_handler_with_ist(
+            18,
+            machine_check_handler as ExceptionHandler,
+            crate::process::tss::MACHINE_CHECK_IST_INDEX + 1,
+        );
+        idt.add_handler(19, simd_floating_point_handler);
+        idt.add_handler_with_ist(
+            14,
+            page_fault_handler as ExceptionHandlerWithErrCode,
+            crate::process::tss::PAGE_FAULT_IST_INDEX + 1,
+        );
+        // Device IRQ lines 1..=15 (vectors 33..=47) all go through
+        // `interrupts::irq`'s trampolines; drivers hook them with
+        // `register_irq`. The timer keeps its own asm entry.
+        crate::interrupts::irq::install_trampolines(&mut idt);
+        crate::interrupts::irq::register_irq(1, keyboard_irq);
+        crate::interrupts::irq::register_irq(4, serial_irq);
+        idt.add_handler_addr(32, crate::process::timer_preempt::timer_interrupt_entry as u64);
+        // Only ever raised once `lapic::init_timer` has enabled the LAPIC.
+        idt.add_handler(crate::lapic::SPURIOUS_VECTOR, crate::lapic::spurious_handler);
         // Syscalls are now handled via the `syscall` instruction (LSTAR MSR),
         // not via int 0x80.  No IDT entry needed.
         idt
@@ -71,16 +102,17 @@ const PF_RESERVED: u64 = 1 << 3;   // 1 = reserved PTE bit set
 // INTERRUPT HANDLERS
 // ============================================================================
 
-extern "x86-interrupt" fn keyboard_interrupt_handler(_: &mut ExceptionStackFrame) {
+/// IRQ1 — PS/2 keyboard. Registered via `interrupts::irq::register_irq`,
+/// which sends the EOI after this returns.
+fn keyboard_irq() {
     let scancode = unsafe {
         x86_64::instructions::port::PortReadOnly::<u8>::new(0x60).read()
     };
     keyboard::process_scancode(scancode);
     // Wake any process blocked on stdin read.
     crate::process::syscall::stdin_wakeup();
-    // Wake any process blocked in poll/epoll_wait watching stdin for POLLIN.
-    crate::process::syscall::poll_wakeup_for_fd0();
-    crate::interrupts::pic::end_of_interrupt(crate::interrupts::pic::Irq::Keyboard.as_u8());
+    // Wake any process blocked in poll/epoll_wait on stdin or /dev/kbd.
+    crate::process::syscall::poll_wakeup_ready();
 }
 
 /// COM1 receive interrupt — lets serial input act as stdin, alongside the
@@ -90,7 +122,7 @@ extern "x86-interrupt" fn keyboard_interrupt_handler(_: &mut ExceptionStackFrame
 /// which physical source a byte came from.  This is what lets `qemu
 /// -serial stdio` be used to type/pipe input into the shell instead of the
 /// QEMU-monitor `sendkey` workaround.
-extern "x86-interrupt" fn serial_interrupt_handler(_: &mut ExceptionStackFrame) {
+fn serial_irq() {
     use x86_64::instructions::port::Port;
     const LSR: u16 = 0x3FD;
     const RBR: u16 = 0x3F8;
@@ -102,30 +134,18 @@ extern "x86-interrupt" fn serial_interrupt_handler(_: &mut ExceptionStackFrame)
         // The 16550 FIFO may hold several bytes by the time we get to run.
         while lsr.read() & DATA_READY != 0 {
             let byte = rbr.read();
-            // Same ISIG line discipline the PS/2 path goes through (see
-            // `keyboard::push`/`tty::feed_input`) — a byte consumed as a
-            // signal (Ctrl-C over `-serial stdio`, say) never becomes input,
-            // so skip the wakeups too: there's nothing new for a stdin
+            // Same line discipline the PS/2 path goes through (see
+            // `keyboard::push`/`tty::feed_input`), which queues the byte
+            // itself — one consumed as a signal (Ctrl-C over `-serial
+            // stdio`, say) or still buffered in an unfinished cooked-mode
+            // line skips the wakeups: there's nothing new for a stdin
             // reader to consume.
             if crate::tty::feed_input(byte as char) {
-                crate::keyboard_buffer::KEYBOARD_BUFFER.push(byte as char);
                 crate::process::syscall::stdin_wakeup();
-                crate::process::syscall::poll_wakeup_for_fd0();
+                crate::process::syscall::poll_wakeup_ready();
             }
         }
     }
-    crate::interrupts::pic::end_of_interrupt(crate::interrupts::pic::Irq::Com1.as_u8());
-}
-
-/// IRQ12 — PS/2 auxiliary device (mouse). Each byte belongs to a 3-byte
-/// packet; `mouse::process_byte` does the reassembly/decode, same shape
-/// as `keyboard::process_scancode` does for IRQ1.
-extern "x86-interrupt" fn mouse_interrupt_handler(_: &mut ExceptionStackFrame) {
-    let data = unsafe {
-        x86_64::instructions::port::PortReadOnly::<u8>::new(0x60).read()
-    };
-    crate::mouse::process_byte(data);
-    crate::interrupts::pic::end_of_interrupt(crate::interrupts::pic::Irq::Mouse.as_u8());
 }
 
 extern "x86-interrupt" fn divide_by_zero_handler(sf: &mut ExceptionStackFrame) {
@@ -133,7 +153,41 @@ extern "x86-interrupt" fn divide_by_zero_handler(sf: &mut ExceptionStackFrame) {
         kill_current_user_process("DIVIDE BY ZERO");
         // unreachable — kill_current_user_process diverges
     }
-    panic!("DIVIDE BY ZERO at {:#x}", sf.instruction_pointer);
+    panic!("DIVIDE BY ZERO at {}", crate::symbols::Sym(sf.instruction_pointer));
+}
+
+/// Set while `nmi_handler` is running. The CPU itself blocks further NMIs
+/// until the handler's `iretq` — but *any* `iretq` ends that window,
+/// including one returning from a fault taken inside the handler. A second
+/// NMI after that would start again at the top of the same IST stack and
+/// overwrite this one's frame, so returning would jump to garbage.
+static IN_NMI: AtomicBool = AtomicBool::new(false);
+
+/// NMI — nothing in this kernel raises one on purpose (no watchdog, no
+/// perf counters), so one arriving means hardware (an error line, QEMU's
+/// `nmi` monitor command) wants attention: dump where the CPU was, in the
+/// panic format, and resume. Runs on its own IST stack
+/// (`tss::NMI_IST_INDEX`). A nested one (see `IN_NMI`) can't resume,
+/// because the frame it would return through is gone — it halts instead.
+extern "x86-interrupt" fn nmi_handler(sf: &mut ExceptionStackFrame) {
+    if IN_NMI.swap(true, Ordering::Acquire) {
+        crate::serial_println_raw!("\n=== NESTED NMI at {:#x} — outer frame clobbered, halting ===", sf.instruction_pointer);
+        loop { unsafe { core::arch::asm!("cli; hlt"); } }
+    }
+    crate::debug::inc_nmis();
+    crate::panic::dump_exception("NMI", sf);
+    IN_NMI.store(false, Ordering::Release);
+}
+
+/// #MC — only delivered once `cpu::mce::init` has set CR4.MCE (otherwise
+/// the CPU resets). Never recoverable here (see `cpu/mce.rs`), so: dump
+/// the frame and the MCA banks and halt. Own IST stack
+/// (`tss::MACHINE_CHECK_IST_INDEX`) since the error may be in whatever
+/// the current stack was using.
+extern "x86-interrupt" fn machine_check_handler(sf: &mut ExceptionStackFrame) {
+    crate::panic::dump_exception("MACHINE CHECK (#MC)", sf);
+    crate::cpu::mce::dump_banks();
+    loop { unsafe { core::arch::asm!("cli; hlt"); } }
 }
 
 extern "x86-interrupt" fn invalid_opcode_handler(sf: &mut ExceptionStackFrame) {
@@ -141,14 +195,45 @@ extern "x86-interrupt" fn invalid_opcode_handler(sf: &mut ExceptionStackFrame) {
         kill_current_user_process("INVALID OPCODE");
         // unreachable — kill_current_user_process diverges
     }
-    panic!("INVALID OPCODE at {:#x}", sf.instruction_pointer);
+    panic!("INVALID OPCODE at {}", crate::symbols::Sym(sf.instruction_pointer));
+}
+
+/// #NM — only raised with CR0.TS set (or CR0.EM, which `fpu::enable_sse`
+/// clears), and nothing sets TS: FPU state is switched eagerly, see the
+/// "Why eager" note in `process/fpu.rs`. Reaching this means that
+/// assumption broke, so it's treated like any other fault rather than
+/// silently clearing TS and carrying on with someone else's registers.
+extern "x86-interrupt" fn device_not_available_handler(sf: &mut ExceptionStackFrame) {
+    if sf.code_segment & 0x3 != 0 {
+        kill_current_user_process("DEVICE NOT AVAILABLE (#NM)");
+    }
+    panic!("DEVICE NOT AVAILABLE (#NM) at {} — CR0.TS set?", crate::symbols::Sym(sf.instruction_pointer));
+}
+
+/// #MF — an unmasked x87 exception (the default x87 control word masks
+/// them all, so a program has to have unmasked one itself).
+extern "x86-interrupt" fn x87_floating_point_handler(sf: &mut ExceptionStackFrame) {
+    if sf.code_segment & 0x3 != 0 {
+        kill_current_user_process("x87 FLOATING-POINT EXCEPTION (#MF)");
+    }
+    panic!("x87 FLOATING-POINT EXCEPTION (#MF) at {}", crate::symbols::Sym(sf.instruction_pointer));
+}
+
+/// #XM — an unmasked SSE exception in MXCSR. Delivered here rather than as
+/// #UD because `fpu::enable_sse` sets CR4.OSXMMEXCPT. The kernel target is
+/// soft-float, so only user code can raise it in practice.
+extern "x86-interrupt" fn simd_floating_point_handler(sf: &mut ExceptionStackFrame) {
+    if sf.code_segment & 0x3 != 0 {
+        kill_current_user_process("SIMD FLOATING-POINT EXCEPTION (#XM)");
+    }
+    panic!("SIMD FLOATING-POINT EXCEPTION (#XM) at {}", crate::symbols::Sym(sf.instruction_pointer));
 }
 
 extern "x86-interrupt" fn double_fault_handler(
     sf: &mut ExceptionStackFrame,
     error_code: u64
 ) -> ! {
-    panic!("DOUBLE FAULT (error: {}) at {:#x}", error_code, sf.instruction_pointer);
+    panic!("DOUBLE FAULT (error: {}) at {}", error_code, crate::symbols::Sym(sf.instruction_pointer));
 }
 
 extern "x86-interrupt" fn general_protection_fault_handler(
@@ -159,7 +244,7 @@ extern "x86-interrupt" fn general_protection_fault_handler(
         kill_current_user_process("GENERAL PROTECTION FAULT");
         // unreachable — kill_current_user_process diverges
     }
-    panic!("GENERAL PROTECTION FAULT (error: {}) at {:#x}", error_code, sf.instruction_pointer);
+    panic!("GENERAL PROTECTION FAULT (error: {}) at {}", error_code, crate::symbols::Sym(sf.instruction_pointer));
 }
 
 /// Page fault handler — bridges memory and process layers.
@@ -169,6 +254,11 @@ extern "x86-interrupt" fn general_protection_fault_handler(
 ///   2. VMA lookup via scheduler
 ///   3. Map page via demand_paging::map_demand_page
 ///   4. On failure: kill user process OR panic (kernel fault)
+///
+/// Runs on its own IST stack (`tss::PAGE_FAULT_IST_INDEX`), so it must
+/// never fault on an unmapped address itself — a nested #PF would reset
+/// RSP to the same stack top and overwrite this frame. Nothing here
+/// touches user memory or blocks, so that holds today.
 extern "x86-interrupt" fn page_fault_handler(
     sf: &mut ExceptionStackFrame,
     error_code: u64
@@ -181,6 +271,29 @@ extern "x86-interrupt" fn page_fault_handler(
 
     let _ = sf; // ExceptionStackFrame values unreliable for user-mode PFs
 
+    // ── Kernel stack overflow ─────────────────────────────────────
+    //
+    // A kernel-mode fault inside the running process's kernel stack guard
+    // page (see `init::processes::allocate_kernel_stack`). Checked first
+    // so it gets named as what it is instead of the generic "kernel,
+    // not demand-pageable" panic below. Only reachable at all because
+    // this handler runs on its own IST stack — on the overflowed one it
+    // would have double-faulted. RSP0 in the TSS is the running process's
+    // stack top (set on every switch), so no scheduler lock is needed.
+    if !is_user {
+        let rsp0 = unsafe { crate::process::tss::KERNEL_RSP0 };
+        if rsp0 != 0
+            && crate::init::processes::kernel_stack_guard_range(x86_64::VirtAddr::new(rsp0))
+                .contains(&fault_addr)
+        {
+            panic!(
+                "kernel stack overflow for PID {}\n  Address: {:#x} (guard page)\n  RIP: {}\n  RSP: {:#x}",
+                crate::process::scheduler::current_pid_fast(),
+                fault_addr, crate::symbols::Sym(sf.instruction_pointer), sf.stack_pointer
+            );
+        }
+    }
+
     // ── COW write fault: page present + write, no reserved bit ───
     //
     // This must be checked BEFORE is_demand_pageable, which returns Err
@@ -209,7 +322,12 @@ extern "x86-interrupt" fn page_fault_handler(
         && (error_code & PF_RESERVED) == 0
     {
         let handled = unsafe {
-            if let Some((_, vma)) = crate::process::scheduler::find_vma_fast(fault_addr) {
+            // A write to a present `Shared` page can only be a write to a
+            // read-only mapping — there's nothing to copy-on-write there,
+            // and copying would quietly unshare it.
+            if let Some((_, vma)) = crate::process::scheduler::find_vma_fast(fault_addr)
+                .filter(|(_, vma)| vma.kind != crate::memory::vma::VmaKind::Shared)
+            {
                 let vma_flags = vma.page_table_flags();
                 crate::process::scheduler::current_as_fast()
                     .map(|as_| as_.handle_cow_fault(fault_addr, vma_flags))
@@ -224,7 +342,7 @@ extern "x86-interrupt" fn page_fault_handler(
             return;
         }
 
-        serial_println!(
+        crate::warn!(
             "⚠️  COW fault failed at {:#x} (error {:#b})",
             fault_addr, error_code
         );
@@ -235,7 +353,7 @@ extern "x86-interrupt" fn page_fault_handler(
     // Step 1: Is this fault potentially demand-pageable?
     if let Err(reason) = demand_paging::is_demand_pageable(error_code) {
         if is_user {
-            serial_println!(
+            crate::warn!(
                 "⚠️  User page fault at {:#x} (error {:#b}): {}",
                 fault_addr, error_code, reason
             );
@@ -244,8 +362,8 @@ extern "x86-interrupt" fn page_fault_handler(
         }
         let (cr3, _) = x86_64::registers::control::Cr3::read();
         panic!(
-            "PAGE FAULT (kernel)\n  Address: {:#x}\n  Error: {:#b}\n  Reason: {}\n  RIP: {:#x}\n  CS: {:#x}\n  RSP: {:#x}\n  CR3: {:#x}\n  running PID: {}",
-            fault_addr, error_code, reason, sf.instruction_pointer, sf.code_segment, sf.stack_pointer,
+            "PAGE FAULT (kernel)\n  Address: {:#x}\n  Error: {:#b}\n  Reason: {}\n  RIP: {}\n  CS: {:#x}\n  RSP: {:#x}\n  CR3: {:#x}\n  running PID: {}",
+            fault_addr, error_code, reason, crate::symbols::Sym(sf.instruction_pointer), sf.code_segment, sf.stack_pointer,
             cr3.start_address().as_u64(),
             crate::process::scheduler::current_pid_fast()
         );
@@ -258,7 +376,7 @@ extern "x86-interrupt" fn page_fault_handler(
         Some(result) => result,
         None => {
             if is_user {
-                serial_println!(
+                crate::warn!(
                     "⚠️  Segfault: PID {} accessed {:#x} (no VMA)",
                     crate::process::scheduler::current_pid_fast(), fault_addr
                 );
@@ -266,8 +384,8 @@ extern "x86-interrupt" fn page_fault_handler(
                 // unreachable — kill_current_user_process diverges
             }
             panic!(
-                "PAGE FAULT (kernel, no VMA)\n  Address: {:#x}\n  Error: {:#b}\n  RIP: {:#x}",
-                fault_addr, error_code, sf.instruction_pointer
+                "PAGE FAULT (kernel, no VMA)\n  Address: {:#x}\n  Error: {:#b}\n  RIP: {}",
+                fault_addr, error_code, crate::symbols::Sym(sf.instruction_pointer)
             );
         }
     };
@@ -275,7 +393,7 @@ extern "x86-interrupt" fn page_fault_handler(
     // Step 3: Map the page (passes is_write for zero-page optimisation).
     if let Err(reason) = demand_paging::map_demand_page(fault_addr, &vma, pid, is_write) {
         if is_user {
-            serial_println!(
+            crate::warn!(
                 "⚠️  Demand paging failed for PID {}: {} (addr {:#x})",
                 pid, reason, fault_addr
             );
@@ -283,11 +401,13 @@ extern "x86-interrupt" fn page_fault_handler(
             // unreachable — kill_current_user_process diverges
         }
         panic!(
-            "PAGE FAULT (kernel, map failed)\n  Address: {:#x}\n  Reason: {}\n  RIP: {:#x}",
-            fault_addr, reason, sf.instruction_pointer
+            "PAGE FAULT (kernel, map failed)\n  Address: {:#x}\n  Reason: {}\n  RIP: {}",
+            fault_addr, reason, crate::symbols::Sym(sf.instruction_pointer)
         );
     }
 
+    crate::debug::inc_demand_faults();
+
     // Success — CPU retries the faulting instruction on iret.
 }
 
@@ -322,7 +442,7 @@ fn kill_current_user_process(reason: &str) -> ! {
         // `self.running`.
         let (dead_pid, parent_pid) = match scheduler.running_mut() {
             Some(proc) => {
-                proc.killed_by_signal = Some(crate::process::signal::SIGSEGV);
+                proc.exit_status = crate::process::wait_status::ExitReason::killed_by(crate::process::signal::SIGSEGV);
                 let parent = if proc.is_thread { None } else { proc.parent_pid };
                 (proc.pid.0, parent)
             }
@@ -333,9 +453,9 @@ fn kill_current_user_process(reason: &str) -> ! {
         scheduler.notify_child_death(dead_pid, parent_pid);
         // Same side-table cleanup `sys_exit`/the uncaught-signal path do —
         // see `process::syscall::cancel_all_waiters`'s doc comment.
-        crate::process::syscall::cancel_all_waiters(dead_pid);
+        crate::process::syscall::cancel_all_waiters(dead_pid, &mut scheduler);
 
-        serial_println!("  → Switching to next process (full TrapFrame restore)");
+        crate::debug!("  → Switching to next process (full TrapFrame restore)");
         ptr
         // Lock is dropped here before we jump
     };
@@ -362,9 +482,10 @@ extern "x86-interrupt" fn timer_handler(_sf: &mut ExceptionStackFrame) {
 pub fn draw_boot_screen() {
     let mut fb = framebuffer::FRAMEBUFFER.lock();
     if let Some(fb) = fb.as_mut() {
-        fb.clear(Color::rgb(0, 0, 0));
-        fb.draw_text(10, 10, "ConstanOS v0.1", Color::rgb(0, 200, 255), Color::rgb(0, 0, 0), 2);
-        fb.draw_text(10, 770, "Allocator: Ready", Color::rgb(0, 255, 0), Color::rgb(0, 0, 0), 2);
+        fb.clear(Color::BLACK);
+        fb.draw_text(10, 10, "ConstanOS v0.1", Color::rgb(0, 200, 255), Color::BLACK, 2);
+        fb.draw_text(10, 770, "Allocator: Ready", Color::rgb(0, 255, 0), Color::BLACK, 2);
+        fb.present_dirty();
     }
 }
 
@@ -377,5 +498,5 @@ pub fn init_hardware_interrupts() {
     load_idt();
 
     crate::serial::init_interrupts();
-    crate::pit::init(100);
+    crate::pit::init(crate::time::clockevent::TICK_HZ);
 }
\ No newline at end of file
@@ -13,22 +13,26 @@ use x86_64::VirtAddr;
 use crate::{
     allocator,
     memory,
-    serial_println,
+    debug,
+    error,
+    info,
+    log::{self, Level},
 };
 
 /// Initialize all memory subsystems in order:
 /// phys offset → buddy → slab (slab uses buddy internally).
 pub fn init_core(phys_mem_offset: VirtAddr, memory_regions: &'static MemoryRegions) {
-    serial_println!("Physical memory offset: {:#x} (PML4 entry {})",
+    info!("Physical memory offset: {:#x} (PML4 entry {})",
         phys_mem_offset.as_u64(),
         phys_mem_offset.as_u64() >> 39
     );
 
     memory::init(phys_mem_offset);
+    memory::memmap::record(memory_regions);
 
     // Initialize Buddy allocator — sole owner of all usable physical memory.
     {
-        let mut buddy = allocator::buddy_allocator::BUDDY.lock();
+        let mut buddy = allocator::buddy_allocator::lock_buddy();
 
         for region in memory_regions.iter() {
             if region.kind == MemoryRegionKind::Usable {
@@ -39,9 +43,9 @@ pub fn init_core(phys_mem_offset: VirtAddr, memory_regions: &'static MemoryRegio
         }
     }
 
-    serial_println!("Buddy stats:");
-    {
-        let buddy = allocator::buddy_allocator::BUDDY.lock();
+    if log::enabled(Level::Debug) {
+        debug!("Buddy stats:");
+        let buddy = allocator::buddy_allocator::lock_buddy();
         buddy.debug_print_stats();
     }
 }
@@ -55,37 +59,82 @@ pub fn test_allocators() {
         let ptr = unsafe { alloc::alloc::alloc(layout) };
 
         if ptr.is_null() {
-            serial_println!("  FAILED: Got null pointer");
+            error!("  FAILED: Got null pointer");
             panic!("Slab allocation failed");
         } else {
-            serial_println!("  SUCCESS: Got pointer {:#x}", ptr as u64);
+            debug!("  SUCCESS: Got pointer {:#x}", ptr as u64);
             unsafe {
                 *(ptr as *mut u64) = 0xDEADBEEF;
                 let val = *(ptr as *const u64);
-                serial_println!("  Write/read test: {:#x}", val);
+                debug!("  Write/read test: {:#x}", val);
                 assert_eq!(val, 0xDEADBEEF);
                 alloc::alloc::dealloc(ptr, layout);
             }
-            serial_println!("  SUCCESS: Deallocation complete");
+            debug!("  SUCCESS: Deallocation complete");
         }
     }
 
     {
         use alloc::vec::Vec;
-        serial_println!("  Creating Vec...");
+        debug!("  Creating Vec...");
         let mut v: Vec<u8> = Vec::new();
         v.push(1);
         v.push(2);
         v.push(3);
-        serial_println!("  Vec OK: len={}", v.len());
+        debug!("  Vec OK: len={}", v.len());
     }
 
     {
         use alloc::string::String;
-        serial_println!("  Creating String...");
+        debug!("  Creating String...");
         let s = String::from("Hello Slab!");
-        serial_println!("  String test: {}", s);
+        debug!("  String test: {}", s);
+    }
+
+    if log::enabled(Level::Debug) {
+        allocator::slab::slab_stats();
+    }
+}
+/// Time the slab (64-byte `Box`) and buddy (order-0 frame) alloc/free
+/// paths with the TSC. Runs right after `cpu::tsc::init()` so there's a
+/// frequency to convert with; logged in ns per op when the TSC is
+/// invariant, raw cycles per op otherwise (see `cpu::tsc::tsc_to_ns`).
+/// Debug log level only — skipped entirely otherwise.
+pub fn bench_allocators() {
+    use crate::cpu::tsc;
+
+    if !log::enabled(Level::Debug) {
+        return;
+    }
+
+    const ROUNDS: u64 = 1000;
+
+    fn report(what: &str, start: u64) {
+        match tsc::elapsed_ns(start) {
+            Some(ns) => debug!("alloc bench: {} {} ns/op", what, ns / ROUNDS),
+            None => debug!(
+                "alloc bench: {} {} cycles/op (TSC not invariant)",
+                what, tsc::now().wrapping_sub(start) / ROUNDS
+            ),
+        }
+    }
+
+    let start = tsc::now();
+    for i in 0..ROUNDS {
+        let b = alloc::boxed::Box::new([i; 8]);
+        core::hint::black_box(&b);
     }
+    report("slab 64B alloc+free", start);
 
-    allocator::slab::slab_stats();
-}
\ No newline at end of file
+    let start = tsc::now();
+    for _ in 0..ROUNDS {
+        match unsafe { allocator::phys_alloc(12) } {
+            Some(frame) => unsafe { allocator::phys_free(frame, 12) },
+            None => {
+                error!("alloc bench: buddy out of memory");
+                return;
+            }
+        }
+    }
+    report("buddy 4K alloc+free", start);
+}
@@ -13,12 +13,16 @@ use bootloader_api::BootInfo;
 use x86_64::VirtAddr;
 
 use crate::{
-    framebuffer::{Framebuffer, init_global_framebuffer},
+    framebuffer::{Framebuffer, PixelFormat, init_global_framebuffer},
+    debug,
+    info,
+    log::{self, Level},
     process,
-    serial_println,
 };
 
 pub fn boot(boot_info: &'static mut BootInfo) -> ! {
+    // First, so every line of boot output goes out at a known baud rate.
+    crate::serial::init();
     devices::init_idt();
 
     // ── Framebuffer setup ──────────────────────────────────────────
@@ -36,6 +40,7 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
         info.height as usize,
         info.stride as usize,
         info.bytes_per_pixel as usize,
+        PixelFormat::from_bootloader(info.pixel_format),
     );
 
     init_global_framebuffer(framebuffer);
@@ -52,6 +57,12 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
 
     memory::test_allocators();
 
+    // ── CPU features ───────────────────────────────────────────────
+    // CPUID once, cached — everything below that enables an optional
+    // feature gates on `cpu::features::features()` instead of assuming it.
+    crate::cpu::features::init();
+    crate::cpu::mce::init();
+
     // ── ACPI tables ────────────────────────────────────────────────
     // Best-effort, parse-only (bounded, never hangs boot) — see
     // `acpi::AcpiDriver`. Does NOT touch the existing 8259 PIC/IDT
@@ -66,6 +77,10 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
     crate::hal::run_all(&mut [&mut acpi_driver]);
 
     // ── Boot screen ────────────────────────────────────────────────
+    // From here on everything draws into a RAM back buffer and is flushed
+    // to video memory by dirty rectangle (`Framebuffer::present_dirty`);
+    // it needs the heap, hence not at `init_global_framebuffer` above.
+    crate::framebuffer::enable_global_back_buffer();
     devices::draw_boot_screen();
 
     // ── Hardware interrupts ────────────────────────────────────────
@@ -79,6 +94,11 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
     let mut mouse_driver = crate::mouse::MouseDriver::new();
     crate::hal::run_all(&mut [&mut mouse_driver]);
 
+    // ── PCI inventory ───────────────────────────────────────────────
+    // Read-only config-space scan, logged for reference (also live at
+    // /proc/pci). Nothing binds drivers from it — see pci.rs.
+    crate::pci::log_devices();
+
     // ── AC97 audio ──────────────────────────────────────────────────
     // Best-effort (bounded polls, never hangs boot) — see ac97::Ac97Driver.
     // Needs phys_alloc/physical_memory_offset, both already up from
@@ -91,18 +111,28 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
     // ── TSC calibration ────────────────────────────────────────────
     // PIT is now running; interrupts still masked — safe to busy-poll.
     crate::cpu::tsc::init();
-    serial_println!("TSC: {} MHz", crate::cpu::tsc::freq_hz() / 1_000_000);
+    info!("TSC: {} MHz", crate::cpu::tsc::freq_hz() / 1_000_000);
+    memory::bench_allocators();
+
+    // ── Tick source ────────────────────────────────────────────────
+    // Local APIC timer if CPUID reports one, calibrated against the PIT
+    // just like the TSC above; otherwise the PIT's IRQ0 stays the tick.
+    // Same `TICK_HZ` either way.
+    let hz = crate::time::clockevent::TICK_HZ;
+    if !crate::lapic::init_timer(hz) {
+        info!("tick: PIT IRQ0 at {} Hz", hz);
+    }
 
     // ── Time subsystem ─────────────────────────────────────────────
     crate::time::init();
-    serial_println!("clocksource: {}", crate::time::clocksource::clocksource_name());
+    info!("clocksource: {}", crate::time::clocksource::clocksource_name());
 
     // ── VFS ────────────────────────────────────────────────────────
     crate::fs::init();
-    serial_println!("VFS: initramfs @ /bin, devfs @ /dev");
+    info!("VFS: initramfs @ /bin, devfs @ /dev");
 
     // ── TSS + GDT ──────────────────────────────────────────────────
-    serial_println!("Step 9: Initializing TSS and GDT");
+    info!("Step 9: Initializing TSS and GDT");
     process::tss::init();
     process::tss::init_syscall_msrs();
 
@@ -113,10 +143,12 @@ pub fn boot(boot_info: &'static mut BootInfo) -> ! {
     process::fpu::init();
 
     // ── Processes ──────────────────────────────────────────────────
-    serial_println!("\nStep 10: Creating processes");
+    info!("\nStep 10: Creating processes");
     processes::init_all();
-    processes::debug_file_descriptors();
+    if log::enabled(Level::Debug) {
+        processes::debug_file_descriptors();
+    }
 
-    serial_println!("DEBUG: About to start first process");
+    debug!("DEBUG: About to start first process");
     process::start_first_process();
 }
\ No newline at end of file
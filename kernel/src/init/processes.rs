@@ -8,12 +8,13 @@
 //   - Process creation is unified: both paths produce an AddressSpace + entry
 //     point + stack, which feed into Process::new_user().
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 use x86_64::VirtAddr;
 
 use crate::{
     memory::{
         address_space::AddressSpace,
+        elf_loader::UserLayout,
         vma::{Vma, VmaKind},
     },
     process::{
@@ -21,7 +22,10 @@ use crate::{
         Pid, Process,
         user_programs::ProgramSource,
     },
+    debug,
+    info,
     serial_println,
+    warn,
 };
 
 // ============================================================================
@@ -30,12 +34,13 @@ use crate::{
 
 /// Create all processes: idle, user programs.
 pub fn init_all() {
-    serial_println!("\n🔧 Creating processes with isolated address spaces...");
+    info!("\n🔧 Creating processes with isolated address spaces...");
 
+    crate::process::user_test_fileio::validate_layout();
     create_idle_process();
     create_user_processes();
 
-    serial_println!("✅ All processes created!\n");
+    info!("✅ All processes created!\n");
 }
 
 /// Print open file descriptors for every process (debug).
@@ -100,6 +105,14 @@ fn kernel_stack_base(stack_top: VirtAddr) -> (VirtAddr, x86_64::PhysAddr) {
     (virt_base, phys_base)
 }
 
+/// The guard page of the kernel stack whose top is `stack_top` — the
+/// range a kernel-mode page fault lands in when that stack overflows
+/// (`init::devices::page_fault_handler` checks the running process's).
+pub fn kernel_stack_guard_range(stack_top: VirtAddr) -> core::ops::Range<u64> {
+    let (virt_base, _) = kernel_stack_base(stack_top);
+    virt_base.as_u64()..virt_base.as_u64() + 4096
+}
+
 /// Return a kernel stack (as returned by `allocate_kernel_stack`) to the Buddy.
 ///
 /// Callers must make sure the CPU isn't still executing on this stack —
@@ -131,9 +144,13 @@ pub fn free_kernel_stack(stack_top: VirtAddr) {
 /// `free_kernel_stack` unconditionally from `tick()`) froze the kernel
 /// solid (idle task never reached its `hlt`, vCPU pegged at ~25% CPU)
 /// within a second or two of boot.
+///
+/// `lock_buddy` now masks interrupts for as long as it's held, so on this
+/// single core the interrupted code can no longer be holding it and the
+/// `try` can't fail; it stays non-blocking for when another CPU could be.
 pub fn try_free_kernel_stack(stack_top: VirtAddr) -> bool {
     let (virt_base, phys_base) = kernel_stack_base(stack_top);
-    match crate::allocator::buddy_allocator::BUDDY.try_lock() {
+    match crate::allocator::buddy_allocator::try_lock_buddy() {
         Some(mut buddy) => {
             unsafe {
                 // Page-table-only, no locks involved — safe to do
@@ -169,16 +186,16 @@ fn create_idle_process() {
 
     {
         let mut scheduler = crate::process::scheduler::local_scheduler();
-        scheduler.add_process(idle_proc);
+        scheduler.add_process(idle_proc).expect("idle is the first process");
     }
 
-    serial_println!("✅ Created idle process (PID 0)");
+    debug!("✅ Created idle process (PID 0)");
 }
 
 /// Create user processes from the embedded program registry.
 ///
-/// For each program in user_programs::list_programs(), spawns one
-/// process using either the ELF loader or the legacy raw-code path.
+/// Only the userspace `shell` is auto-started (see `spawn_user`), plus the
+/// `yield` test pair; every other program is exec'd on demand from it.
 fn create_user_processes() {
     let programs = process::user_programs::list_programs();
     process::user_programs::print_available();
@@ -187,64 +204,155 @@ fn create_user_processes() {
         // Only auto-start the userspace shell; other programs are exec'd on demand.
         if *name != "shell" { continue; }
 
-        serial_println!("\n📝 Loading program '{}' (index {})", name, i);
-
-        let result = match source {
-            ProgramSource::Elf(elf_bytes) => load_elf_process(elf_bytes, i),
-            ProgramSource::RawCode { code_ptr, code_size } => {
-                load_raw_process(code_ptr(), *code_size, i)
-            }
-        };
+        debug!("\n📝 Loading program '{}' (index {})", name, i);
 
-        let (address_space, entry_point, user_stack_top) = match result {
-            Ok(v) => v,
+        // argv[0] is the program's path, as if exec'd from /bin — there's
+        // no caller to inherit an environment from, so envp is empty.
+        let argv = [alloc::format!("/bin/{}", name).into_bytes()];
+        let pid = match spawn_user(name, source, i, 5, &UserLayout::default(), &argv, &[]) {
+            Ok(pid) => pid,
             Err(e) => {
-                serial_println!("❌ Failed to load '{}': {}", name, e);
+                warn!("❌ Failed to load '{}': {}", name, e);
                 continue;
             }
         };
 
-        // ── Allocate PID and create process ───────────────────────────
+        // The shell is the only process spawned at boot (everything
+        // else is exec'd on demand from it) — it becomes the tty's
+        // initial foreground process group (its own pgid, set by
+        // `Process::new_user`), so Ctrl-C/Ctrl-Z at the keyboard/serial
+        // reach it (and whatever job it later brings to the foreground
+        // via `tcsetpgrp`) instead of going nowhere. See `tty.rs`.
+        //
+        // It's also init: the signals that would kill or stop it are
+        // dropped (`process::signal`'s INIT section).
+        crate::tty::FOREGROUND_PGID.store(pid.0 as u32, core::sync::atomic::Ordering::Relaxed);
+        crate::process::signal::set_init_pid(pid.0);
+
+        info!("✅ Created user process '{}' (PID {})", name, pid.0);
+    }
 
-        let kernel_stack = allocate_kernel_stack();
+    #[cfg(feature = "boot-yield-pair")]
+    spawn_yield_pair();
+}
 
-        let pid = {
-            let mut scheduler = crate::process::scheduler::local_scheduler();
-            scheduler.allocate_pid()
-        };
+/// Start two copies of the `yield` user test (`user_test_fileio`'s TEST 4)
+/// next to the shell: their `Y<pid>` markers must alternate strictly in
+/// the serial log — the check that `sched_yield` really hands the CPU
+/// over. Each runs 16 rounds and exits. Only built with the
+/// `boot-yield-pair` feature (`cargo run --features boot-yield-pair`);
+/// a single copy can still be started at runtime with `kdebug run yield`.
+#[cfg(feature = "boot-yield-pair")]
+fn spawn_yield_pair() {
+    for _ in 0..2 {
+        match spawn_user_test("yield") {
+            Ok(pid) => info!("✅ Created user test 'yield' (PID {})", pid.0),
+            Err(e) => warn!("❌ Failed to start user test 'yield': {}", e),
+        }
+    }
+}
 
-        // Debug: show all VMAs
-        address_space.dump_vmas(pid.0);
-
-        {
-            let mut user_proc = Box::new(Process::new_user(
-                pid,
-                entry_point,
-                user_stack_top,
-                kernel_stack,
-                address_space,
-            ));
-
-            user_proc.set_name(name);
-            user_proc.set_priority(5);
-            user_proc.exe_name = alloc::format!("/{}", name);
-
-            // The shell is the only process spawned at boot (everything
-            // else is exec'd on demand from it) — it becomes the tty's
-            // initial foreground process group (its own pgid, set by
-            // `Process::new_user`), so Ctrl-C/Ctrl-Z at the keyboard/serial
-            // reach it (and whatever job it later brings to the foreground
-            // via `tcsetpgrp`) instead of going nowhere. See `tty.rs`.
-            if *name == "shell" {
-                crate::tty::FOREGROUND_PGID.store(pid.0 as u32, core::sync::atomic::Ordering::Relaxed);
-            }
+/// Why `spawn_user` failed.
+#[derive(Debug)]
+pub enum SpawnError {
+    /// Building the address space (ELF load or raw-code mapping) failed.
+    Load(&'static str),
+    /// `MAX_PROCESSES` already reached.
+    TooManyProcesses,
+    /// `spawn_user_test`: no user test by that name.
+    NoSuchTest,
+}
 
-            let mut scheduler = crate::process::scheduler::local_scheduler();
-            scheduler.add_process(user_proc);
+impl core::fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            SpawnError::Load(e) => f.write_str(e),
+            SpawnError::TooManyProcesses => f.write_str("too many processes"),
+            SpawnError::NoSuchTest => f.write_str("no such user test"),
         }
+    }
+}
+
+/// Create a user process running `source` and queue it on the scheduler:
+/// address space (ELF loader, or eager raw-code copy), code/stack VMAs,
+/// kernel stack, PID, `Process`. `process_index` offsets the user stack
+/// base when `layout` isn't randomized, and `layout` sets the stack size,
+/// initial heap and randomization (see `elf_loader::UserLayout`; an
+/// out-of-range one is `SpawnError::Load`). Returns the new PID; on
+/// failure nothing is left allocated.
+///
+/// An ELF program starts on the same SysV initial stack `sys_exec` builds
+/// — argc, then `argv`, `envp` and the auxv, `rsp` 16-byte aligned (see
+/// `elf_loader::build_initial_stack`). Raw code gets a bare stack; `argv`
+/// and `envp` are ignored for it.
+pub fn spawn_user(
+    name: &str,
+    source: &ProgramSource,
+    process_index: usize,
+    priority: u8,
+    layout: &UserLayout,
+    argv: &[Vec<u8>],
+    envp: &[Vec<u8>],
+) -> Result<Pid, SpawnError> {
+    let (address_space, entry_point, user_stack_top) = match source {
+        ProgramSource::Elf(elf_bytes) => load_elf_process(elf_bytes, process_index, layout, argv, envp),
+        ProgramSource::RawCode { code } => load_raw_process(code(), process_index, layout),
+    }
+    .map_err(SpawnError::Load)?;
+    queue_user(name, priority, address_space, entry_point, user_stack_top)
+}
+
+/// Start user test `name` (`user_test_fileio::user_tests`) as a new
+/// process with no parent: the `boot-yield-pair` feature's `yield` pair,
+/// and `kdebug run <test>` (`kdebug_ctl` 15) at runtime. `spawn_user`'s
+/// `RawCode` path, for a test picked by name instead of a registry entry.
+pub fn spawn_user_test(name: &str) -> Result<Pid, SpawnError> {
+    let code = process::user_test_fileio::test_code(name).ok_or(SpawnError::NoSuchTest)?;
+    let (address_space, entry_point, user_stack_top) =
+        load_raw_process(code, 0, &UserLayout::default()).map_err(SpawnError::Load)?;
+    queue_user(name, 5, address_space, entry_point, user_stack_top)
+}
+
+/// The part of `spawn_user` after loading: kernel stack, PID, `Process`,
+/// and the scheduler. Frees what it allocated if the scheduler refuses.
+fn queue_user(
+    name: &str,
+    priority: u8,
+    address_space: AddressSpace,
+    entry_point: VirtAddr,
+    user_stack_top: VirtAddr,
+) -> Result<Pid, SpawnError> {
+    let kernel_stack = allocate_kernel_stack();
+
+    // The scheduler lock is only taken for the pid and the final
+    // `add_process`; everything that allocates happens outside it (see
+    // `lock_order`).
+    let pid = crate::process::scheduler::local_scheduler().allocate_pid();
+
+    // Debug: show all VMAs
+    address_space.dump_vmas(pid.0);
 
-        serial_println!("✅ Created user process '{}' (PID {})", name, pid.0);
+    let mut user_proc = Box::new(Process::new_user(
+        pid,
+        entry_point,
+        user_stack_top,
+        kernel_stack,
+        address_space,
+    ));
+
+    user_proc.set_name(name);
+    user_proc.set_priority(priority);
+    user_proc.exe_name = alloc::format!("/{}", name);
+
+    let added = crate::process::scheduler::local_scheduler().add_process(user_proc);
+    if let Err(refused) = added {
+        // Same cleanup as `sys_fork`'s.
+        drop(refused);
+        crate::process::scheduler::local_scheduler().release_pid(pid);
+        free_kernel_stack(kernel_stack);
+        return Err(SpawnError::TooManyProcesses);
     }
+    Ok(pid)
 }
 
 // ============================================================================
@@ -257,14 +365,15 @@ fn create_user_processes() {
 fn load_elf_process(
     elf_bytes: &[u8],
     process_index: usize,
+    layout: &UserLayout,
+    argv: &[Vec<u8>],
+    envp: &[Vec<u8>],
 ) -> Result<(AddressSpace, VirtAddr, VirtAddr), &'static str> {
     let loaded = unsafe {
-        // Boot-time processes (idle/user/shell) start with an empty
-        // argv/envp — nothing here has a caller to inherit them from.
-        crate::memory::elf_loader::load_elf(elf_bytes, process_index, &[], &[])?
+        crate::memory::elf_loader::load_elf(elf_bytes, process_index, layout, argv, envp)?
     };
 
-    serial_println!(
+    debug!(
         "  ELF loaded: entry={:#x} stack_top={:#x}",
         loaded.entry_point.as_u64(),
         loaded.user_stack_top.as_u64(),
@@ -276,12 +385,22 @@ fn load_elf_process(
 /// Legacy loader: manually map raw code bytes (inline assembly tests).
 ///
 /// This replicates the old create_user_processes logic for backward
-/// compatibility until all programs are ELF binaries.
+/// compatibility until all programs are ELF binaries. Honors `layout`'s
+/// stack size and randomization; there's no heap region for raw code.
+/// Maps as many pages as `code` spans, up to `USER_CODE_SIZE`.
 fn load_raw_process(
-    code_ptr: *const u8,
-    code_size: usize,
+    code: &[u8],
     process_index: usize,
+    layout: &UserLayout,
 ) -> Result<(AddressSpace, VirtAddr, VirtAddr), &'static str> {
+    layout.validate()?;
+    if code.is_empty() {
+        return Err("Raw code is empty");
+    }
+    if code.len() > crate::memory::user_code::USER_CODE_SIZE {
+        return Err("Raw code too large");
+    }
+
     // ── 1. Create address space ───────────────────────────────────────
 
     let mut address_space = unsafe {
@@ -289,15 +408,15 @@ fn load_raw_process(
             .map_err(|_| "Failed to create user address space")?
     };
 
-    serial_println!(
+    debug!(
         "  Legacy: address space PML4 at {:#x}",
         address_space.root_frame().start_address().as_u64(),
     );
 
     // ── 2. Map user code eagerly ──────────────────────────────────────
 
-    let code_start = 0x0000_0000_0040_0000_u64;
-    let num_code_pages = (code_size + 4095) / 4096;
+    let code_start = crate::memory::user_code::USER_CODE_BASE;
+    let num_code_pages = code.len().div_ceil(4096);
 
     let flags = x86_64::structures::paging::PageTableFlags::PRESENT
               | x86_64::structures::paging::PageTableFlags::USER_ACCESSIBLE;
@@ -312,15 +431,11 @@ fn load_raw_process(
             let frame = address_space.map_user_page(page, flags)
                 .map_err(|_| "Failed to map code page")?;
 
-            let src = code_ptr.add(page_idx * 4096);
-            let copy_size = code_size.saturating_sub(page_idx * 4096).min(4096);
+            let chunk = &code[page_idx * 4096..code.len().min((page_idx + 1) * 4096)];
 
             let dst = (phys_offset + frame.start_address().as_u64()).as_mut_ptr::<u8>();
-            core::ptr::copy_nonoverlapping(src, dst, copy_size);
-
-            if copy_size < 4096 {
-                core::ptr::write_bytes(dst.add(copy_size), 0, 4096 - copy_size);
-            }
+            // The tail past `chunk` is already zero (`map_user_page`).
+            core::ptr::copy_nonoverlapping(chunk.as_ptr(), dst, chunk.len());
         }
     }
 
@@ -334,8 +449,8 @@ fn load_raw_process(
     }).map_err(|_| "Failed to register code VMA")?;
 
     // Stack VMA (demand-paged)
-    let user_stack_base = 0x0000_7100_0000_0000_u64 + (process_index as u64 * 0x10000);
-    let stack_pages: usize = 16;
+    let user_stack_base = crate::memory::elf_loader::user_stack_base(process_index, layout);
+    let stack_pages = layout.stack_pages;
 
     let stack_flags = x86_64::structures::paging::PageTableFlags::PRESENT
                     | x86_64::structures::paging::PageTableFlags::WRITABLE
@@ -348,11 +463,14 @@ fn load_raw_process(
         kind: VmaKind::Anonymous,
     }).map_err(|_| "Failed to register stack VMA")?;
 
+    // Same (possibly randomized) mmap window as an ELF process gets.
+    address_space.set_mmap_base(crate::memory::elf_loader::user_mmap_base(layout));
+
     let user_stack_top = VirtAddr::new(
         user_stack_base + (stack_pages as u64 * 4096) - 8
     );
 
-    serial_println!(
+    debug!(
         "  Legacy: code={:#x} stack_top={:#x}",
         code_start,
         user_stack_top.as_u64(),
@@ -371,6 +489,9 @@ fn load_raw_process(
 
 fn idle_task() -> ! {
     loop {
+        // Zombies reaped on a blocked parent's behalf are only torn down
+        // once off their own stacks — see `scheduler::reap_pending`.
+        crate::process::scheduler::reap_pending();
         unsafe { core::arch::asm!("hlt"); }
     }
 }
\ No newline at end of file
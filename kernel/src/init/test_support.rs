@@ -33,13 +33,16 @@ use bootloader_api::BootInfo;
 use x86_64::VirtAddr;
 
 /// Boots just enough of the kernel for hardware-path integration tests:
-/// IDT *built* (not loaded — no interrupts are enabled in test mode, so
-/// there is nothing to route to it yet), physical memory offset recorded,
+/// UART programmed (`serial::init`, same as real boot), IDT *built* (not
+/// loaded — no interrupts are enabled in test mode, so there is nothing to
+/// route to it yet), physical memory offset recorded,
 /// Buddy allocator seeded from the bootloader's memory map, the shared
-/// zero-page frame allocated, and the ACPI driver run through the same
+/// zero-page frame allocated, SSE enabled with its FXSAVE template
+/// captured, and the ACPI driver run through the same
 /// best-effort `hal::run_all` registry real boot uses — the one driver
 /// today's test cases need already initialized before `test_main` runs.
 pub fn boot_for_tests(boot_info: &'static mut BootInfo) {
+    crate::serial::init();
     super::devices::init_idt();
 
     let phys_mem_offset = VirtAddr::new(
@@ -57,6 +60,10 @@ pub fn boot_for_tests(boot_info: &'static mut BootInfo) {
         crate::memory::cow::init_zero_frame();
     }
 
+    // Same as real boot: `Process::new_*` copies the template this
+    // captures, and the scheduler tests build processes they never run.
+    crate::process::fpu::init();
+
     // Same driver, same registry call, as the real boot's ACPI step
     // (`init/mod.rs`) — see `hw_tests.rs`'s `acpi_selftest_passes`, the
     // consumer. A future APIC test would add its own driver to this same
@@ -0,0 +1,53 @@
+// kernel/src/interrupts/guard.rs
+//
+// The kernel's one way to run code with interrupts masked.
+//
+// `InterruptGuard` saves RFLAGS.IF, executes `cli`, and on `Drop` executes
+// `sti` only if IF was set when it was taken. Because it restores what it
+// found instead of unconditionally re-enabling, guards nest freely and are
+// safe to take from any context — a syscall body (entered with IF=0 via
+// SFMASK), an ISR (IF=0 from the interrupt gate), or ordinary kernel code
+// with IF=1. `without_interrupts` is the closure form for short critical
+// sections that don't need to span an early `return`.
+//
+// This lived in `process::irq_guard` while syscall bodies were its only
+// users; it moved here once log.rs and tty.rs wanted the same thing and
+// were reaching for `x86_64::instructions::interrupts::without_interrupts`
+// directly. `process::irq_guard::SchedGuard` builds on it.
+//
+// Deliberately NOT converted (they are one-way, not save/restore pairs):
+//   - `panic.rs`'s leading `cli` and the `cli; hlt` loops in
+//     `init/devices.rs` — the CPU never comes back, nothing to restore.
+//   - `trapframe::jump_to_user`'s `cli` — the following `iretq`/`sysretq`
+//     reloads RFLAGS from the user frame, so the "restore" is the return
+//     to user mode itself, and a guard's `Drop` would never run.
+//   - `start_first_process`'s `sti` — the single point where boot hands
+//     interrupts on for the first time; there is no prior state to save.
+
+/// `cli` on construction; on `Drop`, `sti` only if interrupts were
+/// enabled when it was constructed (RFLAGS.IF read via `pushfq`). No lock.
+pub struct InterruptGuard {
+    was_enabled: bool,
+}
+
+impl InterruptGuard {
+    pub fn new() -> Self {
+        let was_enabled = x86_64::instructions::interrupts::are_enabled();
+        unsafe { core::arch::asm!("cli"); }
+        Self { was_enabled }
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        if self.was_enabled {
+            unsafe { core::arch::asm!("sti"); }
+        }
+    }
+}
+
+/// Run `f` with interrupts masked, restoring the previous IF afterwards.
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    let _irq = InterruptGuard::new();
+    f()
+}
@@ -8,8 +8,15 @@
 //     an optional IST index.
 //   - This ensures double faults use a dedicated stack, preventing
 //     triple faults on stack overflow.
+//
+// Gate code selectors come from `process::tss::kernel_code_selector()`
+// (passed to `InterruptDescriptorTable::new`), not a hardcoded 0x08 —
+// that was only right because `tss::init` happens to append the kernel
+// code descriptor first; reordering the GDT would have silently pointed
+// every gate at the wrong segment.
 
 use core::marker::PhantomData;
+use x86_64::structures::gdt::SegmentSelector;
 use crate::interrupts::exception::ExceptionStackFrame;
 
 // ============================================================================
@@ -74,11 +81,11 @@ impl<F> IdtEntry<F> {
         }
     }
 
-    pub fn set_handler_addr(&mut self, addr: u64) -> &mut Self {
+    pub fn set_handler_addr(&mut self, addr: u64, code_selector: SegmentSelector) -> &mut Self {
         self.pointer_low = addr as u16;
         self.pointer_middle = (addr >> 16) as u16;
         self.pointer_high = (addr >> 32) as u32;
-        self.gdt_selector = 8; // Kernel code segment
+        self.gdt_selector = code_selector.0;
         self.options = IdtEntryOptions::interrupt_gate();
         self
     }
@@ -113,11 +120,27 @@ impl<F> IdtEntry<F> {
     /// IST is in the LOW byte (byte 4), bits 0:2.
     /// Gate type, DPL, P are in the HIGH byte (byte 5).
     pub fn set_ist_index(&mut self, index: u16) -> &mut Self {
-        debug_assert!(index <= 7, "IST index must be 0-7, got {}", index);
+        // A hard assert, not debug-only: an out-of-range index would be
+        // masked down to some *other* IST slot and silently run the
+        // handler on the wrong stack.
+        assert!(index <= 7, "IST index must be 0-7, got {}", index);
         // Clear old IST bits (low 3 bits of the u16) and set new ones
         self.options.0 = (self.options.0 & !0x07) | (index & 0x07);
         self
     }
+
+    /// The IST index currently encoded in bits 0:2 (0 = no IST).
+    #[cfg(test)]
+    pub fn ist_index(&self) -> u16 {
+        self.options.0 & 0x07
+    }
+
+    /// Raw type/DPL/P byte (byte 5 of the entry) — for checking that
+    /// setting the IST index left it alone.
+    #[cfg(test)]
+    pub fn gate_byte(&self) -> u8 {
+        (self.options.0 >> 8) as u8
+    }
 }
 
 // ============================================================================
@@ -128,58 +151,95 @@ pub type ExceptionHandler = extern "x86-interrupt" fn(&mut ExceptionStackFrame);
 pub type ExceptionHandlerWithErrCode = extern "x86-interrupt" fn(&mut ExceptionStackFrame, error_code: u64);
 pub type DoubleFaultHandler = extern "x86-interrupt" fn(&mut ExceptionStackFrame, error_code: u64) -> !;
 
+/// Any of the handler signatures above — lets `add_handler_with_ist`
+/// take whichever shape the vector's CPU frame needs.
+pub trait IdtHandler {
+    fn addr(self) -> u64;
+}
+
+impl IdtHandler for ExceptionHandler {
+    fn addr(self) -> u64 { self as usize as u64 }
+}
+
+impl IdtHandler for ExceptionHandlerWithErrCode {
+    fn addr(self) -> u64 { self as usize as u64 }
+}
+
+impl IdtHandler for DoubleFaultHandler {
+    fn addr(self) -> u64 { self as usize as u64 }
+}
+
 // ============================================================================
 // Interrupt Descriptor Table
 // ============================================================================
 
 #[derive(Debug)]
-#[repr(C)]
 pub struct InterruptDescriptorTable {
     pub entries: [IdtEntry<ExceptionHandler>; 256],
+    /// Kernel CS every gate below is installed with.
+    code_selector: SegmentSelector,
 }
 
 impl InterruptDescriptorTable {
-    pub fn new() -> Self {
+    pub fn new(code_selector: SegmentSelector) -> Self {
         InterruptDescriptorTable {
             entries: [IdtEntry::missing(); 256],
+            code_selector,
         }
     }
 
     pub fn add_handler(&mut self, vector: u8, handler: ExceptionHandler) {
-        self.entries[vector as usize]
-            .set_handler_addr(handler as u64);
+        self.add_handler_addr(vector, handler as usize as u64);
     }
 
     pub fn add_handler_with_error(&mut self, vector: u8, handler: ExceptionHandlerWithErrCode) {
-        self.entries[vector as usize]
-            .set_handler_addr(handler as u64);
+        self.add_handler_addr(vector, handler as usize as u64);
+    }
+
+    /// Install a raw entry point (a hand-written asm stub like the timer's
+    /// `timer_interrupt_entry`, which isn't an `extern "x86-interrupt"` fn).
+    pub fn add_handler_addr(&mut self, vector: u8, addr: u64) {
+        let selector = self.code_selector;
+        self.entries[vector as usize].set_handler_addr(addr, selector);
     }
 
-    /// Register a double fault handler with an IST index.
+    /// Register a handler that runs on an IST stack.
     ///
     /// The IST index ensures the CPU switches to a known-good stack
-    /// before invoking the handler.  This prevents triple faults when
-    /// the original stack is corrupted (e.g. stack overflow).
+    /// before invoking the handler, whatever state the interrupted
+    /// stack is in (e.g. a kernel stack overflow into its guard page).
     ///
     /// `ist_index` is 1-based (1..=7), matching TSS.interrupt_stack_table
     /// indices (0-based internally, but the CPU uses 1-based in the IDT).
+    ///
+    /// The CPU resets RSP to the top of the IST stack on *every* entry,
+    /// so a handler using one must never be re-entered while it's still
+    /// running (a nested fault of the same vector would overwrite its
+    /// frame) and must never block or switch away and come back later.
+    pub fn add_handler_with_ist<H: IdtHandler>(&mut self, vector: u8, handler: H, ist_index: u16) {
+        self.add_handler_addr(vector, handler.addr());
+        self.entries[vector as usize]
+            .set_ist_index(ist_index);
+    }
+
+    /// Register a double fault handler with an IST index — without one,
+    /// a stack overflow causes a triple fault (immediate reset).
     pub fn add_double_fault_handler(
         &mut self,
         vector: u8,
         handler: DoubleFaultHandler,
         ist_index: u16,
     ) {
-        self.entries[vector as usize]
-            .set_handler_addr(handler as u64);
-        self.entries[vector as usize]
-            .set_ist_index(ist_index);
+        self.add_handler_with_ist(vector, handler, ist_index);
     }
 
     pub fn load(&'static self) {
         use core::mem::size_of;
+        // Only `entries` is the hardware table — `code_selector` after it
+        // is ours, so neither the limit nor the base is `Self`'s.
         let descriptor = IdtDescriptor {
-            size: (size_of::<Self>() - 1) as u16,
-            address: self as *const _ as u64,
+            size: (size_of::<[IdtEntry<ExceptionHandler>; 256]>() - 1) as u16,
+            address: self.entries.as_ptr() as u64,
         };
         unsafe {
             core::arch::asm!("lidt [{}]", in(reg) &descriptor, options(nostack));
@@ -0,0 +1,106 @@
+// kernel/src/interrupts/irq.rs
+//
+// Generic device IRQ dispatch for the 16 legacy PIC lines.
+//
+// Before this, every device IRQ was its own hand-written
+// `extern "x86-interrupt"` handler wired to a hardcoded vector in
+// `init::devices::init_idt`, each one ending in the same manual
+// `pic::end_of_interrupt(Irq::X.as_u8())` — adding a device meant editing
+// `init_idt` and copying that boilerplate (and forgetting the EOI wedges
+// the line for good).
+//
+// Now `init_idt` points vectors 33..=47 (IRQ lines 1..=15) at one
+// trampoline per line, installed up front whether or not anything is
+// registered; each trampoline looks up `HANDLERS[line]`, calls it if set,
+// then sends the EOI itself. Drivers just call `register_irq(line, f)`
+// with a plain `fn()` — in any order relative to `init_idt`/`load_idt`,
+// since the IDT entries never change after boot — and unmask the line
+// with `pic::enable_irq` as before (registration deliberately doesn't
+// unmask: some lines need device setup first, see `serial::init_interrupts`).
+// An unregistered line that fires anyway is EOI'd and otherwise ignored
+// instead of hitting a not-present IDT entry.
+//
+// SPURIOUS IRQ7 / IRQ15
+// ─────────────────────
+// When a request goes away between the PIC raising INTR and the CPU's
+// acknowledge, the PIC still delivers a vector — its lowest-priority
+// line, 7 on the master, 15 on the slave — without setting that line's
+// In-Service bit. EOI'ing one of those would retire whatever *real*
+// interrupt is in service instead. So lines 7 and 15 check the ISR first:
+// spurious IRQ7 gets no EOI at all; spurious IRQ15 EOIs only the master,
+// which did see its cascade line (IRQ2) fire. Neither reaches the
+// registered handler. Counted in `SPURIOUS`.
+//
+// Line 0 (timer, vector 32) is NOT routed through here: it needs the
+// hand-written asm entry in `process::timer_preempt` that saves a full
+// TrapFrame so it can switch processes. `register_irq(0, ..)` panics.
+// Syscalls don't use the IDT at all (LSTAR, see `process::tss`).
+//
+// Handlers run with interrupts disabled (interrupt gate) on the
+// interrupted context's kernel stack — same rules as any ISR: no
+// blocking locks that non-ISR code takes with interrupts enabled.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::interrupts::{
+    exception::ExceptionStackFrame,
+    idt::{ExceptionHandler, InterruptDescriptorTable},
+    pic::PIC1_OFFSET,
+};
+
+pub const IRQ_LINES: usize = 16;
+
+/// `fn()` pointers stored as `usize` (0 = nothing registered), so the
+/// trampolines can read them lock-free from interrupt context.
+static HANDLERS: [AtomicUsize; IRQ_LINES] = [const { AtomicUsize::new(0) }; IRQ_LINES];
+
+/// Spurious IRQ7/IRQ15 deliveries dropped since boot.
+static SPURIOUS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn spurious_count() -> usize {
+    SPURIOUS.load(Ordering::Relaxed)
+}
+
+/// Route IRQ `line` (1..=15) to `handler`, replacing any previous one.
+/// The trampoline sends the EOI after `handler` returns.
+pub fn register_irq(line: u8, handler: fn()) {
+    assert!(
+        line != 0 && (line as usize) < IRQ_LINES,
+        "register_irq: line {} is not a routable PIC line (timer IRQ0 has its own entry)",
+        line
+    );
+    HANDLERS[line as usize].store(handler as usize, Ordering::Release);
+}
+
+extern "x86-interrupt" fn irq_trampoline<const LINE: u8>(_: &mut ExceptionStackFrame) {
+    if (LINE == 7 || LINE == 15) && crate::interrupts::pic::read_isr() & (1 << LINE) == 0 {
+        SPURIOUS.fetch_add(1, Ordering::Relaxed);
+        if LINE == 15 {
+            crate::interrupts::pic::end_of_interrupt_master();
+        }
+        return;
+    }
+    let raw = HANDLERS[LINE as usize].load(Ordering::Acquire);
+    if raw != 0 {
+        // Safe: only `register_irq` stores here, and it stores a `fn()`.
+        let handler: fn() = unsafe { core::mem::transmute::<usize, fn()>(raw) };
+        handler();
+    }
+    crate::interrupts::pic::end_of_interrupt(PIC1_OFFSET + LINE);
+}
+
+/// Point vectors `PIC1_OFFSET + 1 ..= PIC1_OFFSET + 15` at their
+/// trampolines. Called once from `init_idt`, before the timer entry is
+/// set (which takes vector `PIC1_OFFSET` itself).
+pub fn install_trampolines(idt: &mut InterruptDescriptorTable) {
+    const TRAMPOLINES: [ExceptionHandler; IRQ_LINES - 1] = [
+        irq_trampoline::<1>,  irq_trampoline::<2>,  irq_trampoline::<3>,
+        irq_trampoline::<4>,  irq_trampoline::<5>,  irq_trampoline::<6>,
+        irq_trampoline::<7>,  irq_trampoline::<8>,  irq_trampoline::<9>,
+        irq_trampoline::<10>, irq_trampoline::<11>, irq_trampoline::<12>,
+        irq_trampoline::<13>, irq_trampoline::<14>, irq_trampoline::<15>,
+    ];
+    for (i, &trampoline) in TRAMPOLINES.iter().enumerate() {
+        idt.add_handler(PIC1_OFFSET + 1 + i as u8, trampoline);
+    }
+}
@@ -1,3 +1,7 @@
 pub mod idt;
 pub mod pic;
-pub mod exception;
\ No newline at end of file
+pub mod exception;
+pub mod irq;
+pub mod guard;
+
+pub use guard::{without_interrupts, InterruptGuard};
@@ -6,6 +6,7 @@ use core::arch::asm;
 // Comandos del PIC
 const CMD_INIT: u8 = 0x11;
 const CMD_END_OF_INTERRUPT: u8 = 0x20;
+const CMD_READ_ISR: u8 = 0x0B; // OCW3: next read of the command port returns the ISR
 
 // Puertos del PIC
 const PIC1_COMMAND: u16 = 0x20;
@@ -17,21 +18,6 @@ const PIC2_DATA: u16 = 0xA1;
 pub const PIC1_OFFSET: u8 = 32;
 pub const PIC2_OFFSET: u8 = PIC1_OFFSET + 8;
 
-#[derive(Debug, Clone, Copy)]
-#[repr(u8)]
-pub enum Irq {
-    Timer = PIC1_OFFSET,
-    Keyboard, // 33
-    Com1 = PIC1_OFFSET + 4, // 36 — serial (COM1) receive
-    Mouse = PIC2_OFFSET + 4, // 44 — PS/2 auxiliary device (IRQ12)
-}
-
-impl Irq {
-    pub fn as_u8(self) -> u8 {
-        self as u8
-    }
-}
-
 /// Escribe un byte a un puerto
 fn outb(port: u16, value: u8) {
     unsafe {
@@ -82,6 +68,20 @@ pub fn end_of_interrupt(irq: u8) {
     outb(PIC1_COMMAND, CMD_END_OF_INTERRUPT);
 }
 
+/// EOI only the master — for a spurious IRQ15, where the slave never
+/// raised anything but the master did see its cascade line (IRQ2) fire.
+pub fn end_of_interrupt_master() {
+    outb(PIC1_COMMAND, CMD_END_OF_INTERRUPT);
+}
+
+/// In-Service Register of both PICs: master in bits 0-7, slave in 8-15.
+/// A line's bit is set while the PIC considers that IRQ being serviced.
+pub fn read_isr() -> u16 {
+    outb(PIC1_COMMAND, CMD_READ_ISR);
+    outb(PIC2_COMMAND, CMD_READ_ISR);
+    ((inb(PIC2_COMMAND) as u16) << 8) | inb(PIC1_COMMAND) as u16
+}
+
 /// Habilita una línea de IRQ específica (0-15)
 pub fn enable_irq(irq_line: u8) {
     let port = if irq_line < 8 {
@@ -93,3 +93,17 @@ pub fn enable_irq(irq_line: u8) {
     let mask = inb(port);
     outb(port, mask & !(1 << irq_line));
 }
+
+/// Deshabilita (enmascara) una línea de IRQ específica (0-15) — usado para
+/// apagar IRQ0 (PIT) cuando el timer del Local APIC toma el tick, ver
+/// `crate::lapic::init_timer`.
+pub fn disable_irq(irq_line: u8) {
+    let port = if irq_line < 8 {
+        PIC1_DATA
+    } else {
+        PIC2_DATA
+    };
+    let irq_line = if irq_line < 8 { irq_line } else { irq_line - 8 };
+    let mask = inb(port);
+    outb(port, mask | (1 << irq_line));
+}
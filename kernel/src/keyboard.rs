@@ -7,8 +7,9 @@
 // the raw scancode already arrives from the ISR). This file just holds the
 // `hal::keyboard::KeyDecoder` in an ISR-safe static and executes the
 // effects `KeyDecoder::process` describes: pushing the raw press/release
-// transition into `RAW_KEY_EVENTS`, and routing each decoded char through
-// the tty line discipline (`tty::feed_input`) into `KEYBOARD_BUFFER`.
+// transition into `RAW_KEY_EVENTS`, paging the framebuffer console's
+// scrollback (Shift+PgUp/PgDn), and routing each decoded char through the
+// tty line discipline (`tty::feed_input`) into `KEYBOARD_BUFFER`.
 //
 // process_scancode() is called from the keyboard ISR.
 // read_key() is the non-blocking consumer API.
@@ -44,6 +45,10 @@ pub fn process_scancode(scancode: u8) {
         crate::keyboard_buffer::RAW_KEY_EVENTS.push(raw.keycode, raw.pressed);
     }
 
+    if let Some(scroll) = out.scroll {
+        crate::drivers::framebuffer_console::try_scroll(scroll);
+    }
+
     for &c in out.chars() {
         push(c);
     }
@@ -70,13 +75,14 @@ pub fn read_raw_event() -> Option<crate::keyboard_buffer::RawKeyEvent> {
 // HELPERS
 // ============================================================================
 
-/// Routes every character through the tty's ISIG line discipline
-/// (`tty::feed_input`) before queueing it — a byte that matches the
-/// current VINTR/VQUIT/VSUSP setting is turned into a real signal to the
-/// foreground process group instead of becoming input (Ctrl-C/Ctrl-\/
-/// Ctrl-Z). See `tty.rs`'s module doc comment.
+/// Routes every character through the tty's line discipline
+/// (`tty::feed_input`), which does the queueing itself — a byte that
+/// matches the current VINTR/VQUIT/VSUSP setting is turned into a real
+/// signal to the foreground process group instead of becoming input
+/// (Ctrl-C/Ctrl-\/Ctrl-Z), and in cooked mode nothing is queued until a
+/// whole line is. See `tty.rs`'s module doc comment. The keyboard ISR
+/// wakes stdin/poll waiters unconditionally afterwards, so the return
+/// value isn't needed here.
 fn push(c: char) {
-    if crate::tty::feed_input(c) {
-        KEYBOARD_BUFFER.push(c);
-    }
+    crate::tty::feed_input(c);
 }
@@ -0,0 +1,144 @@
+// kernel/src/lapic.rs
+//
+// Local APIC timer as the scheduler tick source — thin adapter around
+// `hal::lapic`'s `LapicMmio`-generic register protocol and calibration
+// math (host-tested, see `hal/src/lapic.rs`). This file is only the parts
+// that need real hardware: CPUID detection, the IA32_APIC_BASE MSR, the
+// MMIO mapping, and busy-waiting on the PIT for calibration.
+//
+// Boot flow (`init_timer`, called from `init::init` right after TSC
+// calibration, interrupts still off):
+//   1. `cpu::features().apic` (CPUID.1:EDX[9]) — no APIC, keep the PIT.
+//   2. IA32_APIC_BASE: set the global enable bit, read the base. An
+//      x2APIC-mode LAPIC (bit 10) has no MMIO window — keep the PIT.
+//   3. Make the base page uncacheable in the physmap (bootloader 0.11 maps
+//      at least the first 4 GiB, which covers the usual 0xFEE0_0000).
+//   4. Software-enable it with `SPURIOUS_VECTOR`.
+//   5. Count the LAPIC timer down across one PIT period
+//      (`cpu::tsc::across_one_pit_period`, the same window TSC calibration
+//      uses) and turn that into an initial count for `hz`.
+//   6. Periodic mode on vector 32 — the existing `timer_interrupt_entry`,
+//      so `Scheduler::tick` and everything downstream is unchanged — then
+//      mask IRQ0 on the PIC. The PIT itself keeps counting: `cpu::tsc`
+//      and `pcspeaker` (channel 2) don't depend on its interrupt.
+// Any failure before step 6 logs why and leaves the PIT tick as it was.
+//
+// The one thing that differs per source afterwards is the EOI —
+// `timer_eoi` picks the right one for whichever source is live. Sending
+// the PIC's non-specific EOI for a LAPIC interrupt would acknowledge
+// whatever PIC line happens to be in service instead.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use hal::lapic::{Lapic, LapicMmio};
+
+use crate::interrupts::exception::ExceptionStackFrame;
+
+const IA32_APIC_BASE: u32 = 0x1B;
+const APIC_BASE_ENABLE: u64 = 1 << 11;
+const APIC_BASE_X2APIC: u64 = 1 << 10;
+const APIC_BASE_ADDR_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+
+/// Vector for LAPIC spurious interrupts. 0xFF is the conventional choice:
+/// its low four bits must be all ones on older (P6-era) LAPICs.
+pub const SPURIOUS_VECTOR: u8 = 0xFF;
+
+/// Same vector the PIC delivers IRQ0 on (`PIC1_OFFSET`), i.e.
+/// `timer_interrupt_entry`'s IDT slot.
+const TIMER_VECTOR: u8 = crate::interrupts::pic::PIC1_OFFSET;
+
+/// Virtual address (physmap) of the LAPIC register page once the LAPIC
+/// timer is the tick source; 0 while the PIT still is.
+static LAPIC_VIRT: AtomicU64 = AtomicU64::new(0);
+
+/// Production `LapicMmio`: volatile 32-bit accesses through the physmap.
+#[derive(Clone, Copy)]
+struct PhysmapLapic {
+    base: u64,
+}
+
+impl LapicMmio for PhysmapLapic {
+    fn read(&self, offset: u32) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset as u64) as *const u32) }
+    }
+    fn write(&self, offset: u32, val: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset as u64) as *mut u32, val) }
+    }
+}
+
+/// Switch the scheduler tick from the PIT to the LAPIC timer at `hz`.
+/// Returns `false` (PIT tick left untouched) if there's no usable LAPIC —
+/// see the module comment for the steps and what each one falls back on.
+///
+/// Must run after `pit::init()` and `cpu::tsc::init()`, with interrupts
+/// disabled.
+pub fn init_timer(hz: u32) -> bool {
+    if !crate::cpu::features::features().apic {
+        crate::info!("lapic: no APIC (CPUID), keeping the PIT tick");
+        return false;
+    }
+
+    let mut msr = x86_64::registers::model_specific::Msr::new(IA32_APIC_BASE);
+    let base_msr = unsafe { msr.read() };
+    if base_msr & APIC_BASE_X2APIC != 0 {
+        crate::info!("lapic: firmware left it in x2APIC mode, keeping the PIT tick");
+        return false;
+    }
+    unsafe { msr.write(base_msr | APIC_BASE_ENABLE); }
+
+    let phys = base_msr & APIC_BASE_ADDR_MASK;
+    let virt = crate::memory::physical_memory_offset() + phys;
+    if let Err(e) = unsafe { crate::memory::page_table_manager::map_physmap_uncached(virt) } {
+        crate::warn!("lapic: can't map registers at {:#x} ({}), keeping the PIT tick", phys, e);
+        return false;
+    }
+
+    let lapic = Lapic::new(PhysmapLapic { base: virt.as_u64() });
+    lapic.enable(SPURIOUS_VECTOR);
+
+    let mut started = false;
+    let mut elapsed = 0;
+    crate::cpu::tsc::across_one_pit_period(|| {
+        if !started {
+            lapic.start_calibration();
+            started = true;
+        } else {
+            elapsed = lapic.calibration_elapsed();
+        }
+    });
+    lapic.stop();
+
+    let count = match hal::lapic::initial_count_for_hz(elapsed, crate::cpu::tsc::pit_hz() as u32, hz) {
+        Ok(c) => c,
+        Err(e) => {
+            crate::warn!("lapic: calibration failed ({:?}, {} ticks), keeping the PIT tick", e, elapsed);
+            return false;
+        }
+    };
+
+    LAPIC_VIRT.store(virt.as_u64(), Ordering::Relaxed);
+    lapic.start_periodic(TIMER_VECTOR, count);
+    crate::interrupts::pic::disable_irq(0);
+    crate::info!(
+        "lapic: timer at {} Hz (id {}, base {:#x}, initial count {}, bus/16 {} kHz)",
+        hz, lapic.id(), phys, count,
+        elapsed as u64 * crate::cpu::tsc::pit_hz() / 1000
+    );
+    true
+}
+
+/// Acknowledge a timer interrupt at whichever controller delivered it.
+/// Called first thing by `timer_preempt_handler`.
+#[inline]
+pub fn timer_eoi() {
+    let base = LAPIC_VIRT.load(Ordering::Relaxed);
+    if base != 0 {
+        Lapic::new(PhysmapLapic { base }).eoi();
+    } else {
+        crate::interrupts::pic::end_of_interrupt(TIMER_VECTOR);
+    }
+}
+
+/// LAPIC spurious interrupts need no EOI (the LAPIC never set an ISR bit
+/// for them) — the handler exists only so the vector isn't a #GP/#NP.
+pub extern "x86-interrupt" fn spurious_handler(_sf: &mut ExceptionStackFrame) {}
@@ -0,0 +1,105 @@
+// kernel/src/lock_order.rs
+//
+// The kernel's lock hierarchy, and a debug-build check that it's followed.
+//
+// ORDER (outermost first):
+//   SCHEDULER — a CPU's run queues (`process::scheduler::local_scheduler`)
+//   VMA       — one address space's VMA list (`AddressSpace::lock_vmas`)
+//   BUDDY     — physical frames (`allocator::buddy_allocator::lock_buddy`)
+//
+// A lock may be taken only while every tracked lock this CPU already
+// holds ranks *above* it. BUDDY is the leaf: every heap allocation can end
+// in it (a slab refill), so any code holding SCHEDULER or a VMA list may
+// allocate, and code holding BUDDY may take nothing else at all. The
+// inversion this rules out is the classic one: a path holding BUDDY that
+// then waits on SCHEDULER, against the many paths that allocate under
+// SCHEDULER.
+//
+// Keep big allocations *out* of SCHEDULER-held regions anyway (the
+// process creators build the `Process` — address space, kernel stack,
+// fd table — first and take the lock only for `allocate_pid` and
+// `add_process`): it's a spinlock the timer ISR needs every tick.
+//
+// CHECK: debug builds keep a per-CPU bitmask of the classes held and
+// panic, naming both locks, on an out-of-order blocking acquire. It's
+// exact because all three are held only with interrupts off — SCHEDULER
+// by its own LOCKING DISCIPLINE, VMA and BUDDY because their guards mask
+// interrupts for their lifetime — so an IRQ handler never sees bits that
+// belong to the code it interrupted. `try_lock` acquisitions can't
+// deadlock and skip the check, but are still recorded so whatever is
+// taken under them is checked. Release builds compile it all away.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// One rank in the hierarchy above; a higher value is taken first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LockClass {
+    Buddy = 0,
+    Vma = 1,
+    Scheduler = 2,
+}
+
+impl LockClass {
+    const ALL: [LockClass; 3] = [LockClass::Buddy, LockClass::Vma, LockClass::Scheduler];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Bits of this class and every class ranked below it — the ones that
+    /// must not be held when this one is acquired.
+    fn at_or_below(self) -> u8 {
+        (self.bit() << 1) - 1
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LockClass::Buddy => "BUDDY",
+            LockClass::Vma => "VMA",
+            LockClass::Scheduler => "SCHEDULER",
+        }
+    }
+}
+
+static HELD: [AtomicU8; crate::cpu::MAX_CPUS] = [const { AtomicU8::new(0) }; crate::cpu::MAX_CPUS];
+
+/// Call just before a blocking acquire of a `class` lock. Panics (debug
+/// builds) if this CPU already holds it or anything ranked below it.
+#[track_caller]
+pub fn acquire(class: LockClass) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let held = HELD[crate::cpu::cpu_id()].load(Ordering::Relaxed);
+    let conflict = held & class.at_or_below();
+    if conflict != 0 {
+        let worst = LockClass::ALL.into_iter().find(|c| conflict & c.bit() != 0).unwrap();
+        panic!(
+            "lock order violation: acquiring {} while holding {} (held mask {:#05b}) — see lock_order.rs",
+            class.name(), worst.name(), held,
+        );
+    }
+    HELD[crate::cpu::cpu_id()].fetch_or(class.bit(), Ordering::Relaxed);
+}
+
+/// Call after a successful `try_lock` of a `class` lock: recorded as held,
+/// no ordering check.
+pub fn acquired_nonblocking(class: LockClass) {
+    if cfg!(debug_assertions) {
+        HELD[crate::cpu::cpu_id()].fetch_or(class.bit(), Ordering::Relaxed);
+    }
+}
+
+/// Call when a `class` lock is released.
+pub fn release(class: LockClass) {
+    if cfg!(debug_assertions) {
+        HELD[crate::cpu::cpu_id()].fetch_and(!class.bit(), Ordering::Relaxed);
+    }
+}
+
+/// Whether this CPU is recorded as holding a `class` lock (debug builds).
+#[cfg(test)]
+pub(crate) fn holds(class: LockClass) -> bool {
+    HELD[crate::cpu::cpu_id()].load(Ordering::Relaxed) & class.bit() != 0
+}
@@ -0,0 +1,190 @@
+// kernel/src/log.rs
+//
+// Kernel message ring buffer — the in-memory copy of everything written
+// to serial via `serial_print!`/`serial_println!` (locked) and
+// `serial_print_raw!`/`serial_println_raw!` (lock-free), so recent kernel
+// output (demand paging, scheduler decisions, reaps) can be replayed from
+// inside the system without a serial cable: `cat /dev/kmsg`
+// (`drivers::dev_kmsg`).
+//
+// Fixed KMSG_SIZE bytes of static storage — usable from the first
+// `serial_println!` of boot, long before the heap exists. Oldest bytes
+// are overwritten once it wraps; readers track an absolute byte offset
+// into the (monotonic) stream, so one that falls behind just resumes at
+// the oldest byte still held.
+//
+// CONTEXT RULES
+// ─────────────
+// `record` runs from any context the raw serial writer does (allocators,
+// ISRs, panic), so it must never block: the ring lock is held only with
+// interrupts off (no ISR on this core can find it held) and taken with
+// `try_lock` — a message that loses the race against another CPU, or that
+// comes from a fault taken *inside* `record` itself, is simply not
+// recorded. Serial still gets it either way.
+//
+// LOG LEVELS
+// ──────────
+// `error!`/`warn!`/`info!`/`debug!`/`trace!` print (and record) a line
+// only if its level is at or below `LOG_LEVEL` — one relaxed atomic load
+// + branch otherwise, the same cost model as `ktrace!`. The two are
+// orthogonal: `ktrace!` gates by *subsystem* (opt-in, default all-off),
+// the level gates by *severity* across everything. Boot default is
+// `DEFAULT_LEVEL` (Info): boot progress and failures, none of the
+// per-allocation/per-exec chatter, which is `debug!`/`trace!`. Change it
+// live with `kdebug loglevel <n>` (`kdebug_ctl` cmd 2/3). Plain
+// `serial_println!` stays unconditional — use it only for output someone
+// explicitly asked for (stat dumps, `ps`-style listings, test results).
+
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+use spin::Mutex;
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    pub fn from_u8(n: u8) -> Option<Level> {
+        match n {
+            1 => Some(Level::Error),
+            2 => Some(Level::Warn),
+            3 => Some(Level::Info),
+            4 => Some(Level::Debug),
+            5 => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+pub const DEFAULT_LEVEL: Level = Level::Info;
+
+/// Highest level that gets printed. 0 silences even `error!`.
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(DEFAULT_LEVEL as u8);
+
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+pub fn level() -> u8 {
+    LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Set the level (0..=5), returning the previous one, or `None` if `n`
+/// is out of range.
+pub fn set_level(n: u8) -> Option<u8> {
+    if n as usize > Level::Trace as usize {
+        return None;
+    }
+    Some(LOG_LEVEL.swap(n, Ordering::Relaxed))
+}
+
+/// Level-gated line. Goes through the lock-free raw writer, like
+/// `ktrace!`, because the `debug!`/`trace!` sites include allocators and
+/// ISRs where the locked `SERIAL` writer could deadlock.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! klog {
+    ($lvl:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($lvl) {
+            $crate::serial_println_raw!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => ($crate::klog!($crate::log::Level::Error, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => ($crate::klog!($crate::log::Level::Warn, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => ($crate::klog!($crate::log::Level::Info, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => ($crate::klog!($crate::log::Level::Debug, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => ($crate::klog!($crate::log::Level::Trace, $($arg)*));
+}
+
+pub const KMSG_SIZE: usize = 16 * 1024;
+
+struct Ring {
+    buf: [u8; KMSG_SIZE],
+    /// Total bytes ever recorded; `written % KMSG_SIZE` is the next slot.
+    written: u64,
+}
+
+static KMSG: Mutex<Ring> = Mutex::new(Ring { buf: [0; KMSG_SIZE], written: 0 });
+
+impl fmt::Write for Ring {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &b in s.as_bytes() {
+            self.buf[(self.written % KMSG_SIZE as u64) as usize] = b;
+            self.written += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Append a formatted message to the ring. Called by the serial print
+/// macros — use those rather than this directly.
+pub fn record(args: fmt::Arguments) {
+    use fmt::Write;
+    crate::interrupts::without_interrupts(|| {
+        if let Some(mut ring) = KMSG.try_lock() {
+            let _ = ring.write_fmt(args);
+        }
+    });
+}
+
+/// Copy recorded bytes starting at absolute offset `*pos` into `out`,
+/// stopping after the last complete line that fits (a line longer than
+/// `out` is split rather than never returned), and advance `*pos`. If
+/// `*pos` has already been overwritten, skips ahead to the oldest byte
+/// still held. Returns the number of bytes copied; 0 means caught up.
+pub fn read_from(pos: &mut u64, out: &mut [u8]) -> usize {
+    crate::interrupts::without_interrupts(|| {
+        let ring = KMSG.lock();
+        let oldest = ring.written.saturating_sub(KMSG_SIZE as u64);
+        let start = (*pos).max(oldest);
+        let avail = (ring.written - start) as usize;
+        let mut n = avail.min(out.len());
+        for (i, slot) in out[..n].iter_mut().enumerate() {
+            *slot = ring.buf[((start + i as u64) % KMSG_SIZE as u64) as usize];
+        }
+        if n < avail {
+            if let Some(last_nl) = out[..n].iter().rposition(|&b| b == b'\n') {
+                n = last_nl + 1;
+            }
+        }
+        *pos = start + n as u64;
+        n
+    })
+}
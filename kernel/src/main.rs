@@ -15,10 +15,12 @@ extern crate alloc;
 mod ac97;
 mod acpi;
 mod allocator;
+mod backtrace;
 mod block;
 mod cpu;
 mod debug;
 mod drivers;
+mod font;
 mod framebuffer;
 mod fs;
 mod hal;
@@ -29,15 +31,20 @@ mod interrupts;
 mod ipc;
 mod keyboard;
 mod keyboard_buffer;
+mod lapic;
+mod lock_order;
+mod log;
 mod memory;
 mod mouse;
-#[cfg(not(test))]
 mod panic;
 mod pci;
 mod process;
+mod pcspeaker;
 mod pit;
+mod power;
 mod rtc;
 mod serial;
+mod symbols;
 #[cfg(test)]
 mod test_framework;
 mod time;
@@ -6,14 +6,16 @@
 // This is the only structural addition of the refactor.  Everything
 // else is wiring changes.
 
+use alloc::sync::Arc;
 use core::sync::atomic::{AtomicU64, Ordering};
-use spin::Mutex;
+use spin::{Mutex, Once};
 use x86_64::{
     VirtAddr,
     structures::paging::{Page, PageTableFlags, PhysFrame, Size2MiB, Size4KiB, mapper::MapToError},
 };
 
 use super::page_table_manager::{OwnedPageTable, USER_MMAP_BASE};
+use super::tlb::TlbFlush;
 use super::vma::{Vma, VmaKind, VmaList};
 
 /// `vmas` and `mmap_base` use interior mutability (`Mutex`/`AtomicU64`
@@ -27,10 +29,33 @@ pub struct AddressSpace {
     pub page_table: OwnedPageTable,
     vmas: Mutex<VmaList>,
     /// Bump pointer for kernel-assigned anonymous mmap addresses.
-    /// Starts at USER_MMAP_BASE; advances on each mmap allocation.
+    /// Starts at USER_MMAP_BASE (or a randomized offset above it, see
+    /// `elf_loader::user_mmap_base`); advances on each mmap allocation.
     mmap_base: AtomicU64,
 }
 
+/// `AddressSpace::lock_vmas`'s guard. Same field order as
+/// `buddy_allocator::BuddyGuard`: unlock, then restore IF.
+struct VmaGuard<'a> {
+    vmas: spin::MutexGuard<'a, VmaList>,
+    _irq: crate::interrupts::InterruptGuard,
+}
+
+impl core::ops::Deref for VmaGuard<'_> {
+    type Target = VmaList;
+    fn deref(&self) -> &VmaList { &self.vmas }
+}
+
+impl core::ops::DerefMut for VmaGuard<'_> {
+    fn deref_mut(&mut self) -> &mut VmaList { &mut self.vmas }
+}
+
+impl Drop for VmaGuard<'_> {
+    fn drop(&mut self) {
+        crate::lock_order::release(crate::lock_order::LockClass::Vma);
+    }
+}
+
 // SAFETY: same invariant as the existing `Send` impl below — this kernel is
 // single-CPU with cli-discipline around every scheduler/address-space
 // mutation, so there is never true concurrent access. `Sync` is required so
@@ -45,14 +70,24 @@ impl AddressSpace {
     // CONSTRUCTORS
     // ====================================================================
 
-    /// Kernel address space: wraps the current CR3, no VMAs.
-    /// Used by idle and shell processes.
-    pub fn kernel() -> Self {
-        Self {
+    /// The kernel address space: the boot page table, no VMAs. One shared
+    /// instance — every kernel process (idle) holds a clone of the same
+    /// `Arc`, and `Process::teardown` swaps it in as the placeholder for a
+    /// reaped process's real one.
+    ///
+    /// Captured from CR3 on the first call, so that call has to happen
+    /// while the boot page table is still live (`create_idle_process`,
+    /// before any user process has ever been switched to). A fresh
+    /// `from_current()` per caller, as this used to be, would wrap
+    /// whichever page table happened to be active — during a reap that's
+    /// the reaping parent's user PML4, not the kernel's.
+    pub fn kernel() -> Arc<Self> {
+        static KERNEL: Once<Arc<AddressSpace>> = Once::new();
+        KERNEL.call_once(|| Arc::new(Self {
             page_table: OwnedPageTable::from_current(),
             vmas: Mutex::new(VmaList::new()),
             mmap_base: AtomicU64::new(USER_MMAP_BASE),
-        }
+        })).clone()
     }
 
     /// New user address space: fresh page table with kernel entries
@@ -69,19 +104,36 @@ impl AddressSpace {
         })
     }
 
+    /// Move the mmap bump pointer — only meaningful before the first
+    /// kernel-placed mmap. Used by `elf_loader` (and the raw-code loader
+    /// in `init::processes`) to randomize the window.
+    pub fn set_mmap_base(&self, base: u64) {
+        self.mmap_base.store(base, Ordering::Relaxed);
+    }
+
     // ====================================================================
     // VMA MANAGEMENT
     // ====================================================================
 
+    /// The VMA list, locked, with interrupts masked until the guard drops
+    /// — `lock_order`'s VMA rank: below SCHEDULER, above BUDDY, so nothing
+    /// that holds a `BuddyGuard` may call in here.
+    #[track_caller]
+    fn lock_vmas(&self) -> VmaGuard<'_> {
+        let _irq = crate::interrupts::InterruptGuard::new();
+        crate::lock_order::acquire(crate::lock_order::LockClass::Vma);
+        VmaGuard { vmas: self.vmas.lock(), _irq }
+    }
+
     /// Register a virtual memory area.
     pub fn add_vma(&self, vma: Vma) -> Result<(), &'static str> {
-        self.vmas.lock().add(vma)
+        self.lock_vmas().add(vma)
     }
 
     /// Find the VMA containing `addr`, if any.
     /// Returns a copy (Vma is Copy).
     pub fn find_vma(&self, addr: u64) -> Option<Vma> {
-        self.vmas.lock().find(addr).copied()
+        self.lock_vmas().find(addr).copied()
     }
 
     /// Try to grow a `GrowableStack` VMA to cover `addr` — see
@@ -89,12 +141,12 @@ impl AddressSpace {
     /// Called by the page fault handler only after `find_vma` already
     /// came back empty.
     pub fn grow_stack_vma(&self, addr: u64) -> Option<Vma> {
-        self.vmas.lock().grow_stack(addr)
+        self.lock_vmas().grow_stack(addr)
     }
 
     /// Debug: print all VMAs (uses serial, no allocation).
     pub fn dump_vmas(&self, label: usize) {
-        self.vmas.lock().dump(label);
+        self.lock_vmas().dump(label);
     }
 
     // ====================================================================
@@ -113,6 +165,12 @@ impl AddressSpace {
         self.page_table.translate_page(page)
     }
 
+    /// Physical address behind any mapped user address, huge pages
+    /// included — see `OwnedPageTable::translate_addr`.
+    pub unsafe fn translate_addr(&self, addr: VirtAddr) -> Option<x86_64::PhysAddr> {
+        self.page_table.translate_addr(addr)
+    }
+
     /// Map a single user page.  Allocates data + intermediate frames
     /// from the Buddy allocator.
     pub unsafe fn map_user_page(
@@ -155,14 +213,20 @@ impl AddressSpace {
     /// Buddy allocator must be initialized.  Call with interrupts disabled.
     pub unsafe fn fork(&self) -> Result<Self, &'static str> {
         let child = Self::new_user()?;
-        let vmas_snapshot = self.vmas.lock().clone();
-        *child.vmas.lock() = vmas_snapshot.clone();
+        let vmas_snapshot = self.lock_vmas().clone();
+        *child.lock_vmas() = vmas_snapshot.clone();
         child.mmap_base.store(self.mmap_base.load(Ordering::Relaxed), Ordering::Relaxed);
 
         for vma in vmas_snapshot.iter() {
             let orig_flags = vma.page_table_flags();
-            // Shared mapping is always read-only regardless of original flags.
-            let shared_flags = orig_flags & !PageTableFlags::WRITABLE;
+            // Shared mapping is always read-only regardless of original flags
+            // — except a `VmaKind::Shared` one, which is aliased as-is (see
+            // there): both sides keep writing the one frame.
+            let shared_flags = if vma.kind == VmaKind::Shared {
+                orig_flags
+            } else {
+                orig_flags & !PageTableFlags::WRITABLE
+            };
 
             for page_idx in 0..vma.size_pages {
                 let addr = vma.start + page_idx as u64 * 4096;
@@ -183,7 +247,7 @@ impl AddressSpace {
                 }
 
                 // Mark the parent's page read-only (COW protection).
-                if orig_flags.contains(PageTableFlags::WRITABLE) {
+                if shared_flags != orig_flags {
                     self.page_table.update_page_flags(page, shared_flags)?;
                 }
 
@@ -232,14 +296,11 @@ impl AddressSpace {
         // ── Zero-page: promote the shared zero frame to a private writable copy.
         // Must be checked BEFORE the refcount path (zero frame has refcount 0).
         if crate::memory::cow::is_zero_frame(old_frame) {
-            let phys_offset = crate::memory::physical_memory_offset();
-            let Some(new_frame) = crate::allocator::phys_alloc(12).map(|a| PhysFrame::containing_address(a)) else {
+            let Some(new_frame) = crate::allocator::phys_alloc_zeroed(12).map(PhysFrame::containing_address) else {
                 crate::debug::inc_cow_failed();
                 return Err("COW zero-frame: OOM");
             };
             crate::memory::cow::set_ref(new_frame, 1);
-            let dst = (phys_offset + new_frame.start_address().as_u64()).as_mut_ptr::<u8>();
-            core::ptr::write_bytes(dst, 0, 4096);
             // Do NOT dec_ref the zero frame — it is permanent.
             crate::ktrace!(MM, "zero-frame promotion at {:#x} -> new_frame {:#x}",
                 fault_addr, new_frame.start_address().as_u64());
@@ -332,11 +393,15 @@ impl AddressSpace {
     /// Returns the mapped virtual address on success.
     /// No physical frames are allocated here; the demand paging fault handler
     /// handles first-touch allocation for Anonymous VMAs.
+    ///
+    /// `shared` (`MAP_SHARED`) is the exception: always 4 KiB pages, all
+    /// allocated and zeroed right here — see `VmaKind::Shared`.
     pub fn sys_mmap_anon(
         &self,
         addr: u64,
         length: u64,
         prot: u32,
+        shared: bool,
     ) -> Result<u64, &'static str> {
         if length == 0 {
             return Err("mmap: zero length");
@@ -350,7 +415,7 @@ impl AddressSpace {
 
         // ── Huge pages (2 MiB) for large allocations ──────────────────
         const HUGE_2M: u64 = 0x200_000;
-        if length >= HUGE_2M {
+        if length >= HUGE_2M && !shared {
             let length_aligned = (length + HUGE_2M - 1) & !(HUGE_2M - 1);
             let size_pages = (length_aligned / 4096) as usize; // in 4 KiB units
 
@@ -364,7 +429,7 @@ impl AddressSpace {
                 if addr & (HUGE_2M - 1) != 0 {
                     return Err("mmap: huge page addr not 2MB-aligned");
                 }
-                if self.vmas.lock().overlaps(addr, size_pages) {
+                if self.lock_vmas().overlaps(addr, size_pages) {
                     return Err("mmap: MAP_FIXED conflict with existing VMA");
                 }
                 addr
@@ -376,7 +441,7 @@ impl AddressSpace {
                 flags: flags.bits(),
                 kind: VmaKind::Huge2M,
             };
-            self.vmas.lock().add(vma).map_err(|_| "mmap: VMA list full")?;
+            self.lock_vmas().add(vma).map_err(|_| "mmap: VMA list full")?;
             return Ok(vaddr);
         }
 
@@ -392,7 +457,7 @@ impl AddressSpace {
             if addr & 0xFFF != 0 {
                 return Err("mmap: addr not page-aligned");
             }
-            if self.vmas.lock().overlaps(addr, size_pages) {
+            if self.lock_vmas().overlaps(addr, size_pages) {
                 return Err("mmap: MAP_FIXED conflict with existing VMA");
             }
             addr
@@ -406,13 +471,86 @@ impl AddressSpace {
             start: vaddr,
             size_pages,
             flags: flags.bits(),
-            kind: VmaKind::Anonymous,
+            kind: if shared { VmaKind::Shared } else { VmaKind::Anonymous },
         };
-        self.vmas.lock().add(vma).map_err(|_| "mmap: VMA list full")?;
+        self.lock_vmas().add(vma).map_err(|_| "mmap: VMA list full")?;
+
+        if shared {
+            if let Err(e) = unsafe { self.populate_shared(&vma) } {
+                let _ = unsafe { self.sys_munmap(vaddr, length) };
+                return Err(e);
+            }
+        }
 
         Ok(vaddr)
     }
 
+    /// Allocate and map every page of a fresh `VmaKind::Shared` VMA
+    /// (zeroed by `map_user_page`). On failure the pages mapped so far
+    /// stay mapped — the caller's `sys_munmap` of the whole VMA frees them.
+    ///
+    /// # Safety
+    /// Must be called with interrupts disabled (cli).
+    unsafe fn populate_shared(&self, vma: &Vma) -> Result<(), &'static str> {
+        for i in 0..vma.size_pages {
+            let page = Page::<Size4KiB>::containing_address(VirtAddr::new(vma.start + i as u64 * 4096));
+            self.page_table.map_user_page(page, vma.page_table_flags())
+                .map_err(|_| "mmap: out of memory for MAP_SHARED pages")?;
+        }
+        Ok(())
+    }
+
+    /// Eagerly map every still-unmapped page of `[start, start + len)` —
+    /// the backend of `madvise(MADV_WILLNEED)`. Trades the one-fault-per-
+    /// page cost of touching a fresh region for committing its frames up
+    /// front, which only the caller can know is worth it.
+    ///
+    /// The whole range must be covered by VMAs (checked before anything is
+    /// mapped); only `Anonymous`/`GrowableStack` pages are populated.
+    /// `Code`/`Shared` are mapped at creation anyway, and `Huge2M` keeps
+    /// its own one-fault-per-2-MiB path. A page that is already present —
+    /// including one read-faulted onto the shared zero frame — is left
+    /// alone. On OOM the pages mapped so far stay mapped (they're ordinary
+    /// demand-paged pages; `sys_munmap` frees them as usual).
+    ///
+    /// Returns the number of pages newly mapped.
+    ///
+    /// # Safety
+    /// Must be called with interrupts disabled (cli).
+    pub unsafe fn prefault(&self, start: u64, len: u64) -> Result<usize, &'static str> {
+        let first = start & !0xFFF;
+        let end = start.checked_add(len).ok_or("prefault: range overflows")?;
+        let end = (end + 0xFFF) & !0xFFF;
+
+        let mut addr = first;
+        while addr < end {
+            let vma = self.find_vma(addr).ok_or("prefault: range not covered by a VMA")?;
+            addr = vma.end();
+        }
+
+        let mut mapped = 0;
+        let mut addr = first;
+        while addr < end {
+            let vma = self.find_vma(addr).ok_or("prefault: VMA vanished")?;
+            let run_end = vma.end().min(end);
+            if matches!(vma.kind, VmaKind::Anonymous | VmaKind::GrowableStack) {
+                while addr < run_end {
+                    let page = Page::<Size4KiB>::containing_address(VirtAddr::new(addr));
+                    if self.translate_page(page).is_none() {
+                        self.page_table.map_user_page(page, vma.page_table_flags())
+                            .map_err(|_| "prefault: out of memory")?;
+                        mapped += 1;
+                    }
+                    addr += 4096;
+                }
+            }
+            addr = run_end;
+        }
+
+        crate::debug::add_prefaulted(mapped as u64);
+        Ok(mapped)
+    }
+
     /// Unmap an anonymous region previously created by `sys_mmap_anon`.
     ///
     /// Currently requires an exact match on `addr` (the VMA start address).
@@ -433,20 +571,22 @@ impl AddressSpace {
         }
 
         let size_pages = ((length + 4095) / 4096) as usize;
-        let vma = self.vmas.lock().remove(addr).map_err(|_| "munmap: VMA not found")?;
+        let vma = self.lock_vmas().remove(addr).map_err(|_| "munmap: VMA not found")?;
 
         if vma.size_pages != size_pages {
             // Re-insert and signal partial munmap is unsupported.
-            let _ = self.vmas.lock().add(vma);
+            let _ = self.lock_vmas().add(vma);
             return Err("munmap: partial unmap not supported");
         }
 
+        // Flushed once after the loop (or on an early `?` return, by Drop).
+        let mut tlb = TlbFlush::new();
         match vma.kind {
-            VmaKind::Anonymous | VmaKind::Code | VmaKind::GrowableStack => {
+            VmaKind::Anonymous | VmaKind::Code | VmaKind::GrowableStack | VmaKind::Shared => {
                 for i in 0..vma.size_pages {
                     let va = vma.start + i as u64 * 4096;
                     let page = Page::<Size4KiB>::containing_address(VirtAddr::new(va));
-                    self.page_table.unmap_page_and_free(page)?;
+                    self.page_table.unmap_page_and_free(page, &mut tlb)?;
                 }
             }
             VmaKind::Huge2M => {
@@ -455,10 +595,11 @@ impl AddressSpace {
                 for i in 0..n_huge {
                     let va = vma.start + i as u64 * 0x200_000;
                     let page = Page::<Size2MiB>::containing_address(VirtAddr::new(va));
-                    self.page_table.unmap_page_and_free_2m(page)?;
+                    self.page_table.unmap_page_and_free_2m(page, &mut tlb)?;
                 }
             }
         }
+        tlb.flush();
 
         Ok(())
     }
@@ -479,18 +620,22 @@ impl AddressSpace {
     /// doc comment) — no COW refcount involved, unlike the 4 KiB Anonymous
     /// path `sys_munmap` also supports.
     pub unsafe fn try_free_huge_vma(&self, start: u64, size_pages: usize) -> bool {
-        let mut buddy = match crate::allocator::buddy_allocator::BUDDY.try_lock() {
+        let mut buddy = match crate::allocator::buddy_allocator::try_lock_buddy() {
             Some(b) => b,
             None => return false,
         };
 
+        let mut tlb = TlbFlush::new();
         let n_huge = size_pages / 512;
         for i in 0..n_huge {
             let va = start + i as u64 * 0x200_000;
             let page = Page::<Size2MiB>::containing_address(VirtAddr::new(va));
-            let _ = self.page_table.unmap_page_and_free_2m_with_buddy(page, &mut buddy);
+            let _ = self.page_table.unmap_page_and_free_2m_with_buddy(page, &mut buddy, &mut tlb);
         }
-        let _ = self.vmas.lock().remove(start);
+        tlb.flush();
+        // BUDDY is the leaf of `lock_order`: release it before the VMA list.
+        drop(buddy);
+        let _ = self.lock_vmas().remove(start);
         true
     }
 }
\ No newline at end of file
@@ -119,10 +119,7 @@ static ZERO_FRAME_PHYS: AtomicU64 = AtomicU64::new(0);
 /// Must be called after the Buddy allocator is initialized, before any
 /// user processes start.
 pub unsafe fn init_zero_frame() {
-    let addr = crate::allocator::phys_alloc(12).expect("zero frame alloc");
-    let phys_offset = crate::memory::physical_memory_offset();
-    let virt = (phys_offset + addr.as_u64()).as_mut_ptr::<u8>();
-    core::ptr::write_bytes(virt, 0, 4096);
+    let addr = crate::allocator::phys_alloc_zeroed(12).expect("zero frame alloc");
     ZERO_FRAME_PHYS.store(addr.as_u64(), Ordering::Relaxed);
 }
 
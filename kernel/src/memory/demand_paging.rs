@@ -5,6 +5,8 @@
 // This module provides two functions:
 //   1. `is_demand_pageable(error_code)` — pre-filter on CPU error code
 //   2. `map_demand_page(fault_addr, vma, pid)` — allocate, zero, map
+//      (plus, if enabled, its fault-around neighbours — see
+//      `FAULT_AROUND_PAGES`)
 //
 // The PAGE FAULT HANDLER (in init/devices.rs) is responsible for:
 //   - Reading CR2
@@ -24,6 +26,8 @@
 // between the memory and process layers.
 // ───────────────────────────────────────────────────────────────────
 
+use core::sync::atomic::{AtomicUsize, Ordering};
+
 use x86_64::{
     VirtAddr,
     registers::control::Cr3,
@@ -34,13 +38,46 @@ use x86_64::{
 };
 
 use crate::memory::vma::{Vma, VmaKind};
-use crate::memory::page_table_manager::BuddyFrameAllocator;
+use crate::memory::page_table_manager::{BuddyFrameAllocator, ZeroedBuddyFrameAllocator};
 
 // Page fault error code bits
 const PF_PRESENT: u64 = 1 << 0;    // 0 = not present, 1 = protection violation
 const PF_WRITE: u64 = 1 << 1;      // 0 = read, 1 = write
 const PF_RESERVED: u64 = 1 << 3;   // 1 = reserved bit set in page table
 
+/// Fault-around cluster size, in 4 KiB pages, for anonymous write faults.
+///
+/// `1` (the default) keeps the original one-page-per-fault behaviour: the
+/// only memory a fault ever commits is the page that was actually touched.
+/// Larger values make `map_demand_page` also map the still-unmapped pages
+/// of the naturally aligned `N`-page window around the faulting page
+/// (clipped to the VMA), so a program sweeping a fresh `mmap` takes one
+/// fault per cluster instead of one per page — the cost being up to
+/// `N - 1` pages committed that might never be touched, which is why this
+/// isn't on by default. Tuned at runtime through `kdebug_ctl` (cmds 4/5);
+/// always a power of two in `1..=MAX_FAULT_AROUND_PAGES`.
+static FAULT_AROUND_PAGES: AtomicUsize = AtomicUsize::new(1);
+
+/// Upper bound for `set_fault_around_pages` — 64 KiB per fault is plenty
+/// to amortise the trap, and keeps a single fault's worst-case frame
+/// commitment (and time spent with IF=0 in the handler) small.
+pub const MAX_FAULT_AROUND_PAGES: usize = 16;
+
+/// Current fault-around cluster size (see `FAULT_AROUND_PAGES`).
+pub fn fault_around_pages() -> usize {
+    FAULT_AROUND_PAGES.load(Ordering::Relaxed)
+}
+
+/// Set the fault-around cluster size. Rejects anything that isn't a power
+/// of two in `1..=MAX_FAULT_AROUND_PAGES`, so the window stays aligned.
+pub fn set_fault_around_pages(pages: usize) -> Result<(), &'static str> {
+    if !pages.is_power_of_two() || pages > MAX_FAULT_AROUND_PAGES {
+        return Err("fault-around must be a power of two <= MAX_FAULT_AROUND_PAGES");
+    }
+    FAULT_AROUND_PAGES.store(pages, Ordering::Relaxed);
+    Ok(())
+}
+
 /// Read CR2 (faulting address) via inline assembly.
 #[inline]
 pub fn read_cr2() -> u64 {
@@ -127,6 +164,9 @@ pub fn map_demand_page(
         VmaKind::Huge2M => {
             return map_demand_page_2m(fault_addr, vma, pid);
         }
+        VmaKind::Shared => {
+            return Err("Shared page not present (mapped at mmap time)");
+        }
         VmaKind::Anonymous | VmaKind::GrowableStack => { /* fall through */ }
     }
 
@@ -149,20 +189,14 @@ pub fn map_demand_page(
         return Ok(());
     }
 
-    // ── Write fault: allocate a real frame, zero-fill, map writable ───
+    // ── Write fault: allocate a zeroed frame, map writable ────────────
     let mut buddy_alloc = BuddyFrameAllocator;
-    let frame = buddy_alloc
+    let frame = ZeroedBuddyFrameAllocator
         .allocate_frame()
         .ok_or("Demand paging: frame allocation failed (OOM)")?;
 
     unsafe { crate::memory::cow::set_ref(frame, 1); }
 
-    unsafe {
-        let phys_offset = crate::memory::physical_memory_offset();
-        let frame_virt = phys_offset + frame.start_address().as_u64();
-        core::ptr::write_bytes(frame_virt.as_mut_ptr::<u8>(), 0, 4096);
-    }
-
     unsafe {
         let mut mapper = create_cr3_mapper();
         mapper
@@ -171,9 +205,54 @@ pub fn map_demand_page(
             .flush();
     }
 
+    let cluster = fault_around_pages();
+    if cluster > 1 {
+        fault_around(page, vma, cluster);
+    }
+
     Ok(())
 }
 
+/// Map the still-unmapped neighbours of `page` within its aligned
+/// `cluster`-page window, clipped to `vma`. Best effort: the faulting page
+/// itself is already mapped by the caller, so an OOM or a `map_to` failure
+/// here just stops early — the neighbours will fault in normally later.
+///
+/// Only write faults get here: a read fault maps the shared zero frame,
+/// which costs nothing to repeat and would be wasted work to pre-populate
+/// (the first write still has to COW every page).
+fn fault_around(page: Page<Size4KiB>, vma: &Vma, cluster: usize) {
+    let window = (cluster as u64) * 4096;
+    let lo = (page.start_address().as_u64() & !(window - 1)).max(vma.start);
+    let hi = ((page.start_address().as_u64() & !(window - 1)) + window).min(vma.end());
+
+    let flags = vma.page_table_flags();
+    let mut buddy_alloc = BuddyFrameAllocator;
+    let mut mapped = 0u64;
+    unsafe {
+        let mut mapper = create_cr3_mapper();
+        let mut addr = lo;
+        while addr < hi {
+            let neighbour: Page<Size4KiB> = Page::containing_address(VirtAddr::new(addr));
+            addr += 4096;
+            if neighbour == page || mapper.translate_page(neighbour).is_ok() {
+                continue;
+            }
+            let Some(frame) = ZeroedBuddyFrameAllocator.allocate_frame() else { break };
+            crate::memory::cow::set_ref(frame, 1);
+            match mapper.map_to(neighbour, frame, flags, &mut buddy_alloc) {
+                Ok(flush) => { flush.flush(); mapped += 1; }
+                Err(_) => {
+                    crate::memory::cow::set_ref(frame, 0);
+                    crate::allocator::phys_free(frame.start_address(), 12);
+                    break;
+                }
+            }
+        }
+    }
+    crate::debug::add_prefaulted(mapped);
+}
+
 /// Map a 2 MiB huge page for `fault_addr` inside a `Huge2M` VMA.
 fn map_demand_page_2m(fault_addr: u64, vma: &Vma, _pid: usize) -> Result<(), &'static str> {
     const PAGE_2M: u64 = 0x200000;
@@ -181,17 +260,10 @@ fn map_demand_page_2m(fault_addr: u64, vma: &Vma, _pid: usize) -> Result<(), &'s
     let page = Page::<Size2MiB>::containing_address(VirtAddr::new(page_start));
 
     let mut buddy_alloc = BuddyFrameAllocator;
-    let frame: x86_64::structures::paging::PhysFrame<Size2MiB> = buddy_alloc
+    let frame: x86_64::structures::paging::PhysFrame<Size2MiB> = ZeroedBuddyFrameAllocator
         .allocate_frame()
         .ok_or("Demand paging 2M: OOM")?;
 
-    // Zero-fill 2 MiB.
-    unsafe {
-        let phys_offset = crate::memory::physical_memory_offset();
-        let virt = (phys_offset + frame.start_address().as_u64()).as_mut_ptr::<u8>();
-        core::ptr::write_bytes(virt, 0, 0x200000);
-    }
-
     // map_to for Size2MiB sets the HUGE_PAGE bit automatically.
     unsafe {
         let mut mapper = create_cr3_mapper();
@@ -33,6 +33,9 @@ const EM_X86_64: u16 = 62;
 // Program header types
 /// Loadable segment — must be mapped into memory.
 pub const PT_LOAD: u32 = 1;
+/// Path of the dynamic linker — present only in dynamically linked
+/// executables, which `parse` rejects.
+pub const PT_INTERP: u32 = 3;
 
 // Program header flags (p_flags)
 /// Segment is executable.
@@ -175,7 +178,18 @@ impl<'a> Elf64<'a> {
             return Err("ELF: program header entry too small");
         }
 
-        Ok(Self { data, header })
+        // ── Static only ───────────────────────────────────────────────
+        //
+        // An ET_EXEC can still be dynamically linked (non-PIE against a
+        // shared libc). There's no dynamic linker to hand it to, and
+        // mapping it anyway would jump into unrelocated PLT stubs — so
+        // say so here instead of crashing it on its first libc call.
+        let elf = Self { data, header };
+        if elf.program_headers().any(|ph| ph.p_type == PT_INTERP) {
+            return Err("ELF: dynamically linked (PT_INTERP); only static executables are supported");
+        }
+
+        Ok(elf)
     }
 
     // ====================================================================
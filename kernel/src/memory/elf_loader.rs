@@ -11,11 +11,13 @@
 //      c. Copy file data (p_filesz) into the mapped pages
 //      d. Zero the remainder (p_memsz - p_filesz) — BSS
 //      e. Register a VMA for the region
-//   4. Register a demand-paged stack VMA (no physical pages yet)
+//   4. Register a demand-paged stack VMA (no physical pages yet), at a
+//      randomized base unless ASLR is off (see `user_stack_base`)
 //   5. Return LoadedElf { entry_point, address_space, user_stack_top }
 //
 // LIMITATIONS:
-//   - Static executables only (no dynamic linker / PT_INTERP).
+//   - Static executables only (no dynamic linker; `Elf64::parse` rejects
+//     PT_INTERP).
 //   - No relocations.
 //   - Segments must not overlap (undefined behavior if they do).
 //   - User code must live in the lower half of the address space.
@@ -35,12 +37,30 @@ use super::vma::{Vma, VmaKind};
 // ============================================================================
 
 /// Default user stack base address.
-/// Each process gets its stack at a unique offset (base + pid * gap).
+/// With ASLR off, each process gets its stack at a unique offset
+/// (base + pid * gap); with it on, anywhere in this PML4 entry.
 const DEFAULT_STACK_BASE: u64 = 0x0000_7100_0000_0000;
 
 /// Gap between process stacks (64 KiB guard + 64 KiB stack = 128 KiB per process).
 const STACK_PROCESS_GAP: u64 = 0x10000;
 
+/// Bytes covered by one PML4 entry (512 GiB). The stack and the mmap
+/// window each have exactly one entry reserved for them
+/// (`page_table_manager::USER_PML4_ENTRIES`); a randomized base that
+/// strays past it would fault on every access.
+const PML4_SPAN: u64 = 1 << 39;
+
+/// How far above `USER_MMAP_BASE` a randomized mmap window may start:
+/// the lower half of PML4[128], leaving the upper 256 GiB (and the
+/// sigreturn trampoline at its very top) for the bump allocator to grow
+/// into.
+const MMAP_RANDOM_SPAN: u64 = PML4_SPAN / 2;
+
+/// Address-space layout randomization for user stacks and mmap windows.
+/// Building with `--features no-aslr` turns it off, bringing back the
+/// fixed per-index layout for reproducible debugging.
+pub const ASLR: bool = !cfg!(feature = "no-aslr");
+
 /// Initial stack size, in 4 KiB pages — every process starts here
 /// regardless of what it'll actually need. The VMA is registered as
 /// `VmaKind::GrowableStack` (see `memory::vma`), so the page fault
@@ -61,6 +81,80 @@ const STACK_PROCESS_GAP: u64 = 0x10000;
 /// value controls.)
 const STACK_PAGES: usize = 16; // 64 KiB
 
+/// Per-process address-space layout: how big the stack starts, whether
+/// to reserve an initial heap region, and whether to randomize bases.
+/// `Default` is what every process got before this existed — 16 stack
+/// pages, no heap region — plus `ASLR` as the `randomize` default.
+#[derive(Debug, Clone, Copy)]
+pub struct UserLayout {
+    /// Initial stack VMA size in pages. It still grows on demand up to
+    /// `STACK_MAX_PAGES`, so this only matters for programs that want
+    /// their stack present without faulting it in page by page.
+    pub stack_pages: usize,
+    /// Pages of demand-paged anonymous memory reserved at the start of
+    /// the mmap window, ahead of anything `mmap` hands out. 0 = none.
+    /// Only `load_elf` honors it; raw-code programs get no heap.
+    pub heap_initial_pages: usize,
+    /// Randomize the stack base and mmap window (`user_stack_base`,
+    /// `user_mmap_base`).
+    pub randomize: bool,
+}
+
+impl Default for UserLayout {
+    fn default() -> Self {
+        UserLayout { stack_pages: STACK_PAGES, heap_initial_pages: 0, randomize: ASLR }
+    }
+}
+
+impl UserLayout {
+    /// Reject sizes that don't fit the PML4 entries reserved for them: a
+    /// stack larger than `STACK_MAX_PAGES` (it couldn't grow, and a
+    /// randomized base leaves exactly that much room), or a heap that
+    /// would run from the highest randomized mmap base into the
+    /// trampoline page.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.stack_pages == 0 || self.stack_pages > crate::memory::vma::STACK_MAX_PAGES {
+            return Err("layout: stack_pages must be 1..=STACK_MAX_PAGES");
+        }
+        if self.heap_initial_pages as u64 >= (PML4_SPAN - MMAP_RANDOM_SPAN) / 4096 - 1 {
+            return Err("layout: heap_initial_pages does not fit the mmap entry");
+        }
+        Ok(())
+    }
+}
+
+/// A uniformly random page-aligned offset in `0..span` (`span` a
+/// multiple of 4096), from the kernel PRNG behind `/dev/random`.
+fn random_page_offset(span: u64) -> u64 {
+    let mut bytes = [0u8; 8];
+    crate::drivers::dev_random::fill(&mut bytes);
+    (u64::from_le_bytes(bytes) % (span / 4096)) * 4096
+}
+
+/// Lowest address of a new process's stack VMA.
+///
+/// Randomized, a random page in PML4[226] chosen so the VMA can still
+/// grow down its full `STACK_MAX_PAGES` without leaving the entry and its
+/// initial `layout.stack_pages` end at or below the entry's top.
+pub fn user_stack_base(process_index: usize, layout: &UserLayout) -> u64 {
+    if !layout.randomize {
+        return DEFAULT_STACK_BASE + (process_index as u64 * STACK_PROCESS_GAP);
+    }
+    let lowest = DEFAULT_STACK_BASE + (crate::memory::vma::STACK_MAX_PAGES as u64 * 4096);
+    let highest = DEFAULT_STACK_BASE + PML4_SPAN - (layout.stack_pages as u64 * 4096);
+    lowest + random_page_offset(highest - lowest + 4096)
+}
+
+/// Where a new process's mmap bump allocator starts: `USER_MMAP_BASE`,
+/// shifted by a random page offset when `layout.randomize`.
+pub fn user_mmap_base(layout: &UserLayout) -> u64 {
+    let base = super::page_table_manager::USER_MMAP_BASE;
+    if !layout.randomize {
+        return base;
+    }
+    base + random_page_offset(MMAP_RANDOM_SPAN)
+}
+
 // ============================================================================
 // Result type
 // ============================================================================
@@ -82,9 +176,13 @@ pub struct LoadedElf {
 /// Load an ELF64 binary into a new user address space.
 ///
 /// `elf_bytes` is the raw ELF file content (e.g. from `include_bytes!`).
-/// `process_index` is used to offset the stack base so processes don't
-/// share stack addresses. The stack VMA starts at `STACK_PAGES` and grows
-/// on demand up to `STACK_MAX_PAGES` — see that constant's doc comment.
+/// `process_index` offsets the stack base so processes don't share stack
+/// addresses when `layout.randomize` is off; with it on, the stack base and
+/// mmap window are random instead (`user_stack_base`, `user_mmap_base`).
+/// The stack VMA starts at `layout.stack_pages` and grows on demand up to
+/// `STACK_MAX_PAGES` — see `STACK_PAGES`'s doc comment. A non-zero
+/// `layout.heap_initial_pages` is registered as an anonymous VMA at the
+/// start of the mmap window, which then begins one guard page past it.
 ///
 /// # Safety
 /// - Buddy allocator must be initialized.
@@ -92,14 +190,17 @@ pub struct LoadedElf {
 pub unsafe fn load_elf(
     elf_bytes: &[u8],
     process_index: usize,
+    layout: &UserLayout,
     argv: &[Vec<u8>],
     envp: &[Vec<u8>],
 ) -> Result<LoadedElf, &'static str> {
+    layout.validate()?;
+
     // ── 1. Parse ELF ──────────────────────────────────────────────────
 
     let elf = Elf64::parse(elf_bytes)?;
 
-    crate::serial_println!(
+    crate::debug!(
         "ELF: entry={:#x}, {} program headers",
         elf.entry_point(),
         elf.ph_count(),
@@ -110,7 +211,7 @@ pub unsafe fn load_elf(
     let mut address_space = AddressSpace::new_user()
         .map_err(|_| "ELF loader: failed to create address space")?;
 
-    crate::serial_println!(
+    crate::debug!(
         "ELF: address space created, PML4 at {:#x}",
         address_space.root_frame().start_address().as_u64(),
     );
@@ -121,9 +222,34 @@ pub unsafe fn load_elf(
         load_segment(&elf, ph, &mut address_space)?;
     }
 
+    // ── 3b. Place the mmap window (and initial heap, if any) ──────────
+
+    let mut mmap_base = user_mmap_base(layout);
+    if layout.heap_initial_pages > 0 {
+        let heap_flags = PageTableFlags::PRESENT
+            | PageTableFlags::WRITABLE
+            | PageTableFlags::USER_ACCESSIBLE;
+        address_space.add_vma(Vma {
+            start: mmap_base,
+            size_pages: layout.heap_initial_pages,
+            flags: heap_flags.bits(),
+            kind: VmaKind::Anonymous,
+        }).map_err(|_| "ELF loader: failed to register heap VMA")?;
+        crate::debug!(
+            "ELF: heap VMA {:#x}..{:#x} ({} pages, demand-paged)",
+            mmap_base,
+            mmap_base + (layout.heap_initial_pages as u64 * 4096),
+            layout.heap_initial_pages,
+        );
+        // One guard page, same as between two mmap allocations.
+        mmap_base += (layout.heap_initial_pages as u64 + 1) * 4096;
+    }
+    address_space.set_mmap_base(mmap_base);
+
     // ── 4. Set up demand-paged stack VMA ──────────────────────────────
 
-    let stack_base = DEFAULT_STACK_BASE + (process_index as u64 * STACK_PROCESS_GAP);
+    let stack_base = user_stack_base(process_index, layout);
+    let stack_pages = layout.stack_pages;
 
     let stack_flags = PageTableFlags::PRESENT
         | PageTableFlags::WRITABLE
@@ -131,16 +257,16 @@ pub unsafe fn load_elf(
 
     address_space.add_vma(Vma {
         start: stack_base,
-        size_pages: STACK_PAGES,
+        size_pages: stack_pages,
         flags: stack_flags.bits(),
         kind: VmaKind::GrowableStack,
     }).map_err(|_| "ELF loader: failed to register stack VMA")?;
 
-    crate::serial_println!(
+    crate::debug!(
         "ELF: stack VMA {:#x}..{:#x} ({} pages, demand-paged, grows to {} max)",
         stack_base,
-        stack_base + (STACK_PAGES as u64 * 4096),
-        STACK_PAGES,
+        stack_base + (stack_pages as u64 * 4096),
+        stack_pages,
         crate::memory::vma::STACK_MAX_PAGES,
     );
 
@@ -172,7 +298,7 @@ pub unsafe fn load_elf(
     }
 
     // Pre-allocate and map the top stack page so we can write to it now.
-    let top_page_vaddr = stack_base + ((STACK_PAGES as u64 - 1) * 4096);
+    let top_page_vaddr = stack_base + ((stack_pages as u64 - 1) * 4096);
     let top_page = Page::<Size4KiB>::containing_address(VirtAddr::new(top_page_vaddr));
     let stack_page_frame = address_space
         .map_user_page(top_page, stack_flags)
@@ -180,11 +306,9 @@ pub unsafe fn load_elf(
 
     let phys_offset = crate::memory::physical_memory_offset();
     let page_virt = phys_offset + stack_page_frame.start_address().as_u64();
+    // Already zeroed by `map_user_page`.
     let page_ptr = page_virt.as_mut_ptr::<u8>();
 
-    // Zero the page.
-    core::ptr::write_bytes(page_ptr, 0, 4096);
-
     // Write argc/argv/envp/auxv (see build_initial_stack's doc comment for
     // the exact layout) into this page and get back the resulting RSP.
     let rsp_va = build_initial_stack(
@@ -192,7 +316,7 @@ pub unsafe fn load_elf(
         phdr_vaddr, elf.ph_count(), elf.entry_point(),
     )?;
 
-    crate::serial_println!(
+    crate::debug!(
         "ELF: initial stack at {:#x} (argc={}, envc={}, phdr_vaddr={:#x}, ph_count={})",
         rsp_va, argv.len(), envp.len(), phdr_vaddr, elf.ph_count(),
     );
@@ -215,7 +339,6 @@ pub unsafe fn load_elf(
             .map_err(|_| "ELF loader: failed to map sigreturn trampoline")?;
 
         let tramp_virt = (phys_offset + tramp_frame.start_address().as_u64()).as_mut_ptr::<u8>();
-        core::ptr::write_bytes(tramp_virt, 0, 4096);
         core::ptr::copy_nonoverlapping(TRAMPOLINE_CODE.as_ptr(), tramp_virt, TRAMPOLINE_CODE.len());
 
         address_space.add_vma(Vma {
@@ -375,7 +498,7 @@ unsafe fn load_segment(
 
     let flags = elf_flags_to_page_flags(ph.p_flags);
 
-    crate::serial_println!(
+    crate::debug!(
         "ELF: LOAD {:#x}..{:#x} ({} pages) filesz={:#x} memsz={:#x} flags={:#x}",
         aligned_start,
         aligned_end,
@@ -405,30 +528,25 @@ unsafe fn load_segment(
         // in the page 0x401000).  In that case, reuse the existing frame
         // instead of trying to map it again (which would fail with
         // PageAlreadyMapped).
-        let (frame, is_new_page) = match address_space.translate_page(page) {
-            Some(existing) => (existing, false),
-            None => {
-                let f = address_space
-                    .map_user_page(page, flags)
-                    .map_err(|_| "ELF loader: failed to map page")?;
-                (f, true)
-            }
+        let frame = match address_space.translate_page(page) {
+            Some(existing) => existing,
+            None => address_space
+                .map_user_page(page, flags)
+                .map_err(|_| "ELF loader: failed to map page")?,
         };
 
         // Compute how much of this page comes from the file vs BSS
         let frame_virt = phys_offset + frame.start_address().as_u64();
         let dst = frame_virt.as_mut_ptr::<u8>();
 
-        // Zero only freshly-allocated pages.
+        // Freshly-allocated pages come back zeroed from `map_user_page`,
+        // which is what makes the BSS part of a segment read as zero.
         //
         // If the page was already mapped by a previous PT_LOAD segment
-        // (e.g. .text and .rodata share the same 4K page), zeroing it
-        // would destroy the first segment's data.  Reused pages already
-        // contain valid content — we only write the current segment's
-        // bytes at the correct intra-page offset below.
-        if is_new_page {
-            core::ptr::write_bytes(dst, 0, 4096);
-        }
+        // (e.g. .text and .rodata share the same 4K page), it must NOT be
+        // zeroed again — that would destroy the first segment's data.
+        // Reused pages already contain valid content — we only write the
+        // current segment's bytes at the correct intra-page offset below.
 
         // Compute overlap between this page and the file data
         let page_start_in_seg = if page_vaddr >= seg_vaddr {
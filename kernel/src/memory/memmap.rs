@@ -0,0 +1,96 @@
+// kernel/src/memory/memmap.rs
+//
+// Snapshot of the bootloader's physical memory map. `BootInfo` only lives
+// as long as `kernel_main`'s borrow of it, so `init::memory::init_core`
+// copies the regions here (start, end, kind — no heap: it runs before the
+// Buddy is seeded) for `/proc/memmap` and the `memmap` program to show
+// later.
+
+use bootloader_api::info::{MemoryRegionKind, MemoryRegions};
+use spin::Once;
+
+/// UEFI firmware maps run to a hundred-odd entries; anything past this is
+/// counted in `Memmap::dropped` instead of stored.
+const MAX_REGIONS: usize = 256;
+
+#[derive(Clone, Copy)]
+pub struct Region {
+    pub start: u64,
+    pub end: u64,
+    pub kind: MemoryRegionKind,
+}
+
+pub struct Memmap {
+    regions: [Region; MAX_REGIONS],
+    len: usize,
+    dropped: usize,
+}
+
+impl Memmap {
+    pub fn regions(&self) -> &[Region] {
+        &self.regions[..self.len]
+    }
+}
+
+static MEMMAP: Once<Memmap> = Once::new();
+
+/// Copy the bootloader's regions. Called once from `init_core`; later
+/// calls are no-ops.
+pub fn record(memory_regions: &MemoryRegions) {
+    MEMMAP.call_once(|| {
+        let empty = Region { start: 0, end: 0, kind: MemoryRegionKind::Bootloader };
+        let mut map = Memmap { regions: [empty; MAX_REGIONS], len: 0, dropped: 0 };
+        for region in memory_regions.iter() {
+            if map.len == MAX_REGIONS {
+                map.dropped += 1;
+                continue;
+            }
+            map.regions[map.len] = Region { start: region.start, end: region.end, kind: region.kind };
+            map.len += 1;
+        }
+        map
+    });
+}
+
+/// The snapshot, or `None` before `init_core`.
+pub fn memmap() -> Option<&'static Memmap> {
+    MEMMAP.get()
+}
+
+fn kind_name(kind: MemoryRegionKind) -> alloc::string::String {
+    match kind {
+        MemoryRegionKind::Usable => "usable".into(),
+        MemoryRegionKind::Bootloader => "bootloader".into(),
+        MemoryRegionKind::UnknownUefi(t) => alloc::format!("uefi({})", t),
+        MemoryRegionKind::UnknownBios(t) => alloc::format!("bios({})", t),
+        _ => "unknown".into(),
+    }
+}
+
+/// `/proc/memmap`: one `start-end kind size` line per region (end
+/// exclusive, like the bootloader reports it), then per-kind totals.
+pub fn render_memmap() -> alloc::string::String {
+    use core::fmt::Write;
+
+    let mut out = alloc::string::String::new();
+    let Some(map) = memmap() else { return out };
+
+    let (mut usable, mut bootloader, mut reserved) = (0u64, 0u64, 0u64);
+    for r in map.regions() {
+        let size = r.end - r.start;
+        let _ = writeln!(out, "{:#014x}-{:#014x} {:<12} {:>10} KiB",
+            r.start, r.end, kind_name(r.kind), size / 1024);
+        match r.kind {
+            MemoryRegionKind::Usable => usable += size,
+            MemoryRegionKind::Bootloader => bootloader += size,
+            _ => reserved += size,
+        }
+    }
+    if map.dropped > 0 {
+        let _ = writeln!(out, "({} more regions not recorded)", map.dropped);
+    }
+    let _ = writeln!(out, "usable:     {:>10} KiB", usable / 1024);
+    let _ = writeln!(out, "bootloader: {:>10} KiB", bootloader / 1024);
+    let _ = writeln!(out, "reserved:   {:>10} KiB", reserved / 1024);
+    out
+}
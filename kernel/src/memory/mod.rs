@@ -4,17 +4,18 @@ use x86_64::VirtAddr;
 use core::sync::atomic::{AtomicU64, Ordering};
 
 pub mod paging;
-pub mod frame_allocator;
 pub mod user_pages;
 pub mod user_code;
 pub mod page_table_manager;
 pub mod vma;
 pub mod cow;
 pub mod demand_paging;
+pub mod tlb;
 pub mod address_space;
 pub mod elf;
 pub mod elf_loader;
 pub mod signal_trampoline;
+pub mod memmap;
 
 static PHYSICAL_MEMORY_OFFSET: AtomicU64 = AtomicU64::new(0);
 
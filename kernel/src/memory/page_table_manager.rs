@@ -15,22 +15,27 @@
 //    Copying them would SHARE the intermediate page tables (PDPT/PD/PT)
 //    between processes, causing PageAlreadyMapped on the second process.
 //
-// 3. All frame allocations use the Buddy allocator (not BootInfoFrameAllocator)
-//    to avoid double-allocation with the heap.
+// 3. All frame allocations use the Buddy allocator (the old
+//    BootInfoFrameAllocator drew from the same regions, double-allocating
+//    with the heap — it has since been removed).
 //
 // 4. NX (No-Execute) bit: Do NOT set unless EFER.NXE is confirmed enabled.
+//    Whether the CPU supports it at all is `cpu::features::features().nx`;
+//    nothing sets EFER.NXE yet, so nothing sets the bit either.
 
 use x86_64::{
     PhysAddr, VirtAddr,
     registers::control::Cr3,
     structures::paging::{
         FrameAllocator, Mapper, OffsetPageTable, Page, PageTable,
-        PageTableFlags, PhysFrame, Size2MiB, Size4KiB,
+        PageTableFlags, PhysFrame, Size2MiB, Size4KiB, Translate,
         page_table::FrameError,
         mapper::MapToError,
     },
 };
 
+use super::tlb::TlbFlush;
+
 
 // ============================================================================
 // User address layout — which PML4 entries user processes own
@@ -90,6 +95,30 @@ unsafe impl FrameAllocator<Size2MiB> for BuddyFrameAllocator {
     }
 }
 
+/// `BuddyFrameAllocator` whose frames come back zero-filled
+/// (`phys_alloc_zeroed`). Use it for every frame user space can read
+/// before writing — anonymous/demand pages, fresh stack and BSS pages,
+/// `MAP_SHARED` pages — so "new memory reads as zero" doesn't depend on
+/// each call site remembering its own `write_bytes`. Intermediate page
+/// tables don't need it: `map_to` zeroes the tables it creates itself.
+pub struct ZeroedBuddyFrameAllocator;
+
+unsafe impl FrameAllocator<Size4KiB> for ZeroedBuddyFrameAllocator {
+    fn allocate_frame(&mut self) -> Option<PhysFrame<Size4KiB>> {
+        unsafe {
+            crate::allocator::phys_alloc_zeroed(12).map(PhysFrame::containing_address)
+        }
+    }
+}
+
+unsafe impl FrameAllocator<Size2MiB> for ZeroedBuddyFrameAllocator {
+    fn allocate_frame(&mut self) -> Option<PhysFrame<Size2MiB>> {
+        unsafe {
+            crate::allocator::phys_alloc_zeroed(21).map(PhysFrame::containing_address)
+        }
+    }
+}
+
 // ============================================================================
 // OwnedPageTable
 // ============================================================================
@@ -168,7 +197,7 @@ impl OwnedPageTable {
 
             if is_user_pml4_entry(i) {
                 skipped += 1;
-                crate::serial_println!(
+                crate::trace!(
                     "  PML4[{}]: SKIPPED (user address range, flags={:#x})",
                     i,
                     kernel_pml4[i].flags().bits()
@@ -180,11 +209,11 @@ impl OwnedPageTable {
             copied += 1;
         }
 
-        crate::serial_println!(
+        crate::debug!(
             "  Creating new page table: PML4 at {:#x}",
             new_frame.start_address().as_u64()
         );
-        crate::serial_println!(
+        crate::debug!(
             "  Copied {} kernel entries, skipped {} user-range entries",
             copied, skipped
         );
@@ -243,7 +272,17 @@ impl OwnedPageTable {
         mapper.translate_page(page).ok()
     }
 
+    /// Physical address behind `addr`, through 4 KiB and 2 MiB leaves alike
+    /// (`translate_page` only sees 4 KiB ones — a `Huge2M` stack VMA comes
+    /// back `None` there). `None` if nothing is mapped.
+    pub unsafe fn translate_addr(&self, addr: VirtAddr) -> Option<PhysAddr> {
+        self.create_mapper().translate_addr(addr)
+    }
+
     /// Map one user page.  Allocates data + intermediate frames from Buddy.
+    /// The data frame comes back zeroed (`ZeroedBuddyFrameAllocator`), so
+    /// callers only write what they actually have — no zero pass for BSS
+    /// or the tail of a partially copied page.
     /// Sets the frame's COW refcount to 1 (single owner).
     pub unsafe fn map_user_page(
         &self,
@@ -252,7 +291,7 @@ impl OwnedPageTable {
     ) -> Result<PhysFrame, MapToError<Size4KiB>> {
         let mut buddy_alloc = BuddyFrameAllocator;
 
-        let frame = buddy_alloc
+        let frame = ZeroedBuddyFrameAllocator
             .allocate_frame()
             .ok_or(MapToError::FrameAllocationFailed)?;
 
@@ -345,10 +384,17 @@ impl OwnedPageTable {
     /// If the page is not mapped (not yet demand-paged), this is a no-op.
     /// Decrements the COW refcount; if it reaches zero, returns the frame
     /// to the Buddy allocator.  Intermediate page table frames are preserved.
+    /// The TLB entry is queued on `tlb` rather than invalidated here — see
+    /// `memory::tlb` for why freeing the frame before that batch flushes is
+    /// fine on a single core.
     ///
     /// # Safety
     /// Must be called with interrupts disabled (cli).
-    pub unsafe fn unmap_page_and_free(&self, page: Page<Size4KiB>) -> Result<(), &'static str> {
+    pub unsafe fn unmap_page_and_free(
+        &self,
+        page: Page<Size4KiB>,
+        tlb: &mut TlbFlush,
+    ) -> Result<(), &'static str> {
         let frame = match self.translate_page(page) {
             Some(f) => f,
             None => return Ok(()),  // never demand-paged; nothing to free
@@ -358,7 +404,8 @@ impl OwnedPageTable {
         let (_, flush) = mapper
             .unmap(page)
             .map_err(|_| "unmap_page_and_free: unmap failed")?;
-        flush.flush();
+        flush.ignore();
+        tlb.add(page.start_address());
 
         // Zero-frame is permanent — it has no refcount entry, never free it.
         if !crate::memory::cow::is_zero_frame(frame) {
@@ -374,15 +421,17 @@ impl OwnedPageTable {
     ///
     /// If the page is not mapped, this is a no-op.
     /// Huge frames are freed with order=21 directly (no COW refcount).
+    /// Queues the TLB entry on `tlb`, like `unmap_page_and_free`.
     ///
     /// # Safety
     /// Must be called with interrupts disabled (cli).
     pub unsafe fn unmap_page_and_free_2m(
         &self,
         page: Page<Size2MiB>,
+        tlb: &mut TlbFlush,
     ) -> Result<(), &'static str> {
-        let mut buddy = crate::allocator::buddy_allocator::BUDDY.lock();
-        self.unmap_page_and_free_2m_with_buddy(page, &mut buddy)
+        let mut buddy = crate::allocator::buddy_allocator::lock_buddy();
+        self.unmap_page_and_free_2m_with_buddy(page, &mut buddy, tlb)
     }
 
     /// Same as `unmap_page_and_free_2m`, but takes an already-locked Buddy
@@ -397,13 +446,15 @@ impl OwnedPageTable {
         &self,
         page: Page<Size2MiB>,
         buddy: &mut crate::allocator::buddy_allocator::BuddyAllocator,
+        tlb: &mut TlbFlush,
     ) -> Result<(), &'static str> {
         let mut mapper = self.create_mapper();
         let (frame, flush) = match mapper.unmap(page) {
             Ok(r) => r,
             Err(_) => return Ok(()),  // not mapped — nothing to free
         };
-        flush.flush();
+        flush.ignore();
+        tlb.add(page.start_address());
         buddy.deallocate(frame.start_address(), 21);
         Ok(())
     }
@@ -574,12 +625,22 @@ impl OwnedPageTable {
     }
 }
 
+/// Freeing a page table that is still loaded in CR3 — exec() drops the old
+/// address space before `jump_to_user` switches to the new one — would
+/// leave the CPU walking a PML4 (and TLB entries pointing at data frames)
+/// already handed back to the Buddy, where the next allocation can
+/// overwrite them. Switching to the kernel page table first makes the
+/// teardown safe and, being a CR3 write, also drops every stale user TLB
+/// entry; an inactive table has none to begin with (see `memory::tlb`).
 impl Drop for OwnedPageTable {
     fn drop(&mut self) {
         if !self.owned {
             // Kernel page table (from_current) — never free, it belongs to the kernel.
             return;
         }
+        if Cr3::read().0 == self.pml4_frame {
+            unsafe { crate::memory::address_space::AddressSpace::kernel().activate(); }
+        }
         unsafe { self.release_user_pages(); }
     }
 }
@@ -764,4 +825,135 @@ pub unsafe fn remap_kernel_guard_page(virt_addr: VirtAddr) -> Result<(), &'stati
 
     x86_64::instructions::tlb::flush(virt_addr);
     Ok(())
-}
\ No newline at end of file
+}
+/// Make the physmap page at `virt_addr` uncacheable (PCD|PWT), for an MMIO
+/// register page reached through the physmap instead of a dedicated
+/// mapping — the Local APIC's, see `crate::lapic`. The bootloader maps the
+/// whole physmap write-back, which the SDM says device registers must not
+/// be. Splits the covering 2MiB page first if needed (see
+/// `split_physmap_2m`); errors if the physmap doesn't reach `virt_addr`
+/// at all.
+///
+/// # Safety
+/// `virt_addr` must be `physical_memory_offset() + phys` for an MMIO page
+/// no RAM allocation can land in (the split itself is then as safe as for
+/// `unmap_kernel_guard_page`). Boot only, interrupts disabled.
+pub unsafe fn map_physmap_uncached(virt_addr: VirtAddr) -> Result<(), &'static str> {
+    split_physmap_2m(virt_addr)?;
+    let (pt, pt_idx) = walk_to_pt(virt_addr)?;
+    let flags = pt[pt_idx].flags();
+    if !flags.contains(PageTableFlags::PRESENT) {
+        return Err("map_physmap_uncached: page not in physmap");
+    }
+    pt[pt_idx].set_flags(flags | PageTableFlags::NO_CACHE | PageTableFlags::WRITE_THROUGH);
+    x86_64::instructions::tlb::flush(virt_addr);
+    Ok(())
+}
+
+// ============================================================================
+// Page walk (diagnostics)
+// ============================================================================
+
+/// One level of a `walk`: which table, the index used, and the raw entry.
+#[derive(Clone, Copy)]
+pub struct WalkLevel {
+    pub table: &'static str,
+    pub index: usize,
+    pub entry: u64,
+}
+
+impl WalkLevel {
+    pub fn flags(&self) -> PageTableFlags {
+        PageTableFlags::from_bits_truncate(self.entry)
+    }
+}
+
+/// Result of `walk`: the entries visited, top-down, and where the walk
+/// ended — `phys`/`page_size` if it reached a leaf (a 4KiB PTE, or a
+/// huge PDE/PDPTE, reported rather than descended), `None` if the last
+/// entry visited was not present.
+pub struct PageWalk {
+    pub addr: VirtAddr,
+    levels: [WalkLevel; 4],
+    depth: usize,
+    pub phys: Option<PhysAddr>,
+    pub page_size: u64,
+}
+
+impl PageWalk {
+    pub fn levels(&self) -> &[WalkLevel] {
+        &self.levels[..self.depth]
+    }
+}
+
+/// Walk the page table loaded in CR3 (the current process's address space)
+/// for `addr`, PML4 → PDPT → PD → PT, without changing anything.
+///
+/// # Safety
+/// The physical memory offset must be set (`memory::init`); tables are
+/// read through the physmap.
+pub unsafe fn walk(addr: VirtAddr) -> PageWalk {
+    const ADDR_MASK: u64 = 0x000F_FFFF_FFFF_F000;
+    const TABLES: [(&str, u32, u64); 4] = [
+        ("PML4", 39, 0),
+        ("PDPT", 30, 1 << 30),
+        ("PD", 21, 2 << 20),
+        ("PT", 12, 4 << 10),
+    ];
+
+    let phys_offset = crate::memory::physical_memory_offset();
+    let va = addr.as_u64();
+    let empty = WalkLevel { table: "", index: 0, entry: 0 };
+    let mut walk = PageWalk { addr, levels: [empty; 4], depth: 0, phys: None, page_size: 0 };
+
+    let mut table_phys = Cr3::read().0.start_address().as_u64();
+    for (level, &(table, shift, page_size)) in TABLES.iter().enumerate() {
+        let index = ((va >> shift) & 0x1FF) as usize;
+        let entries = &*(phys_offset + table_phys).as_ptr::<[u64; 512]>();
+        let entry = entries[index];
+        walk.levels[level] = WalkLevel { table, index, entry };
+        walk.depth = level + 1;
+
+        let flags = PageTableFlags::from_bits_truncate(entry);
+        if !flags.contains(PageTableFlags::PRESENT) {
+            return walk;
+        }
+        // PS in a PDPTE/PDE is a 1GiB/2MiB leaf; in the PT, every entry is.
+        let leaf = level == 3 || (level > 0 && flags.contains(PageTableFlags::HUGE_PAGE));
+        if leaf {
+            let base = entry & ADDR_MASK & !(page_size - 1);
+            walk.phys = Some(PhysAddr::new(base + (va & (page_size - 1))));
+            walk.page_size = page_size;
+            return walk;
+        }
+        table_phys = entry & ADDR_MASK;
+    }
+    walk
+}
+
+impl core::fmt::Display for PageWalk {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "pagewalk {:#x}", self.addr.as_u64())?;
+        for level in self.levels() {
+            let flags = level.flags();
+            write!(f, "  {:<4}[{:>3}] = {:#018x}", level.table, level.index, level.entry)?;
+            for (bit, name) in [
+                (PageTableFlags::PRESENT, "present"),
+                (PageTableFlags::WRITABLE, "writable"),
+                (PageTableFlags::USER_ACCESSIBLE, "user"),
+                (PageTableFlags::NO_EXECUTE, "nx"),
+                (PageTableFlags::HUGE_PAGE, "huge"),
+            ] {
+                if flags.contains(bit) {
+                    write!(f, " {}", name)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        match (self.phys, self.levels().last()) {
+            (Some(phys), _) => writeln!(f, "  -> phys {:#x} ({} KiB page)", phys.as_u64(), self.page_size / 1024),
+            (None, Some(last)) => writeln!(f, "  not mapped: {} entry not present", last.table),
+            (None, None) => writeln!(f, "  not mapped"),
+        }
+    }
+}
@@ -17,8 +17,9 @@
 /// `USER_MMAP_BASE`), or 226 (stack, `elf_loader::DEFAULT_STACK_BASE`); any
 /// other PML4 index gets a kernel-copied (non-user) entry and `map_user_page`
 /// fails there. Placed near the top of PML4[128]'s ~512 GiB range — mmap's
-/// bump allocator starts at `USER_MMAP_BASE` and grows upward, so this is
-/// unreachable by any realistic mmap usage.
+/// bump allocator starts at most 256 GiB above `USER_MMAP_BASE` (ASLR, see
+/// `elf_loader::user_mmap_base`) and grows upward, so this is unreachable
+/// by any realistic mmap usage.
 pub const TRAMPOLINE_VA: u64 = 0x0000_407F_FFFF_F000;
 
 /// `mov eax, SYS_SIGRETURN ; syscall` — SYS_SIGRETURN must match
@@ -0,0 +1,93 @@
+// kernel/src/memory/tlb.rs
+//
+// Batched TLB invalidation for multi-page unmaps.
+//
+// Unmapping one page and `invlpg`-ing it right away is what every
+// `MapperFlush::flush()` call site used to do, one at a time. That's
+// correct, but a `munmap` of a big region (or anything else that tears
+// down many pages in a row) pays one serialising `invlpg` per page, and
+// there was no single place that said when the TLB is guaranteed coherent
+// again. `TlbFlush` is that place: unmap sites record the pages they
+// cleared with `add`, and the batch is invalidated in one go when it's
+// flushed (explicitly, or when it drops — so an early `?` return can't
+// leave stale entries behind). Past `FLUSH_ALL_THRESHOLD` pages, a full
+// CR3 reload is cheaper than that many `invlpg`s, so the batch stops
+// recording addresses and just does that.
+//
+// ── Single core only ───────────────────────────────────────────────
+// This only invalidates the *current* CPU's TLB, which is all there is
+// today. Callers free the unmapped frames before the batch flushes; that's
+// fine here because they run with IF=0 and never touch the unmapped user
+// addresses in between, so no stale entry can be used before the flush.
+// On SMP it would not be: other cores could still hold the old
+// translations, so this would need a shootdown IPI and frames would have
+// to be freed only after every core acknowledged it. That's out of scope
+// until there's a second core to run on.
+//
+// Process teardown doesn't need a batch at all: a page table that isn't
+// loaded has no TLB entries (user pages are never GLOBAL, and there's no
+// PCID, so the CR3 write that switched away from it dropped them all), and
+// `OwnedPageTable`'s `Drop` switches to the kernel page table first if the
+// one being freed is still loaded (the exec() case) — see its doc comment.
+
+use x86_64::VirtAddr;
+
+/// Above this many pages, one CR3 reload replaces the individual `invlpg`s.
+/// Linux settles on 33 for the same trade-off (`tlb_single_page_flush_ceiling`);
+/// 32 keeps the pending-address array a round 256 bytes.
+pub const FLUSH_ALL_THRESHOLD: usize = 32;
+
+/// Pending TLB invalidations for pages unmapped from the active page table.
+/// Flushes on drop if `flush` wasn't called.
+#[must_use = "a TlbFlush flushes when dropped; keep it alive for the whole unmap batch"]
+pub struct TlbFlush {
+    pages: [u64; FLUSH_ALL_THRESHOLD],
+    count: usize,
+    full: bool,
+}
+
+impl TlbFlush {
+    pub const fn new() -> Self {
+        Self { pages: [0; FLUSH_ALL_THRESHOLD], count: 0, full: false }
+    }
+
+    /// Record that the page containing `addr` (4 KiB or 2 MiB — `invlpg` on
+    /// any address inside a huge page drops its whole entry) was unmapped.
+    pub fn add(&mut self, addr: VirtAddr) {
+        if self.full {
+            return;
+        }
+        if self.count == FLUSH_ALL_THRESHOLD {
+            self.full = true;
+            return;
+        }
+        self.pages[self.count] = addr.as_u64();
+        self.count += 1;
+    }
+
+    /// Make every page recorded so far coherent: one `invlpg` each, or a
+    /// single `flush_all` if the batch overflowed. Leaves the batch empty,
+    /// so it can keep being used.
+    pub fn flush(&mut self) {
+        if self.full {
+            flush_all();
+        } else {
+            for &addr in &self.pages[..self.count] {
+                x86_64::instructions::tlb::flush(VirtAddr::new(addr));
+            }
+        }
+        self.count = 0;
+        self.full = false;
+    }
+}
+
+impl Drop for TlbFlush {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Drop every non-global TLB entry by reloading CR3.
+pub fn flush_all() {
+    x86_64::instructions::tlb::flush_all();
+}
@@ -35,7 +35,7 @@ where
 
     let num_pages = (code_size + 4095) / 4096;
     
-    crate::serial_println!(
+    crate::debug!(
         "Setting up user code: {} bytes ({} pages)",
         code_size,
         num_pages
@@ -77,7 +77,7 @@ where
             core::ptr::write_bytes(dst.add(copy_size), 0, 4096 - copy_size);
         }
 
-        crate::serial_println!(
+        crate::trace!(
             "  Page {}: virt={:#x} -> phys={:#x}, copied {} bytes",
             i,
             page_addr.as_u64(),
@@ -86,27 +86,6 @@ where
         );
     }
 
-    crate::serial_println!("User code setup complete");
+    crate::debug!("User code setup complete");
     Ok(VirtAddr::new(USER_CODE_BASE))
 }
-
-/// Obtiene el tamaño del código de una función
-/// 
-/// HACK: Asumimos que la siguiente función está después de esta.
-/// Para producción, necesitarías símbolos del linker.
-/// 
-/// # Safety
-/// Esto es extremadamente unsafe y solo funciona como heurística
-pub unsafe fn estimate_code_size(func_ptr: *const u8, next_func_ptr: Option<*const u8>) -> usize {
-    let start = func_ptr as usize;
-    
-    if let Some(next) = next_func_ptr {
-        let end = next as usize;
-        if end > start {
-            return end - start;
-        }
-    }
-    
-    // Fallback: asumir 4KB (1 página)
-    4096
-}
\ No newline at end of file
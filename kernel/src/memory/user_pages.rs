@@ -8,6 +8,8 @@ use x86_64::{
     },
 };
 
+use super::tlb::TlbFlush;
+
 /// Mapea páginas con permisos de usuario (USER_ACCESSIBLE)
 /// 
 /// # Safety
@@ -29,7 +31,7 @@ where
               | PageTableFlags::WRITABLE
               | PageTableFlags::USER_ACCESSIBLE; // ← Clave para Ring 3
     
-    crate::serial_println!(
+    crate::debug!(
         "Mapping {} user pages at {:#x}",
         num_pages,
         start.as_u64()
@@ -44,7 +46,7 @@ where
             .allocate_frame()
             .ok_or("Failed to allocate frame for user page")?;
         
-        crate::serial_println!(
+        crate::trace!(
             "  Page {}: virt={:#x} -> phys={:#x}",
             i,
             page_addr.as_u64(),
@@ -58,16 +60,22 @@ where
             .flush();
     }
     
-    crate::serial_println!("User pages mapped successfully");
+    crate::debug!("User pages mapped successfully");
     Ok(())
 }
 
 /// Desmapea páginas de usuario
+///
+/// Los `invlpg` se juntan en un `TlbFlush` (ver `memory::tlb`): uno por
+/// página hasta `FLUSH_ALL_THRESHOLD`, un recargue de CR3 por encima. Si
+/// un `unmap` falla a mitad de camino, el `Drop` del batch igual invalida
+/// las páginas que sí se desmapearon.
 pub unsafe fn unmap_user_pages(
     mapper: &mut impl Mapper<Size4KiB>,
     start: VirtAddr,
     num_pages: usize,
 ) -> Result<(), &'static str> {
+    let mut tlb = TlbFlush::new();
     for i in 0..num_pages {
         let page_addr = start + (i as u64 * 4096);
         let page: Page<Size4KiB> = Page::containing_address(page_addr);
@@ -76,8 +84,10 @@ pub unsafe fn unmap_user_pages(
             .unmap(page)
             .map_err(|_| "Failed to unmap user page")?
             .1
-            .flush();
+            .ignore();
+        tlb.add(page_addr);
     }
+    tlb.flush();
     
     Ok(())
 }
\ No newline at end of file
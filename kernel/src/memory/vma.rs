@@ -60,6 +60,17 @@ pub enum VmaKind {
     /// actually used, same idea as a real OS's `RLIMIT_STACK`-capped
     /// growable stack VMA. See `VmaList::grow_stack`.
     GrowableStack,
+    /// `mmap(MAP_SHARED | MAP_ANONYMOUS)`: every page is allocated, zeroed
+    /// and mapped at `mmap` time (`AddressSpace::sys_mmap_anon`), never
+    /// demand-paged, and `fork()` maps the *same* frames into the child
+    /// writable instead of write-protecting both sides for COW — so a store
+    /// in either process is seen by the other. Eager because sharing
+    /// happens at fork: a page neither side had touched yet would otherwise
+    /// be demand-faulted separately by each, into two different frames.
+    /// Each sharer holds one `cow` refcount on every frame, so `munmap`/
+    /// teardown free a frame only when its last sharer lets go — same
+    /// bookkeeping as a COW-shared frame.
+    Shared,
 }
 
 /// A single virtual memory area.
@@ -234,6 +245,7 @@ impl VmaList {
                 VmaKind::Code => "code",
                 VmaKind::Huge2M => "huge2m",
                 VmaKind::GrowableStack => "stack(grows down)",
+                VmaKind::Shared => "shared",
             };
             crate::serial_println!(
                 "  {:#x}..{:#x} ({} pages) [{}] flags={:#x}",
@@ -3,14 +3,14 @@
 // PS/2 mouse (auxiliary device) driver — thin kernel-side adapter around
 // `hal::mouse`'s pure packet decoder + PortIo-generic 8042 enable
 // sequence. Parallel to keyboard.rs's role for the primary PS/2 port:
-// process_byte() is called from the IRQ12 ISR, read_event() is the
+// process_byte() is called from the IRQ12 handler, read_event() is the
 // non-blocking consumer API (mirrors keyboard::read_raw_event), backing
 // /dev/input/event1 (drivers/dev_mouse_event.rs).
 //
 // This module owns everything that's genuinely hardware access or global
 // state: the `X86PortIo` construction, the `pic::enable_irq` calls (a
 // different seam/module than the 8042 protocol itself — see
-// `hal::mouse::enable_aux`'s doc comment), every `serial_println!`, and the
+// `hal::mouse::enable_aux`'s doc comment), every log line, and the
 // ISR-safe decoder + event-ring statics. The 8042 round-trip and the
 // 3-byte packet decode/assembly now live in `hal::mouse`, where they're
 // unit tested on the host with `cargo test` (see `hal/src/mouse.rs`).
@@ -55,17 +55,18 @@ impl Driver for MouseDriver {
         let io = X86PortIo;
         match hal::mouse::enable_aux(&io) {
             Ok(()) => {
+                crate::interrupts::irq::register_irq(12, irq_handler);
                 crate::interrupts::pic::enable_irq(2); // cascade: master's slave-PIC input
                 crate::interrupts::pic::enable_irq(12); // the mouse's own line
-                crate::serial_println!("mouse: PS/2 auxiliary device enabled (IRQ12)");
+                crate::info!("mouse: PS/2 auxiliary device enabled (IRQ12)");
                 Ok(())
             }
             Err(hal::mouse::MouseInitError::AuxEnableTimeout) => {
-                crate::serial_println!("mouse: 8042 aux-enable timed out — no PS/2 mouse?");
+                crate::info!("mouse: 8042 aux-enable timed out — no PS/2 mouse?");
                 Err(DriverError::NotFound)
             }
             Err(hal::mouse::MouseInitError::ReportingNotAcked) => {
-                crate::serial_println!("mouse: 'enable reporting' not ACKed — giving up");
+                crate::warn!("mouse: 'enable reporting' not ACKed — giving up");
                 Err(DriverError::NotFound)
             }
         }
@@ -84,7 +85,18 @@ unsafe impl Sync for DecoderCell {}
 
 static DECODER: DecoderCell = DecoderCell(UnsafeCell::new(hal::mouse::PacketDecoder::new()));
 
-/// Called from the IRQ12 ISR with each raw byte from the auxiliary device.
+/// IRQ12 handler, registered with `interrupts::irq::register_irq` once the
+/// device is enabled (the trampoline sends the EOI). Each byte belongs to
+/// a 3-byte packet; `process_byte` does the reassembly/decode, same shape
+/// as `keyboard::process_scancode` does for IRQ1.
+fn irq_handler() {
+    let data = unsafe {
+        x86_64::instructions::port::PortReadOnly::<u8>::new(0x60).read()
+    };
+    process_byte(data);
+}
+
+/// Called from the IRQ12 handler with each raw byte from the auxiliary device.
 pub fn process_byte(byte: u8) {
     // SAFETY: only ever called from the IRQ12 ISR, which never reentrs
     // itself.
@@ -2,7 +2,8 @@
 
 use core::panic::PanicInfo;
 use core::fmt::Write;
-use crate::framebuffer::{Color, Framebuffer};
+use crate::font::LEGACY_8X8;
+use crate::framebuffer::{Color, Framebuffer, TextCursor};
 
 #[cfg(not(test))]
 #[panic_handler]
@@ -21,6 +22,7 @@ fn panic(info: &PanicInfo) -> ! {
         );
     }
     crate::serial_println_raw!("  {}", info.message());
+    crate::backtrace::print_from(crate::backtrace::current_rbp());
 
     // Dump the always-on debug counters (forks/execs/COW faults, lock
     // diagnostics, the cow.rs IF-invariant violation counter — see
@@ -49,7 +51,7 @@ fn panic(info: &PanicInfo) -> ! {
 
     if let Some(fb) = fb_lock.as_mut()  {
 
-        fb.clear(Color::rgb(0, 0, 170));
+        fb.clear(Color::BLUE);
         
         let mut writer = FramebufferWriter::new(fb, 10, 10);
         
@@ -72,7 +74,8 @@ fn panic(info: &PanicInfo) -> ! {
         // Agregar info del stack frame si quisieras (más avanzado)
         let _ = writeln!(writer, "");
         let _ = writeln!(writer, "Press any key to reboot (jk, reinicia manualmente)");
-        
+
+        fb.present_dirty();
     }
     
     loop {
@@ -80,29 +83,71 @@ fn panic(info: &PanicInfo) -> ! {
     }
 }
 
-// Helper para escribir línea por línea
+/// Volcado estilo panic para excepciones que *no* pasan por `panic!` —
+/// NMI y #MC (ver `init/devices.rs`), que corren en su propio stack IST y
+/// pueden llegar en cualquier instrucción, con cualquier lock tomado.
+/// Mismas reglas que el panic handler: serial sin lock primero, los
+/// contadores de `debug::print_panic_snapshot` (sin allocar), y el
+/// framebuffer sólo si `try_lock` lo consigue. En pantalla no se borra
+/// nada: es un renglón marcador arriba de todo, porque un NMI no es
+/// necesariamente fatal y lo que había en pantalla puede seguir sirviendo.
+pub fn dump_exception(title: &str, sf: &crate::interrupts::exception::ExceptionStackFrame) {
+    use x86_64::registers::control::{Cr0, Cr2, Cr3, Cr4};
+
+    crate::serial_println_raw!("\n=== {} ===", title);
+    crate::serial_println_raw!(
+        "  RIP={} CS={:#x} RFLAGS={:#x}",
+        crate::symbols::Sym(sf.instruction_pointer), sf.code_segment, sf.cpu_flags
+    );
+    crate::serial_println_raw!("  RSP={:#018x} SS={:#x}", sf.stack_pointer, sf.stack_segment);
+    crate::serial_println_raw!(
+        "  CR0={:#x} CR2={:#x} CR3={:#x} CR4={:#x}",
+        Cr0::read_raw(),
+        Cr2::read_raw(),
+        Cr3::read().0.start_address().as_u64(),
+        Cr4::read_raw()
+    );
+    crate::backtrace::print_from(crate::backtrace::current_rbp());
+    crate::debug::print_panic_snapshot();
+
+    if let Some(mut fb_lock) = crate::framebuffer::FRAMEBUFFER.try_lock() {
+        if let Some(fb) = fb_lock.as_mut() {
+            let mut writer = FramebufferWriter::new(fb, 10, 10);
+            let _ = write!(writer, "{} at RIP={:#x} (ver serial)", title, sf.instruction_pointer);
+            fb.present_dirty();
+        }
+    }
+}
+
+// Helper para escribir en pantalla por trozos: `writeln!` entrega cada
+// línea en varios `write_str` (texto literal, cada argumento, el `\n`), así
+// que el cursor que devuelve `draw_text_at` se guarda entre llamadas. Antes
+// cada trozo empezaba una línea nueva en `x` fijo — "File:" y el nombre del
+// archivo salían en renglones distintos.
 struct FramebufferWriter<'a> {
     fb: &'a mut Framebuffer,
-    x: usize,
-    y: usize,
-    line_height: usize,
+    cursor: TextCursor,
 }
 
+/// Altura de línea del panic screen: glifo de 8 px + 2 px de separación.
+const LINE_HEIGHT: usize = 10;
+
 impl<'a> FramebufferWriter<'a> {
     fn new(fb: &'a mut Framebuffer, x: usize, y: usize) -> Self {
-        Self { fb, x, y, line_height: 10 }
+        Self { fb, cursor: TextCursor::new(x, y) }
     }
 }
 
 impl<'a> core::fmt::Write for FramebufferWriter<'a> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        for line in s.lines() {
-            self.fb.draw_text(self.x, self.y, line, Color::rgb(255, 255, 255), Color::rgb(0, 0, 170), 1);
-            self.y += self.line_height;
-        }
-        if s.ends_with('\n') {
-            self.y += self.line_height;
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.cursor.newline(LINE_HEIGHT);
+            }
+            self.cursor = self.fb.draw_text_at(
+                self.cursor, line, Color::WHITE, Color::BLUE, 1, &LEGACY_8X8,
+            );
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,14 +1,22 @@
 // kernel/src/pci.rs
 //
-// Minimal PCI config-space access + bus 0 device enumeration. Written from
+// Minimal PCI config-space access + device enumeration. Written from
 // scratch for ac97.rs — nothing in this kernel touched PCI before (every
 // other device driver targets a fixed legacy ISA port, e.g. block/ata.rs's
 // hardcoded 0x170/0x376, keyboard/mouse's 0x60/0x64).
 //
 // Legacy mechanism #1 (CONFIG_ADDRESS/CONFIG_DATA, ports 0xCF8/0xCFC) —
-// universally supported, no MMCONFIG/ECAM needed for a handful of devices
-// on bus 0, which is all QEMU's i440fx machine has.
+// universally supported, no MMCONFIG/ECAM needed for a handful of devices.
+//
+// Two consumers: `find_device` (driver lookup by vendor/device, bus 0
+// only — all QEMU's i440fx machine has, and all ac97.rs needs) and
+// `enumerate` (read-only inventory of every function on every bus, for the
+// boot log and `/proc/pci`, which the `lspci` program prints). Enumeration
+// brute-forces all 256 buses rather than walking bridges: 256 × 32 probes
+// of one config dword each is nothing at boot, and it can't miss a bus
+// behind a bridge it failed to decode. Nothing binds drivers from it.
 
+use alloc::{format, string::String, vec::Vec};
 use x86_64::instructions::port::Port;
 
 const CONFIG_ADDRESS: u16 = 0xCF8;
@@ -107,6 +115,152 @@ pub fn find_device(vendor: u16, device: u16) -> Option<PciDevice> {
     None
 }
 
+/// One function found by `enumerate`: identity, class code and raw BARs.
+#[derive(Clone, Copy)]
+pub struct PciFunction {
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub class: u8,
+    pub subclass: u8,
+    pub prog_if: u8,
+    /// Raw BAR registers, as read (not sized — sizing means writing
+    /// all-ones to each BAR, which this read-only scan won't do). Only the
+    /// first `bar_count` are BARs at all: 6 for a normal device (header
+    /// type 0), 2 for a PCI-to-PCI bridge (type 1, the rest of its header
+    /// at those offsets is bus numbers and windows), none for CardBus.
+    pub bars: [u32; 6],
+    pub bar_count: usize,
+    pub interrupt_line: u8,
+}
+
+fn read_function(bus: u8, device: u8, function: u8) -> Option<PciFunction> {
+    let id = config_read32(bus, device, function, 0x00);
+    if id as u16 == 0xFFFF {
+        return None; // nothing answers here
+    }
+    let class_rev = config_read32(bus, device, function, 0x08);
+    let header_type = (config_read32(bus, device, function, 0x0C) >> 16) as u8 & 0x7F;
+    let bar_count = match header_type {
+        0 => 6,
+        1 => 2,
+        _ => 0,
+    };
+    let mut bars = [0u32; 6];
+    for (i, bar) in bars.iter_mut().enumerate().take(bar_count) {
+        *bar = config_read32(bus, device, function, 0x10 + 4 * i as u8);
+    }
+    Some(PciFunction {
+        bus,
+        device,
+        function,
+        vendor_id: id as u16,
+        device_id: (id >> 16) as u16,
+        class: (class_rev >> 24) as u8,
+        subclass: (class_rev >> 16) as u8,
+        prog_if: (class_rev >> 8) as u8,
+        bars,
+        bar_count,
+        interrupt_line: config_read32(bus, device, function, 0x3C) as u8,
+    })
+}
+
+/// Every function on every bus, in bus/device/function order. Functions
+/// 1-7 are only probed when function 0 sets the multifunction bit — some
+/// single-function devices decode the function number loosely and would
+/// otherwise show up eight times.
+pub fn enumerate() -> Vec<PciFunction> {
+    let mut found = Vec::new();
+    for bus in 0..=255u8 {
+        for dev in 0..32u8 {
+            let Some(f0) = read_function(bus, dev, 0) else { continue };
+            let multifunction = (config_read32(bus, dev, 0, 0x0C) >> 16) as u8 & 0x80 != 0;
+            found.push(f0);
+            if multifunction {
+                found.extend((1..8).filter_map(|func| read_function(bus, dev, func)));
+            }
+        }
+    }
+    found
+}
+
+/// Base-class name, from the PCI Code and ID Assignment spec.
+pub fn class_name(class: u8) -> &'static str {
+    match class {
+        0x00 => "unclassified",
+        0x01 => "mass storage",
+        0x02 => "network",
+        0x03 => "display",
+        0x04 => "multimedia",
+        0x05 => "memory",
+        0x06 => "bridge",
+        0x07 => "communication",
+        0x08 => "system peripheral",
+        0x09 => "input",
+        0x0A => "docking station",
+        0x0B => "processor",
+        0x0C => "serial bus",
+        0x0D => "wireless",
+        0x0E => "intelligent I/O",
+        0x0F => "satellite",
+        0x10 => "encryption",
+        0x11 => "signal processing",
+        0x12 => "processing accelerator",
+        0xFF => "vendor-specific",
+        _ => "unknown",
+    }
+}
+
+/// One `lspci`-style line per function, plus an indented line per
+/// non-empty BAR: I/O BARs as a port, memory BARs as an address (a 64-bit
+/// BAR takes the next slot as its high half, so that slot isn't printed
+/// separately).
+pub fn render_list(functions: &[PciFunction]) -> String {
+    let mut out = String::new();
+    for f in functions {
+        out.push_str(&format!(
+            "{:02x}:{:02x}.{} [{:04x}:{:04x}] class {:02x}{:02x} prog-if {:02x} ({}) irq {}\n",
+            f.bus, f.device, f.function, f.vendor_id, f.device_id,
+            f.class, f.subclass, f.prog_if, class_name(f.class), f.interrupt_line,
+        ));
+        let mut i = 0;
+        while i < f.bar_count {
+            let bar = f.bars[i];
+            if bar & 1 != 0 {
+                if bar & !0x3 != 0 {
+                    out.push_str(&format!("    BAR{} io  {:#06x}\n", i, bar & !0x3));
+                }
+            } else if bar & 0x6 == 0x4 && i + 1 < f.bar_count {
+                let addr = (f.bars[i + 1] as u64) << 32 | (bar & !0xF) as u64;
+                if addr != 0 {
+                    let pf = if bar & 0x8 != 0 { ", prefetchable" } else { "" };
+                    out.push_str(&format!("    BAR{} mem {:#x} (64-bit{})\n", i, addr, pf));
+                }
+                i += 1;
+            } else if bar & !0xF != 0 {
+                let pf = if bar & 0x8 != 0 { " (prefetchable)" } else { "" };
+                out.push_str(&format!("    BAR{} mem {:#010x}{}\n", i, bar & !0xF, pf));
+            }
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Boot-log summary: one `info!` line per function found.
+pub fn log_devices() {
+    let functions = enumerate();
+    crate::info!("pci: {} function(s)", functions.len());
+    for f in &functions {
+        crate::info!(
+            "pci: {:02x}:{:02x}.{} [{:04x}:{:04x}] {}",
+            f.bus, f.device, f.function, f.vendor_id, f.device_id, class_name(f.class)
+        );
+    }
+}
+
 /// Sets the Command register's I/O Space Enable (bit0) and Bus Master
 /// Enable (bit2) bits — required before the device will respond to I/O
 /// port access or perform DMA. Offset 0x04 is a 32-bit-aligned dword
@@ -0,0 +1,97 @@
+// kernel/src/pcspeaker.rs
+//
+// PC speaker — thin adapter around `hal::pcspeaker`'s `PortIo`-generic
+// register protocol (PIT channel 2 + the port 0x61 gate bits, host tested,
+// see `hal/src/pcspeaker.rs`). This side owns the two things the hal layer
+// deliberately doesn't: timing for `beep` (an hrtimer that stops the
+// tone), and remembering what port 0x61 looked like before the speaker
+// was switched on.
+//
+// Channel 2 only — the scheduler tick on channel 0 (`crate::pit`) is never
+// reprogrammed, so a tone doesn't disturb `pit::frequency()`, which
+// `cpu::tsc` and `lapic` calibrate against.
+//
+// Userspace reaches this through two console ioctls (`sys_ioctl`):
+// KIOCSOUND (tone on/off) and KDMKTONE (tone for N ms, via `beep`), both
+// non-blocking as on Linux. `/mnt/bin/beep` is the shell-side front end.
+//
+// Like `crate::pit`, not a `crate::hal::Driver`: there is nothing to probe
+// or initialise at boot — the speaker is silent until someone asks.
+
+use core::sync::atomic::{AtomicU16, AtomicU32, Ordering};
+
+use hal::pit::PitError;
+
+use crate::hal::X86PortIo;
+
+/// Port 0x61 as it was before the current tone started, or `NOT_SAVED`
+/// while the speaker is off. Only the *first* tone of a run saves —
+/// a second one while already sounding would otherwise save the gate-open
+/// value and "restore" the speaker to on.
+static SAVED_PORT_61: AtomicU16 = AtomicU16::new(NOT_SAVED);
+const NOT_SAVED: u16 = 0xFFFF;
+
+/// The hrtimer that will end the current `beep`, or 0 if none is pending.
+static STOP_TIMER: AtomicU32 = AtomicU32::new(0);
+
+/// Start a continuous tone at `hz`, or stop the current one if `hz == 0`.
+/// Either way a pending `beep` stop is cancelled — this tone is the
+/// caller's to end. An unprogrammable frequency (above the PIT's input
+/// clock, or below its ~18.2 Hz floor) is returned as an error and leaves
+/// the speaker as it was.
+pub fn set_tone(hz: u32) -> Result<(), PitError> {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    cancel_stop();
+    program(hz)
+}
+
+/// Sound `freq_hz` for `duration_ms`, then restore the speaker. Returns
+/// right away: an hrtimer (`stop_beep`) ends the tone from the timer ISR,
+/// so nothing spins with interrupts off for the duration. A new `beep` or
+/// `set_tone` replaces the pending stop. A bad frequency returns an error
+/// and arms nothing.
+pub fn beep(freq_hz: u32, duration_ms: u64) -> Result<(), PitError> {
+    // IF=0 across cancel/arm: the ISR takes the hrtimer QUEUE lock, and
+    // mustn't fire `stop_beep` between `start` and the `STOP_TIMER` store.
+    let _irq = crate::interrupts::InterruptGuard::new();
+    cancel_stop();
+    program(freq_hz)?;
+    let expiry = crate::time::ktime_get().saturating_add(duration_ms.saturating_mul(1_000_000));
+    let id = crate::time::hrtimer::start(expiry, crate::time::hrtimer::HrTimerAction::KernelFn(stop_beep));
+    STOP_TIMER.store(id, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Drop the pending `beep` stop, if any.
+fn cancel_stop() {
+    let id = STOP_TIMER.swap(0, Ordering::Relaxed);
+    if id != 0 {
+        crate::time::hrtimer::cancel(id);
+    }
+}
+
+/// `beep`'s hrtimer callback. Runs in the timer ISR with the hrtimer
+/// QUEUE held, so it only touches ports and `STOP_TIMER` — never
+/// `hrtimer::cancel`.
+fn stop_beep() {
+    STOP_TIMER.store(0, Ordering::Relaxed);
+    let _ = program(0);
+}
+
+/// Program the speaker: `hz` on, or off for 0. Stopping puts port 0x61
+/// back exactly as the first tone of the run found it.
+fn program(hz: u32) -> Result<(), PitError> {
+    let spk = hal::pcspeaker::PcSpeaker::new(X86PortIo);
+    if hz == 0 {
+        match SAVED_PORT_61.swap(NOT_SAVED, Ordering::Relaxed) {
+            NOT_SAVED => spk.silence(),
+            prev => spk.restore(prev as u8),
+        }
+        return Ok(());
+    }
+    let prev = spk.start(hz)?;
+    let _ = SAVED_PORT_61.compare_exchange(
+        NOT_SAVED, prev as u16, Ordering::Relaxed, Ordering::Relaxed,
+    );
+    Ok(())
+}
@@ -8,22 +8,69 @@
 //
 // Not folded into the `crate::hal::Driver` registry (`crate::hal::run_all`):
 // `Driver::init()` takes no arguments, but the PIT's rate is caller-supplied
-// (`init_hardware_interrupts` always passes 100 Hz today, matching
-// `cpu::tsc`'s `PIT_HZ` assumption) — forcing this through a zero-arg
-// lifecycle would mean hardcoding 100 Hz *inside* this adapter, hiding the
-// real call site's intent instead of just keeping the direct call.
+// (`init_hardware_interrupts` passes `time::clockevent::TICK_HZ`) — forcing
+// this through a zero-arg lifecycle would mean hardcoding the rate *inside*
+// this adapter, hiding the real call site's intent instead of just keeping
+// the direct call.
+//
+// ACHIEVED RATE: the divisor is `1193182 / hz` floored, so the chip runs
+// at `hal::pit::achieved_hz(divisor)`, not necessarily `hz`. `init` records
+// that in `FREQUENCY_HZ`; `cpu::tsc` and `lapic` calibrate against one
+// period of it (`cpu::tsc::pit_hz`).
+//
+// WHY MODE 2, NOT MODE 3: calibration (`cpu::tsc::across_one_pit_period`)
+// finds period boundaries by watching the latched count jump back up. In
+// mode 2 it counts divisor..1 once per period; mode 3 (square wave) counts
+// down by 2 twice per period, so the same check would see two "wraps" and
+// measure half a period. The IRQ0 rate is the same either way.
+
+use core::sync::atomic::{AtomicU32, Ordering};
 
 use crate::hal::X86PortIo;
 
-/// Programs the PIT's channel 0 to `frequency` Hz (mode 2, rate generator).
-/// Best-effort: an invalid frequency (`0`, or below the hardware's ~18.2 Hz
-/// floor) is logged and left un-programmed rather than panicking — the
-/// original computed `1193182 / frequency` inline and would panic (division
-/// by zero) on `frequency == 0`, and would silently truncate into a wildly
+/// The rate channel 0 was actually programmed to, in Hz; 0 until `init`
+/// has succeeded.
+static FREQUENCY_HZ: AtomicU32 = AtomicU32::new(0);
+
+/// Programs the PIT's channel 0 to `frequency` Hz (mode 2, rate generator)
+/// and returns the rate it really runs at. Best-effort: an invalid
+/// frequency (`0`, or below the hardware's ~18.2 Hz floor) is logged and
+/// left un-programmed (`None`) rather than panicking — the original
+/// computed `1193182 / frequency` inline and would panic (division by
+/// zero) on `frequency == 0`, and would silently truncate into a wildly
 /// wrong rate below the floor. See `hal::pit::PitError`.
-pub fn init(frequency: u32) {
+///
+/// Afterwards the channel's mode is read back (`mode_ok`) and a mismatch
+/// is warned about — the timing built on this would be wrong.
+pub fn init(frequency: u32) -> Option<u32> {
     let pit = hal::pit::Pit::new(X86PortIo);
-    if let Err(e) = pit.set_rate(frequency) {
-        crate::serial_println!("pit: failed to program {} Hz: {:?}", frequency, e);
+    let hz = match pit.set_rate(frequency) {
+        Ok(hz) => hz,
+        Err(e) => {
+            crate::warn!("pit: failed to program {} Hz: {:?}", frequency, e);
+            return None;
+        }
+    };
+    if hz != frequency {
+        crate::warn!("pit: asked for {} Hz, the nearest divisor gives {} Hz", frequency, hz);
+    }
+    if !mode_ok() {
+        crate::warn!(
+            "pit: read-back status {:#04x}, expected channel 0 in mode 2 (lobyte/hibyte, binary)",
+            pit.read_status(),
+        );
     }
+    FREQUENCY_HZ.store(hz, Ordering::Relaxed);
+    Some(hz)
+}
+
+/// Channel 0's rate in Hz as programmed by `init`, or 0 if it hasn't been.
+pub fn frequency() -> u32 {
+    FREQUENCY_HZ.load(Ordering::Relaxed)
+}
+
+/// Whether channel 0 reads back (8254 read-back command) as the mode 2
+/// rate generator `init` programs.
+pub fn mode_ok() -> bool {
+    hal::pit::is_rate_generator_status(hal::pit::Pit::new(X86PortIo).read_status())
 }
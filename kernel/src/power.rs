@@ -0,0 +1,53 @@
+// kernel/src/power.rs
+//
+// Power off and reboot — thin adapter around `hal::power`'s port writes
+// (host tested, see `hal/src/power.rs` for which ports and why shutdown
+// isn't real ACPI S5). Both are QEMU/firmware-specific: success means the
+// machine is gone before the next instruction, so the only thing this side
+// can do is wait a moment, and if we're still running, say so and return
+// to the caller instead of silently spinning. `reboot(2)` (`sys_reboot`)
+// and the `shutdown`/`reboot` shell commands (`userspace/c/`) sit on top.
+//
+// The interactive counterpart of `test_framework::exit_qemu`, which uses
+// the `isa-debug-exit` device — only present when QEMU is started with it,
+// and meant for reporting a test verdict, not for turning the machine off.
+
+use crate::hal::X86PortIo;
+
+/// How long to wait for a port write to take effect before concluding it
+/// didn't. QEMU acts on both synchronously; this is slack for real
+/// firmware, whose reset logic can lag the 8042 command by a few ms.
+const SETTLE_MS: u64 = 100;
+
+fn settle() {
+    let deadline = crate::cpu::tsc::uptime_ms() + SETTLE_MS;
+    while crate::cpu::tsc::uptime_ms() < deadline {
+        core::hint::spin_loop();
+    }
+}
+
+/// Power the machine off. Returns only if it didn't work (not QEMU, or a
+/// chipset without a PM1a block at either known port) — logged.
+pub fn shutdown() {
+    crate::info!("power: shutting down");
+    hal::power::request_shutdown(&X86PortIo);
+    settle();
+    crate::warn!(
+        "power: shutdown had no effect (no ACPI PM1a_CNT at {:#x} or {:#x})",
+        hal::power::QEMU_PM1A_CNT,
+        hal::power::BOCHS_PM1A_CNT,
+    );
+}
+
+/// Reset the machine through the 8042 keyboard controller. Returns only if
+/// the reset didn't happen — logged.
+pub fn reboot() {
+    crate::info!("power: rebooting");
+    let ready = hal::power::request_reset(&X86PortIo);
+    settle();
+    if ready {
+        crate::warn!("power: reboot had no effect (8042 reset pulse ignored)");
+    } else {
+        crate::warn!("power: reboot had no effect (8042 never ready for the reset command)");
+    }
+}
@@ -0,0 +1,60 @@
+// kernel/src/process/cpuset.rs
+//
+// CPU affinity masks — the kernel side of `sched_getaffinity`/
+// `sched_setaffinity`'s `cpu_set_t`.
+//
+// This kernel schedules on one CPU, so the only mask a process can
+// actually hold is {0}. The type still carries a full 64-bit word (one
+// `unsigned long` of `cpu_set_t`), so an SMP scheduler can start honoring
+// more bits without changing the syscalls or `Process::affinity`.
+
+/// CPUs the scheduler runs on. Bits at or above this are ignored.
+pub const NR_CPUS: usize = 1;
+
+/// Set of CPUs a process may run on; bit N = CPU N.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuSet(u64);
+
+impl CpuSet {
+    /// Every CPU that exists — the default for every process.
+    pub const fn all() -> Self {
+        CpuSet(u64::MAX >> (64 - NR_CPUS))
+    }
+
+    /// A mask as userspace passed it, restricted to CPUs that exist, or
+    /// `None` if that leaves nothing to run on.
+    pub fn from_user(bits: u64) -> Option<Self> {
+        let set = CpuSet(bits & Self::all().0);
+        if set.0 == 0 { None } else { Some(set) }
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Linux's `Cpus_allowed_list` form: `0`, `0-3`, `0,2`.
+    pub fn to_list(self) -> alloc::string::String {
+        use core::fmt::Write;
+
+        let mut out = alloc::string::String::new();
+        let mut cpu = 0;
+        while cpu < 64 {
+            if self.0 & (1 << cpu) == 0 {
+                cpu += 1;
+                continue;
+            }
+            let first = cpu;
+            while cpu < 64 && self.0 & (1 << cpu) != 0 {
+                cpu += 1;
+            }
+            if !out.is_empty() {
+                out.push(',');
+            }
+            let _ = match cpu - 1 - first {
+                0 => write!(out, "{}", first),
+                _ => write!(out, "{}-{}", first, cpu - 1),
+            };
+        }
+        out
+    }
+}
@@ -7,6 +7,9 @@
 // and drivers.
 
 use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use spin::Mutex;
 
 // ============================================================================
 // ERRORS
@@ -31,10 +34,29 @@ pub enum FileError {
     /// perform the actual block_current/jump_to_trapframe themselves — see
     /// their doc comments for why this can't happen inside `read`/`write`.
     WouldBlock,
+    /// Every slot of the fd table is in use — maps to EMFILE.
+    TooManyOpenFiles,
 }
 
 pub type FileResult<T> = Result<T, FileError>;
 
+/// What `FileHandle::poll` reports — the handle-side half of `poll(2)`'s
+/// POLLIN/POLLOUT/POLLHUP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Readiness {
+    /// A `read` would return data (or EOF) without blocking.
+    pub readable: bool,
+    /// A `write` would make progress (or fail) without blocking.
+    pub writable: bool,
+    /// The other end is gone (a pipe with no writers or no readers left).
+    pub hangup: bool,
+}
+
+impl Readiness {
+    /// Never blocks either way — plain devices and regular files.
+    pub const ALWAYS: Self = Self { readable: true, writable: true, hangup: false };
+}
+
 /// Shared `lseek(2)` offset arithmetic for regular-file handles (ramfs,
 /// initramfs, ext2) — same SEEK_SET/SEEK_CUR/SEEK_END semantics, only the
 /// "current position" and "file size" inputs differ per filesystem.
@@ -102,13 +124,14 @@ pub trait FileHandle: Send {
         "<unknown>"
     }
 
-    /// Duplicate this handle for inheritance across `fork()`.
+    /// Duplicate this handle for inheritance across `fork()` and for
+    /// dup/dup2/F_DUPFD.
     ///
-    /// Default `None` means "not inheritable" — matches today's behavior
-    /// for device handles (fork only special-cases stdio, see
-    /// `FileDescriptorTable::clone`). Handles backed by shared state (e.g.
-    /// pipe ends) override this to clone their `Arc` and bump the relevant
-    /// refcount, so both parent and child end up sharing the same
+    /// Default `None` means "no cheap copy of its own" — the fd table then
+    /// moves the handle behind a `SharedHandle` and aliases that instead
+    /// (see `FileDescriptorTable::share`). Handles backed by shared state
+    /// (e.g. pipe ends) override this to clone their `Arc` and bump the
+    /// relevant refcount, so both parent and child end up sharing the same
     /// underlying buffer — required for pipe semantics across fork.
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
         None
@@ -125,6 +148,34 @@ pub trait FileHandle: Send {
         Err(FileError::NotSupported)
     }
 
+    /// Level-triggered readiness for `poll`/`epoll_wait`. Default
+    /// `Readiness::ALWAYS` is right for anything whose `read`/`write`
+    /// never return `WouldBlock` or park the caller; pipe ends and the
+    /// keyboard-fed devices override it.
+    fn poll(&self) -> Readiness {
+        Readiness::ALWAYS
+    }
+
+    /// `pread(2)`: read at `offset` without moving the file position.
+    /// Default is seek there, read, seek back — so anything without
+    /// `seek` (pipes, character devices) gets `NotSupported` for free.
+    fn read_at(&mut self, buf: &mut [u8], offset: u64) -> FileResult<usize> {
+        let saved = self.seek(0, 1)?;
+        self.seek(offset as i64, 0)?;
+        let result = self.read(buf);
+        self.seek(saved, 0)?;
+        result
+    }
+
+    /// `pwrite(2)`: `read_at`'s counterpart, same save/seek/restore.
+    fn write_at(&mut self, buf: &[u8], offset: u64) -> FileResult<usize> {
+        let saved = self.seek(0, 1)?;
+        self.seek(offset as i64, 0)?;
+        let result = self.write(buf);
+        self.seek(saved, 0)?;
+        result
+    }
+
     /// Change this open file's permission bits — `fchmod(2)`. Default
     /// `Ok(())` matches `Inode::chmod`'s same "pre-existing stub behavior"
     /// default (see its doc comment); only `ext2::Ext2FileHandle`
@@ -139,11 +190,15 @@ pub trait FileHandle: Send {
 // FILE DESCRIPTOR TABLE
 // ============================================================================
 
-const MAX_FILES: usize = 16;
+/// Slots per fd table — the per-process open-file limit.
+pub const MAX_FILES: usize = 16;
 
 /// Per-process table of open file descriptors.
 pub struct FileDescriptorTable {
     files: [Option<Box<dyn FileHandle>>; MAX_FILES],
+    /// Per-fd FD_CLOEXEC bit. Belongs to the fd, not the open file: a dup
+    /// of a close-on-exec fd starts out without it.
+    cloexec: [bool; MAX_FILES],
 }
 
 impl FileDescriptorTable {
@@ -152,6 +207,7 @@ impl FileDescriptorTable {
         const NONE: Option<Box<dyn FileHandle>> = None;
         Self {
             files: [NONE; MAX_FILES],
+            cloexec: [false; MAX_FILES],
         }
     }
 
@@ -162,31 +218,32 @@ impl FileDescriptorTable {
 
         let mut table = Self::new();
 
-        // FD 0: stdin — bound to the console (serial), same device as
-        // stderr. `sys_read`'s fd==0 branch hardcodes reading straight from
-        // the keyboard buffer regardless of which handle sits here, so this
-        // choice never affected *reading* — but it does matter for
-        // isatty()/tcgetattr()/ioctl(TCGETS): a real interactive shell
-        // (e.g. BusyBox ash) checks `isatty(0) && isatty(1)` to decide
-        // whether to consider itself interactive at all (print a banner,
-        // prompt, enable job control...). Binding this to `/dev/null` (the
-        // previous "for now" placeholder) made that check permanently
-        // false, silently forcing every shell into non-interactive mode.
-        table.files[0] = Some(drivers::open_device("/dev/console")
+        // FD 0: stdin — bound to the terminal (`/dev/tty`), same device as
+        // stdout/stderr. `sys_read`'s fd==0 branch hardcodes reading straight
+        // from the keyboard buffer regardless of which handle sits here, so
+        // this choice never affected *reading* — but it does matter for
+        // isatty()/tcgetattr()/ioctl(TCGETS): a real interactive shell (e.g.
+        // BusyBox ash) checks `isatty(0) && isatty(1)` to decide whether to
+        // consider itself interactive at all (print a banner, prompt, enable
+        // job control...). Binding this to `/dev/null` (the previous "for now"
+        // placeholder) made that check permanently false, silently forcing
+        // every shell into non-interactive mode.
+        table.files[0] = Some(drivers::open_device("/dev/tty")
             .unwrap_or_else(|| Box::new(NullFallback)));
 
-        // FD 1: stdout (framebuffer)
-        table.files[1] = Some(drivers::open_device("/dev/fb")
+        // FD 1: stdout — the terminal: by default the framebuffer plus its
+        // serial mirror, see `dev_tty`'s OUTPUTS.
+        table.files[1] = Some(drivers::open_device("/dev/tty")
             .unwrap_or_else(|| Box::new(NullFallback)));
 
-        // FD 2: stderr (framebuffer, same as stdout). Used to be bound to
+        // FD 2: stderr (the terminal, same as stdout). Used to be bound to
         // `/dev/console` (serial-only) — errors like `ash: clear: not
         // found` were then invisible on the actual screen, only visible by
         // grepping serial.log, since nothing mirrors fb output *back* to
-        // serial's own writes. Binding it to `/dev/fb` instead means stderr
-        // is on-screen like stdout, and still reaches serial.log too via
-        // `framebuffer_console`'s own `mirror_to_serial`.
-        table.files[2] = Some(drivers::open_device("/dev/fb")
+        // serial's own writes. Binding it to the terminal instead means
+        // stderr is on-screen like stdout, and still reaches serial.log too
+        // via `framebuffer_console`'s own `mirror_to_serial`.
+        table.files[2] = Some(drivers::open_device("/dev/tty")
             .unwrap_or_else(|| Box::new(NullFallback)));
 
         table
@@ -222,28 +279,65 @@ impl FileDescriptorTable {
         for (i, slot) in self.files.iter_mut().enumerate() {
             if slot.is_none() {
                 *slot = Some(handle);
+                self.cloexec[i] = false;
                 return Ok(i);
             }
         }
 
-        Err(FileError::InvalidArgument) // Too many files open
+        Err(FileError::TooManyOpenFiles)
+    }
+
+    /// FD_CLOEXEC for an open `fd` (fcntl F_GETFD).
+    pub fn cloexec(&self, fd: usize) -> FileResult<bool> {
+        self.get(fd)?;
+        Ok(self.cloexec[fd])
     }
 
-    /// dup(2): install a clone of `fd`'s handle at the first free slot
-    /// `>= min_fd`. Relies on `FileHandle::dup()` — fds backed by a handle
-    /// that doesn't implement it (returns `None`) can't be dup'd; today
-    /// that's only directory handles (opendir), which nothing needs to
-    /// dup in practice.
+    /// Set or clear FD_CLOEXEC on an open `fd` (fcntl F_SETFD, O_CLOEXEC).
+    pub fn set_cloexec(&mut self, fd: usize, on: bool) -> FileResult<()> {
+        self.get(fd)?;
+        self.cloexec[fd] = on;
+        Ok(())
+    }
+
+    /// Number of open fds.
+    pub fn count(&self) -> usize {
+        self.files.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// A second handle onto the same open file as `fd` — the handle's own
+    /// `dup()` if it has one, otherwise the slot's handle is moved behind a
+    /// `SharedHandle` (once) and the wrapper is aliased. Either way the
+    /// two fds share one offset/state, as POSIX wants after dup or fork.
+    fn share(&mut self, fd: usize) -> FileResult<Box<dyn FileHandle>> {
+        if let Some(cloned) = self.get(fd)?.dup() {
+            return Ok(cloned);
+        }
+        let handle = self.files[fd].take().ok_or(FileError::BadFileDescriptor)?;
+        let shared = SharedHandle::new(handle);
+        let cloned = shared.alias();
+        self.files[fd] = Some(Box::new(shared));
+        Ok(cloned)
+    }
+
+    /// dup(2): install a second handle onto `fd`'s open file (see `share`)
+    /// at the first free slot `>= min_fd`. A `min_fd` past the table is
+    /// `InvalidArgument` (F_DUPFD's EINVAL), no free slot from there
+    /// `TooManyOpenFiles`.
     pub fn dup(&mut self, fd: usize, min_fd: usize) -> FileResult<usize> {
-        let cloned = self.get(fd)?.dup().ok_or(FileError::NotSupported)?;
+        let cloned = self.share(fd)?;
+        if min_fd >= MAX_FILES {
+            return Err(FileError::InvalidArgument);
+        }
 
         for i in min_fd..MAX_FILES {
             if self.files[i].is_none() {
                 self.files[i] = Some(cloned);
+                self.cloexec[i] = false;
                 return Ok(i);
             }
         }
-        Err(FileError::InvalidArgument) // no free fd
+        Err(FileError::TooManyOpenFiles)
     }
 
     /// dup2(2): install a clone of `oldfd`'s handle at exactly `newfd`,
@@ -259,12 +353,13 @@ impl FileDescriptorTable {
             return Ok(newfd);
         }
 
-        let cloned = self.get(oldfd)?.dup().ok_or(FileError::NotSupported)?;
+        let cloned = self.share(oldfd)?;
 
         if let Some(mut old) = self.files[newfd].take() {
             let _ = old.close();
         }
         self.files[newfd] = Some(cloned);
+        self.cloexec[newfd] = false;
         Ok(newfd)
     }
 
@@ -274,6 +369,7 @@ impl FileDescriptorTable {
             return Err(FileError::BadFileDescriptor);
         }
 
+        self.cloexec[fd] = false;
         if let Some(mut handle) = self.files[fd].take() {
             handle.close()?;
         }
@@ -281,9 +377,19 @@ impl FileDescriptorTable {
         Ok(())
     }
 
+    /// execve(2)'s half of close-on-exec: close every fd with FD_CLOEXEC
+    /// set. Everything else is inherited by the new image unchanged.
+    pub fn close_on_exec(&mut self) {
+        for fd in 0..MAX_FILES {
+            if self.cloexec[fd] {
+                let _ = self.close(fd);
+            }
+        }
+    }
+
     /// Debug: list all open FDs to serial.
     pub fn debug_list(&self) {
-        crate::serial_println!("Open file descriptors:");
+        crate::serial_println!("Open file descriptors ({}/{}):", self.count(), MAX_FILES);
         for (i, slot) in self.files.iter().enumerate() {
             if let Some(handle) = slot {
                 crate::serial_println!("  FD {}: {}", i, handle.name());
@@ -300,36 +406,111 @@ impl FileHandle for NullFallback {
     fn name(&self) -> &str { "<fallback>" }
 }
 
-// Every fd, including 0-2, is inherited via `dup()` when the underlying
-// handle supports it (e.g. pipe ends, redirected regular files) — needed so
-// a pipe created before `fork()` is usable by both parent and child, and so
-// a shell redirect (`< file`, done via `open()`+`dup2()` onto fd 0 before
-// `fork()`) survives into the child instead of silently reverting to the
-// real console. fds 0-2 fall back to a fresh stdio handle only when nothing
-// is open there, or the open handle doesn't support `dup()`.
-impl Clone for FileDescriptorTable {
-    fn clone(&self) -> Self {
-        let mut new_table = Self::new();
-
-        if self.files[0].is_some() {
-            new_table.files[0] = self.files[0].as_ref().unwrap().dup()
-                .or_else(|| crate::drivers::open_device("/dev/console"));
-        }
-        if self.files[1].is_some() {
-            new_table.files[1] = self.files[1].as_ref().unwrap().dup()
-                .or_else(|| crate::drivers::open_device("/dev/fb"));
-        }
-        if self.files[2].is_some() {
-            new_table.files[2] = self.files[2].as_ref().unwrap().dup()
-                .or_else(|| crate::drivers::open_device("/dev/fb"));
-        }
-
-        for i in 3..MAX_FILES {
-            if let Some(ref handle) = self.files[i] {
-                new_table.files[i] = handle.dup();
+impl FileDescriptorTable {
+    /// The child's table for `fork()`: every open fd aliases the parent's
+    /// open file (see `share`), so offsets and pipe/socket state are
+    /// shared rather than copied, and nothing is dropped for lack of a
+    /// `FileHandle::dup`. Takes `&mut self` because sharing a handle
+    /// without its own `dup()` rewraps it in the parent's slot too.
+    /// FD_CLOEXEC bits are copied — they only take effect at exec.
+    pub fn fork(&mut self) -> Self {
+        let mut child = Self::new();
+        for fd in 0..MAX_FILES {
+            if self.files[fd].is_some() {
+                child.files[fd] = self.share(fd).ok();
+                child.cloexec[fd] = self.cloexec[fd];
             }
         }
+        child
+    }
+}
+
+/// A handle with no `dup()` of its own, shared between several fds (or
+/// processes, after fork) through one `Arc`. The inner handle is only
+/// `close()`d when the last alias goes — the same rule pipe ends
+/// implement by hand with their reader/writer counts.
+///
+/// The name is copied out at wrap time: `name()` returns a borrow, which
+/// can't outlive a lock on `inner`.
+struct SharedHandle {
+    inner: Arc<SharedFile>,
+    name: String,
+}
+
+/// The open file behind every alias of a `SharedHandle`. It's closed in
+/// `Drop`, i.e. by whichever alias releases the last `Arc`: checking
+/// `Arc::strong_count` in `close()` instead races — two aliases closing
+/// at once can both see 2 and neither closes it.
+struct SharedFile(Mutex<Box<dyn FileHandle>>);
 
-        new_table
+impl SharedFile {
+    fn lock(&self) -> spin::MutexGuard<'_, Box<dyn FileHandle>> {
+        self.0.lock()
     }
-}
\ No newline at end of file
+}
+
+impl Drop for SharedFile {
+    fn drop(&mut self) {
+        let _ = self.0.get_mut().close();
+    }
+}
+
+impl SharedHandle {
+    fn new(handle: Box<dyn FileHandle>) -> Self {
+        let name = String::from(handle.name());
+        Self { inner: Arc::new(SharedFile(Mutex::new(handle))), name }
+    }
+
+    fn alias(&self) -> Box<dyn FileHandle> {
+        Box::new(SharedHandle { inner: self.inner.clone(), name: self.name.clone() })
+    }
+}
+
+impl FileHandle for SharedHandle {
+    fn read(&mut self, buf: &mut [u8]) -> FileResult<usize> {
+        self.inner.lock().read(buf)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> FileResult<usize> {
+        self.inner.lock().write(buf)
+    }
+
+    // No `close()`: dropping this alias releases its `Arc`, and the last
+    // one closes the inner handle (`SharedFile`'s `Drop`).
+
+    fn stat(&self) -> Option<crate::fs::types::Stat> {
+        self.inner.lock().stat()
+    }
+
+    fn getdents64(&mut self, buf: &mut [u8]) -> i64 {
+        self.inner.lock().getdents64(buf)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dup(&self) -> Option<Box<dyn FileHandle>> {
+        Some(self.alias())
+    }
+
+    fn seek(&mut self, offset: i64, whence: i32) -> FileResult<i64> {
+        self.inner.lock().seek(offset, whence)
+    }
+
+    fn poll(&self) -> Readiness {
+        self.inner.lock().poll()
+    }
+
+    fn read_at(&mut self, buf: &mut [u8], offset: u64) -> FileResult<usize> {
+        self.inner.lock().read_at(buf, offset)
+    }
+
+    fn write_at(&mut self, buf: &[u8], offset: u64) -> FileResult<usize> {
+        self.inner.lock().write_at(buf, offset)
+    }
+
+    fn chmod(&mut self, mode: u32) -> FileResult<()> {
+        self.inner.lock().chmod(mode)
+    }
+}
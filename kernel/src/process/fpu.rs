@@ -15,6 +15,25 @@
 // `sys_fork` is the one exception: a forked child gets a *copy* of the
 // parent's actual live registers (real `fork()` semantics), not the
 // template — see `syscall::sys_fork`.
+//
+// ── Why eager, not lazy (#NM) ──────────────────────────────────────
+// The lazy alternative sets CR0.TS on every switch and only swaps FPU
+// state in the #NM handler, the first time the new process actually
+// touches x87/SSE. It's not worth it here:
+//   - Almost every process does touch XMM registers: mlibc/BusyBox
+//     `memcpy`/`strlen` are SSE2, so #NM would fire right after nearly
+//     every switch and cost more than the `fxsave`/`fxrstor` it avoids.
+//   - The registers' owner would no longer be `running`. Every existing
+//     save/restore site (the scheduler's switch paths, `sys_fork`'s live
+//     capture, `exec`'s reset) and process teardown would have to consult
+//     and maintain a separate owner, and a mistake there silently hands
+//     one process another's registers.
+//   - Linux defaulted to eager in 4.6 and removed lazy FPU in 4.14 for the
+//     same cost reason, plus the LazyFP speculative leak.
+// So TS is never set, and the #NM handler (`init/devices.rs`) treats a
+// device-not-available fault as a bug, not as a switch request. The kernel
+// itself never needs FPU state switched around it: `x86_64-unknown-none`
+// is a soft-float target, so kernel code doesn't emit x87/SSE.
 
 use core::arch::asm;
 
@@ -38,7 +57,7 @@ pub fn init() {
         save(&mut area);
     }
     TEMPLATE.call_once(|| area);
-    crate::serial_println!("fpu: SSE enabled, default FXSAVE template captured");
+    crate::debug!("fpu: SSE enabled, default FXSAVE template captured");
 }
 
 /// CR0.EM=0 (no #NM trap on SSE/x87 instructions — this kernel isn't
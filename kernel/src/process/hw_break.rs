@@ -0,0 +1,241 @@
+// kernel/src/process/hw_break.rs
+//
+// Per-process hardware breakpoints (`set_hwbreak`, custom syscall 406)
+// through the debug registers: DR0-DR3 hold up to four user addresses,
+// DR7 enables each one as an execute, write or read/write watch of 1, 2, 4
+// or 8 bytes.
+//
+// OPT-IN:
+//   A process's breakpoints live in `Process::hw_breaks` and only reach
+//   the hardware in `load`, called at every switch-in next to
+//   `fpu::restore`. A process with none costs one per-CPU flag check —
+//   DR7 is only written to clear it after one that had some (`LOADED`).
+//   Nothing needs saving on switch-out: DR0-DR3/DR7 only change when this
+//   module writes them.
+//
+// HITS:
+//   The #DB handler (`single_step::debug_trap_handler`) passes DR6's B0-B3
+//   bits to `report_hits`, which logs each hit over serial and queues
+//   SIGTRAP on the process (default action: terminate, so the shell sees
+//   it killed by signal 5; a handler can catch it instead). An execute
+//   breakpoint is a fault, reported with the rip of the instruction about
+//   to run; RF is set so the resumed instruction doesn't hit again. Data
+//   breakpoints are traps, so their rip is the instruction *after* the
+//   access. The kernel touching a watched user address (a `read()` into
+//   it, say) is logged too, but nothing is signalled.
+//
+//   Not inherited across fork; cleared by exec.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::trapframe::TrapFrame;
+
+/// DR6 B0-B3: which breakpoint(s) this #DB is for.
+pub const DR6_HITS: u64 = 0xF;
+
+/// RFLAGS.RF — suppress instruction breakpoints for one instruction.
+const RFLAGS_RF: u64 = 1 << 16;
+
+/// DR7.LE — exact data breakpoints (recommended whenever any are set).
+const DR7_LE: u64 = 1 << 8;
+
+/// First address past the user half.
+const USER_END: u64 = 0x0000_8000_0000_0000;
+
+/// What a breakpoint traps on — the value is DR7's R/W encoding (2, I/O,
+/// isn't usable without CR4.DE and isn't offered).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BreakKind {
+    Execute = 0b00,
+    Write = 0b01,
+    ReadWrite = 0b11,
+}
+
+impl BreakKind {
+    pub fn from_u64(kind: u64) -> Option<Self> {
+        match kind {
+            0 => Some(BreakKind::Execute),
+            1 => Some(BreakKind::Write),
+            3 => Some(BreakKind::ReadWrite),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            BreakKind::Execute => "exec",
+            BreakKind::Write => "write",
+            BreakKind::ReadWrite => "rw",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub addr: u64,
+    pub len: u8,
+    pub kind: BreakKind,
+}
+
+/// Why `HwBreakpoints::set` refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HwBreakError {
+    /// Bad length, unaligned or non-user address, or a multi-byte
+    /// execute breakpoint.
+    Invalid,
+    /// All four debug registers are in use.
+    NoFreeSlot,
+}
+
+/// One process's DR0-DR3 assignments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HwBreakpoints {
+    slots: [Option<Breakpoint>; 4],
+}
+
+impl HwBreakpoints {
+    pub const fn new() -> Self {
+        HwBreakpoints { slots: [None; 4] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    pub fn get(&self, slot: usize) -> Option<Breakpoint> {
+        self.slots.get(slot).copied().flatten()
+    }
+
+    /// Put a breakpoint in the first free slot and return its index.
+    /// `len` is 1, 2, 4 or 8 and `addr` must be aligned to it (the CPU
+    /// ignores the low address bits); execute breakpoints are `len` 1.
+    pub fn set(&mut self, addr: u64, len: u8, kind: BreakKind) -> Result<usize, HwBreakError> {
+        let len_ok = matches!(len, 1 | 2 | 4 | 8) && (kind != BreakKind::Execute || len == 1);
+        if !len_ok || !addr.is_multiple_of(len as u64) || addr.checked_add(len as u64).is_none_or(|end| end > USER_END) {
+            return Err(HwBreakError::Invalid);
+        }
+        let slot = self.slots.iter().position(Option::is_none).ok_or(HwBreakError::NoFreeSlot)?;
+        self.slots[slot] = Some(Breakpoint { addr, len, kind });
+        Ok(slot)
+    }
+
+    /// Remove the breakpoint at `addr`, returning the slot it was in.
+    pub fn clear(&mut self, addr: u64) -> Option<usize> {
+        let slot = self.slots.iter().position(|b| b.is_some_and(|b| b.addr == addr))?;
+        self.slots[slot] = None;
+        Some(slot)
+    }
+
+    /// DR7 for these slots: local enable, R/W and LEN per slot, plus LE.
+    pub fn dr7(&self) -> u64 {
+        let mut dr7 = 0;
+        for (i, bp) in self.slots.iter().enumerate() {
+            let Some(bp) = bp else { continue };
+            let len_bits: u64 = match bp.len {
+                1 => 0b00,
+                2 => 0b01,
+                8 => 0b10,
+                _ => 0b11,
+            };
+            dr7 |= 1 << (2 * i);
+            dr7 |= (bp.kind as u64) << (16 + 4 * i);
+            dr7 |= len_bits << (18 + 4 * i);
+        }
+        if dr7 != 0 { dr7 | DR7_LE } else { 0 }
+    }
+}
+
+/// Whether this CPU's DR7 may still hold some process's breakpoints.
+static LOADED: [AtomicBool; crate::cpu::MAX_CPUS] = [const { AtomicBool::new(false) }; crate::cpu::MAX_CPUS];
+
+/// Program the debug registers for the process about to run.
+///
+/// # Safety
+/// Must be called with interrupts off, as part of a switch to the process
+/// owning `bps` (or right after changing the running process's set).
+pub unsafe fn load(bps: &HwBreakpoints) {
+    let loaded = &LOADED[crate::cpu::cpu_id()];
+    if bps.is_empty() {
+        if loaded.swap(false, Ordering::Relaxed) {
+            unsafe { write_dr7(0); }
+        }
+        return;
+    }
+    unsafe {
+        // Disable first, so no half-written combination is ever live.
+        write_dr7(0);
+        for slot in 0..4 {
+            write_dr(slot, bps.get(slot).map_or(0, |b| b.addr));
+        }
+        write_dr7(bps.dr7());
+    }
+    loaded.store(true, Ordering::Relaxed);
+}
+
+/// Handle DR6 breakpoint bits `hits` for the #DB frame `tf`: log each one,
+/// and for a user-mode hit queue SIGTRAP on the running process.
+///
+/// Logged lock-free (`serial_println_raw!`): a kernel-mode hit can land
+/// with the UART lock held — `serial::console_write` reads the user's
+/// buffer under it, so a watch on the buffer passed to `write(1, …)` fires
+/// right there.
+pub fn report_hits(tf: &mut TrapFrame, hits: u64) {
+    // From user mode nothing kernel-side is held; from kernel mode the
+    // scheduler lock may be, so only the registers are consulted.
+    let owner = if tf.cs & 0x3 != 0 {
+        let mut sched = super::scheduler::local_scheduler();
+        sched.running_mut().map(|proc| {
+            super::signal::queue_signal(proc, super::signal::SIGTRAP);
+            (proc.pid.0, proc.hw_breaks)
+        })
+    } else {
+        None
+    };
+
+    for slot in (0..4).filter(|i| hits & (1 << i) != 0) {
+        match owner.and_then(|(pid, bps)| Some((pid, bps.get(slot)?))) {
+            Some((pid, bp)) => {
+                if bp.kind == BreakKind::Execute {
+                    tf.rflags |= RFLAGS_RF;
+                }
+                crate::serial_println_raw!(
+                    "[hwbreak] pid {} slot {} {} {:#x}/{} hit, rip={:#x}",
+                    pid, slot, bp.kind.name(), bp.addr, bp.len, tf.rip,
+                );
+            }
+            None => crate::serial_println_raw!(
+                "[hwbreak] kernel access to watched {:#x} (slot {}), rip={:#x}",
+                unsafe { read_dr(slot) }, slot, tf.rip,
+            ),
+        }
+    }
+}
+
+unsafe fn write_dr7(value: u64) {
+    unsafe { core::arch::asm!("mov dr7, {}", in(reg) value, options(nomem, nostack)); }
+}
+
+unsafe fn write_dr(slot: usize, value: u64) {
+    unsafe {
+        match slot {
+            0 => core::arch::asm!("mov dr0, {}", in(reg) value, options(nomem, nostack)),
+            1 => core::arch::asm!("mov dr1, {}", in(reg) value, options(nomem, nostack)),
+            2 => core::arch::asm!("mov dr2, {}", in(reg) value, options(nomem, nostack)),
+            _ => core::arch::asm!("mov dr3, {}", in(reg) value, options(nomem, nostack)),
+        }
+    }
+}
+
+unsafe fn read_dr(slot: usize) -> u64 {
+    let value: u64;
+    unsafe {
+        match slot {
+            0 => core::arch::asm!("mov {}, dr0", out(reg) value, options(nomem, nostack)),
+            1 => core::arch::asm!("mov {}, dr1", out(reg) value, options(nomem, nostack)),
+            2 => core::arch::asm!("mov {}, dr2", out(reg) value, options(nomem, nostack)),
+            _ => core::arch::asm!("mov {}, dr3", out(reg) value, options(nomem, nostack)),
+        }
+    }
+    value
+}
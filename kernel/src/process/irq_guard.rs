@@ -20,31 +20,24 @@
 // why automatic cli/sti was rejected at that layer (risk of corrupting IRQ
 // nesting state across ISR/`jump_to_user` boundaries).
 //
-// Do not nest these guards on the same core. `SchedGuard` nesting would
-// self-deadlock immediately via the non-reentrant `spin::Mutex` underneath
-// `local_scheduler()` — loud and easy to diagnose. `InterruptGuard` nesting
-// would have the inner guard's `sti` fire while the outer guard is still
-// logically active — same non-nesting discipline the manual cli/sti code
-// already required, just narrower in scope now (one guard's lifetime instead
-// of matching two free-standing asm calls by hand).
+// `InterruptGuard` restores the interrupt flag it found instead of
+// unconditionally executing `sti` on drop. That matters more than it looks:
+// every syscall is *entered* with IF=0 (SFMASK clears it), so the old
+// "cli ... sti" pair turned interrupts on part-way through the first
+// syscall body that used a guard — and in a nested guard, while the outer
+// one was still logically active. Now a guard taken with IF=0 leaves IF=0,
+// a syscall body runs with interrupts off from entry until its `iretq`
+// (which restores the user's saved RFLAGS), and `InterruptGuard`s nest
+// freely. `SchedGuard` still must not nest: the non-reentrant
+// `spin::Mutex` underneath `local_scheduler()` would self-deadlock —
+// loud and easy to diagnose.
+//
+// `InterruptGuard` itself now lives in `crate::interrupts::guard` (with a
+// `without_interrupts` closure helper) so non-process code can share it;
+// only the scheduler-specific `SchedGuard` stays here.
 
 use super::scheduler::TrackedSchedulerGuard;
-
-/// `cli` on construction, `sti` on `Drop`. No lock.
-pub struct InterruptGuard(());
-
-impl InterruptGuard {
-    pub fn new() -> Self {
-        unsafe { core::arch::asm!("cli"); }
-        Self(())
-    }
-}
-
-impl Drop for InterruptGuard {
-    fn drop(&mut self) {
-        unsafe { core::arch::asm!("sti"); }
-    }
-}
+use crate::interrupts::InterruptGuard;
 
 /// `cli` + the current core's SCHEDULER lock, unlock + `sti` on `Drop`.
 ///
@@ -8,26 +8,58 @@ use x86_64::VirtAddr;
 use crate::memory::address_space::AddressSpace;
 
 pub mod scheduler;
+pub mod single_step;
+pub mod hw_break;
 pub mod trapframe;
 pub mod timer_preempt;
 pub mod tss;
 pub mod syscall;
-mod irq_guard;
+pub mod irq_guard;
 pub mod file;
 pub mod fpu;
 pub mod pipe;
 pub mod signal;
 pub mod user_test_fileio;
 pub mod user_programs;
+pub mod wait_status;
+pub mod cpuset;
 
 pub use signal::SignalAction;
 
 pub use trapframe::TrapFrame;
 pub use file::{FileDescriptorTable, FileHandle};
 
+/// A process id exactly as userspace sees it, packing two things: the low
+/// `PID_INDEX_BITS` bits are a slot index the scheduler recycles once the
+/// process is reaped, the rest is that slot's generation, bumped on every
+/// reuse. A recycled slot therefore never hands out the same number twice
+/// (until the generation wraps), so a stale pid a program kept around —
+/// to `kill()` or `waitpid()` later — can't silently hit whatever process
+/// got the slot next; see `Scheduler::pid_is_stale`. Generation 0 is the
+/// plain index, so PIDs look exactly as before until the first reuse.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Pid(pub usize);
 
+pub const PID_INDEX_BITS: u32 = 15;
+/// Number of PID slots (slot 0 is idle, permanently).
+pub const PID_SLOTS: usize = 1 << PID_INDEX_BITS;
+/// Generations wrap at 16 bits, so a pid always fits in a positive `pid_t`.
+pub const PID_GENERATION_MASK: u32 = 0xFFFF;
+
+impl Pid {
+    pub const fn new(index: usize, generation: u32) -> Self {
+        Pid(((generation & PID_GENERATION_MASK) as usize) << PID_INDEX_BITS | index)
+    }
+
+    pub const fn index(self) -> usize {
+        self.0 & (PID_SLOTS - 1)
+    }
+
+    pub const fn generation(self) -> u32 {
+        (self.0 >> PID_INDEX_BITS) as u32
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProcessState {
     Ready,
@@ -68,8 +100,19 @@ pub enum PrivilegeLevel {
 
 pub struct Process {
     pub pid: Pid,
+    /// Thread group id — what `getpid()` reports. For a normal process
+    /// (fork/exec/kernel) this is just `pid`; a thread created by
+    /// `clone()` inherits its creator's `tgid`, so every thread of one
+    /// program answers `getpid()` with the same number while `gettid()`
+    /// (`pid`) still tells them apart, as on Linux. Nothing else keys off
+    /// it yet — signals, `waitpid` and `/proc/<pid>` all still address
+    /// individual `pid`s.
+    pub tgid: Pid,
     pub parent_pid: Option<Pid>,
-    pub exit_status: i32,
+    /// How this process ended — `ExitReason::RUNNING` until it does. Set
+    /// once, by `sys_exit` or just before a fatal signal/fault kills it;
+    /// serialized for `waitpid()` by `wait_status_word()`.
+    pub exit_status: wait_status::ExitReason,
     pub state: ProcessState,
     pub privilege: PrivilegeLevel,
 
@@ -81,6 +124,12 @@ pub struct Process {
     /// Restored toward `priority` by periodic aging.
     pub effective_priority: u8,
 
+    /// Priority-inheritance floor: while this process owns a PI futex a
+    /// higher-priority process is blocked on (`FUTEX_LOCK_PI`), that
+    /// waiter's priority — `effective_priority` is raised to it and never
+    /// decays below it. 0 = not boosted. See `set_pi_floor`.
+    pub pi_floor: u8,
+
     pub name: [u8; 16],
     pub trapframe: Box<TrapFrame>,
     pub kernel_stack: VirtAddr,
@@ -120,12 +169,6 @@ pub struct Process {
     /// written into user memory once it's safe to.
     pub pending_wait_status: Option<i32>,
 
-    /// Set just before this process is killed by an uncaught signal or a
-    /// hardware fault (segfault, GPF, divide-by-zero, ...) — `None` for a
-    /// normal `exit()`. Hardware faults are all reported as `SIGSEGV` for
-    /// wait-status purposes (this kernel doesn't distinguish fault kinds
-    /// at the signal level). Read by `wait_status_word()`.
-    pub killed_by_signal: Option<u32>,
 
     /// Process group id (job control). Defaults to this process's own pid
     /// (group leader) at creation; `fork()`/`clone()` inherit the parent's
@@ -191,7 +234,9 @@ pub struct Process {
     /// reset) like real POSIX cwd; NOT shared between `clone()`-created
     /// threads (each gets its own `String` copy at creation time) — a
     /// simplification vs. real Linux `CLONE_FS`, same spirit as
-    /// `signal_handlers` not being inherited across `fork()`.
+    /// `signal_handlers` not being inherited across `fork()`. A path, not an
+    /// inode reference, so removing or renaming the directory leaves it
+    /// dangling — `sys_getcwd` reports that as `ENOENT`.
     pub cwd: alloc::string::String,
 
     /// The `PROGRAMS` registry name (see `user_programs.rs`) that resolved
@@ -212,6 +257,66 @@ pub struct Process {
     /// `fork()` in this implementation (every new `Process` starts with all
     /// `Default` — a simplification vs. real POSIX, which does inherit).
     pub signal_handlers: [SignalAction; signal::NUM_SIGNALS],
+
+    /// Timer ticks this process was the one `running` for — charged by
+    /// `Scheduler::tick()` *before* any preemption decision, so a tick is
+    /// always billed to whoever actually ran during it, never to the
+    /// process being switched to. No user/system split: a syscall running
+    /// on this process's behalf is charged here too. Reported as `utime`
+    /// by `times()` and `/proc/<pid>/stat`.
+    pub cpu_ticks: u64,
+    /// Sum of `cpu_ticks` (plus their own `children_cpu_ticks`) of every
+    /// child this process has reaped via `waitpid()` — `times()`'s
+    /// `tms_cutime`, same "only waited-for children count" rule as POSIX.
+    pub children_cpu_ticks: u64,
+    /// Times this process gave up the CPU itself (blocked, yielded,
+    /// stopped) vs. had it taken away by the timer — Linux's
+    /// `nvcsw`/`nivcsw`. Counted by the scheduler only when another
+    /// process actually ran next; see `Scheduler::note_switch_out`.
+    pub nvcsw: u64,
+    pub nivcsw: u64,
+    /// Single-step trace steps still to log (`kdebug trace <pid>`); 0 =
+    /// not tracing. Not inherited across fork. See `single_step`.
+    pub single_step_left: u32,
+    /// Hardware breakpoints set with `set_hwbreak`, loaded into DR0-DR7
+    /// at switch-in only when there are any. Not inherited across fork,
+    /// cleared by exec. See `hw_break`.
+    pub hw_breaks: hw_break::HwBreakpoints,
+    /// Weighted CPU time for the `cfs` scheduler build — advanced by
+    /// `Scheduler::tick()` by `VRUNTIME_SCALE / priority` per tick, so a
+    /// higher base priority accrues it more slowly and gets picked more
+    /// often. Unused (stays 0) by the default run-queue scheduler.
+    pub vruntime: u64,
+    /// `scheduler::total_ticks()` at the last tick this process was
+    /// `running` for (bumped by `Scheduler::tick()`) — only reported by the
+    /// scheduler watchdog, to say how long a starved process has been off
+    /// the CPU.
+    pub last_ran_tick: u64,
+    /// `scheduler::total_ticks()` when this process last became Ready
+    /// (created, woken, continued, or preempted back into its queue) —
+    /// what the scheduler watchdog measures starvation from. Priority
+    /// aging re-queues a process without touching it.
+    pub ready_since_tick: u64,
+    /// CPUs this process may run on (`sched_setaffinity`). Always
+    /// `CpuSet::all()` — i.e. CPU 0 — on this uniprocessor kernel; stored
+    /// so `/proc/<pid>/status` reports it and `fork()`/`clone()` inherit
+    /// it.
+    pub affinity: cpuset::CpuSet,
+}
+
+/// Everything `Process::new_thread` takes besides the new thread's ids:
+/// where it starts, and the state it shares with (or inherits from) the
+/// `clone()` caller — see `syscall::sys_clone`.
+pub struct ThreadInit {
+    pub entry: VirtAddr,
+    pub stack: VirtAddr,
+    pub kernel_stack: VirtAddr,
+    pub address_space: Arc<AddressSpace>,
+    pub files: Arc<Mutex<FileDescriptorTable>>,
+    pub owned_stack_vma: Option<(u64, usize)>,
+    pub cwd: alloc::string::String,
+    pub pgid: u32,
+    pub exe_name: alloc::string::String,
 }
 
 impl Process {
@@ -220,7 +325,7 @@ impl Process {
         pid: Pid,
         entry: VirtAddr,
         kernel_stack: VirtAddr,
-        address_space: AddressSpace,
+        address_space: Arc<AddressSpace>,
     ) -> Self {
         let mut trapframe = Box::new(TrapFrame::default());
         
@@ -246,29 +351,30 @@ impl Process {
         trapframe.r14 = 0;
         trapframe.r15 = 0;
         
-        crate::serial_println!(
+        crate::debug!(
             "Creating KERNEL process PID {}: entry={:#x} stack={:#x}",
             pid.0, entry.as_u64(), kernel_stack.as_u64()
         );
         
         Process {
             pid,
+            tgid: pid,
             parent_pid: None,
-            exit_status: 0,
+            exit_status: wait_status::ExitReason::RUNNING,
             state: ProcessState::Ready,
             privilege: PrivilegeLevel::Kernel,
             priority: 5,
             effective_priority: 5,
+            pi_floor: 0,
             name: [0; 16],
             trapframe,
             kernel_stack,
-            address_space: Arc::new(address_space),
+            address_space,
             files: Arc::new(Mutex::new(FileDescriptorTable::new_with_stdio())),
             waiting_for: None,
             waiting_options: 0,
             waiting_status_ptr: 0,
             pending_wait_status: None,
-            killed_by_signal: None,
             pgid: pid.0 as u32,
             stopped_by_signal: None,
             stop_reported: false,
@@ -281,6 +387,16 @@ impl Process {
             signal_handlers: [SignalAction::Default; signal::NUM_SIGNALS],
             blocked_signals: 0,
             pending_signals: 0,
+            cpu_ticks: 0,
+            children_cpu_ticks: 0,
+            nvcsw: 0,
+            nivcsw: 0,
+            single_step_left: 0,
+            hw_breaks: hw_break::HwBreakpoints::new(),
+            vruntime: 0,
+            last_ran_tick: 0,
+            ready_since_tick: 0,
+            affinity: cpuset::CpuSet::all(),
         }
     }
 
@@ -316,19 +432,21 @@ impl Process {
         trapframe.r14 = 0;
         trapframe.r15 = 0;
         
-        crate::serial_println!(
+        crate::debug!(
             "Creating USER process PID {}: entry={:#x} user_stack={:#x} kernel_stack={:#x}",
             pid.0, entry.as_u64(), user_stack.as_u64(), kernel_stack.as_u64()
         );
         
         Process {
             pid,
+            tgid: pid,
             parent_pid: None,
-            exit_status: 0,
+            exit_status: wait_status::ExitReason::RUNNING,
             state: ProcessState::Ready,
             privilege: PrivilegeLevel::User,
             priority: 5,
             effective_priority: 5,
+            pi_floor: 0,
             name: [0; 16],
             trapframe,
             kernel_stack,
@@ -338,7 +456,6 @@ impl Process {
             waiting_options: 0,
             waiting_status_ptr: 0,
             pending_wait_status: None,
-            killed_by_signal: None,
             pgid: pid.0 as u32,
             stopped_by_signal: None,
             stop_reported: false,
@@ -351,6 +468,16 @@ impl Process {
             signal_handlers: [SignalAction::Default; signal::NUM_SIGNALS],
             blocked_signals: 0,
             pending_signals: 0,
+            cpu_ticks: 0,
+            children_cpu_ticks: 0,
+            nvcsw: 0,
+            nivcsw: 0,
+            single_step_left: 0,
+            hw_breaks: hw_break::HwBreakpoints::new(),
+            vruntime: 0,
+            last_ran_tick: 0,
+            ready_since_tick: 0,
+            affinity: cpuset::CpuSet::all(),
         }
     }
 
@@ -376,19 +503,21 @@ impl Process {
         exe_name: alloc::string::String,
         fpu_state: Box<fpu::FpuState>,
     ) -> Self {
-        crate::serial_println!(
+        crate::debug!(
             "Creating FORKED process PID {} (parent PID {})",
             pid.0, parent_pid.0,
         );
         crate::debug::inc_forks();
         Process {
             pid,
+            tgid: pid,
             parent_pid: Some(parent_pid),
-            exit_status: 0,
+            exit_status: wait_status::ExitReason::RUNNING,
             state: ProcessState::Ready,
             privilege: PrivilegeLevel::User,
             priority: 5,
             effective_priority: 5,
+            pi_floor: 0,
             name: [0; 16],
             trapframe,
             kernel_stack,
@@ -398,7 +527,6 @@ impl Process {
             waiting_options: 0,
             waiting_status_ptr: 0,
             pending_wait_status: None,
-            killed_by_signal: None,
             pgid: parent_pgid,
             stopped_by_signal: None,
             stop_reported: false,
@@ -411,6 +539,16 @@ impl Process {
             signal_handlers: [SignalAction::Default; signal::NUM_SIGNALS],
             blocked_signals: 0,
             pending_signals: 0,
+            cpu_ticks: 0,
+            children_cpu_ticks: 0,
+            nvcsw: 0,
+            nivcsw: 0,
+            single_step_left: 0,
+            hw_breaks: hw_break::HwBreakpoints::new(),
+            vruntime: 0,
+            last_ran_tick: 0,
+            ready_since_tick: 0,
+            affinity: cpuset::CpuSet::all(),
         }
     }
 
@@ -426,19 +564,11 @@ impl Process {
     /// `files` is the caller's own `Arc<Mutex<FileDescriptorTable>>`, passed
     /// in (not built fresh) so the new thread shares fd space with its
     /// siblings — POSIX threads see each other's open files.
-    pub fn new_thread(
-        pid: Pid,
-        parent_pid: Pid,
-        entry: VirtAddr,
-        stack: VirtAddr,
-        kernel_stack: VirtAddr,
-        address_space: Arc<AddressSpace>,
-        files: Arc<Mutex<FileDescriptorTable>>,
-        owned_stack_vma: Option<(u64, usize)>,
-        cwd: alloc::string::String,
-        parent_pgid: u32,
-        exe_name: alloc::string::String,
-    ) -> Self {
+    pub fn new_thread(pid: Pid, tgid: Pid, parent_pid: Pid, init: ThreadInit) -> Self {
+        let ThreadInit {
+            entry, stack, kernel_stack, address_space, files, owned_stack_vma, cwd,
+            pgid: parent_pgid, exe_name,
+        } = init;
         let mut trapframe = Box::new(TrapFrame::default());
 
         trapframe.rip = entry.as_u64();
@@ -463,19 +593,21 @@ impl Process {
         trapframe.r14 = 0;
         trapframe.r15 = 0;
 
-        crate::serial_println!(
+        crate::debug!(
             "Creating THREAD PID {} (parent PID {}): entry={:#x} stack={:#x}, sharing address space",
             pid.0, parent_pid.0, entry.as_u64(), stack.as_u64(),
         );
 
         Process {
             pid,
+            tgid,
             parent_pid: Some(parent_pid),
-            exit_status: 0,
+            exit_status: wait_status::ExitReason::RUNNING,
             state: ProcessState::Ready,
             privilege: PrivilegeLevel::User,
             priority: 5,
             effective_priority: 5,
+            pi_floor: 0,
             name: [0; 16],
             trapframe,
             kernel_stack,
@@ -485,7 +617,6 @@ impl Process {
             waiting_options: 0,
             waiting_status_ptr: 0,
             pending_wait_status: None,
-            killed_by_signal: None,
             pgid: parent_pgid,
             stopped_by_signal: None,
             stop_reported: false,
@@ -498,9 +629,67 @@ impl Process {
             signal_handlers: [SignalAction::Default; signal::NUM_SIGNALS],
             blocked_signals: 0,
             pending_signals: 0,
+            cpu_ticks: 0,
+            children_cpu_ticks: 0,
+            nvcsw: 0,
+            nivcsw: 0,
+            single_step_left: 0,
+            hw_breaks: hw_break::HwBreakpoints::new(),
+            vruntime: 0,
+            last_ran_tick: 0,
+            ready_since_tick: 0,
+            affinity: cpuset::CpuSet::all(),
         }
     }
 
+    /// Release everything a dead process still holds: its fd table (closing
+    /// any pipe ends / files left open), its address space — user frames,
+    /// page-table frames and VMA list, via `OwnedPageTable`'s `Drop`, but
+    /// only if this was the last reference; a thread group's surviving
+    /// members keep it alive — and its kernel stack. Logs how much physical
+    /// memory came back to the Buddy, so a leak shows up as a reap that
+    /// reclaims less than the usual amount.
+    ///
+    /// The one place a zombie's resources are actually freed: called on
+    /// every reaped zombie (`sys_waitpid`), never on a live process.
+    /// Idempotent — a second call finds nothing left to free.
+    ///
+    /// # Safety requirements (not `unsafe fn` — mirrors `free_kernel_stack`)
+    /// - The CPU must not be executing on this process's kernel stack or
+    ///   running in its address space, i.e. the scheduler has already
+    ///   switched away from it. Never call this on `running`.
+    /// - The scheduler lock must NOT be held: closing a pipe end wakes its
+    ///   peer through the scheduler (same reason `sys_exit` drops its old
+    ///   fd table outside the lock).
+    pub fn teardown(&mut self) {
+        let free_before = crate::allocator::buddy_allocator::lock_buddy().free_bytes();
+
+        drop(core::mem::replace(
+            &mut self.files,
+            Arc::new(Mutex::new(FileDescriptorTable::new())),
+        ));
+
+        // The shared kernel address space as the placeholder — it's never
+        // the last reference, and its page table is unowned anyway, so
+        // swapping it in can never free anything itself.
+        drop(core::mem::replace(
+            &mut self.address_space,
+            AddressSpace::kernel(),
+        ));
+
+        if self.kernel_stack != VirtAddr::zero() {
+            crate::init::processes::free_kernel_stack(self.kernel_stack);
+            self.kernel_stack = VirtAddr::zero();
+        }
+
+        let free_after = crate::allocator::buddy_allocator::lock_buddy().free_bytes();
+        crate::debug!(
+            "[reap] PID {}: reclaimed {} KiB",
+            self.pid.0,
+            free_after.saturating_sub(free_before) / 1024,
+        );
+    }
+
     pub fn set_name(&mut self, name: &str) {
         let bytes = name.as_bytes();
         let len = core::cmp::min(bytes.len(), 15);
@@ -510,36 +699,34 @@ impl Process {
     pub fn set_priority(&mut self, priority: u8) {
         let p = core::cmp::min(priority, 10);
         self.priority = p;
-        self.effective_priority = p;
+        self.effective_priority = p.max(self.pi_floor);
     }
 
-    /// Encodes this (dead) process's exit condition into this kernel's
-    /// wait(2)-ABI status word.
-    ///
-    /// `mlibc-port/constanos-sysdeps/include/abi-bits/wait.h` uses the
-    /// dripos-style encoding (`WIFEXITED` = bit `0x200`, `WIFSIGNALED` =
-    /// bit `0x400` with the signal number in bits 24-31) rather than
-    /// Linux's `WTERMSIG(x) == 0` trick — see the mlibc-port ABI-bug
-    /// history for why this port follows that header instead of assuming
-    /// Linux's layout here.
+    /// Priority inheritance: set the floor to `floor` (0 = none), the best
+    /// priority blocked on a PI futex this process owns. `effective_priority`
+    /// is raised to it, or loses whatever boost is left above both it and
+    /// the base `priority`.
+    pub fn set_pi_floor(&mut self, floor: u8) {
+        self.pi_floor = floor;
+        self.effective_priority = self.effective_priority.min(self.priority.max(floor)).max(floor);
+    }
+
+    /// Encodes this (dead) process's exit condition into the wait(2)
+    /// status word — see `wait_status` for the layout.
     pub fn wait_status_word(&self) -> i32 {
-        match self.killed_by_signal {
-            Some(sig) => (((sig as i32) & 0xFF) << 24) | 0x400,
-            None => 0x200 | (self.exit_status & 0xFF),
-        }
+        self.exit_status.to_wait_status()
     }
 
     /// Encodes this (currently `Stopped`) process's condition into a
-    /// `WUNTRACED` wait status: `WIFSTOPPED` = bit `0x800`, stop signal in
-    /// bits 16-23 (`WSTOPSIG`) — see `abi-bits/wait.h`. Returns a plain
+    /// `WUNTRACED` wait status (`wait_status::stopped_status`). Returns a plain
     /// exited(0) word if called on a process that isn't actually stopped
     /// (shouldn't happen — callers only reach this via a `Stopped`-state
     /// match — but this avoids a bogus status word if that invariant is
     /// ever violated).
     pub fn stop_status_word(&self) -> i32 {
         match self.stopped_by_signal {
-            Some(sig) => 0x800 | (((sig as i32) & 0xFF) << 16),
-            None => 0x200,
+            Some(sig) => wait_status::stopped_status(sig),
+            None => wait_status::ExitReason::Exited(0).to_wait_status(),
         }
     }
 }
@@ -29,14 +29,21 @@
 //
 // Lock order: `PipeBuffer`'s mutex is always dropped before taking
 // `SCHEDULER` (never nested), matching `sys_futex`'s FUTEX_WAITERS ->
-// SCHEDULER pattern.
+// SCHEDULER pattern. The one nesting the other way is `poll()`, which
+// `syscall::poll`'s readiness check calls with SCHEDULER held — fine,
+// since nothing here ever holds the pipe lock while waiting on SCHEDULER.
+// Every state change a poller could be waiting for (data in, space freed,
+// an end closing) ends in `poll_wakeup_ready`, after the pipe lock is gone.
+// That includes `Drop`, so an end must never be dropped with SCHEDULER
+// held: a dead process's fd table is dropped by `Process::teardown`, which
+// runs after the lock is released (`Scheduler::pending_reaps`).
 
 use alloc::boxed::Box;
 use alloc::sync::Arc;
 use spin::Mutex;
 use x86_64::{VirtAddr, structures::paging::{Page, Size4KiB}};
 
-use super::file::{FileError, FileHandle, FileResult};
+use super::file::{FileError, FileHandle, FileResult, Readiness};
 use super::{Process, ProcessState};
 
 const PIPE_CAPACITY: usize = 4096;
@@ -221,6 +228,7 @@ impl FileHandle for PipeReadEnd {
                     self.buf.lock().try_write(&tmp[..got]);
                 }
             }
+            super::syscall::poll_wakeup_ready();
             return Ok(n);
         }
 
@@ -243,6 +251,11 @@ impl FileHandle for PipeReadEnd {
 
     fn name(&self) -> &str { "<pipe:r>" }
 
+    fn poll(&self) -> Readiness {
+        let pb = self.buf.lock();
+        Readiness { readable: pb.len > 0 || pb.writers == 0, writable: false, hangup: pb.writers == 0 }
+    }
+
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
         self.buf.lock().readers += 1;
         Some(Box::new(PipeReadEnd { buf: self.buf.clone() }))
@@ -277,6 +290,7 @@ impl FileHandle for PipeWriteEnd {
             if let Some((w, tmp, got)) = delivered {
                 wake_reader(w, &tmp[..got]);
             }
+            super::syscall::poll_wakeup_ready();
             return Ok(n);
         }
 
@@ -292,6 +306,11 @@ impl FileHandle for PipeWriteEnd {
 
     fn name(&self) -> &str { "<pipe:w>" }
 
+    fn poll(&self) -> Readiness {
+        let pb = self.buf.lock();
+        Readiness { readable: false, writable: pb.len < PIPE_CAPACITY || pb.readers == 0, hangup: pb.readers == 0 }
+    }
+
     fn dup(&self) -> Option<Box<dyn FileHandle>> {
         self.buf.lock().writers += 1;
         Some(Box::new(PipeWriteEnd { buf: self.buf.clone() }))
@@ -308,6 +327,7 @@ impl Drop for PipeReadEnd {
             if let Some(w) = waiter {
                 wake_writer_error(w, super::syscall::errno::EPIPE);
             }
+            super::syscall::poll_wakeup_ready();
         }
     }
 }
@@ -322,6 +342,7 @@ impl Drop for PipeWriteEnd {
             if let Some(w) = waiter {
                 wake_reader(w, &[]); // EOF
             }
+            super::syscall::poll_wakeup_ready();
         }
     }
 }
@@ -4,21 +4,69 @@
 //
 // STRUCTURE:
 //   run_queues[0..=10]  — ONLY Ready processes, indexed by effective_priority
+//   nonempty_mask        — bit p set iff run_queues[p] is non-empty, so
+//                          `pick_next` finds the highest non-empty queue
+//                          with one `leading_zeros` instead of a scan
 //   wait_queue           — Blocked and Zombie processes (not scanned by scheduler)
 //   running              — the single currently executing process
 //
 // A process moves between these containers:
-//   add_process()   → run_queues[eff_pri]
+//   add_process()   → run_queues[eff_pri]  (refused past MAX_PROCESSES)
 //   switch_to_next  → running ↔ run_queues  (Ready processes only)
 //   block_current() → running → wait_queue  (future: I/O wait)
 //   wake(pid)       → wait_queue → run_queues[eff_pri]  (future: I/O complete)
 //   kill_current()  → running → wait_queue as Zombie  (segfault, sys_exit)
 //
 // TIME SLICES + AGING:
-//   Each process gets quantum = BASE_QUANTUM + eff_pri * BONUS ticks.
+//   Each process gets quantum = BASE_QUANTUM_MS + eff_pri * BONUS_MS,
+//   in ticks at TICK_HZ (2 + eff_pri ticks at 100 Hz).
 //   When exhausted: preempt, decay eff_pri by 1.
 //   Every AGING_EPOCH ticks: boost waiting processes' eff_pri toward base.
 //
+// IDLE POLICY:
+//   The idle process (PID 0) lives in run_queues[IDLE_QUEUE] and nothing
+//   else ever does — every other process is clamped to
+//   MIN_EFFECTIVE_PRIORITY..=10 (see `queue_index`).  Idle is never aged
+//   or decayed, is picked only when queues 1..=10 are all empty, and is
+//   never a candidate for `start_first`.  Every "what runs next" decision
+//   goes through `pick_next`, so this can't drift between call sites.
+//
+// CFS MODE (`--features cfs`):
+//   Replaces priority decay + aging with a per-process `vruntime`.  Every
+//   tick advances the running process's vruntime by VRUNTIME_SCALE /
+//   priority, and real processes live in `cfs_tree` keyed by (vruntime,
+//   pid) instead of in run_queues — `pick_next` takes the leftmost entry.
+//   A process entering the tree is placed at no less than `min_vruntime`,
+//   so one that slept for a long time can't come back with a vruntime
+//   far behind everyone else's and monopolize the CPU.  The slice is
+//   still quantum_for(priority), but priority never moves (no decay, no
+//   aging).  Idle stays in run_queues[IDLE_QUEUE] under the same IDLE
+//   POLICY as above.  Both modes always compile (`CFS` is a plain const),
+//   only the cargo feature picks which one runs.
+//
+// LOCKING DISCIPLINE:
+//   - The scheduler lock is only ever taken with interrupts disabled
+//     (asserted by `local_scheduler`/`try_local_scheduler` on acquire and
+//     by `TrackedSchedulerGuard::drop` on release) — so no IRQ can arrive
+//     on this core while it's held, and an IRQ handler that takes it can't
+//     be interrupting its own holder.
+//   - It is never held across `iretq`/`jump_to_trapframe`: every switch
+//     path takes the next `*const TrapFrame` out, drops the guard, then
+//     jumps. (A guard alive across a diverging jump is never released.)
+//   - IRQ handlers that wake processes (keyboard/serial → stdin/poll
+//     wakeups, tty job-control signals) use the blocking `local_scheduler`:
+//     by the first rule, the code they interrupted can't be holding it.
+//   - The timer ISR is the one that runs on *every* tick, so it's the one
+//     that would turn a broken first rule (a stray `sti` mid-critical-
+//     section) into a guaranteed hang rather than a rare one. It uses
+//     `try_local_scheduler` and skips the tick (see `timer_preempt.rs`)
+//     if the lock is held, counting it in `sched_lock_busy_ticks`.
+//   - Exceptions (#PF etc.) that take the lock do so only to kill a user
+//     process; a fault *while holding it* is a kernel bug that panics first.
+//   - It's the outermost lock of the hierarchy in `lock_order.rs`: a VMA
+//     list or BUDDY may be taken (heap allocation included) while holding
+//     it, never the other way round — checked in debug builds.
+//
 // HISTORY:
 //   - Removed IretFrame and kill_and_switch().  Replaced with
 //     kill_and_switch_tf() which returns a *const TrapFrame, enabling
@@ -26,8 +74,8 @@
 //     The old approach only overwrote the 5-field exception stack frame,
 //     leaking RAX..R15 from the killed process into the next one.
 
-use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
-use core::sync::atomic::{AtomicUsize, Ordering};
+use alloc::{boxed::Box, collections::{BTreeMap, VecDeque}, vec::Vec};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 /// Thin wrapper around `spin::MutexGuard<Scheduler>` that (1) reports every
 /// acquire/release through `debug::SCHEDULER_LOCK` — permanent, always-on
@@ -69,6 +117,7 @@ impl core::ops::DerefMut for TrackedSchedulerGuard {
 impl Drop for TrackedSchedulerGuard {
     fn drop(&mut self) {
         self.0 = None;
+        crate::lock_order::release(crate::lock_order::LockClass::Scheduler);
         crate::debug::SCHEDULER_LOCK.record_release();
         assert!(
             !x86_64::instructions::interrupts::are_enabled(),
@@ -124,6 +173,7 @@ use x86_64::VirtAddr;
 use super::{Process, Pid, ProcessState, TrapFrame};
 use crate::memory::address_space::AddressSpace;
 use crate::memory::vma::Vma;
+use crate::time::clockevent::ms_to_ticks;
 
 // ============================================================================
 // Per-CPU fast-path pointers (updated on every context switch, IF=0)
@@ -177,13 +227,74 @@ fn clear_current_fast() {
     CURRENT_PID_FAST[cpu].store(0, Ordering::Release);
 }
 
+/// Timer ticks since boot, across every CPU's scheduler — the denominator
+/// for a per-process CPU-usage percentage (`Process::cpu_ticks` / this).
+static TOTAL_TICKS: AtomicU64 = AtomicU64::new(0);
+
+/// See `TOTAL_TICKS`.
+pub fn total_ticks() -> u64 {
+    TOTAL_TICKS.load(Ordering::Relaxed)
+}
+
 const NUM_PRIORITIES: usize = 11;
 
-const BASE_QUANTUM: u32 = 2;
-const PRIORITY_QUANTUM_BONUS: u32 = 1;
-const AGING_EPOCH: u32 = 50;
+/// Run queue reserved for the idle process (PID 0) — see the module
+/// comment's IDLE POLICY section.
+const IDLE_QUEUE: usize = 0;
+
+// `Scheduler::nonempty_mask` is a u16, one bit per run queue.
+const _: () = assert!(NUM_PRIORITIES <= u16::BITS as usize);
+
+// Intervals are chosen in milliseconds and converted at the configured
+// tick rate (`clockevent::TICK_HZ`), so they mean the same at any rate.
+const BASE_QUANTUM_MS: u32 = 20;
+const PRIORITY_QUANTUM_BONUS_MS: u32 = 10;
+const AGING_EPOCH: u32 = ms_to_ticks(500);
 const MIN_EFFECTIVE_PRIORITY: u8 = 1;
 
+/// How often (in scheduler ticks — 1 s) `tick()` runs `watchdog_check`.
+const WATCHDOG_INTERVAL_TICKS: u32 = ms_to_ticks(1000);
+/// A real process Ready this long (10 s) without running gets reported.
+/// Far above anything legitimate: the longest quantum is 120 ms and aging
+/// lifts a waiting process every `AGING_EPOCH`.
+const WATCHDOG_STARVE_TICKS: u64 = ms_to_ticks(10_000) as u64;
+
+/// Scheduling policy, fixed at compile time: `false` is the priority
+/// run-queue scheduler, `true` the vruntime one (module comment's CFS
+/// MODE).
+const CFS: bool = cfg!(feature = "cfs");
+
+/// vruntime a priority-1 process accrues per tick; priority `p` accrues
+/// `VRUNTIME_SCALE / p`. 2520 = lcm(1..=10), so every priority divides
+/// it exactly.
+const VRUNTIME_SCALE: u64 = 2520;
+
+/// Most processes (threads and not-yet-reaped zombies included) one
+/// scheduler will hold at once. Each costs a 64 KiB kernel stack plus its
+/// page tables, so without a cap a fork loop just runs the Buddy dry.
+pub const MAX_PROCESSES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedError {
+    /// `MAX_PROCESSES` already reached — maps to EAGAIN.
+    TooManyProcesses,
+}
+
+/// A process `add_process` turned away, handed back with the reason.
+/// Dropping it frees the process's address space and fd table, so the
+/// caller does that once the scheduler lock is released — never under it
+/// (see `Process::teardown`).
+pub struct Refused {
+    pub error: SchedError,
+    pub process: Box<Process>,
+}
+
+impl core::fmt::Debug for Refused {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "PID {} refused: {:?}", self.process.pid.0, self.error)
+    }
+}
+
 static SCHEDULERS: [Mutex<Scheduler>; crate::cpu::MAX_CPUS] = [
     Mutex::new(Scheduler::new()),
     Mutex::new(Scheduler::new()),
@@ -208,15 +319,45 @@ pub fn local_scheduler() -> TrackedSchedulerGuard {
          caller must `cli` first. Same bug class `TrackedSchedulerGuard`'s \
          drop-time assertion catches on the other end; see its doc comment."
     );
+    crate::lock_order::acquire(crate::lock_order::LockClass::Scheduler);
     let guard = SCHEDULERS[crate::cpu::cpu_id()].lock();
     crate::debug::SCHEDULER_LOCK.record_acquire(core::panic::Location::caller());
     TrackedSchedulerGuard(Some(guard))
 }
 
+/// Non-blocking `local_scheduler`: `None` if the lock is already held.
+/// Same interrupts-off requirement (asserted). Only the timer ISR needs
+/// this — see the module comment's LOCKING DISCIPLINE.
+#[track_caller]
+pub fn try_local_scheduler() -> Option<TrackedSchedulerGuard> {
+    assert!(
+        !x86_64::instructions::interrupts::are_enabled(),
+        "try_local_scheduler() called with interrupts enabled (IF=1)"
+    );
+    let guard = SCHEDULERS[crate::cpu::cpu_id()].try_lock()?;
+    crate::lock_order::acquired_nonblocking(crate::lock_order::LockClass::Scheduler);
+    crate::debug::SCHEDULER_LOCK.record_acquire(core::panic::Location::caller());
+    Some(TrackedSchedulerGuard(Some(guard)))
+}
+
 pub struct Scheduler {
     /// Per-priority run queues — ONLY Ready processes.
     run_queues: [VecDeque<Box<Process>>; NUM_PRIORITIES],
 
+    /// Bit `p` set iff `run_queues[p]` is non-empty. Only ever changed by
+    /// `push_queue`/`pop_queue`/`remove_from_queue` — never touch
+    /// `run_queues` structurally anywhere else, or this goes stale.
+    nonempty_mask: u16,
+
+    /// CFS mode only: Ready real (non-idle) processes, keyed by
+    /// (vruntime, pid) so the leftmost entry is the one to run next.
+    /// Always empty in the default run-queue mode.
+    cfs_tree: BTreeMap<(u64, usize), Box<Process>>,
+
+    /// CFS mode only: monotonic floor for vruntimes entering `cfs_tree` —
+    /// the largest vruntime `pick_next` has handed out so far.
+    min_vruntime: u64,
+
     /// Blocked and Zombie processes.  Not scanned during scheduling.
     pub wait_queue: VecDeque<Box<Process>>,
 
@@ -226,12 +367,26 @@ pub struct Scheduler {
     /// Remaining ticks for the running process.
     remaining_ticks: u32,
 
+    /// The process that last left the CPU and whether it went voluntarily
+    /// — set by `note_switch_out`, consumed by `count_switch` once the
+    /// next process is chosen.
+    switched_out: Option<(usize, bool)>,
+
     /// Global tick counter for aging epochs.
     global_ticks: u32,
 
-    /// Monotonic PID counter (0 is reserved for idle).
+    /// Next never-used PID slot (0 is reserved for idle). Only grows once
+    /// `free_pid_slots` runs dry.
     next_pid: usize,
 
+    /// Slots of reaped processes, reused oldest-first so a just-freed
+    /// number stays out of circulation as long as possible.
+    free_pid_slots: VecDeque<usize>,
+
+    /// Current generation of every slot handed out so far, indexed by
+    /// slot — see `Pid`.
+    pid_generations: Vec<u32>,
+
     /// Kernel stacks awaiting `phys_free` — populated by `kill_current`'s
     /// thread-reap path, which runs *on the dying thread's own kernel
     /// stack* (called mid-syscall/exception, before the switch-away has
@@ -251,6 +406,15 @@ pub struct Scheduler {
     /// already been dropped — it may otherwise be the last reference if the
     /// thread's parent process has also exited.
     pending_vma_frees: Vec<(alloc::sync::Arc<AddressSpace>, u64, usize)>,
+
+    /// Zombies already collected by `notify_child_death` on behalf of a
+    /// parent blocked in `waitpid()`, plus the exited threads and parentless
+    /// processes `kill_current` reaps itself — out of `wait_queue`, but not
+    /// yet torn down: that happens on the dying process's own kernel stack,
+    /// and `Process::teardown` can't run under this lock anyway. Drained
+    /// by the next `sys_waitpid`, `sys_exit` or idle-task wakeup on this
+    /// CPU (`take_pending_reaps`, `reap_pending`).
+    pending_reaps: Vec<Process>,
 }
 
 impl Scheduler {
@@ -262,13 +426,20 @@ impl Scheduler {
                 VecDeque::new(), VecDeque::new(), VecDeque::new(),
                 VecDeque::new(), VecDeque::new(),
             ],
+            nonempty_mask: 0,
+            cfs_tree: BTreeMap::new(),
+            min_vruntime: 0,
             wait_queue: VecDeque::new(),
             running: None,
             remaining_ticks: 0,
+            switched_out: None,
             global_ticks: 0,
             next_pid: 1,
+            free_pid_slots: VecDeque::new(),
+            pid_generations: Vec::new(),
             pending_stack_frees: Vec::new(),
             pending_vma_frees: Vec::new(),
+            pending_reaps: Vec::new(),
         }
     }
 
@@ -277,7 +448,151 @@ impl Scheduler {
     // ====================================================================
 
     fn quantum_for(effective_priority: u8) -> u32 {
-        BASE_QUANTUM + (effective_priority as u32) * PRIORITY_QUANTUM_BONUS
+        ms_to_ticks(BASE_QUANTUM_MS + (effective_priority as u32) * PRIORITY_QUANTUM_BONUS_MS)
+    }
+
+    // ====================================================================
+    // Idle policy / next-process selection
+    // ====================================================================
+
+    fn is_idle(proc: &Process) -> bool {
+        proc.pid.0 == 0
+    }
+
+    /// Run queue `proc` belongs in: `IDLE_QUEUE` for idle, otherwise its
+    /// effective priority clamped to `MIN_EFFECTIVE_PRIORITY..=10` — a real
+    /// process must never share idle's queue, or `pick_next(false)` could
+    /// skip it and `pick_next(true)` could starve it behind idle.
+    fn queue_index(proc: &Process) -> usize {
+        if Self::is_idle(proc) {
+            IDLE_QUEUE
+        } else {
+            (proc.effective_priority as usize)
+                .clamp(MIN_EFFECTIVE_PRIORITY as usize, NUM_PRIORITIES - 1)
+        }
+    }
+
+    /// vruntime charged per tick to a process of base priority `priority`.
+    fn vruntime_delta(priority: u8) -> u64 {
+        VRUNTIME_SCALE / (priority.max(MIN_EFFECTIVE_PRIORITY) as u64)
+    }
+
+    fn enqueue(&mut self, mut proc: Box<Process>) {
+        proc.ready_since_tick = TOTAL_TICKS.load(Ordering::Relaxed);
+        if CFS && !Self::is_idle(&proc) {
+            proc.vruntime = proc.vruntime.max(self.min_vruntime);
+            self.cfs_tree.insert((proc.vruntime, proc.pid.0), proc);
+            return;
+        }
+        let pri = Self::queue_index(&proc);
+        self.push_queue(pri, proc);
+    }
+
+    /// Pop the highest-priority Ready process. Idle is only returned when
+    /// `allow_idle` is set and every real-process queue is empty —
+    /// `start_first` passes `false` (idle is never "a real process to
+    /// start"), every context-switch site passes `true`.
+    fn pick_next(&mut self, allow_idle: bool) -> Option<Box<Process>> {
+        // In CFS mode the tree holds every real process and the run queues
+        // hold only idle, so this falls through to the idle check below.
+        if let Some(((vruntime, _), proc)) = self.cfs_tree.pop_first() {
+            self.min_vruntime = self.min_vruntime.max(vruntime);
+            return Some(proc);
+        }
+        let real = self.nonempty_mask & !(1 << IDLE_QUEUE);
+        if real != 0 {
+            let priority = (u16::BITS - 1 - real.leading_zeros()) as usize;
+            return self.pop_queue(priority);
+        }
+        if allow_idle {
+            self.pop_queue(IDLE_QUEUE)
+        } else {
+            None
+        }
+    }
+
+    /// Pid `pick_next(true)` would return, without dequeuing it. Only for
+    /// `hw_tests.rs`, which drives a private `Scheduler` it never switches to.
+    #[cfg(test)]
+    pub(crate) fn peek_next(&self) -> Option<usize> {
+        if let Some((&(_, pid), _)) = self.cfs_tree.first_key_value() {
+            return Some(pid);
+        }
+        let real = self.nonempty_mask & !(1 << IDLE_QUEUE);
+        let pri = if real != 0 {
+            (u16::BITS - 1 - real.leading_zeros()) as usize
+        } else {
+            IDLE_QUEUE
+        };
+        self.run_queues[pri].front().map(|p| p.pid.0)
+    }
+
+    /// `pick_next`, dropping what it returns — for `hw_tests.rs`, which
+    /// checks the order processes come out in without switching to them.
+    #[cfg(test)]
+    pub(crate) fn take_next(&mut self, allow_idle: bool) -> Option<usize> {
+        self.pick_next(allow_idle).map(|p| p.pid.0)
+    }
+
+    /// Ready process `pid` preempted the way `park_running` treats a
+    /// Running one: decayed and requeued. For `hw_tests.rs` — the real
+    /// path also saves registers and needs `pid` to be running.
+    #[cfg(test)]
+    pub(crate) fn preempt_ready(&mut self, pid: usize) -> bool {
+        let Some(mut proc) = self.take_ready(pid) else { return false };
+        Self::decay(&mut proc);
+        self.enqueue(proc);
+        true
+    }
+
+    /// One `AGING_EPOCH` boundary's worth of aging, without `tick`'s other
+    /// work — for `hw_tests.rs`, where `tick` would also bump the global
+    /// `TOTAL_TICKS` and run the watchdog against a private scheduler.
+    #[cfg(test)]
+    pub(crate) fn age_once(&mut self) {
+        self.age_processes();
+    }
+
+    // ====================================================================
+    // Run-queue mutation (keeps `nonempty_mask` in sync)
+    // ====================================================================
+
+    fn push_queue(&mut self, pri: usize, proc: Box<Process>) {
+        self.run_queues[pri].push_back(proc);
+        self.nonempty_mask |= 1 << pri;
+        self.debug_check_mask();
+    }
+
+    fn pop_queue(&mut self, pri: usize) -> Option<Box<Process>> {
+        let proc = self.run_queues[pri].pop_front();
+        if self.run_queues[pri].is_empty() {
+            self.nonempty_mask &= !(1 << pri);
+        }
+        self.debug_check_mask();
+        proc
+    }
+
+    fn remove_from_queue(&mut self, pri: usize, index: usize) -> Option<Box<Process>> {
+        let proc = self.run_queues[pri].remove(index);
+        if self.run_queues[pri].is_empty() {
+            self.nonempty_mask &= !(1 << pri);
+        }
+        self.debug_check_mask();
+        proc
+    }
+
+    #[inline]
+    fn debug_check_mask(&self) {
+        if cfg!(debug_assertions) {
+            for (pri, queue) in self.run_queues.iter().enumerate() {
+                debug_assert_eq!(
+                    self.nonempty_mask & (1 << pri) != 0,
+                    !queue.is_empty(),
+                    "nonempty_mask {:#06x} out of sync with run_queues[{}] (len {})",
+                    self.nonempty_mask, pri, queue.len(),
+                );
+            }
+        }
     }
 
     // ====================================================================
@@ -285,23 +600,78 @@ impl Scheduler {
     // ====================================================================
 
     pub fn allocate_pid(&mut self) -> Pid {
-        let pid = Pid(self.next_pid);
+        if let Some(index) = self.free_pid_slots.pop_front() {
+            let generation = (self.pid_generations[index] + 1) & super::PID_GENERATION_MASK;
+            self.pid_generations[index] = generation;
+            return Pid::new(index, generation);
+        }
+        let index = self.next_pid;
+        assert!(index < super::PID_SLOTS, "allocate_pid: all {} PID slots in use", super::PID_SLOTS);
         self.next_pid += 1;
-        pid
+        if self.pid_generations.len() <= index {
+            self.pid_generations.resize(index + 1, 0);
+        }
+        Pid::new(index, 0)
+    }
+
+    /// Return a reaped process's slot for reuse. Must be called exactly
+    /// once per process, after it has left every queue for good. Idle's
+    /// slot is never released.
+    pub fn release_pid(&mut self, pid: Pid) {
+        if pid.index() == 0 {
+            return;
+        }
+        debug_assert_eq!(
+            self.pid_generations.get(pid.index()).copied(),
+            Some(pid.generation()),
+            "release_pid: PID {} isn't the current holder of its slot", pid.0,
+        );
+        self.free_pid_slots.push_back(pid.index());
+    }
+
+    /// True if `pid`'s slot has since been handed to a different process,
+    /// i.e. the caller is holding on to a dead process's number. Callers
+    /// that take a pid from userspace (`sys_kill`, `sys_waitpid`) check
+    /// this first so the answer is an honest ESRCH/ECHILD; a plain lookup
+    /// would also miss (the new holder's number differs), this just makes
+    /// the reason explicit.
+    pub fn pid_is_stale(&self, pid: usize) -> bool {
+        let pid = Pid(pid);
+        self.pid_generations
+            .get(pid.index())
+            .is_some_and(|&generation| generation != pid.generation())
     }
 
     // ====================================================================
     // Process insertion
     // ====================================================================
 
-    pub fn add_process(&mut self, mut process: Box<Process>) {
+    /// Queue a newly created process. Refuses once `MAX_PROCESSES` are
+    /// live, returning `process` in the `Refused` for the caller to drop
+    /// after releasing this lock; the caller also still owns, and must
+    /// free, its kernel stack and pid (see `sys_fork`).
+    pub fn add_process(&mut self, mut process: Box<Process>) -> Result<(), Refused> {
+        if self.process_count() >= MAX_PROCESSES {
+            crate::warn!(
+                "Scheduler: refusing PID {}: {} processes already live",
+                process.pid.0, MAX_PROCESSES
+            );
+            return Err(Refused { error: SchedError::TooManyProcesses, process });
+        }
         process.effective_priority = process.priority;
-        let pri = (process.effective_priority as usize).min(NUM_PRIORITIES - 1);
-        crate::serial_println!(
+        let pri = Self::queue_index(&process);
+        crate::debug!(
             "Scheduler: Added PID {} (base pri {}, effective {}) to queue[{}]",
             process.pid.0, process.priority, process.effective_priority, pri
         );
-        self.run_queues[pri].push_back(process);
+        self.enqueue(process);
+        Ok(())
+    }
+
+    /// Live processes on this scheduler: running, Ready, Blocked, Stopped
+    /// and unreaped zombies — everything `iter_all` visits.
+    pub fn process_count(&self) -> usize {
+        self.iter_all().count()
     }
 
     // ====================================================================
@@ -332,6 +702,9 @@ impl Scheduler {
                     .flat_map(|q| q.iter())
                     .map(|b| b.as_ref())
             )
+            .chain(
+                self.cfs_tree.values().map(|b| b.as_ref())
+            )
             .chain(
                 self.wait_queue.iter().map(|b| b.as_ref())
             )
@@ -384,7 +757,7 @@ impl Scheduler {
                     // `kill_and_switch_tf` below takes it out of `self.running`.
                     let (dead_pid, parent_pid) = match self.running_mut() {
                         Some(proc) => {
-                            proc.killed_by_signal = Some(sig);
+                            proc.exit_status = super::wait_status::ExitReason::killed_by(sig);
                             let parent = if proc.is_thread { None } else { proc.parent_pid };
                             (proc.pid.0, parent)
                         }
@@ -398,7 +771,7 @@ impl Scheduler {
                     // for why skipping this here specifically caused a
                     // stale poll waiter to leak and later spuriously affect
                     // an unrelated process.
-                    super::syscall::cancel_all_waiters(dead_pid);
+                    super::syscall::cancel_all_waiters(dead_pid, self);
                 }
                 super::signal::SignalOutcome::Stop(sig) => {
                     // Same shape as Terminate above, but parks the process
@@ -416,7 +789,12 @@ impl Scheduler {
                     tf = self.stop_and_switch_tf(tf);
                     self.notify_child_stopped(stopped_pid, parent_pid);
                 }
-                _ => return tf,
+                _ => {
+                    if let Some(proc) = self.running_ref() {
+                        unsafe { super::single_step::arm(proc, tf as *mut TrapFrame); }
+                    }
+                    return tf;
+                }
             }
         }
     }
@@ -431,6 +809,9 @@ impl Scheduler {
                 return Some(proc.as_mut());
             }
         }
+        if let Some(proc) = self.cfs_tree.values_mut().find(|p| p.pid.0 == pid) {
+            return Some(proc.as_mut());
+        }
         self.wait_queue.iter_mut().find(|p| p.pid.0 == pid).map(|p| p.as_mut())
     }
 
@@ -440,7 +821,7 @@ impl Scheduler {
 
     /// Mark the running process as Zombie and move it to the wait queue —
     /// unless it's a thread (`is_thread`), in which case it's reaped
-    /// immediately instead (dropped here and now).
+    /// immediately instead (straight to `pending_reaps`).
     ///
     /// Threads never get an explicit `waitpid()` call collecting them:
     /// mlibc's `pthread_join()` (upstream, shared by every sysdeps port —
@@ -450,12 +831,17 @@ impl Scheduler {
     /// ever remove it from `wait_queue`. So this is the thread-exit
     /// equivalent of an implicit, always-successful `waitpid()`.
     ///
+    /// Same problem for a process with no parent at all (spawned at boot
+    /// by `spawn_user`): no `waitpid()` can name it, so it goes straight
+    /// to `pending_reaps` instead, torn down once off its stack.
+    ///
     /// Returns true if a process was killed, false if nothing was running.
     /// After calling this, the caller must trigger a context switch
     /// (the running slot is now empty).
     pub fn kill_current(&mut self, reason: &str) -> bool {
         if let Some(mut proc) = self.running.take() {
-            crate::serial_println!(
+            self.note_switch_out(&proc, true);
+            crate::info!(
                 "💀 Killed PID {} ({}): {}",
                 proc.pid.0,
                 core::str::from_utf8(&proc.name)
@@ -464,19 +850,30 @@ impl Scheduler {
                 reason,
             );
             if proc.is_thread {
-                crate::serial_println!("  → thread, reaped immediately (no waitpid() will ever collect it)");
+                crate::debug!("  → thread, reaped immediately (no waitpid() will ever collect it)");
                 // Defer the kernel stack's phys_free — see pending_stack_frees'
-                // doc comment for why it can't happen right here.
+                // doc comment for why it can't happen right here. Zeroed so
+                // the `teardown` below doesn't free it a second time.
                 self.pending_stack_frees.push(proc.kernel_stack);
+                proc.kernel_stack = VirtAddr::zero();
+                self.release_pid(proc.pid);
                 // Same deferral for the thread's own mmap'd user stack, if
                 // sys_clone found one — see pending_vma_frees' doc comment.
                 if let Some((start, size_pages)) = proc.owned_stack_vma {
                     self.pending_vma_frees.push((proc.address_space.clone(), start, size_pages));
                 }
-                // `proc` drops here: releases the Process struct itself and its
-                // Arc references to the shared AddressSpace/FileDescriptorTable
-                // (safe immediately — unlike the kernel stack, that's ordinary
-                // kernel-heap memory, not the stack this code is executing on).
+                // Not dropped here: if this thread holds the last reference
+                // to its fd table, dropping it closes pipe ends, and a pipe
+                // end's close wakes poll waiters through this very lock.
+                // `pending_reaps` tears it down once the lock is released.
+                proc.state = ProcessState::Zombie;
+                self.pending_reaps.push(*proc);
+            } else if proc.parent_pid.is_none() {
+                crate::debug!("  → no parent, reaped immediately");
+                proc.state = ProcessState::Zombie;
+                self.release_pid(proc.pid);
+                self.pending_reaps.push(*proc);
+                crate::debug::inc_reaps();
             } else {
                 proc.state = ProcessState::Zombie;
                 self.wait_queue.push_back(proc);
@@ -503,23 +900,23 @@ impl Scheduler {
         self.kill_current(reason);
 
         // Find and schedule next Ready process
-        for priority in (0..NUM_PRIORITIES).rev() {
-            if let Some(mut proc) = self.run_queues[priority].pop_front() {
-                proc.state = ProcessState::Running;
+        if let Some(mut proc) = self.pick_next(true) {
+            proc.state = ProcessState::Running;
+            self.count_switch(proc.pid.0);
 
-                unsafe {
-                    proc.address_space.activate();
-                }
-                super::tss::set_kernel_stack(proc.kernel_stack);
-                unsafe { super::fpu::restore(&proc.fpu_state); }
+            unsafe {
+                proc.address_space.activate();
+            }
+            super::tss::set_kernel_stack(proc.kernel_stack);
+            unsafe { super::fpu::restore(&proc.fpu_state); }
+            unsafe { super::hw_break::load(&proc.hw_breaks); }
 
-                self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+            self.remaining_ticks = Self::quantum_for(proc.effective_priority);
 
-                let tf_ptr = &*proc.trapframe as *const TrapFrame;
-                update_current_fast(&proc);
-                self.running = Some(proc);
-                return tf_ptr;
-            }
+            let tf_ptr = &*proc.trapframe as *const TrapFrame;
+            update_current_fast(&proc);
+            self.running = Some(proc);
+            return tf_ptr;
         }
 
         panic!("No process to switch to after killing user process");
@@ -542,29 +939,30 @@ impl Scheduler {
             unsafe { *proc.trapframe = *tf; }
             proc.fs_base = read_fs_base();
             unsafe { super::fpu::save(&mut proc.fpu_state); }
-            crate::serial_println!(
+            crate::debug!(
                 "⏸ Stopped PID {} ({})",
                 proc.pid.0,
                 core::str::from_utf8(&proc.name).unwrap_or("<?>").trim_end_matches('\0'),
             );
             proc.state = ProcessState::Stopped;
+            self.note_switch_out(&proc, true);
             self.wait_queue.push_back(proc);
         }
         clear_current_fast();
 
-        for priority in (0..NUM_PRIORITIES).rev() {
-            if let Some(mut proc) = self.run_queues[priority].pop_front() {
-                proc.state = ProcessState::Running;
-                unsafe { proc.address_space.activate(); }
-                super::tss::set_kernel_stack(proc.kernel_stack);
-                write_fs_base(proc.fs_base);
-                unsafe { super::fpu::restore(&proc.fpu_state); }
-                self.remaining_ticks = Self::quantum_for(proc.effective_priority);
-                let tf_ptr = &*proc.trapframe as *const TrapFrame;
-                update_current_fast(&proc);
-                self.running = Some(proc);
-                return tf_ptr;
-            }
+        if let Some(mut proc) = self.pick_next(true) {
+            proc.state = ProcessState::Running;
+            self.count_switch(proc.pid.0);
+            unsafe { proc.address_space.activate(); }
+            super::tss::set_kernel_stack(proc.kernel_stack);
+            write_fs_base(proc.fs_base);
+            unsafe { super::fpu::restore(&proc.fpu_state); }
+            unsafe { super::hw_break::load(&proc.hw_breaks); }
+            self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+            let tf_ptr = &*proc.trapframe as *const TrapFrame;
+            update_current_fast(&proc);
+            self.running = Some(proc);
+            return tf_ptr;
         }
 
         panic!("No process to switch to after stopping process");
@@ -589,6 +987,11 @@ impl Scheduler {
                 }
             }
         }
+        for proc in self.cfs_tree.values_mut() {
+            if proc.pgid == pgid {
+                super::signal::queue_signal(proc, sig);
+            }
+        }
         for proc in self.wait_queue.iter_mut() {
             if proc.pgid == pgid {
                 super::signal::queue_signal(proc, sig);
@@ -608,8 +1011,7 @@ impl Scheduler {
             if let Some(mut proc) = self.wait_queue.remove(pos) {
                 proc.state = ProcessState::Ready;
                 proc.stopped_by_signal = None;
-                let pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                self.run_queues[pri].push_back(proc);
+                self.enqueue(proc);
             }
             true
         } else {
@@ -631,24 +1033,25 @@ impl Scheduler {
             proc.fs_base = read_fs_base();
             unsafe { super::fpu::save(&mut proc.fpu_state); }
             proc.state = ProcessState::Blocked;
+            self.note_switch_out(&proc, true);
             self.wait_queue.push_back(proc);
         }
         // No process running on this CPU until we schedule the next one.
         clear_current_fast();
 
-        for priority in (0..NUM_PRIORITIES).rev() {
-            if let Some(mut proc) = self.run_queues[priority].pop_front() {
-                proc.state = ProcessState::Running;
-                unsafe { proc.address_space.activate(); }
-                super::tss::set_kernel_stack(proc.kernel_stack);
-                write_fs_base(proc.fs_base);
-                unsafe { super::fpu::restore(&proc.fpu_state); }
-                self.remaining_ticks = Self::quantum_for(proc.effective_priority);
-                let tf_ptr = &*proc.trapframe as *const TrapFrame;
-                update_current_fast(&proc);
-                self.running = Some(proc);
-                return tf_ptr;
-            }
+        if let Some(mut proc) = self.pick_next(true) {
+            proc.state = ProcessState::Running;
+            self.count_switch(proc.pid.0);
+            unsafe { proc.address_space.activate(); }
+            super::tss::set_kernel_stack(proc.kernel_stack);
+            write_fs_base(proc.fs_base);
+            unsafe { super::fpu::restore(&proc.fpu_state); }
+            unsafe { super::hw_break::load(&proc.hw_breaks); }
+            self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+            let tf_ptr = &*proc.trapframe as *const TrapFrame;
+            update_current_fast(&proc);
+            self.running = Some(proc);
+            return tf_ptr;
         }
 
         panic!("No process to switch to after blocking");
@@ -661,8 +1064,7 @@ impl Scheduler {
         }) {
             if let Some(mut proc) = self.wait_queue.remove(pos) {
                 proc.state = ProcessState::Ready;
-                let pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                self.run_queues[pri].push_back(proc);
+                self.enqueue(proc);
             }
         }
     }
@@ -679,8 +1081,7 @@ impl Scheduler {
             if let Some(mut proc) = self.wait_queue.remove(pos) {
                 proc.trapframe.rax = rax;
                 proc.state = ProcessState::Ready;
-                let pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                self.run_queues[pri].push_back(proc);
+                self.enqueue(proc);
             }
         }
     }
@@ -717,7 +1118,8 @@ impl Scheduler {
         // stance that nothing meaningful ever `waitpid()`s a thread's tid.
         let dead = self.wait_queue.iter()
             .find(|p| p.pid.0 == dead_pid && matches!(p.state, ProcessState::Zombie));
-        let status_word = dead.map(|p| p.wait_status_word()).unwrap_or(0x200);
+        let status_word = dead.map(|p| p.wait_status_word())
+            .unwrap_or(super::wait_status::ExitReason::RUNNING.to_wait_status());
         let dead_pgid = dead.map(|p| p.pgid).unwrap_or(0);
 
         // Only the real parent can be woken — `WaitTarget::AnyChild`/`Pgid`
@@ -737,10 +1139,31 @@ impl Scheduler {
             }
         }
         if let Some(pid) = waker_pid {
+            // The parent has its status now — nothing will ever waitpid()
+            // this zombie again, so collect it here instead of leaving it
+            // in `wait_queue` forever.
+            if let Some(pos) = self.wait_queue.iter()
+                .position(|p| p.pid.0 == dead_pid && matches!(p.state, ProcessState::Zombie))
+            {
+                let zombie = self.wait_queue.remove(pos).unwrap();
+                let child_ticks = zombie.cpu_ticks + zombie.children_cpu_ticks;
+                if let Some(parent) = self.wait_queue.iter_mut().find(|p| p.pid.0 == pid) {
+                    parent.children_cpu_ticks += child_ticks;
+                }
+                self.release_pid(zombie.pid);
+                self.pending_reaps.push(*zombie);
+                crate::debug::inc_reaps();
+            }
             self.wake(pid);
         }
     }
 
+    /// Hand over the zombies `notify_child_death` collected — see
+    /// `pending_reaps`. Caller tears them down after releasing the lock.
+    pub fn take_pending_reaps(&mut self) -> Vec<Process> {
+        core::mem::take(&mut self.pending_reaps)
+    }
+
     /// Called once a child transitions to `ProcessState::Stopped` (SIGSTOP/
     /// SIGTSTP) — queues `SIGCHLD` on the parent (matches real POSIX: a
     /// child stopping is also a `SIGCHLD`-worthy event, not just exiting)
@@ -828,6 +1251,19 @@ impl Scheduler {
     /// should happen (time slice exhausted).
     pub fn tick(&mut self) -> bool {
         self.global_ticks = self.global_ticks.wrapping_add(1);
+        TOTAL_TICKS.fetch_add(1, Ordering::Relaxed);
+
+        // Charge the tick that just elapsed to whoever ran during it. Must
+        // happen here, before the caller acts on our return value — once
+        // `switch_to_next` runs, `running` is already the *next* process.
+        let now = TOTAL_TICKS.load(Ordering::Relaxed);
+        if let Some(proc) = self.running.as_deref_mut() {
+            proc.cpu_ticks += 1;
+            proc.last_ran_tick = now;
+            if CFS {
+                proc.vruntime += Self::vruntime_delta(proc.priority);
+            }
+        }
 
         // Safe w.r.t. *which* stacks these are: reaching a new timer tick
         // means the CPU already executed some process's iretq since any
@@ -849,10 +1285,14 @@ impl Scheduler {
             !unsafe { address_space.try_free_huge_vma(*start, *size_pages) }
         });
 
-        if self.global_ticks % AGING_EPOCH == 0 {
+        if !CFS && self.global_ticks.is_multiple_of(AGING_EPOCH) {
             self.age_processes();
         }
 
+        if self.global_ticks.is_multiple_of(WATCHDOG_INTERVAL_TICKS) {
+            self.watchdog_check(now);
+        }
+
         if self.remaining_ticks > 0 {
             self.remaining_ticks -= 1;
         }
@@ -865,23 +1305,27 @@ impl Scheduler {
     // ====================================================================
 
     /// Boost effective_priority of all Ready processes in run queues
-    /// toward their base_priority.
+    /// toward their base_priority. Idle's queue is skipped entirely —
+    /// idle is never aged (see the module comment's IDLE POLICY).
     fn age_processes(&mut self) {
-        for pri in 0..NUM_PRIORITIES {
+        for pri in IDLE_QUEUE + 1..NUM_PRIORITIES {
             let mut i = 0;
             while i < self.run_queues[pri].len() {
                 let proc = &self.run_queues[pri][i];
 
-                if proc.pid.0 == 0 {
-                    i += 1;
-                    continue;
-                }
-
                 if proc.effective_priority < proc.priority {
-                    let mut proc = self.run_queues[pri].remove(i).unwrap();
+                    let mut proc = self.remove_from_queue(pri, i).unwrap();
                     proc.effective_priority = (proc.effective_priority + 1).min(proc.priority);
-                    let new_pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                    self.run_queues[new_pri].push_back(proc);
+                    // Not `enqueue`: that restarts `ready_since_tick`, and
+                    // a process being aged is exactly the kind that's been
+                    // waiting — the watchdog must keep seeing how long.
+                    // (Aging only runs in run-queue mode, so the CFS branch
+                    // of `enqueue` isn't being skipped.)
+                    let new_pri = Self::queue_index(&proc);
+                    if new_pri != pri {
+                        crate::debug::inc_queue_migrations();
+                    }
+                    self.push_queue(new_pri, proc);
                     // Don't increment i — next element shifted into position i
                 } else {
                     i += 1;
@@ -890,74 +1334,266 @@ impl Scheduler {
         }
     }
 
+    // ====================================================================
+    // Watchdog
+    // ====================================================================
+
+    /// Report anything that looks like a wedged scheduler — a real
+    /// process Ready for `WATCHDOG_STARVE_TICKS` without running, or no
+    /// process running at all (not even idle). Diagnostic only: it never
+    /// changes what runs, the point is a log line naming the PID the
+    /// moment a reconciliation bug starts starving someone, instead of a
+    /// silent hang to bisect later.
+    ///
+    /// Each starvation episode is reported once: only a wait that crossed
+    /// the threshold since the previous check (the `..+ INTERVAL` window)
+    /// prints, so a process that stays starved doesn't flood the log.
+    fn watchdog_check(&self, now: u64) {
+        if self.running.is_none() {
+            crate::warn!(
+                "watchdog: no process running at tick {} ({} Ready, {} waiting)",
+                now, self.ready_count(), self.wait_queue.len()
+            );
+        }
+
+        let window = WATCHDOG_STARVE_TICKS..WATCHDOG_STARVE_TICKS + WATCHDOG_INTERVAL_TICKS as u64;
+        let ready = self.run_queues[IDLE_QUEUE + 1..].iter().flatten()
+            .chain(self.cfs_tree.values());
+        for proc in ready {
+            let waited = now.saturating_sub(proc.ready_since_tick);
+            if window.contains(&waited) {
+                crate::debug::inc_watchdog_warnings();
+                crate::warn!(
+                    "watchdog: PID {} ({}) Ready for {} ticks without running (last ran at tick {}, eff pri {}); running: {:?}",
+                    proc.pid.0,
+                    core::str::from_utf8(&proc.name).unwrap_or("<?>").trim_end_matches('\0'),
+                    waited, proc.last_ran_tick, proc.effective_priority,
+                    self.running.as_ref().map(|p| p.pid.0),
+                );
+            }
+        }
+    }
+
+    /// Ready processes across the run queues and the CFS tree, idle included.
+    fn ready_count(&self) -> usize {
+        self.run_queues.iter().map(|q| q.len()).sum::<usize>() + self.cfs_tree.len()
+    }
+
     // ====================================================================
     // Context switch
     // ====================================================================
 
     /// Save current process, find next Ready, activate, return new TrapFrame.
+    /// The timer's preemption path; a process giving up the CPU on its own
+    /// goes through `yield_current` instead, so the switch is counted as
+    /// voluntary.
     pub fn switch_to_next(&mut self, current_tf: *const TrapFrame) -> *const TrapFrame {
-        // ── 1. Save current process back to its run queue ─────────────
-
-        if let Some(mut proc) = self.running.take() {
-            unsafe {
-                *proc.trapframe = *current_tf;
-            }
-            proc.fs_base = read_fs_base();
-            unsafe { super::fpu::save(&mut proc.fpu_state); }
+        self.reschedule(current_tf, false)
+    }
 
-            match proc.state {
-                ProcessState::Running => {
-                    // Normal preemption — put back in run queue as Ready
-                    proc.state = ProcessState::Ready;
+    /// `switch_to_next` for `sched_yield`: same switch, counted as the
+    /// caller's voluntary one.
+    pub fn yield_current(&mut self, current_tf: *const TrapFrame) -> *const TrapFrame {
+        self.reschedule(current_tf, true)
+    }
 
-                    // Decay effective priority (not idle)
-                    if proc.pid.0 != 0 && proc.effective_priority > MIN_EFFECTIVE_PRIORITY {
-                        proc.effective_priority -= 1;
-                    }
+    fn reschedule(&mut self, current_tf: *const TrapFrame, yielded: bool) -> *const TrapFrame {
+        // ── 1. Save current process back to its run queue ─────────────
 
-                    let pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                    self.run_queues[pri].push_back(proc);
-                }
-                ProcessState::Zombie | ProcessState::Blocked | ProcessState::Stopped => {
-                    // Process was killed, blocked, or stopped (job control)
-                    // during its slice.
-                    self.wait_queue.push_back(proc);
-                }
-                ProcessState::Ready => {
-                    let pri = (proc.effective_priority as usize).min(NUM_PRIORITIES - 1);
-                    self.run_queues[pri].push_back(proc);
-                }
-            }
-        }
+        let had_running = self.park_running(current_tf, yielded);
 
         // ── 2. Find highest effective-priority Ready process ──────────
         //
         // Run queues contain ONLY Ready processes, so no need to skip
         // Blocked/Zombie.  Just pop from front.
 
-        for priority in (0..NUM_PRIORITIES).rev() {
-            if let Some(mut proc) = self.run_queues[priority].pop_front() {
-                proc.state = ProcessState::Running;
+        if let Some(proc) = self.pick_next(true) {
+            return self.activate(proc);
+        }
 
-                unsafe {
-                    proc.address_space.activate();
+        // ── 3. Nothing Ready (shouldn't happen if idle exists) ────────
+        //
+        // With a process just taken out of `running` that's fatal: it was
+        // parked (Blocked/Zombie/Stopped — a Running one would have been
+        // re-queued and picked right back), so `current_tf` is no longer
+        // anyone's to resume. Returning it anyway used to iretq into a
+        // process the scheduler believes isn't running, which then wedges
+        // or corrupts far from here. Before the first process ever
+        // started there's nothing to lose, so that case keeps resuming
+        // whatever the tick interrupted.
+        if had_running {
+            panic!(
+                "scheduler wedged: no Ready process (not even idle) after switching out; {} in wait_queue",
+                self.wait_queue.len()
+            );
+        }
+        current_tf
+    }
+
+    /// Directed yield: switch straight to `target` instead of letting
+    /// `pick_next` choose, so a lock releaser or producer can hand the CPU
+    /// to the process it just woke. The caller goes back to its run queue
+    /// exactly as on a normal preemption (`park_running`), and `target`
+    /// starts a fresh quantum.
+    ///
+    /// Returns `None`, touching nothing, if `target` isn't Ready (or is the
+    /// caller, or idle) — the caller then decides whether a plain
+    /// `switch_to_next` is what it wants.
+    pub fn yield_to(&mut self, target: usize, current_tf: *const TrapFrame) -> Option<*const TrapFrame> {
+        if target == 0 || self.current_pid().map(|p| p.0) == Some(target) {
+            return None;
+        }
+        let proc = self.take_ready(target)?;
+        self.park_running(current_tf, true);
+        Some(self.activate(proc))
+    }
+
+    /// Change `pid`'s priority fields through `f` (`set_priority`,
+    /// `set_pi_floor`, ...) wherever it is — running, queued or waiting — and
+    /// move it to the run queue its new `effective_priority` belongs in if
+    /// it's queued there. In CFS mode the tree is ordered by vruntime, so
+    /// nothing moves. Returns false if there's no such process.
+    pub fn reprioritize(&mut self, pid: usize, f: impl FnOnce(&mut Process)) -> bool {
+        if self.running.as_ref().is_some_and(|p| p.pid.0 == pid) {
+            f(self.running.as_deref_mut().unwrap());
+            return true;
+        }
+        for pri in 0..NUM_PRIORITIES {
+            if let Some(index) = self.run_queues[pri].iter().position(|p| p.pid.0 == pid) {
+                let mut proc = self.remove_from_queue(pri, index).unwrap();
+                f(&mut proc);
+                // Not `enqueue`, for the same reason as `age_processes`.
+                let new_pri = Self::queue_index(&proc);
+                if new_pri != pri {
+                    crate::debug::inc_queue_migrations();
                 }
-                super::tss::set_kernel_stack(proc.kernel_stack);
-                write_fs_base(proc.fs_base);
-                unsafe { super::fpu::restore(&proc.fpu_state); }
-                crate::debug::inc_switches();
+                self.push_queue(new_pri, proc);
+                return true;
+            }
+        }
+        match self.find_process_mut(pid) {
+            Some(proc) => {
+                f(proc);
+                true
+            }
+            None => false,
+        }
+    }
 
-                self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+    /// Remove Ready process `pid` from wherever it's queued (a run queue
+    /// or the CFS tree).
+    fn take_ready(&mut self, pid: usize) -> Option<Box<Process>> {
+        if let Some(&key) = self.cfs_tree.iter().find(|(_, p)| p.pid.0 == pid).map(|(k, _)| k) {
+            return self.cfs_tree.remove(&key);
+        }
+        for pri in 0..NUM_PRIORITIES {
+            if let Some(index) = self.run_queues[pri].iter().position(|p| p.pid.0 == pid) {
+                return self.remove_from_queue(pri, index);
+            }
+        }
+        None
+    }
 
-                let tf_ptr = &*proc.trapframe as *const TrapFrame;
-                update_current_fast(&proc);
-                self.running = Some(proc);
-                return tf_ptr;
+    /// Step 1 of a context switch: save `running`'s registers and put it
+    /// back in a run queue (still Running → Ready, with priority decay) or
+    /// the wait queue (Blocked/Zombie/Stopped). Returns whether there was
+    /// a running process at all. `yielded`: it's giving the CPU up itself
+    /// rather than being preempted, which only matters for `schedstat`.
+    fn park_running(&mut self, current_tf: *const TrapFrame, yielded: bool) -> bool {
+        let Some(mut proc) = self.running.take() else {
+            return false;
+        };
+        self.note_switch_out(&proc, yielded || proc.state != ProcessState::Running);
+        unsafe {
+            *proc.trapframe = *current_tf;
+        }
+        proc.fs_base = read_fs_base();
+        unsafe { super::fpu::save(&mut proc.fpu_state); }
+
+        match proc.state {
+            ProcessState::Running => {
+                // Normal preemption — put back in run queue as Ready
+                proc.state = ProcessState::Ready;
+
+                Self::decay(&mut proc);
+                self.enqueue(proc);
+            }
+            ProcessState::Zombie | ProcessState::Blocked | ProcessState::Stopped => {
+                // Process was killed, blocked, or stopped (job control)
+                // during its slice.
+                self.wait_queue.push_back(proc);
+            }
+            ProcessState::Ready => {
+                self.enqueue(proc);
             }
         }
+        true
+    }
 
-        // ── 3. Nothing Ready (shouldn't happen if idle exists) ────────
-        current_tf
+    /// A preemption's priority decay: one step down — never for idle,
+    /// never in CFS mode (where vruntime does the balancing instead), and
+    /// never below an inherited PI floor.
+    fn decay(proc: &mut Process) {
+        if !CFS && !Self::is_idle(proc)
+            && proc.effective_priority > MIN_EFFECTIVE_PRIORITY.max(proc.pi_floor)
+        {
+            let from = Self::queue_index(proc);
+            proc.effective_priority -= 1;
+            if Self::queue_index(proc) != from {
+                crate::debug::inc_queue_migrations();
+            }
+        }
+    }
+
+    /// Last step of a context switch: make `proc` the running process —
+    /// its address space, kernel stack, TLS base and FPU state — with a
+    /// fresh quantum, and return the TrapFrame to resume it from.
+    fn activate(&mut self, mut proc: Box<Process>) -> *const TrapFrame {
+        proc.state = ProcessState::Running;
+
+        unsafe {
+            proc.address_space.activate();
+        }
+        super::tss::set_kernel_stack(proc.kernel_stack);
+        write_fs_base(proc.fs_base);
+        unsafe { super::fpu::restore(&proc.fpu_state); }
+        unsafe { super::hw_break::load(&proc.hw_breaks); }
+        crate::debug::inc_switches();
+        self.count_switch(proc.pid.0);
+
+        self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+
+        let tf_ptr = &*proc.trapframe as *const TrapFrame;
+        update_current_fast(&proc);
+        self.running = Some(proc);
+        tf_ptr
+    }
+
+    /// Record that `proc` is leaving the CPU, and whether on its own
+    /// (blocked, yielded, stopped, exited) or preempted. Nothing is counted
+    /// yet: if the pick that follows hands the CPU straight back to it,
+    /// no switch happened.
+    fn note_switch_out(&mut self, proc: &Process, voluntary: bool) {
+        self.switched_out = Some((proc.pid.0, voluntary));
+    }
+
+    /// Second half of `note_switch_out`, with the process about to run:
+    /// if it's a different one, count the switch globally and on the
+    /// outgoing process — by now parked in a queue, or already gone if it
+    /// was a thread reaped on exit.
+    fn count_switch(&mut self, next_pid: usize) {
+        let Some((prev, voluntary)) = self.switched_out.take() else { return };
+        if prev == next_pid {
+            return;
+        }
+        if voluntary {
+            crate::debug::inc_voluntary_switches();
+        } else {
+            crate::debug::inc_preemptions();
+        }
+        if let Some(proc) = self.find_process_mut(prev) {
+            if voluntary { proc.nvcsw += 1 } else { proc.nivcsw += 1 }
+        }
     }
 
     // ====================================================================
@@ -965,10 +1601,10 @@ impl Scheduler {
     // ====================================================================
 
     pub fn start_first(&mut self) -> *const TrapFrame {
-        crate::serial_println!("Available processes:");
+        crate::debug!("Available processes:");
         for pri in (0..NUM_PRIORITIES).rev() {
             for proc in self.run_queues[pri].iter() {
-                crate::serial_println!(
+                crate::debug!(
                     "  PID {} (base pri {}, eff {}): {:?} - {:?}",
                     proc.pid.0,
                     proc.priority,
@@ -980,37 +1616,43 @@ impl Scheduler {
                 );
             }
         }
+        for proc in self.cfs_tree.values() {
+            crate::debug!(
+                "  PID {} (pri {}, vruntime {}): {:?} - {:?}",
+                proc.pid.0,
+                proc.priority,
+                proc.vruntime,
+                core::str::from_utf8(&proc.name)
+                    .unwrap_or("<?>")
+                    .trim_end_matches('\0'),
+                proc.privilege,
+            );
+        }
 
-        for priority in (1..NUM_PRIORITIES).rev() {
-            let queue = &mut self.run_queues[priority];
-
-            for i in 0..queue.len() {
-                if queue[i].state == ProcessState::Ready && queue[i].pid.0 != 0 {
-                    let mut proc = queue.remove(i).unwrap();
-                    proc.state = ProcessState::Running;
-
-                    crate::serial_println!(
-                        "\n🚀 Starting first process: PID {} ({})",
-                        proc.pid.0,
-                        core::str::from_utf8(&proc.name)
-                            .unwrap_or("<invalid>")
-                            .trim_end_matches('\0'),
-                    );
-
-                    super::tss::set_kernel_stack(proc.kernel_stack);
-                    unsafe {
-                        proc.address_space.activate();
-                    }
-                    unsafe { super::fpu::restore(&proc.fpu_state); }
+        if let Some(mut proc) = self.pick_next(false) {
+            proc.state = ProcessState::Running;
 
-                    self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+            crate::info!(
+                "\n🚀 Starting first process: PID {} ({})",
+                proc.pid.0,
+                core::str::from_utf8(&proc.name)
+                    .unwrap_or("<invalid>")
+                    .trim_end_matches('\0'),
+            );
 
-                    let tf_ptr = &*proc.trapframe as *const TrapFrame;
-                    update_current_fast(&proc);
-                    self.running = Some(proc);
-                    return tf_ptr;
-                }
+            super::tss::set_kernel_stack(proc.kernel_stack);
+            unsafe {
+                proc.address_space.activate();
             }
+            unsafe { super::fpu::restore(&proc.fpu_state); }
+            unsafe { super::hw_break::load(&proc.hw_breaks); }
+
+            self.remaining_ticks = Self::quantum_for(proc.effective_priority);
+
+            let tf_ptr = &*proc.trapframe as *const TrapFrame;
+            update_current_fast(&proc);
+            self.running = Some(proc);
+            return tf_ptr;
         }
 
         panic!("No process to start!");
@@ -1025,30 +1667,48 @@ pub fn current_pid() -> Option<usize> {
     local_scheduler().current_pid().map(|pid| pid.0)
 }
 
-/// Same as `current_pid()`, but self-contained (`cli`/`sti` around the
-/// lock) — safe to call from anywhere, not just from inside an
-/// already-`cli`'d syscall body like `current_pid()` requires (see the
-/// module doc's "Interrupt safety" note: holding `SCHEDULER` with
-/// interrupts enabled can deadlock against the timer ISR). Used by
-/// `fs::procfs`, which isn't part of the syscall dispatch path and so
-/// has no surrounding `cli` to rely on.
+/// Same as `current_pid()`, but self-contained (an `InterruptGuard`
+/// around the lock, restoring whatever IF the caller had) — safe to call
+/// from anywhere, not just from inside an already-`cli`'d syscall body
+/// like `current_pid()` requires (see the module doc's "Interrupt safety"
+/// note: holding `SCHEDULER` with interrupts enabled can deadlock against
+/// the timer ISR). Used by `fs::procfs`, which isn't part of the syscall
+/// dispatch path and so has no surrounding `cli` to rely on.
 pub fn current_pid_safe() -> Option<usize> {
-    unsafe { core::arch::asm!("cli"); }
+    let _irq = crate::interrupts::InterruptGuard::new();
+    // Bound to a `let`, not the tail expression: a tail temporary would
+    // drop *after* `_irq` (edition 2021), releasing the lock with IF
+    // possibly back on.
+    #[allow(clippy::let_and_return)]
     let pid = local_scheduler().current_pid().map(|p| p.0);
-    unsafe { core::arch::asm!("sti"); }
     pid
 }
 
+/// Tear down the zombies `notify_child_death` has collected on this CPU
+/// (`pending_reaps`). SCHEDULER is held only to take the list; each
+/// `Process::teardown` runs after it's released, with interrupts masked
+/// as in `sys_waitpid`. `sys_waitpid` drains the list itself; this is for
+/// the parents that never call it again — the idle task runs it every
+/// time it wakes, and `sys_exit` before the caller dies. Never call it
+/// from a dying process past `kill_and_switch_tf`: it's still on its own
+/// kernel stack and may be in the list.
+pub fn reap_pending() {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    let reaped = local_scheduler().take_pending_reaps();
+    for mut proc in reaped {
+        proc.teardown();
+    }
+}
+
 /// Look up an arbitrary process's `exe_name` by pid — checked against
 /// `running` plus every run queue and the wait queue (see `iter_all`).
 /// Self-contained `cli`/`sti`, same reasoning as `current_pid_safe`.
 /// Backs `/proc/<pid>/exe`'s `readlink()` (`fs::procfs`).
 pub fn exe_name_for_pid(pid: usize) -> Option<alloc::string::String> {
-    unsafe { core::arch::asm!("cli"); }
+    let _irq = crate::interrupts::InterruptGuard::new();
     let name = local_scheduler().iter_all()
         .find(|p| p.pid.0 == pid)
         .map(|p| p.exe_name.clone());
-    unsafe { core::arch::asm!("sti"); }
     name
 }
 
@@ -1057,9 +1717,8 @@ pub fn exe_name_for_pid(pid: usize) -> Option<alloc::string::String> {
 /// lets `ls /proc` / BusyBox `ps`'s `opendir("/proc")` scan see every
 /// process instead of only the ones looked up by exact name/pid.
 pub fn all_pids() -> alloc::vec::Vec<usize> {
-    unsafe { core::arch::asm!("cli"); }
+    let _irq = crate::interrupts::InterruptGuard::new();
     let pids = local_scheduler().iter_all().map(|p| p.pid.0).collect();
-    unsafe { core::arch::asm!("sti"); }
     pids
 }
 
@@ -1070,25 +1729,66 @@ pub fn all_pids() -> alloc::vec::Vec<usize> {
 /// reference, for the same reason: the process could be reaped the moment
 /// the lock is released.
 pub struct ProcStatSnapshot {
+    pub tgid: usize,
     pub ppid: usize,
     pub pgid: u32,
     pub name: [u8; 16],
     pub state: crate::process::ProcessState,
     pub priority: u8,
+    pub cpu_ticks: u64,
+    pub children_cpu_ticks: u64,
+    pub nvcsw: u64,
+    pub nivcsw: u64,
+    /// Open fds, or `None` if the fd table was locked (a thread sharing
+    /// it is mid-syscall) — not worth spinning for with interrupts off.
+    pub fds: Option<usize>,
+    pub affinity: crate::process::cpuset::CpuSet,
+}
+
+/// The `kdebug schedstat` report: the global switch counters from
+/// `crate::debug`, then one line per live process with its voluntary and
+/// involuntary switch counts.
+pub fn schedstat_report() -> alloc::string::String {
+    use core::fmt::Write;
+
+    let (voluntary, preemptions, migrations) = crate::debug::sched_counters();
+    let mut out = alloc::format!(
+        "scheduler: {}\nvoluntary_switches: {}\npreemptions: {}\nmigrations_between_queues: {}\n  PID PRI NAME             NVCSW  NIVCSW\n",
+        if CFS { "cfs" } else { "run queues" }, voluntary, preemptions, migrations,
+    );
+    let _irq = crate::interrupts::InterruptGuard::new();
+    for p in local_scheduler().iter_all() {
+        let _ = writeln!(
+            out,
+            "{:5} {:3} {:16} {:6} {:7}",
+            p.pid.0,
+            p.effective_priority,
+            core::str::from_utf8(&p.name).unwrap_or("<?>").trim_end_matches('\0'),
+            p.nvcsw,
+            p.nivcsw,
+        );
+    }
+    out
 }
 
 pub fn proc_stat_snapshot(pid: usize) -> Option<ProcStatSnapshot> {
-    unsafe { core::arch::asm!("cli"); }
+    let _irq = crate::interrupts::InterruptGuard::new();
     let snap = local_scheduler().iter_all()
         .find(|p| p.pid.0 == pid)
         .map(|p| ProcStatSnapshot {
+            tgid: p.tgid.0,
             ppid: p.parent_pid.map(|pp| pp.0).unwrap_or(0),
             pgid: p.pgid,
             name: p.name,
             state: p.state,
             priority: p.effective_priority,
+            cpu_ticks: p.cpu_ticks,
+            children_cpu_ticks: p.children_cpu_ticks,
+            nvcsw: p.nvcsw,
+            nivcsw: p.nivcsw,
+            fds: p.files.try_lock().map(|files| files.count()),
+            affinity: p.affinity,
         });
-    unsafe { core::arch::asm!("sti"); }
     snap
 }
 
@@ -27,12 +27,26 @@
 // fixed one-instruction trampoline page (mapped into every user address
 // space by `elf_loader.rs`), and the live TrapFrame is redirected to the
 // handler. `rt_sigreturn` (`syscall.rs`) reverses this exactly.
+//
+// INIT
+//
+// PID 1 (`userspace/src/bin/shell.rs`, the `ash` respawner) is the one
+// thing standing between a crashed shell and a dead console, so — like
+// Linux's SIGNAL_UNKILLABLE — `queue_signal` drops any signal it would
+// take the default terminate/stop action for, SIGKILL and SIGSTOP
+// included. `kill 1` from the shell, or a Ctrl-C that reaches init's
+// process group, is a no-op; a handler init installs itself still runs.
+// CPU faults don't go through signals (see `init::devices`), so a
+// crashing init still dies.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 use super::{Process, TrapFrame};
 use crate::memory::signal_trampoline::TRAMPOLINE_VA;
 
 pub const SIGINT: u32 = 2;
 pub const SIGQUIT: u32 = 3;
+pub const SIGTRAP: u32 = 5;
 pub const SIGKILL: u32 = 9;
 pub const SIGUSR1: u32 = 10;
 pub const SIGSEGV: u32 = 11;
@@ -84,13 +98,29 @@ fn default_terminates(sig: u32) -> bool {
     sig != SIGCHLD && sig != SIGCONT
 }
 
+/// Pid of init, protected by `queue_signal` (see INIT above). 0 (idle's
+/// pid, which no signal ever targets) until `set_init_pid`.
+static INIT_PID: AtomicUsize = AtomicUsize::new(0);
+
+/// Called once at boot with the pid of the `shell` process.
+pub fn set_init_pid(pid: usize) {
+    INIT_PID.store(pid, Ordering::Relaxed);
+}
+
 /// Set `sig`'s pending bit. Pending state is independent of whether the
 /// signal is currently blocked — blocking only defers delivery, matching
-/// POSIX `sigprocmask` semantics.
+/// POSIX `sigprocmask` semantics. Signals init would die or stop from are
+/// dropped instead (see INIT above).
 pub fn queue_signal(proc: &mut Process, sig: u32) {
     if sig == 0 || sig as usize >= NUM_SIGNALS {
         return;
     }
+    if proc.pid.0 == INIT_PID.load(Ordering::Relaxed)
+        && matches!(proc.signal_handlers[sig as usize], SignalAction::Default)
+        && default_terminates(sig)
+    {
+        return;
+    }
     proc.pending_signals |= 1u64 << sig;
 }
 
@@ -0,0 +1,216 @@
+// kernel/src/process/single_step.rs
+//
+// Per-process single-step tracing (`kdebug trace <pid>`) via RFLAGS.TF.
+//
+// HOW:
+//   `Process::single_step_left` is the number of steps still to log; 0 =
+//   not tracing. `arm` sets TF in a traced process's user TrapFrame at
+//   every "about to return to user mode" point (it's called from
+//   `Scheduler::resolve_signals`, which all of them go through), so the
+//   CPU raises #DB (vector 1) after one user instruction. `debug_trap_entry`
+//   saves the full register set like the timer's entry does, and the
+//   handler logs it to serial, spends one step and re-arms TF — or clears
+//   it once the budget is gone. The same vector carries hardware
+//   breakpoint hits (`hw_break`); DR6 tells the two apart.
+//
+//   SFMASK clears TF on `syscall` (see `tss::init_syscall_msrs`), so a
+//   syscall is stepped over as one instruction and the kernel never runs
+//   with TF set; interrupt gates clear it on their own.
+//
+// TURNING IT OFF:
+//   `toggle` only zeroes the budget. A TF already in the process's saved
+//   frame still fires once more; the handler sees a process that isn't
+//   traced, clears TF and resumes it silently. Same for a program that
+//   sets TF itself with `popf` — it just doesn't get a SIGTRAP.
+
+use core::arch::global_asm;
+
+use super::scheduler::Scheduler;
+use super::trapframe::TrapFrame;
+use super::Process;
+
+/// RFLAGS.TF.
+pub const RFLAGS_TF: u64 = 1 << 8;
+
+/// Steps logged when `kdebug trace <pid>` doesn't give a count.
+pub const DEFAULT_TRACE_STEPS: u32 = 256;
+
+/// Cap on one trace's budget — every step is a few serial lines, so a
+/// forgotten trace must run out on its own.
+pub const MAX_TRACE_STEPS: u32 = 4096;
+
+/// DR6.BS — this #DB is a single step.
+const DR6_BS: u64 = 1 << 14;
+
+/// Ring-3 code segment selector (see `Process::new_user`'s trapframe.cs).
+const USER_CS: u64 = 0x23;
+
+/// Start tracing `pid` for `steps` steps (0 = `DEFAULT_TRACE_STEPS`,
+/// clamped to `MAX_TRACE_STEPS`), or stop if it's already being traced.
+/// Returns the new budget — 0 means tracing is now off — or `None` if no
+/// such process exists.
+pub fn toggle(sched: &mut Scheduler, pid: usize, steps: u32) -> Option<u32> {
+    let proc = if sched.running_ref().is_some_and(|p| p.pid.0 == pid) {
+        sched.running_mut()
+    } else {
+        sched.find_process_mut(pid)
+    }?;
+    proc.single_step_left = if proc.single_step_left != 0 {
+        0
+    } else if steps == 0 {
+        DEFAULT_TRACE_STEPS
+    } else {
+        steps.min(MAX_TRACE_STEPS)
+    };
+    Some(proc.single_step_left)
+}
+
+/// Set TF in `tf` if `proc` is being traced and `tf` returns to user mode.
+///
+/// # Safety
+/// `tf` must point at `proc`'s live TrapFrame.
+pub unsafe fn arm(proc: &Process, tf: *mut TrapFrame) {
+    if proc.single_step_left != 0 && unsafe { (*tf).cs } == USER_CS {
+        unsafe { (*tf).rflags |= RFLAGS_TF; }
+    }
+}
+
+global_asm!(
+    ".global debug_trap_entry",
+    "debug_trap_entry:",
+
+    // #DB pushes no error code: same frame shape as the timer's entry.
+    "push rax",
+    "push rbx",
+    "push rcx",
+    "push rdx",
+    "push rsi",
+    "push rdi",
+    "push rbp",
+    "push r8",
+    "push r9",
+    "push r10",
+    "push r11",
+    "push r12",
+    "push r13",
+    "push r14",
+    "push r15",
+
+    "mov rdi, rsp",
+    "call debug_trap_handler",
+
+    // Like the timer: the handler may hand back another process's frame.
+    "mov rsp, rax",
+
+    "pop r15",
+    "pop r14",
+    "pop r13",
+    "pop r12",
+    "pop r11",
+    "pop r10",
+    "pop r9",
+    "pop r8",
+    "pop rbp",
+    "pop rdi",
+    "pop rsi",
+    "pop rdx",
+    "pop rcx",
+    "pop rbx",
+    "pop rax",
+    "iretq",
+);
+
+extern "C" {
+    pub fn debug_trap_entry();
+}
+
+/// #DB: DR6 says why — B0-B3 for hardware breakpoints (`hw_break`), BS
+/// for a single step; both can be set at once. Returns the TrapFrame to
+/// resume, which is another process's if a breakpoint's SIGTRAP killed or
+/// stopped this one.
+#[no_mangle]
+extern "C" fn debug_trap_handler(tf_ptr: *mut TrapFrame) -> *const TrapFrame {
+    let tf = unsafe { &mut *tf_ptr };
+    let dr6 = unsafe { take_dr6() };
+    let hits = dr6 & super::hw_break::DR6_HITS;
+
+    if hits != 0 {
+        super::hw_break::report_hits(tf, hits);
+    }
+
+    if tf.cs != USER_CS {
+        // Nothing in the kernel sets TF; don't step through it either way.
+        tf.rflags &= !RFLAGS_TF;
+        if hits == 0 {
+            crate::warn!("#DB in kernel mode at {:#x} (dr6={:#x}), TF cleared", tf.rip, dr6);
+        }
+        return tf_ptr;
+    }
+
+    if dr6 & DR6_BS != 0 || hits == 0 {
+        step(tf);
+    }
+
+    if hits != 0 {
+        // Act on the SIGTRAP `report_hits` queued now, not at the next
+        // tick — the same return-to-user step the timer ISR takes. Entered
+        // from user mode, so no kernel lock can be held here.
+        let mut sched = super::scheduler::local_scheduler();
+        let next = sched.resolve_signals(tf_ptr);
+        sched.resolve_wait_status();
+        return next;
+    }
+    tf_ptr
+}
+
+/// One single-step #DB from user mode: log the registers and spend a step
+/// of the running process's budget, re-arming TF until it's gone.
+fn step(tf: &mut TrapFrame) {
+    // Entered from user mode, so no kernel lock can be held here.
+    let budget = {
+        let mut sched = super::scheduler::local_scheduler();
+        sched.running_mut().and_then(|proc| {
+            if proc.single_step_left == 0 {
+                return None;
+            }
+            proc.single_step_left -= 1;
+            Some((proc.pid.0, proc.single_step_left))
+        })
+    };
+
+    let Some((pid, left)) = budget else {
+        tf.rflags &= !RFLAGS_TF;
+        return;
+    };
+
+    crate::serial_println!(
+        "[step] pid {} left {} rip={:#x} rsp={:#x} rflags={:#x}",
+        pid, left, tf.rip, tf.rsp, tf.rflags & !RFLAGS_TF,
+    );
+    crate::serial_println!(
+        "  rax={:#x} rbx={:#x} rcx={:#x} rdx={:#x} rsi={:#x} rdi={:#x} rbp={:#x}",
+        tf.rax, tf.rbx, tf.rcx, tf.rdx, tf.rsi, tf.rdi, tf.rbp,
+    );
+    crate::serial_println!(
+        "  r8={:#x} r9={:#x} r10={:#x} r11={:#x} r12={:#x} r13={:#x} r14={:#x} r15={:#x}",
+        tf.r8, tf.r9, tf.r10, tf.r11, tf.r12, tf.r13, tf.r14, tf.r15,
+    );
+
+    if left == 0 {
+        tf.rflags &= !RFLAGS_TF;
+        crate::serial_println!("[step] pid {}: step budget used up, single-step off", pid);
+    } else {
+        tf.rflags |= RFLAGS_TF;
+    }
+}
+
+/// Read DR6 and reset it — its status bits are sticky, so a stale BS from
+/// this step would still be set on the next #DB.
+unsafe fn take_dr6() -> u64 {
+    let dr6: u64;
+    unsafe {
+        core::arch::asm!("mov {}, dr6", out(reg) dr6, options(nomem, nostack));
+        core::arch::asm!("mov dr6, {}", in(reg) 0xFFFF_0FF0u64, options(nomem, nostack));
+    }
+    dr6
+}
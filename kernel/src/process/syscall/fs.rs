@@ -24,7 +24,8 @@ static STDIN_WAITER: Mutex<Option<StdinWaiter>> = Mutex::new(None);
 // SYSCALL IMPLEMENTATIONS
 // ============================================================================
 
-/// True iff fd 0 is still bound to the real console device.
+/// True iff fd 0 is still bound to the real console device (`/dev/tty`,
+/// or `/dev/console` opened by hand).
 ///
 /// `sys_read`'s fd==0 fast path bypasses the file table entirely and reads
 /// straight from the keyboard ISR buffer, blocking the caller until a key
@@ -41,7 +42,7 @@ fn stdin_is_console() -> bool {
     let guard = crate::process::irq_guard::SchedGuard::lock();
     match guard.running_ref() {
         Some(proc) => proc.files.lock().get(0)
-            .map(|h| h.name() == "serial")
+            .map(|h| matches!(h.name(), "tty" | "serial"))
             .unwrap_or(false),
         None => false,
     }
@@ -67,7 +68,7 @@ pub(super) fn sys_read(fd: i32, buf: usize, count: usize) -> SyscallResult {
         // dropped: `block_stdin_read` diverges (never returns to this stack
         // frame), so its `Drop`/`sti` glue simply never runs — interrupts
         // stay off across the jump, same as before this was RAII.
-        let irq = crate::process::irq_guard::InterruptGuard::new();
+        let irq = crate::interrupts::InterruptGuard::new();
 
         if let Some(c) = crate::keyboard::read_key() {
             drop(irq);
@@ -93,7 +94,7 @@ pub(super) fn sys_read(fd: i32, buf: usize, count: usize) -> SyscallResult {
         // doc comment for the same class of hazard). `irq`'s Drop handles
         // every early-return path below automatically; on the WouldBlock
         // path it's deliberately left undropped, same reasoning as above.
-        let _irq = crate::process::irq_guard::InterruptGuard::new();
+        let _irq = crate::interrupts::InterruptGuard::new();
 
         let files = {
             let scheduler = crate::process::scheduler::local_scheduler();
@@ -112,7 +113,7 @@ pub(super) fn sys_read(fd: i32, buf: usize, count: usize) -> SyscallResult {
                     };
                     file.read(buffer)
                 }
-                Err(_) => return errno::EBADF,
+                Err(e) => return e.into(),
             }
         };
 
@@ -126,7 +127,7 @@ pub(super) fn sys_read(fd: i32, buf: usize, count: usize) -> SyscallResult {
                 };
                 unsafe { crate::process::trapframe::jump_to_user(next_tf) }
             }
-            Err(_) => errno::EIO,
+            Err(e) => e.into(),
         }
     }
 }
@@ -211,7 +212,7 @@ pub(super) fn sys_write(fd: i32, buf: usize, count: usize) -> SyscallResult {
     }
     crate::ktrace!(crate::debug::FS, "sys_write: fd={} count={}", fd, count);
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
 
     let files = {
         let scheduler = crate::process::scheduler::local_scheduler();
@@ -230,14 +231,12 @@ pub(super) fn sys_write(fd: i32, buf: usize, count: usize) -> SyscallResult {
                 };
                 file.write(buffer)
             }
-            Err(_) => return errno::EBADF,
+            Err(e) => return e.into(),
         }
     };
 
     match result {
         Ok(n) => n as i64,
-        Err(crate::process::file::FileError::BrokenPipe) => errno::EPIPE,
-        Err(crate::process::file::FileError::NoSpace) => errno::ENOSPC,
         Err(crate::process::file::FileError::WouldBlock) => {
             let tf_ptr = current_tf_ptr();
             let next_tf = {
@@ -246,7 +245,7 @@ pub(super) fn sys_write(fd: i32, buf: usize, count: usize) -> SyscallResult {
             };
             unsafe { crate::process::trapframe::jump_to_user(next_tf) }
         }
-        Err(_) => errno::EIO,
+        Err(e) => e.into(),
     }
 }
 
@@ -269,10 +268,15 @@ pub(super) fn sys_open(path_ptr: usize, flags: i32) -> SyscallResult {
     };
 
     // Only take scheduler lock for the FD table insertion
+    let cloexec = crate::fs::types::OpenFlags(flags).is_cloexec();
     with_current_process(|proc| {
-        match proc.files.lock().allocate(handle) {
-            Ok(fd) => fd as i64,
-            Err(_) => errno::EINVAL,
+        let mut files = proc.files.lock();
+        match files.allocate(handle) {
+            Ok(fd) => {
+                let _ = files.set_cloexec(fd, cloexec);
+                fd as i64
+            }
+            Err(e) => e.into(),
         }
     })
 }
@@ -477,11 +481,23 @@ pub(super) fn sys_rename(old_path_ptr: usize, new_path_ptr: usize) -> SyscallRes
 /// returns the number of bytes written to `buffer` (including the NUL) on
 /// success, or a negative errno. `ERANGE` if `size` is too small to hold
 /// the current path + NUL.
+///
+/// `ENOENT` if the cwd no longer resolves to a directory — it was
+/// `rmdir`'d (or an ancestor renamed) out from under the process. Linux
+/// reports the same `ENOENT` for a deleted cwd. The difference is that
+/// `Process::cwd` is a path string, not an inode reference: after a rename
+/// Linux would report the directory's new path, while here the old string
+/// just stops resolving. Without this check `getcwd` would hand back a
+/// path that `open(".")` can't reach.
 pub(super) fn sys_getcwd(buf_ptr: usize, size: usize) -> SyscallResult {
     if size == 0 { return errno::EINVAL; }
     if let Err(e) = validate_user_buffer(buf_ptr as u64, size) { return e; }
 
     let cwd = current_cwd();
+    match crate::fs::vfs::resolve(&cwd) {
+        Ok(inode) if inode.file_type() == crate::fs::types::FileType::Directory => {}
+        _ => return errno::ENOENT,
+    }
     let needed = cwd.len() + 1; // + NUL
     if needed > size {
         return errno::ERANGE;
@@ -537,7 +553,7 @@ pub(super) fn sys_getdents64(fd: i32, buf_ptr: usize, count: usize) -> SyscallRe
     if let Err(e) = validate_user_buffer(buf_ptr as u64, count) { return e; }
     crate::ktrace!(crate::debug::FS, "sys_getdents64: fd={} count={}", fd, count);
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     let files = {
         let scheduler = crate::process::scheduler::local_scheduler();
         match scheduler.running_ref() {
@@ -548,7 +564,7 @@ pub(super) fn sys_getdents64(fd: i32, buf_ptr: usize, count: usize) -> SyscallRe
 
     let mut files_guard = files.lock();
     match files_guard.get_mut(fd as usize) {
-        Err(_) => errno::EBADF,
+        Err(e) => e.into(),
         Ok(f)  => {
             let buf = unsafe {
                 core::slice::from_raw_parts_mut(buf_ptr as *mut u8, count)
@@ -568,7 +584,7 @@ pub(super) fn sys_getdents64(fd: i32, buf_ptr: usize, count: usize) -> SyscallRe
 /// locks aren't reentrant). Instead: clone the `Arc<Mutex<FileDescriptorTable>>`
 /// under a short-lived [`irq_guard::SchedGuard`], let it drop (unlock, then
 /// re-enable interrupts) at the block's closing brace, then close outside
-/// any scheduler lock under a fresh [`irq_guard::InterruptGuard`] — same
+/// any scheduler lock under a fresh [`crate::interrupts::InterruptGuard`] — same
 /// shape sys_fork/sys_exec use for lock-crossing work.
 ///
 /// HISTORY: this used to hand-pair `asm!("cli")`/`asm!("sti")`, and the
@@ -600,11 +616,11 @@ pub(super) fn sys_close(fd: i32) -> SyscallResult {
     // snapshot as a live user context (e.g. for signal delivery, which
     // needs a genuine user rsp/rip) corrupts whatever that kernel rsp
     // actually pointed at.
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     let result = files.lock().close(fd as usize);
     match result {
         Ok(_) => 0,
-        Err(_) => errno::EBADF,
+        Err(e) => e.into(),
     }
 }
 
@@ -618,7 +634,7 @@ pub(super) fn sys_dup(fd: i32) -> SyscallResult {
     with_current_process(|proc| {
         match proc.files.lock().dup(fd as usize, 0) {
             Ok(newfd) => newfd as SyscallResult,
-            Err(_) => errno::EBADF,
+            Err(e) => e.into(),
         }
     })
 }
@@ -641,11 +657,11 @@ pub(super) fn sys_dup2(oldfd: i32, newfd: i32) -> SyscallResult {
         None => return errno::ESRCH,
     };
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     let result = files.lock().dup2(oldfd as usize, newfd as usize);
     match result {
         Ok(nf) => nf as SyscallResult,
-        Err(_) => errno::EBADF,
+        Err(e) => e.into(),
     }
 }
 
@@ -657,32 +673,55 @@ const F_GETFL: i32 = 3;
 const F_SETFL: i32 = 4;
 const F_DUPFD_CLOEXEC: i32 = 1030;
 
+/// fd flag bit for F_GETFD/F_SETFD — the only one there is.
+const FD_CLOEXEC: u64 = 1;
+
 /// fcntl(72): long fcntl(int fd, int cmd, unsigned long arg)
 ///
-/// Only F_DUPFD/F_DUPFD_CLOEXEC actually do something, and they do the
-/// same thing: this kernel has no per-fd close-on-exec flag anywhere, so
-/// there's nothing for the CLOEXEC half to set differently. F_GETFD/
-/// F_SETFD/F_GETFL/F_SETFL are stubbed — `FileDescriptorTable` has no
-/// per-fd flags storage to back real answers with, so the getters always
-/// report 0 and the setters silently accept anything (after checking `fd`
-/// is actually open). Good enough for callers that only care whether the
-/// call succeeded, not a real flags implementation.
+/// F_DUPFD/F_DUPFD_CLOEXEC dup onto the first free fd `>= arg`, the
+/// latter with FD_CLOEXEC set on the new fd. F_GETFD/F_SETFD read and
+/// write that per-fd bit (see `FileDescriptorTable::close_on_exec`).
+/// F_GETFL/F_SETFL are stubbed — open-file status flags aren't stored
+/// anywhere, so the getter reports 0 and the setter silently accepts
+/// anything (after checking `fd` is actually open).
 pub(super) fn sys_fcntl(fd: i32, cmd: i32, arg: u64) -> SyscallResult {
     if fd < 0 { return errno::EBADF; }
     match cmd {
         F_DUPFD | F_DUPFD_CLOEXEC => {
             with_current_process(|proc| {
-                match proc.files.lock().dup(fd as usize, arg as usize) {
-                    Ok(newfd) => newfd as SyscallResult,
-                    Err(_) => errno::EBADF,
+                let mut files = proc.files.lock();
+                match files.dup(fd as usize, arg as usize) {
+                    Ok(newfd) => {
+                        if cmd == F_DUPFD_CLOEXEC {
+                            let _ = files.set_cloexec(newfd, true);
+                        }
+                        newfd as SyscallResult
+                    }
+                    Err(e) => e.into(),
                 }
             })
         }
-        F_GETFD | F_SETFD | F_GETFL | F_SETFL => {
+        F_GETFD => {
+            with_current_process(|proc| {
+                match proc.files.lock().cloexec(fd as usize) {
+                    Ok(on) => if on { FD_CLOEXEC as SyscallResult } else { 0 },
+                    Err(e) => e.into(),
+                }
+            })
+        }
+        F_SETFD => {
+            with_current_process(|proc| {
+                match proc.files.lock().set_cloexec(fd as usize, arg & FD_CLOEXEC != 0) {
+                    Ok(()) => 0,
+                    Err(e) => e.into(),
+                }
+            })
+        }
+        F_GETFL | F_SETFL => {
             with_current_process(|proc| {
                 match proc.files.lock().get(fd as usize) {
                     Ok(_)  => 0,
-                    Err(_) => errno::EBADF,
+                    Err(e) => e.into(),
                 }
             })
         }
@@ -694,34 +733,30 @@ pub(super) fn sys_fcntl(fd: i32, cmd: i32, arg: u64) -> SyscallResult {
 ///
 /// pipefd[0] = read end, pipefd[1] = write end (matches Linux). Both fds
 /// start with one open reference; `fork()` duplicates them (see
-/// `FileHandle::dup` / `FileDescriptorTable::clone`), `clone()` (threads)
+/// `FileHandle::dup` / `FileDescriptorTable::fork`), `clone()` (threads)
 /// shares them automatically via the shared fd table.
 pub(super) fn sys_pipe(pipefd_ptr: u64) -> SyscallResult {
     if let Err(e) = validate_user_buffer(pipefd_ptr, 8) {
         return e;
     }
 
-    let (read_end, write_end) = crate::process::pipe::create();
+    // Both ends stay owned out here unless both fds get installed: a pipe
+    // end's Drop ends in `poll_wakeup_ready`, which locks SCHEDULER, so it
+    // must never run inside `with_current_process`. Checking for two free
+    // slots up front means the allocations below can't fail halfway.
+    let mut ends = Some(crate::process::pipe::create());
 
     with_current_process(|proc| {
         let mut files = proc.files.lock();
-        let rfd = match files.allocate(alloc::boxed::Box::new(read_end)) {
-            Ok(fd) => fd,
-            Err(_) => return errno::EINVAL,
-        };
-        let wfd = match files.allocate(alloc::boxed::Box::new(write_end)) {
-            Ok(fd) => fd,
-            Err(_) => {
-                // Rolling back by dropping the read end here (while SCHEDULER
-                // is held via with_current_process) is safe ONLY because this
-                // pipe was just created in this same call and has never been
-                // exposed to another process — its write_waiter is always
-                // None, so PipeReadEnd::drop() cannot reach the wake path
-                // that would need to re-lock SCHEDULER. Don't reuse this
-                // pattern for closing an fd a process has actually had open.
-                let _ = files.close(rfd);
-                return errno::EINVAL;
-            }
+        if files.count() + 2 > crate::process::file::MAX_FILES {
+            return errno::EMFILE;
+        }
+        let Some((read_end, write_end)) = ends.take() else { return errno::EINVAL; };
+        let (Ok(rfd), Ok(wfd)) = (
+            files.allocate(alloc::boxed::Box::new(read_end)),
+            files.allocate(alloc::boxed::Box::new(write_end)),
+        ) else {
+            unreachable!("sys_pipe: two free fd slots were just checked");
         };
         drop(files);
 
@@ -737,14 +772,19 @@ pub(super) fn sys_pipe(pipefd_ptr: u64) -> SyscallResult {
 /// mmap(9): void *mmap(void *addr, size_t length, int prot, int flags, int fd, off_t offset)
 ///
 /// Only MAP_ANONYMOUS (0x20) is supported.  fd must be -1.
+/// MAP_SHARED (0x01) makes the pages shared with children across `fork()`
+/// instead of copy-on-write (see `VmaKind::Shared`); anything else is
+/// private.
 /// Returns the mapped virtual address on success, or ENOMEM / EINVAL.
 pub(super) fn sys_mmap(addr: u64, length: u64, prot: u32, flags: u32, fd: i32) -> SyscallResult {
+    const MAP_SHARED: u32 = 0x01;
     const MAP_ANONYMOUS: u32 = 0x20;
     if flags & MAP_ANONYMOUS == 0 || fd != -1 {
         return errno::EINVAL;
     }
+    let shared = flags & MAP_SHARED != 0;
     with_current_process(|proc| {
-        match proc.address_space.sys_mmap_anon(addr, length, prot) {
+        match proc.address_space.sys_mmap_anon(addr, length, prot, shared) {
             Ok(vaddr) => vaddr as i64,
             Err(_)    => errno::ENOMEM,
         }
@@ -764,6 +804,38 @@ pub(super) fn sys_munmap(addr: u64, length: u64) -> SyscallResult {
     })
 }
 
+// ── madvise(28) ────────────────────────────────────────────────────────────
+
+const MADV_NORMAL: i32 = 0;
+const MADV_RANDOM: i32 = 1;
+const MADV_SEQUENTIAL: i32 = 2;
+const MADV_WILLNEED: i32 = 3;
+
+/// madvise(28): int madvise(void *addr, size_t length, int advice)
+///
+/// Only `MADV_WILLNEED` does anything: it maps the range's pages up front
+/// via `AddressSpace::prefault`, so a program about to sweep a fresh
+/// `mmap` takes no demand-paging faults for it. The access-pattern hints
+/// are accepted and ignored (there's no readahead to tune); anything else
+/// — notably `MADV_DONTNEED`, whose zero-fill-on-next-touch semantics
+/// callers rely on for correctness — is `EINVAL` rather than a silent
+/// no-op. `ENOMEM` if part of the range isn't mapped at all, like Linux.
+pub(super) fn sys_madvise(addr: u64, length: u64, advice: i32) -> SyscallResult {
+    if addr & 0xFFF != 0 {
+        return errno::EINVAL;
+    }
+    match advice {
+        MADV_NORMAL | MADV_RANDOM | MADV_SEQUENTIAL => 0,
+        MADV_WILLNEED => with_current_process(|proc| {
+            match unsafe { proc.address_space.prefault(addr, length) } {
+                Ok(_) => 0,
+                Err(_) => errno::ENOMEM,
+            }
+        }),
+        _ => errno::EINVAL,
+    }
+}
+
 // ── lseek(8) ───────────────────────────────────────────────────────────────
 
 /// lseek(8): off_t lseek(int fd, off_t offset, int whence)
@@ -788,9 +860,9 @@ pub(super) fn sys_lseek(fd: i32, offset: i64, whence: i32) -> SyscallResult {
             Ok(file) => match file.seek(offset, whence) {
                 Ok(pos) => pos,
                 Err(crate::process::file::FileError::NotSupported) => errno::ESPIPE,
-                Err(_) => errno::EINVAL,
+                Err(e) => e.into(),
             },
-            Err(_) => errno::EBADF,
+            Err(e) => e.into(),
         }
     })
 }
@@ -814,7 +886,9 @@ pub(super) fn sys_brk(_addr: u64) -> SyscallResult {
 /// hooks (which this port implements as thin TCGETS/TCSETS* wrappers, same
 /// as real glibc does — see `mlibc-port/.../generic.cpp::sys_tcgetattr`),
 /// `tcgetpgrp`/`tcsetpgrp` (TIOCGPGRP/TIOCSPGRP — mlibc calls `ioctl()`
-/// directly for these, not a sysdeps hook), and terminal-size queries.
+/// directly for these, not a sysdeps hook), terminal-size queries, and
+/// the Linux console's KIOCSOUND/KDMKTONE speaker requests (see
+/// `crate::pcspeaker`).
 /// A blit request's fixed-size argument struct, written by userspace into
 /// the buffer `FBIO_BLIT`'s `argp` points at: a pointer to its own
 /// `0x00RRGGBB`-packed pixel buffer plus that buffer's dimensions. Matches
@@ -834,16 +908,24 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
     const TIOCGWINSZ: u64 = 0x5413;
     const TIOCGPGRP: u64 = 0x540F;
     const TIOCSPGRP: u64 = 0x5410;
+    // Linux console (`linux/kd.h`) speaker control. Both take a PIT
+    // channel-2 count, not a frequency (`1193182 / hz`, 0 = silence);
+    // KDMKTONE packs a duration in ms into the high 16 bits.
+    const KIOCSOUND: u64 = 0x4B2F;
+    const KDMKTONE: u64 = 0x4B30;
     // Custom, this-kernel-only request code (not a real Linux fbdev ioctl —
     // real fbdev exposes the framebuffer via mmap; we don't support
     // device-backed mmap, so a raw-pixel client instead hands us its own
     // offscreen buffer once per frame and we blit it in).
     const FBIO_BLIT: u64 = 0x4642_0001;
+    // Also custom: switch the text console's font. `argp` is the font's
+    // index in `crate::font::FONTS` (0 = 8x8, 1 = 8x16), passed by value.
+    const FBIO_SETFONT: u64 = 0x4642_0002;
 
     if fd < 0 { return errno::EBADF; }
 
     #[derive(Clone, Copy, PartialEq)]
-    enum FdKind { Serial, Fb, Other }
+    enum FdKind { Serial, Fb, Tty, Other }
 
     // Classify the driver backing `fd`, under the same cli/SCHEDULER-lock/
     // sti dance every other fd-identity check in this function uses (never
@@ -856,21 +938,26 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
             proc.files.lock().get(fd as usize).ok().map(|f| match f.name() {
                 "serial" => FdKind::Serial,
                 "fb" => FdKind::Fb,
+                "tty" => FdKind::Tty,
                 _ => FdKind::Other,
             })
         })
     };
 
     // A handle counts as a tty if it's actually backed by the console
-    // driver (serial or framebuffer) — checked by the handle's identity,
-    // not by fd number. A fixed "fd <= 2" check breaks the moment a tty fd
-    // gets dup'd to something higher, which is exactly what real job
-    // control setup does: ash's `setjobctl()` (shell/ash.c) opens/falls
-    // back to the console, then `fcntl(fd, F_DUPFD_CLOEXEC, 10)`s it to a
-    // fd >= 10 before calling `tcgetpgrp()` on *that* fd — confirmed live,
-    // this was silently sending ash down its "can't access tty, job
-    // control turned off" fallback path.
-    let is_tty = matches!(fd_kind, Some(FdKind::Serial) | Some(FdKind::Fb));
+    // driver (serial, framebuffer, or `/dev/tty` over both) — checked by
+    // the handle's identity, not by fd number. A fixed "fd <= 2" check
+    // breaks the moment a tty fd gets dup'd to something higher, which is
+    // exactly what real job control setup does: ash's `setjobctl()`
+    // (shell/ash.c) opens/falls back to the console, then
+    // `fcntl(fd, F_DUPFD_CLOEXEC, 10)`s it to a fd >= 10 before calling
+    // `tcgetpgrp()` on *that* fd — confirmed live, this was silently
+    // sending ash down its "can't access tty, job control turned off"
+    // fallback path.
+    let is_tty = matches!(fd_kind, Some(FdKind::Serial | FdKind::Fb | FdKind::Tty));
+    // `/dev/tty` draws on the framebuffer too, and is what fd 1 of every
+    // process is — the `FBIO_*` requests below accept it like `/dev/fb`.
+    let is_fb = matches!(fd_kind, Some(FdKind::Fb | FdKind::Tty));
 
     match request {
         TCGETS => {
@@ -893,7 +980,7 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
             // to drain and no queued-but-unread input beyond
             // `keyboard_buffer::KEYBOARD_BUFFER` worth discarding.
             let t = unsafe { core::ptr::read(argp as *const crate::tty::Termios) };
-            *crate::tty::TERMIOS.lock() = t;
+            crate::tty::set_termios(t);
             0
         }
         TIOCGWINSZ => {
@@ -930,8 +1017,25 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
             crate::tty::FOREGROUND_PGID.store(pgid as u32, core::sync::atomic::Ordering::Relaxed);
             0
         }
+        KIOCSOUND | KDMKTONE => {
+            if !is_tty { return errno::ENOTTY; }
+            let count = (argp & 0xFFFF) as u32;
+            let hz = hal::pit::PIT_FREQUENCY_HZ.checked_div(count).unwrap_or(0);
+            // As on Linux, KDMKTONE returns at once and a timer stops the
+            // tone (`pcspeaker::beep`); a zero count or duration is "stop".
+            let ms = (argp >> 16) & 0xFFFF;
+            let res = if request == KDMKTONE && hz != 0 && ms != 0 {
+                crate::pcspeaker::beep(hz, ms)
+            } else {
+                crate::pcspeaker::set_tone(if request == KDMKTONE { 0 } else { hz })
+            };
+            match res {
+                Ok(()) => 0,
+                Err(_) => errno::EINVAL,
+            }
+        }
         FBIO_BLIT => {
-            if fd_kind != Some(FdKind::Fb) { return errno::ENOTTY; }
+            if !is_fb { return errno::ENOTTY; }
             const SZ: usize = core::mem::size_of::<FbBlitArgs>();
             if let Err(e) = validate_user_buffer(argp, SZ) { return e; }
             let args = unsafe { core::ptr::read(argp as *const FbBlitArgs) };
@@ -944,6 +1048,7 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
             let src = unsafe { core::slice::from_raw_parts(args.ptr as *const u32, w * h) };
             if let Some(fb) = crate::framebuffer::FRAMEBUFFER.lock().as_mut() {
                 fb.blit_scaled(src, w, h);
+                fb.present_dirty();
             }
             // Bypasses the text console's cursor/char tracking entirely —
             // flag it so the next text write (e.g. the shell prompt after
@@ -952,18 +1057,111 @@ pub(super) fn sys_ioctl(fd: i32, request: u64, argp: u64) -> SyscallResult {
             crate::drivers::framebuffer_console::mark_raw_dirty();
             0
         }
+        FBIO_SETFONT => {
+            if !is_fb { return errno::ENOTTY; }
+            match crate::drivers::framebuffer_console::set_font(argp as usize) {
+                Ok(()) => 0,
+                Err(()) => errno::EINVAL,
+            }
+        }
         _ => errno::EINVAL,
     }
 }
 
-// ── writev(20) ─────────────────────────────────────────────────────────────
+// ── pread64(17) / pwrite64(18) ─────────────────────────────────────────────
 
-/// writev(20): ssize_t writev(int fd, const struct iovec *iov, int iovcnt)
+/// The running process's fd table, cloned out so the I/O itself runs
+/// without SCHEDULER held — a disk-backed `read_at`/`write_at` can take a
+/// while, same reason `sys_read`/`sys_write` don't use
+/// `with_current_process`.
+fn current_files() -> Option<alloc::sync::Arc<spin::Mutex<crate::process::file::FileDescriptorTable>>> {
+    let scheduler = crate::process::scheduler::local_scheduler();
+    scheduler.running_ref().map(|proc| proc.files.clone())
+}
+
+/// pread64(17): ssize_t pread(int fd, void *buf, size_t count, off_t offset)
 ///
-/// Loops over the iovec array and calls sys_write for each segment.
-/// struct iovec = { void *iov_base (8 bytes), size_t iov_len (8 bytes) }
-pub(super) fn sys_writev(fd: i32, iov_ptr: u64, iovcnt: usize) -> SyscallResult {
-    if iovcnt > 1024 { return errno::EINVAL; }
+/// `FileHandle::read_at` — the fd's own position is left where it was.
+/// Never blocks: the only handles that could (pipes) can't seek, so they
+/// fail with ESPIPE before reading anything. Past EOF reads 0, like any
+/// short read.
+pub(super) fn sys_pread(fd: i32, buf: usize, count: usize, offset: i64) -> SyscallResult {
+    if fd < 0 { return errno::EBADF; }
+    if offset < 0 { return errno::EINVAL; }
+    if count == 0 { return 0; }
+    if let Err(e) = validate_user_buffer(buf as u64, count) { return e; }
+    let _irq = crate::interrupts::InterruptGuard::new();
+    let files = match current_files() {
+        Some(f) => f,
+        None => return errno::ESRCH,
+    };
+    let mut files_guard = files.lock();
+    match files_guard.get_mut(fd as usize) {
+        Ok(file) => {
+            let buffer = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, count) };
+            match file.read_at(buffer, offset as u64) {
+                Ok(n) => n as SyscallResult,
+                Err(crate::process::file::FileError::NotSupported) => errno::ESPIPE,
+                Err(e) => e.into(),
+            }
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// pwrite64(18): ssize_t pwrite(int fd, const void *buf, size_t count, off_t offset)
+///
+/// `sys_pread`'s counterpart via `FileHandle::write_at`.
+pub(super) fn sys_pwrite(fd: i32, buf: usize, count: usize, offset: i64) -> SyscallResult {
+    if fd < 0 { return errno::EBADF; }
+    if offset < 0 { return errno::EINVAL; }
+    if count == 0 { return 0; }
+    if let Err(e) = validate_user_buffer(buf as u64, count) { return e; }
+    let _irq = crate::interrupts::InterruptGuard::new();
+    let files = match current_files() {
+        Some(f) => f,
+        None => return errno::ESRCH,
+    };
+    let mut files_guard = files.lock();
+    match files_guard.get_mut(fd as usize) {
+        Ok(file) => {
+            let buffer = unsafe { core::slice::from_raw_parts(buf as *const u8, count) };
+            match file.write_at(buffer, offset as u64) {
+                Ok(n) => n as SyscallResult,
+                Err(crate::process::file::FileError::NotSupported) => errno::ESPIPE,
+                Err(e) => e.into(),
+            }
+        }
+        Err(e) => e.into(),
+    }
+}
+
+// ── readv(19) / writev(20) ─────────────────────────────────────────────────
+
+/// IOV_MAX — same limit Linux has.
+const IOV_MAX: usize = 1024;
+
+/// Shared loop behind readv/writev: hands each `struct iovec { void
+/// *iov_base; size_t iov_len; }` segment to `io` (`sys_read`/`sys_write`,
+/// which validate the segment's own buffer) and sums the results.
+///
+/// Stops at the first short transfer — the next segment would only block
+/// or find nothing — and, once some bytes have moved, reports those
+/// rather than a later segment's error, like Linux. Only the first
+/// segment may block: a blocked syscall is finished by its waker, which
+/// reports that one transfer alone, so a later segment that blocked would
+/// lose the count of everything already moved (readv: bytes gone from the
+/// fd but never reported; writev: bytes the caller would send again).
+/// Each later segment first asks the handle (`would_block`) and, if it
+/// isn't ready, the call returns the total so far instead.
+fn iov_loop(
+    fd: i32,
+    iov_ptr: u64,
+    iovcnt: usize,
+    io: fn(i32, usize, usize) -> SyscallResult,
+    writing: bool,
+) -> SyscallResult {
+    if iovcnt > IOV_MAX { return errno::EINVAL; }
     if validate_user_buffer(iov_ptr, iovcnt * 16).is_err() {
         return errno::EFAULT;
     }
@@ -973,13 +1171,51 @@ pub(super) fn sys_writev(fd: i32, iov_ptr: u64, iovcnt: usize) -> SyscallResult
         let entry = (iov_ptr + i as u64 * 16) as *const u64;
         let (base, len) = unsafe { (*entry, *entry.add(1)) };
         if len == 0 { continue; }
-        let n = sys_write(fd, base as usize, len as usize);
-        if n < 0 { return n; }
+        // Held from the readiness check through `io`, so nothing can drain
+        // (or fill) the fd in between.
+        let _irq = crate::interrupts::InterruptGuard::new();
+        if total > 0 && would_block(fd, writing) {
+            break;
+        }
+        let n = io(fd, base as usize, len as usize);
+        if n < 0 {
+            return if total > 0 { total } else { n };
+        }
         total += n;
+        if (n as u64) < len { break; }
     }
     total
 }
 
+/// Whether a read (or write) on `fd` would block right now, by the
+/// handle's own `FileHandle::poll`. An fd that isn't open reads as "no",
+/// leaving the EBADF to the transfer itself. Interrupts must be off.
+fn would_block(fd: i32, writing: bool) -> bool {
+    let Some(files) = current_files() else { return false };
+    let files = files.lock();
+    match files.get(fd as usize) {
+        Ok(handle) => {
+            let ready = handle.poll();
+            !if writing { ready.writable } else { ready.readable }
+        }
+        Err(_) => false,
+    }
+}
+
+/// readv(19): ssize_t readv(int fd, const struct iovec *iov, int iovcnt)
+///
+/// Fills the segments in order; see `iov_loop`.
+pub(super) fn sys_readv(fd: i32, iov_ptr: u64, iovcnt: usize) -> SyscallResult {
+    iov_loop(fd, iov_ptr, iovcnt, sys_read, false)
+}
+
+/// writev(20): ssize_t writev(int fd, const struct iovec *iov, int iovcnt)
+///
+/// Writes the segments in order; see `iov_loop`.
+pub(super) fn sys_writev(fd: i32, iov_ptr: u64, iovcnt: usize) -> SyscallResult {
+    iov_loop(fd, iov_ptr, iovcnt, sys_write, true)
+}
+
 /// `struct statvfs` (see `sysroot/usr/include/abi-bits/statvfs.h`) — 11
 /// `unsigned long`/`fsblkcnt_t`/`fsfilcnt_t` fields, all `u64` on x86-64.
 #[repr(C)]
@@ -1018,7 +1254,7 @@ pub(super) fn sys_statvfs(path_ptr: usize, out_ptr: usize) -> SyscallResult {
     }
 
     const BLOCK: u64 = 4096;
-    let buddy = crate::allocator::buddy_allocator::BUDDY.lock();
+    let buddy = crate::allocator::buddy_allocator::lock_buddy();
     let total_blocks = buddy.total_bytes() as u64 / BLOCK;
     let free_blocks = buddy.free_bytes() as u64 / BLOCK;
     drop(buddy);
@@ -1069,9 +1305,9 @@ pub(super) fn sys_fchmod(fd: i32, mode: u32) -> SyscallResult {
         match proc.files.lock().get_mut(fd as usize) {
             Ok(file) => match file.chmod(mode) {
                 Ok(()) => 0,
-                Err(_) => errno::EIO,
+                Err(e) => e.into(),
             },
-            Err(_) => errno::EBADF,
+            Err(e) => e.into(),
         }
     })
 }
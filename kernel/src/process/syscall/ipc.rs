@@ -15,9 +15,9 @@
 // The ISR path only touches the SCHEDULER, not CHANNELS, so this is safe.
 
 
+use alloc::collections::BTreeMap;
 use spin::Mutex;
 use core::sync::atomic::Ordering;
-use crate::serial_println;
 use crate::process::TrapFrame;
 use super::{errno, SyscallResult, validate_user_buffer, CURRENT_SYSCALL_TF};
 
@@ -109,42 +109,53 @@ impl crate::process::file::FileHandle for SocketHandle {
 // in no_std (no Any).  Solution: maintain a per-process fd→channel_id side table
 // in the IPC layer rather than in the FileDescriptorTable.
 //
-// We use a global array indexed by (pid * MAX_FILES + fd).
+// Rows are keyed by the full pid, generation included (see `Pid`): after a
+// slot's first reuse pids are far past any small fixed table size, so an
+// array indexed by raw pid silently stopped tracking them. A process's row
+// goes away with it (`clear_fd_channels`, from `cancel_all_waiters`).
 
-pub(super) const MAX_PROCS: usize = 32;
 pub(super) const MAX_FILES_PER_PROC: usize = 16;
 
-/// fd → channel_id mapping.  0 means "not a socket fd".
-pub(super) static FD_CHANNEL_MAP: Mutex<[[ChannelId; MAX_FILES_PER_PROC]; MAX_PROCS]> =
-    Mutex::new([[0usize; MAX_FILES_PER_PROC]; MAX_PROCS]);
+/// pid → that process's fd → channel_id row.  0 means "not a socket fd".
+pub(super) type FdChannelMap = BTreeMap<usize, [ChannelId; MAX_FILES_PER_PROC]>;
 
-fn set_fd_channel(pid: usize, fd: usize, channel_id: ChannelId) {
-    if pid < MAX_PROCS && fd < MAX_FILES_PER_PROC {
-        FD_CHANNEL_MAP.lock()[pid][fd] = channel_id;
+pub(super) static FD_CHANNEL_MAP: Mutex<FdChannelMap> = Mutex::new(BTreeMap::new());
+
+/// `pid`'s channel for `fd` in an already-locked `FD_CHANNEL_MAP`, or 0.
+pub(super) fn channel_of(map: &FdChannelMap, pid: usize, fd: usize) -> ChannelId {
+    map.get(&pid).and_then(|fds| fds.get(fd)).copied().unwrap_or(0)
+}
+
+pub(super) fn set_fd_channel(pid: usize, fd: usize, channel_id: ChannelId) {
+    if fd < MAX_FILES_PER_PROC {
+        FD_CHANNEL_MAP.lock().entry(pid).or_insert([0; MAX_FILES_PER_PROC])[fd] = channel_id;
     }
 }
 
 fn get_fd_channel(pid: usize, fd: usize) -> Option<ChannelId> {
-    if pid < MAX_PROCS && fd < MAX_FILES_PER_PROC {
-        let id = FD_CHANNEL_MAP.lock()[pid][fd];
-        if id != 0 { Some(id) } else { None }
-    } else {
-        None
-    }
+    let id = channel_of(&FD_CHANNEL_MAP.lock(), pid, fd);
+    if id != 0 { Some(id) } else { None }
 }
 
 fn clear_fd_channel(pid: usize, fd: usize) {
-    if pid < MAX_PROCS && fd < MAX_FILES_PER_PROC {
-        FD_CHANNEL_MAP.lock()[pid][fd] = 0;
+    if let Some(fds) = FD_CHANNEL_MAP.lock().get_mut(&pid) {
+        if let Some(slot) = fds.get_mut(fd) {
+            *slot = 0;
+        }
     }
 }
 
+/// Drop `pid`'s whole row (on exit).
+pub(super) fn clear_fd_channels(pid: usize) {
+    FD_CHANNEL_MAP.lock().remove(&pid);
+}
+
 // ——— sys_socket (revised) — store mapping ——————————————————————————————————
 
 /// Internal helper: open a socket and record the fd→channel mapping.
 pub(super) fn sys_socket_impl() -> SyscallResult {
     let pid_dbg = crate::process::scheduler::current_pid().unwrap_or(0);
-    serial_println!("[DBG] sys_socket PID {}", pid_dbg);
+    crate::debug!("[DBG] sys_socket PID {}", pid_dbg);
     let id = match CHANNELS.lock().alloc() {
         Some(id) => id,
         None => return errno::ENOMEM,
@@ -152,7 +163,7 @@ pub(super) fn sys_socket_impl() -> SyscallResult {
 
     let handle = alloc::boxed::Box::new(SocketHandle { channel_id: id });
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     let mut sched = crate::process::scheduler::local_scheduler();
     match sched.running_mut() {
         Some(proc) => {
@@ -169,9 +180,9 @@ pub(super) fn sys_socket_impl() -> SyscallResult {
                     set_fd_channel(pid, fd, id);
                     fd as i64
                 }
-                Err(_) => {
+                Err(e) => {
                     CHANNELS.lock().free(id);
-                    errno::EINVAL
+                    e.into()
                 }
             }
         }
@@ -229,7 +240,7 @@ pub(super) fn sys_bind_impl(fd: i32, path_ptr: usize, _addrlen: usize) -> Syscal
 /// The server must subsequently call accept() to get the peer fd.
 pub(super) fn sys_connect(fd: i32, path_ptr: usize, _addrlen: usize) -> SyscallResult {
     let pid_dbg = crate::process::scheduler::current_pid().unwrap_or(0);
-    serial_println!("[DBG] sys_connect PID {} fd={}", pid_dbg, fd);
+    crate::debug!("[DBG] sys_connect PID {} fd={}", pid_dbg, fd);
     if let Err(e) = validate_user_buffer(path_ptr as u64, 64) {
         return e;
     }
@@ -298,10 +309,10 @@ pub(super) fn sys_connect(fd: i32, path_ptr: usize, _addrlen: usize) -> SyscallR
     };
 
     if let Some(waiter) = accept_waiter {
-        serial_println!("[DBG] connect: waking accept waiter PID {}", waiter.pid);
+        crate::debug!("[DBG] connect: waking accept waiter PID {}", waiter.pid);
         // Allocate the peer fd inside the blocked process.
         // The guard prevents the timer ISR from preempting while we hold SCHEDULER.
-        let _irq = crate::process::irq_guard::InterruptGuard::new();
+        let _irq = crate::interrupts::InterruptGuard::new();
 
         let handle = alloc::boxed::Box::new(SocketHandle { channel_id: server_peer_id });
         let mut new_fd: i64 = errno::EINVAL;
@@ -321,8 +332,8 @@ pub(super) fn sys_connect(fd: i32, path_ptr: usize, _addrlen: usize) -> SyscallR
                             new_fd = fd as i64;
                             proc.trapframe.rax = fd as u64;
                         }
-                        Err(_) => {
-                            proc.trapframe.rax = (-22i64) as u64; // EINVAL
+                        Err(e) => {
+                            proc.trapframe.rax = i64::from(e) as u64;
                         }
                     }
                     break;
@@ -358,7 +369,7 @@ pub(super) fn sys_accept(fd: i32) -> SyscallResult {
     // `irq` is deliberately never dropped on the slow (blocking) path below
     // — it ends in `jump_to_user` (`-> !`), so interrupts intentionally
     // stay off across that jump; see `sys_read`'s WouldBlock arm.
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
 
     // Fast path: connection already pending from a previous connect().
     let pending = {
@@ -383,7 +394,7 @@ pub(super) fn sys_accept(fd: i32) -> SyscallResult {
             match sched.running_mut() {
                 Some(proc) => match proc.files.lock().allocate(handle) {
                     Ok(fd) => fd as i64,
-                    Err(_) => errno::EINVAL,
+                    Err(e) => e.into(),
                 },
                 None => errno::ESRCH,
             }
@@ -557,7 +568,7 @@ pub(super) fn sys_recvmsg(fd: i32, msg_ptr: u64, _flags: u32) -> SyscallResult {
     // `irq` is deliberately never dropped on the slow (blocking) path below
     // — it ends in `jump_to_user` (`-> !`), so interrupts intentionally
     // stay off across that jump; see `sys_read`'s WouldBlock arm.
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
 
     // Fast path: message already queued.
     let queued = CHANNELS.lock().get_mut(channel_id).and_then(|ch| ch.dequeue());
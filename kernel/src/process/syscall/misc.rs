@@ -1,11 +1,17 @@
 // kernel/src/process/syscall/misc.rs
 //
 // Small standalone syscalls that don't fit any other subsystem: uptime/
-// meminfo/kdebug_ctl (custom, above the Linux syscall range) and
-// clock_gettime (Linux #228).
+// meminfo/kdebug_ctl (custom, above the Linux syscall range),
+// clock_gettime (Linux #228) and reboot (Linux #169).
 
 use super::{errno, SyscallResult, validate_user_buffer};
 
+/// Size of the buffer `kdebug_ctl` cmd 10 (page walk) writes its report to.
+const PAGEWALK_BUF_LEN: usize = 1024;
+/// Size of the buffer `kdebug_ctl` cmd 11 (schedstat) writes its report
+/// to — room for the header plus a line for every one of `MAX_PROCESSES`.
+const SCHEDSTAT_BUF_LEN: usize = 4096;
+
 pub(super) fn sys_uptime_ms() -> SyscallResult {
     crate::cpu::tsc::uptime_ms() as SyscallResult
 }
@@ -17,7 +23,7 @@ pub(super) fn sys_uptime_ms() -> SyscallResult {
 /// runs to catch a leak — see kernel_stack's `pending_stack_frees` /
 /// `free_kernel_stack` for the leak this was added to verify.
 pub(super) fn sys_meminfo_kb() -> SyscallResult {
-    (crate::allocator::buddy_allocator::BUDDY.lock().free_bytes() / 1024) as SyscallResult
+    (crate::allocator::buddy_allocator::lock_buddy().free_bytes() / 1024) as SyscallResult
 }
 
 /// sys_kdebug_ctl (custom #403): long kdebug_ctl(int cmd, const char *name, int enable)
@@ -32,25 +38,39 @@ pub(super) fn sys_meminfo_kb() -> SyscallResult {
 /// `name` (a NUL-terminated string, e.g. "mm") to its subsystem bit and
 /// set or clear it in the mask depending on `enable`; returns the *new*
 /// mask, or `EINVAL` if `name` doesn't match a known subsystem.
+/// 2 = get the `crate::log` level (other args ignored). 3 = set the log
+/// level to `enable` (0 = silent .. 5 = trace); returns the *previous*
+/// level, or `EINVAL` if out of range. 4 = get the demand-paging
+/// fault-around cluster size in pages (`memory::demand_paging`). 5 = set
+/// it to `enable` (a power of two up to `MAX_FAULT_AROUND_PAGES`; 1 =
+/// single-page, the default); returns the *previous* size, or `EINVAL`.
+/// 6 = get the serial console port (1 = COM1, 2 = COM2; `crate::serial`).
+/// 7 = move it to COM `enable`; returns the *previous* port, or `ENXIO`
+/// if that UART isn't present. 8/9 = the same for the kernel log port.
+/// 10 = page-walk the virtual address `enable` in the caller's address
+/// space (`memory::page_table_manager::walk`) and write the report into
+/// `name`, which must point at `PAGEWALK_BUF_LEN` bytes, NUL-terminated
+/// and truncated to fit; returns its length, or `EINVAL` for a
+/// non-canonical address. 11 = write `scheduler::schedstat_report` (switch
+/// counters, global and per process) into `name` the same way, in
+/// `SCHEDSTAT_BUF_LEN` bytes. 12 = toggle single-step tracing of the
+/// process whose pid is passed *in `name`'s slot*, for `enable` steps (0 =
+/// the default; `process::single_step::toggle`); returns the new step
+/// budget, 0 if that turned tracing off, or `ESRCH`. 13 = get where
+/// `/dev/tty` output goes (`drivers::dev_tty`: 1 = framebuffer, 2 =
+/// serial, 3 = both). 14 = set it to `enable`; returns the *previous*
+/// setting, or `EINVAL`. 15 = start the user test named by `name`
+/// (`process::user_test_fileio`, e.g. "yield") as a new process
+/// (`init::processes::spawn_user_test`); returns its pid, `ENOENT` for
+/// no such test, `EAGAIN` at the process limit, `ENOMEM` if it couldn't
+/// be loaded.
 pub(super) fn sys_kdebug_ctl(cmd: u64, name_ptr: u64, enable: u64) -> SyscallResult {
     match cmd {
         0 => crate::debug::get_mask() as SyscallResult,
         1 => {
-            if let Err(e) = validate_user_buffer(name_ptr, 32) {
-                return e;
-            }
-            let name_bytes = unsafe {
-                let ptr = name_ptr as *const u8;
-                let mut len = 0usize;
-                while len < 32 {
-                    if *ptr.add(len) == 0 { break; }
-                    len += 1;
-                }
-                core::slice::from_raw_parts(ptr, len)
-            };
-            let name = match core::str::from_utf8(name_bytes) {
-                Ok(s) => s,
-                Err(_) => return errno::EINVAL,
+            let name = match read_short_name(name_ptr) {
+                Ok(name) => name,
+                Err(e) => return e,
             };
             let Some(bit) = crate::debug::subsystem_bit_by_name(name) else {
                 return errno::EINVAL;
@@ -60,10 +80,188 @@ pub(super) fn sys_kdebug_ctl(cmd: u64, name_ptr: u64, enable: u64) -> SyscallRes
             crate::debug::set_mask(mask);
             mask as SyscallResult
         }
+        2 => crate::log::level() as SyscallResult,
+        3 => match u8::try_from(enable).ok().and_then(crate::log::set_level) {
+            Some(prev) => prev as SyscallResult,
+            None => errno::EINVAL,
+        },
+        4 => crate::memory::demand_paging::fault_around_pages() as SyscallResult,
+        5 => {
+            let prev = crate::memory::demand_paging::fault_around_pages();
+            match crate::memory::demand_paging::set_fault_around_pages(enable as usize) {
+                Ok(()) => prev as SyscallResult,
+                Err(_) => errno::EINVAL,
+            }
+        }
+        6 => crate::serial::console_port() as SyscallResult,
+        7 => match u8::try_from(enable).ok().and_then(crate::serial::set_console_port) {
+            Some(prev) => prev as SyscallResult,
+            None => errno::ENXIO,
+        },
+        8 => crate::serial::log_port() as SyscallResult,
+        9 => match u8::try_from(enable).ok().and_then(crate::serial::set_log_port) {
+            Some(prev) => prev as SyscallResult,
+            None => errno::ENXIO,
+        },
+        10 => {
+            if let Err(e) = validate_user_buffer(name_ptr, PAGEWALK_BUF_LEN) {
+                return e;
+            }
+            let Ok(addr) = x86_64::VirtAddr::try_new(enable) else {
+                return errno::EINVAL;
+            };
+            let report = alloc::format!("{}", unsafe { crate::memory::page_table_manager::walk(addr) });
+            copy_report_out(&report, name_ptr, PAGEWALK_BUF_LEN)
+        }
+        11 => {
+            if let Err(e) = validate_user_buffer(name_ptr, SCHEDSTAT_BUF_LEN) {
+                return e;
+            }
+            let report = crate::process::scheduler::schedstat_report();
+            copy_report_out(&report, name_ptr, SCHEDSTAT_BUF_LEN)
+        }
+        12 => {
+            let steps = u32::try_from(enable).unwrap_or(u32::MAX);
+            let mut sched = crate::process::scheduler::local_scheduler();
+            match crate::process::single_step::toggle(&mut sched, name_ptr as usize, steps) {
+                Some(left) => left as SyscallResult,
+                None => errno::ESRCH,
+            }
+        }
+        13 => crate::drivers::dev_tty::outputs() as SyscallResult,
+        14 => match u8::try_from(enable).ok().and_then(crate::drivers::dev_tty::set_outputs) {
+            Some(prev) => prev as SyscallResult,
+            None => errno::EINVAL,
+        },
+        15 => {
+            let name = match read_short_name(name_ptr) {
+                Ok(name) => name,
+                Err(e) => return e,
+            };
+            use crate::init::processes::SpawnError;
+            match crate::init::processes::spawn_user_test(name) {
+                Ok(pid) => pid.0 as SyscallResult,
+                Err(SpawnError::NoSuchTest) => errno::ENOENT,
+                Err(SpawnError::TooManyProcesses) => errno::EAGAIN,
+                Err(SpawnError::Load(_)) => errno::ENOMEM,
+            }
+        }
         _ => errno::EINVAL,
     }
 }
 
+/// The NUL-terminated name (at most 32 bytes) `kdebug_ctl` takes in its
+/// `name` argument — a subsystem for cmd 1, a user test for cmd 15.
+fn read_short_name(name_ptr: u64) -> Result<&'static str, SyscallResult> {
+    validate_user_buffer(name_ptr, 32)?;
+    let name_bytes = unsafe {
+        let ptr = name_ptr as *const u8;
+        let mut len = 0usize;
+        while len < 32 {
+            if *ptr.add(len) == 0 { break; }
+            len += 1;
+        }
+        core::slice::from_raw_parts(ptr, len)
+    };
+    core::str::from_utf8(name_bytes).map_err(|_| errno::EINVAL)
+}
+
+/// sys_set_hwbreak (custom #406): long set_hwbreak(void *addr, size_t len, int kind)
+///
+/// Watch `addr` with a debug register (`process::hw_break`): `kind` 0 =
+/// execute (`len` must be 1), 1 = write, 3 = read or write, `len` 1, 2, 4
+/// or 8 with `addr` aligned to it. Returns the DR slot used (0-3);
+/// `EINVAL` for a bad combination or a non-user address, `EBUSY` when all
+/// four are taken. `len == 0` removes the breakpoint at `addr` instead
+/// (returns its slot, or `ENOENT`). A hit is logged over serial and raises
+/// SIGTRAP in the calling thread. Per thread, not inherited across fork.
+pub(super) fn sys_set_hwbreak(addr: u64, len: u64, kind: u64) -> SyscallResult {
+    use crate::process::hw_break::{BreakKind, HwBreakError};
+
+    let mut sched = crate::process::scheduler::local_scheduler();
+    let Some(proc) = sched.running_mut() else {
+        return errno::ESRCH;
+    };
+    let result = if len == 0 {
+        proc.hw_breaks.clear(addr).map_or(errno::ENOENT, |slot| slot as SyscallResult)
+    } else {
+        let (Some(kind), Ok(len)) = (BreakKind::from_u64(kind), u8::try_from(len)) else {
+            return errno::EINVAL;
+        };
+        match proc.hw_breaks.set(addr, len, kind) {
+            Ok(slot) => slot as SyscallResult,
+            Err(HwBreakError::Invalid) => errno::EINVAL,
+            Err(HwBreakError::NoFreeSlot) => errno::EBUSY,
+        }
+    };
+    // Running process: its set goes live now, not at the next switch-in.
+    unsafe { crate::process::hw_break::load(&proc.hw_breaks); }
+    result
+}
+
+/// Copy a `kdebug_ctl` text report into the (already validated) user
+/// buffer at `ptr`, NUL-terminated and truncated to `len`; returns the
+/// length copied.
+fn copy_report_out(report: &str, ptr: u64, len: usize) -> SyscallResult {
+    let n = report.len().min(len - 1);
+    unsafe {
+        core::ptr::copy_nonoverlapping(report.as_ptr(), ptr as *mut u8, n);
+        *(ptr as *mut u8).add(n) = 0;
+    }
+    n as SyscallResult
+}
+
+/// reboot(169): int reboot(int magic, int magic2, int cmd, void *arg)
+///
+/// Linux's ABI, so a stock `reboot(RB_POWER_OFF)`/`reboot(RB_AUTOBOOT)`
+/// works: both magic numbers are checked exactly as Linux does, then
+/// POWER_OFF goes to `crate::power::shutdown` and RESTART to
+/// `crate::power::reboot`. Every other command (HALT, CAD_ON/OFF, kexec,
+/// ...) is `EINVAL`. No privilege check — there are no users. If the
+/// request returns at all, the machine ignored it (not QEMU, see
+/// `crate::power`), reported as `EIO` instead of hanging the caller.
+pub(super) fn sys_reboot(magic1: u64, magic2: u64, cmd: u64) -> SyscallResult {
+    const LINUX_REBOOT_MAGIC1: u32 = 0xfee1_dead;
+    const LINUX_REBOOT_MAGIC2: [u32; 4] = [672274793, 85072278, 369367448, 537993216];
+    const LINUX_REBOOT_CMD_RESTART: u32 = 0x0123_4567;
+    const LINUX_REBOOT_CMD_POWER_OFF: u32 = 0x4321_fedc;
+
+    if magic1 as u32 != LINUX_REBOOT_MAGIC1 || !LINUX_REBOOT_MAGIC2.contains(&(magic2 as u32)) {
+        return errno::EINVAL;
+    }
+    match cmd as u32 {
+        LINUX_REBOOT_CMD_POWER_OFF => crate::power::shutdown(),
+        LINUX_REBOOT_CMD_RESTART => crate::power::reboot(),
+        _ => return errno::EINVAL,
+    }
+    errno::EIO
+}
+
+/// getrandom(318): ssize_t getrandom(void *buf, size_t len, unsigned flags)
+///
+/// Fills `buf` from the same generator as `/dev/random`
+/// (`drivers::dev_random::fill`) without an `open()`. There is no entropy
+/// pool to wait on, so `GRND_NONBLOCK` and `GRND_RANDOM` are accepted and
+/// change nothing: every call fills all `len` bytes and returns `len`.
+/// Any other flag bit is `EINVAL`.
+pub(super) fn sys_getrandom(buf_ptr: u64, len: usize, flags: u32) -> SyscallResult {
+    const GRND_NONBLOCK: u32 = 0x1;
+    const GRND_RANDOM: u32 = 0x2;
+    const GRND_INSECURE: u32 = 0x4;
+
+    if flags & !(GRND_NONBLOCK | GRND_RANDOM | GRND_INSECURE) != 0 {
+        return errno::EINVAL;
+    }
+    if len == 0 {
+        return 0;
+    }
+    if let Err(e) = validate_user_buffer(buf_ptr, len) {
+        return e;
+    }
+    let buf = unsafe { core::slice::from_raw_parts_mut(buf_ptr as *mut u8, len) };
+    crate::drivers::dev_random::fill(buf);
+    len as SyscallResult
+}
 
 /// sys_uptime_sec (custom #202) — seconds elapsed since kernel boot.
 ///
@@ -2,7 +2,8 @@
 //
 // All syscalls use `with_current_process` or `with_scheduler` helpers
 // that guarantee cli before lock, lock dropped before sti — both now
-// enforced by RAII (`process::irq_guard`), not hand-paired `asm!("cli")`/
+// enforced by RAII (`process::irq_guard::SchedGuard` and
+// `interrupts::InterruptGuard`), not hand-paired `asm!("cli")`/
 // `asm!("sti")` calls. See `irq_guard.rs`'s module doc comment for why:
 // that manual pattern caused a real, reproducible full-kernel hang once
 // (see `fs::sys_close`'s doc comment).
@@ -17,11 +18,12 @@
 // MODULE LAYOUT: this used to be one 4200+ line file. It's now split by
 // subsystem, matching the section dividers the original file already had:
 //   fs           — read/write/open/close/stat family/getdents64/lseek/mmap/
-//                  munmap/pipe/dup/dup2/fcntl/ioctl/writev/access/rename/
-//                  mkdir/rmdir/unlink/symlink/readlink/chmod/fchmod/statvfs/
-//                  getcwd/chdir, plus the stdin blocking-read machinery.
+//                  munmap/pipe/dup/dup2/fcntl/ioctl/pread/pwrite/readv/writev/
+//                  access/rename/mkdir/rmdir/unlink/symlink/readlink/chmod/
+//                  fchmod/statvfs/getcwd/chdir, plus the stdin blocking-read
+//                  machinery.
 //   process_ctl  — fork/clone/exec/exit/waitpid/kill/getpid/setpgid/getpgid/
-//                  setsid/yield/nanosleep/arch_prctl/set_tid_address.
+//                  setsid/yield/nanosleep/arch_prctl/set_tid_address/times.
 //   signal       — sigaction/sigprocmask/sigreturn.
 //   ipc          — socket/connect/accept/bind/sendmsg/recvmsg.
 //   sync         — futex.
@@ -39,7 +41,11 @@ mod misc;
 
 pub(crate) use fs::{send_to_group, stdin_wakeup};
 pub(crate) use process_ctl::cancel_all_waiters;
-pub(crate) use poll::{poll_wakeup_for_fd0, poll_clear_on_timeout};
+pub(crate) use poll::{poll_wakeup_ready, poll_clear_on_timeout};
+#[cfg(test)]
+pub(crate) use poll::{register_side_tables, side_tables};
+#[cfg(test)]
+pub(crate) use sync::{futex_cancel_waiter, pi_block};
 
 use core::arch::global_asm;
 use core::sync::atomic::{AtomicU64, Ordering};
@@ -179,7 +185,7 @@ extern "C" fn syscall_handler_asm(regs: &SavedRegisters) -> i64 {
     let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *mut TrapFrame;
     unsafe { (*tf_ptr).rax = ret as u64; }
 
-    let irq = super::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
     let resolved_tf = {
         let mut sched = super::scheduler::local_scheduler();
         let tf = sched.resolve_signals(tf_ptr as *const TrapFrame);
@@ -237,8 +243,12 @@ pub enum SyscallNumber {
     Munmap = 11,
     Brk = 12,
     Ioctl = 16,
+    Pread64 = 17,
+    Pwrite64 = 18,
+    Readv = 19,
     Writev = 20,
     Yield = 24,
+    Madvise = 28,
     Nanosleep = 35,
     GetPid = 39,
     Socket = 41,
@@ -253,23 +263,31 @@ pub enum SyscallNumber {
     Exit = 60,
     Waitpid = 61,
     Kill = 62,
+    Times = 100,
     Setpgid = 109,
     Setsid = 112,
     Getpgid = 121,
     ArchPrctl = 158,
+    Reboot = 169,
+    GetTid = 186,
     Futex = 202,
+    SchedSetaffinity = 203,
+    SchedGetaffinity = 204,
     EpollCreate = 213,
     GetDents64 = 217,
     SetTidAddress = 218,
     ClockGettime = 228,
     EpollWait = 232,
     EpollCtl = 233,
+    Getrandom = 318,
     // Custom kernel syscalls (above Linux range)
     UptimeMs = 400,
     UptimeSec = 401,
     MemInfoKb = 402,
     KdebugCtl = 403,
     Statvfs = 404,
+    SchedYieldTo = 405,
+    SetHwbreak = 406,
 }
 
 impl SyscallNumber {
@@ -306,8 +324,12 @@ impl SyscallNumber {
             11 => Some(Self::Munmap),
             12 => Some(Self::Brk),
             16 => Some(Self::Ioctl),
+            17 => Some(Self::Pread64),
+            18 => Some(Self::Pwrite64),
+            19 => Some(Self::Readv),
             20 => Some(Self::Writev),
             24 => Some(Self::Yield),
+            28 => Some(Self::Madvise),
             35 => Some(Self::Nanosleep),
             39 => Some(Self::GetPid),
             41 => Some(Self::Socket),
@@ -322,22 +344,30 @@ impl SyscallNumber {
             60 => Some(Self::Exit),
             61 => Some(Self::Waitpid),
             62 => Some(Self::Kill),
+            100 => Some(Self::Times),
             109 => Some(Self::Setpgid),
             112 => Some(Self::Setsid),
             121 => Some(Self::Getpgid),
             158 => Some(Self::ArchPrctl),
+            169 => Some(Self::Reboot),
+            186 => Some(Self::GetTid),
             202 => Some(Self::Futex),
+            203 => Some(Self::SchedSetaffinity),
+            204 => Some(Self::SchedGetaffinity),
             213 => Some(Self::EpollCreate),
             217 => Some(Self::GetDents64),
             218 => Some(Self::SetTidAddress),
             228 => Some(Self::ClockGettime),
             232 => Some(Self::EpollWait),
             233 => Some(Self::EpollCtl),
+            318 => Some(Self::Getrandom),
             400 => Some(Self::UptimeMs),
             401 => Some(Self::UptimeSec),
             402 => Some(Self::MemInfoKb),
             403 => Some(Self::KdebugCtl),
             404 => Some(Self::Statvfs),
+            405 => Some(Self::SchedYieldTo),
+            406 => Some(Self::SetHwbreak),
             _ => None,
         }
     }
@@ -364,21 +394,46 @@ pub mod errno {
     pub const EEXIST: i64 = -17;
     pub const ENOTDIR: i64 = -20;
     pub const EINVAL: i64 = -22;
+    pub const EMFILE: i64 = -24;
     pub const ENOTTY: i64 = -25;
     pub const ESPIPE: i64 = -29;
     pub const ENOSPC: i64 = -28;
     pub const ERANGE: i64 = -34;
+    pub const EDEADLK: i64 = -35;
     pub const ENOSYS: i64 = -38;
     pub const ELOOP: i64 = -40;
     pub const EAGAIN: i64 = -11;
     pub const EWOULDBLOCK: i64 = -11;
     pub const EPIPE: i64 = -32;
     pub const ENOTSOCK: i64 = -88;
+    pub const EOPNOTSUPP: i64 = -95;
     pub const ENOTCONN: i64 = -107;
     pub const ETIMEDOUT: i64 = -110;
     pub const ECONNREFUSED: i64 = -111;
 }
 
+/// The one place a `FileError` becomes a syscall return value, so handlers
+/// write `Err(e) => e.into()` instead of each picking its own errno.
+/// `EndOfFile` is 0 (a read that hit EOF), not an error; `WouldBlock` only
+/// reaches here from paths that don't block — `sys_read`/`sys_write`
+/// catch it first.
+impl From<crate::process::file::FileError> for i64 {
+    fn from(e: crate::process::file::FileError) -> i64 {
+        use crate::process::file::FileError;
+        match e {
+            FileError::BadFileDescriptor => errno::EBADF,
+            FileError::InvalidArgument => errno::EINVAL,
+            FileError::IOError => errno::EIO,
+            FileError::NotSupported => errno::EOPNOTSUPP,
+            FileError::EndOfFile => 0,
+            FileError::BrokenPipe => errno::EPIPE,
+            FileError::NoSpace => errno::ENOSPC,
+            FileError::WouldBlock => errno::EAGAIN,
+            FileError::TooManyOpenFiles => errno::EMFILE,
+        }
+    }
+}
+
 // ============================================================================
 // SAFE HELPERS
 // ============================================================================
@@ -511,12 +566,17 @@ pub fn syscall_handler(
         SyscallNumber::Fcntl => fs::sys_fcntl(arg1 as i32, arg2 as i32, arg3),
         SyscallNumber::Pipe => fs::sys_pipe(arg1),
         SyscallNumber::Munmap => fs::sys_munmap(arg1, arg2),
+        SyscallNumber::Madvise => fs::sys_madvise(arg1, arg2, arg3 as i32),
         SyscallNumber::Brk => fs::sys_brk(arg1),
         SyscallNumber::Ioctl => fs::sys_ioctl(arg1 as i32, arg2 as u64, arg3),
+        SyscallNumber::Pread64 => fs::sys_pread(arg1 as i32, arg2 as usize, arg3 as usize, arg4 as i64),
+        SyscallNumber::Pwrite64 => fs::sys_pwrite(arg1 as i32, arg2 as usize, arg3 as usize, arg4 as i64),
+        SyscallNumber::Readv => fs::sys_readv(arg1 as i32, arg2, arg3 as usize),
         SyscallNumber::Writev => fs::sys_writev(arg1 as i32, arg2, arg3 as usize),
         SyscallNumber::Yield => process_ctl::sys_yield(),
         SyscallNumber::Nanosleep => process_ctl::sys_nanosleep(arg1),
         SyscallNumber::GetPid => process_ctl::sys_getpid(),
+        SyscallNumber::GetTid => process_ctl::sys_gettid(),
         SyscallNumber::Socket  => ipc::sys_socket_impl(),
         SyscallNumber::Connect => ipc::sys_connect(arg1 as i32, arg2 as usize, arg3 as usize),
         SyscallNumber::Accept  => ipc::sys_accept(arg1 as i32),
@@ -529,21 +589,28 @@ pub fn syscall_handler(
         SyscallNumber::Exit => process_ctl::sys_exit(arg1 as i32),
         SyscallNumber::Waitpid => process_ctl::sys_waitpid(arg1 as i64, arg2 as usize, arg3 as i32),
         SyscallNumber::Kill => process_ctl::sys_kill(arg1 as i64, arg2 as u32),
+        SyscallNumber::Times => process_ctl::sys_times(arg1),
         SyscallNumber::Setpgid => process_ctl::sys_setpgid(arg1 as i64, arg2 as i64),
         SyscallNumber::Setsid => process_ctl::sys_setsid(),
         SyscallNumber::Getpgid => process_ctl::sys_getpgid(arg1 as i64),
         SyscallNumber::ArchPrctl => process_ctl::sys_arch_prctl(arg1 as i32, arg2),
+        SyscallNumber::Reboot => misc::sys_reboot(arg1, arg2, arg3),
         SyscallNumber::Futex => sync::sys_futex(arg1, arg2 as i32, arg3 as i32, arg4),
+        SyscallNumber::SchedSetaffinity => process_ctl::sys_sched_setaffinity(arg1 as i64, arg2 as usize, arg3),
+        SyscallNumber::SchedGetaffinity => process_ctl::sys_sched_getaffinity(arg1 as i64, arg2 as usize, arg3),
         SyscallNumber::SetTidAddress => process_ctl::sys_set_tid_address(arg1),
         SyscallNumber::EpollCreate => poll::sys_epoll_create(arg1 as i32),
         SyscallNumber::GetDents64 => fs::sys_getdents64(arg1 as i32, arg2 as usize, arg3 as usize),
         SyscallNumber::ClockGettime => misc::sys_clock_gettime(arg1, arg2),
         SyscallNumber::EpollWait => poll::sys_epoll_wait(arg1 as i32, arg2, arg3 as i32, arg4 as i32),
         SyscallNumber::EpollCtl => poll::sys_epoll_ctl(arg1 as i32, arg2 as i32, arg3 as i32, arg4),
+        SyscallNumber::Getrandom => misc::sys_getrandom(arg1, arg2 as usize, arg3 as u32),
         SyscallNumber::UptimeMs => misc::sys_uptime_ms(),
         SyscallNumber::UptimeSec => misc::sys_uptime_sec(),
         SyscallNumber::MemInfoKb => misc::sys_meminfo_kb(),
         SyscallNumber::KdebugCtl => misc::sys_kdebug_ctl(arg1, arg2, arg3),
         SyscallNumber::Statvfs => fs::sys_statvfs(arg1 as usize, arg2 as usize),
+        SyscallNumber::SchedYieldTo => process_ctl::sys_sched_yield_to(arg1 as i64),
+        SyscallNumber::SetHwbreak => misc::sys_set_hwbreak(arg1, arg2, arg3),
     }
 }
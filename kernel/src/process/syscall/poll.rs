@@ -1,11 +1,12 @@
 // kernel/src/process/syscall/poll.rs
 //
+use alloc::collections::BTreeMap;
 use spin::Mutex;
 use core::sync::atomic::Ordering;
 use crate::process::TrapFrame;
 use super::{errno, SyscallResult, validate_user_buffer, CURRENT_SYSCALL_TF};
 use crate::ipc::channel::{ChannelId, CHANNELS};
-use super::ipc::{MAX_PROCS, MAX_FILES_PER_PROC, FD_CHANNEL_MAP};
+use super::ipc::{MAX_FILES_PER_PROC, FD_CHANNEL_MAP, channel_of};
 
 // ============================================================================
 // POLL / EPOLL SYSCALLS
@@ -14,23 +15,31 @@ use super::ipc::{MAX_PROCS, MAX_FILES_PER_PROC, FD_CHANNEL_MAP};
 // poll(7), epoll_create(213), epoll_ctl(233), epoll_wait(232)
 //
 // Architecture:
-//   - `fd_check_ready(pid, fd, events)` checks FD readiness without consuming data.
+//   - `fd_check_ready(pid, fd, events)` checks FD readiness without consuming data
+//     (IPC channels by channel state, everything else via `FileHandle::poll`).
 //   - `POLL_WAITERS[pid]` stores a blocked process's buffer info for wakeup delivery.
 //   - `EPOLL_INSTANCES` holds per-epoll-fd watch lists.
 //   - `EPOLL_FD_MAP[pid][fd]` maps epoll FDs to EpollInstanceIds (same pattern as FD_CHANNEL_MAP).
-//   - Wakeup hooks: `poll_wakeup_for_fd0` (keyboard ISR) and
-//     `poll_wakeup_for_channel` (sys_sendmsg).
+//   - Both pid tables are maps keyed by the full pid, generation included,
+//     not arrays indexed by it — see `FD_CHANNEL_MAP`. `cancel_all_waiters`
+//     removes a process's entries when it dies.
+//   - Wakeup hooks: `poll_wakeup_ready` (keyboard/serial ISRs, pipe state
+//     changes) and `poll_wakeup_for_channel` (sys_sendmsg).
 //
 // LOCKING ORDER (cli must be held):
 //   POLL_WAITERS → EPOLL_INSTANCES → FD_CHANNEL_MAP → CHANNELS → (release) → SCHEDULER
-//   SCHEDULER is always acquired last.
+//   SCHEDULER is always acquired last, with none of the others held: the
+//   readiness checks (`fd_check_ready`, which looks the process up in the
+//   scheduler) run on copies of the waiter and of the epoll watch list
+//   (`epoll_watches`). And never the reverse — every hook here locks
+//   SCHEDULER itself, so nothing may call one with SCHEDULER held (nor
+//   drop a pipe end under it: a pipe end's close calls `poll_wakeup_ready`).
 
 // ── Poll bitmasks (POSIX ABI) ──────────────────────────────────────────────
 
 const POLLIN:   i16 = 0x0001;
 const POLLOUT:  i16 = 0x0004;
 const POLLERR:  i16 = 0x0008;
-#[allow(dead_code)]
 const POLLHUP:  i16 = 0x0010;
 const POLLNVAL: i16 = 0x0020;
 
@@ -39,6 +48,7 @@ const POLLNVAL: i16 = 0x0020;
 const EPOLLIN:       u32 = 0x0000_0001;
 const EPOLLOUT:      u32 = 0x0000_0004;
 const EPOLLERR:      u32 = 0x0000_0008;
+const EPOLLHUP:      u32 = 0x0000_0010;
 const EPOLLET:       u32 = 0x8000_0000;
 
 const EPOLL_CTL_ADD: i32 = 1;
@@ -120,9 +130,15 @@ impl EpollInstanceTable {
 
 static EPOLL_INSTANCES: Mutex<EpollInstanceTable> = Mutex::new(EpollInstanceTable::new());
 
+/// A copy of `epoll_id`'s watch list, taken so the readiness checks run
+/// with EPOLL_INSTANCES released.
+fn epoll_watches(epoll_id: EpollInstanceId) -> Option<[Option<EpollWatch>; 16]> {
+    EPOLL_INSTANCES.lock().get(epoll_id).map(|inst| inst.watches)
+}
+
 /// pid×fd → EpollInstanceId side table (0 = not an epoll fd).
-static EPOLL_FD_MAP: Mutex<[[EpollInstanceId; MAX_FILES_PER_PROC]; MAX_PROCS]> =
-    Mutex::new([[0; MAX_FILES_PER_PROC]; MAX_PROCS]);
+static EPOLL_FD_MAP: Mutex<BTreeMap<usize, [EpollInstanceId; MAX_FILES_PER_PROC]>> =
+    Mutex::new(BTreeMap::new());
 
 /// FileHandle marker stored in the FD table for epoll FDs.
 struct EpollHandle {
@@ -146,24 +162,17 @@ impl crate::process::file::FileHandle for EpollHandle {
 // ── EPOLL_FD_MAP helpers ───────────────────────────────────────────────────
 
 fn get_epoll_fd(pid: usize, fd: usize) -> EpollInstanceId {
-    if pid < MAX_PROCS && fd < MAX_FILES_PER_PROC {
-        EPOLL_FD_MAP.lock()[pid][fd]
-    } else {
-        0
-    }
+    EPOLL_FD_MAP.lock().get(&pid).and_then(|fds| fds.get(fd)).copied().unwrap_or(0)
 }
 
 fn set_epoll_fd(pid: usize, fd: usize, epoll_id: EpollInstanceId) {
-    if pid < MAX_PROCS && fd < MAX_FILES_PER_PROC {
-        EPOLL_FD_MAP.lock()[pid][fd] = epoll_id;
+    if fd < MAX_FILES_PER_PROC {
+        EPOLL_FD_MAP.lock().entry(pid).or_insert([0; MAX_FILES_PER_PROC])[fd] = epoll_id;
     }
 }
 
 pub(super) fn clear_epoll_fd_all(pid: usize) {
-    if pid < MAX_PROCS {
-        let mut map = EPOLL_FD_MAP.lock();
-        map[pid] = [0; MAX_FILES_PER_PROC];
-    }
+    EPOLL_FD_MAP.lock().remove(&pid);
 }
 
 // ── Poll waiter ────────────────────────────────────────────────────────────
@@ -187,9 +196,8 @@ struct PollWaiter {
     timer_id: Option<u32>,
 }
 
-/// One slot per PID — a process can only have one outstanding poll/epoll_wait.
-static POLL_WAITERS: Mutex<[Option<PollWaiter>; MAX_PROCS]> =
-    Mutex::new([None; MAX_PROCS]);
+/// One entry per PID — a process can only have one outstanding poll/epoll_wait.
+static POLL_WAITERS: Mutex<BTreeMap<usize, PollWaiter>> = Mutex::new(BTreeMap::new());
 
 // ── FD readiness ───────────────────────────────────────────────────────────
 
@@ -200,47 +208,55 @@ static POLL_WAITERS: Mutex<[Option<PollWaiter>; MAX_PROCS]> =
 ///
 /// Rules:
 ///   - IPC channel fd: POLLIN if rx has messages; POLLOUT if peer's rx is not full.
-///   - stdin (fd=0): POLLIN if keyboard buffer has data.
-///   - All other device fds: always ready for the requested events.
+///   - Anything else: the handle's own `FileHandle::poll` (pipes by buffer
+///     state, the console/`/dev/kbd` by keyboard buffer, plain devices and
+///     files always ready), POLLHUP added regardless of `events`.
+///   - Not open: POLLNVAL.
 fn fd_check_ready(pid: usize, fd: i32, events: i16) -> i16 {
     if fd < 0 { return POLLNVAL; }
     let fd_usize = fd as usize;
 
     // IPC channel?
-    if fd_usize < MAX_FILES_PER_PROC && pid < MAX_PROCS {
-        let channel_id = FD_CHANNEL_MAP.lock()[pid][fd_usize];
-        if channel_id != 0 {
-            let tbl = CHANNELS.lock();
-            let mut rev: i16 = 0;
-            if events & POLLIN != 0 {
-                if tbl.get(channel_id).map(|ch| ch.has_messages()).unwrap_or(false) {
-                    rev |= POLLIN;
-                }
-            }
-            if events & POLLOUT != 0 {
-                // POLLOUT ready if peer's rx buffer is not full
-                let peer_not_full = tbl.get(channel_id)
-                    .and_then(|ch| ch.peer)
-                    .and_then(|peer_id| tbl.get(peer_id))
-                    .map(|peer| !peer.is_rx_full())
-                    .unwrap_or(false);
-                if peer_not_full { rev |= POLLOUT; }
+    let channel_id = channel_of(&FD_CHANNEL_MAP.lock(), pid, fd_usize);
+    if channel_id != 0 {
+        let tbl = CHANNELS.lock();
+        let mut rev: i16 = 0;
+        if events & POLLIN != 0 {
+            if tbl.get(channel_id).map(|ch| ch.has_messages()).unwrap_or(false) {
+                rev |= POLLIN;
             }
-            return rev;
         }
-    }
-
-    // stdin
-    if fd_usize == 0 {
-        let mut rev: i16 = 0;
-        if events & POLLIN != 0 && crate::keyboard::read_key_peek() {
-            rev |= POLLIN;
+        if events & POLLOUT != 0 {
+            // POLLOUT ready if peer's rx buffer is not full
+            let peer_not_full = tbl.get(channel_id)
+                .and_then(|ch| ch.peer)
+                .and_then(|peer_id| tbl.get(peer_id))
+                .map(|peer| !peer.is_rx_full())
+                .unwrap_or(false);
+            if peer_not_full { rev |= POLLOUT; }
         }
         return rev;
     }
 
-    // All other device FDs (always ready)
-    events & (POLLIN | POLLOUT)
+    let Some(ready) = handle_readiness(pid, fd_usize) else { return POLLNVAL; };
+    let mut rev: i16 = 0;
+    if events & POLLIN != 0 && ready.readable { rev |= POLLIN; }
+    if events & POLLOUT != 0 && ready.writable { rev |= POLLOUT; }
+    if ready.hangup { rev |= POLLHUP; }
+    rev
+}
+
+/// `FileHandle::poll` for `pid`'s `fd`, or `None` if it isn't open. The
+/// process is usually not the running one (a blocked waiter being
+/// re-checked from a wakeup hook), hence the lookup by pid. Its fd table
+/// is only `try_lock`ed — this can run from an ISR — and a busy table
+/// reads as "ready", erring toward a spurious wake over a missed one.
+fn handle_readiness(pid: usize, fd: usize) -> Option<crate::process::file::Readiness> {
+    use crate::process::file::Readiness;
+    let sched = crate::process::scheduler::local_scheduler();
+    let proc = sched.iter_all().find(|p| p.pid.0 == pid)?;
+    let Some(files) = proc.files.try_lock() else { return Some(Readiness::ALWAYS); };
+    files.get(fd).ok().map(|h| h.poll())
 }
 
 // ── deliver_poll_result_phys ───────────────────────────────────────────────
@@ -270,13 +286,11 @@ fn deliver_poll_result_phys(waiter: &PollWaiter, phys_offset: u64) -> usize {
         PollWaiterKind::EpollWait { epoll_id, maxevents } => {
             // phys_buf → array of EpollEvent structs (12 bytes each, packed)
             let base = phys_offset + waiter.phys_buf;
-            let instances = EPOLL_INSTANCES.lock();
-            let inst = match instances.get(epoll_id) {
-                Some(i) => i,
-                None => return 0,
-            };
+            // Copied out: `fd_check_ready` may take SCHEDULER, which is
+            // never acquired under EPOLL_INSTANCES (see LOCKING ORDER).
+            let Some(watches) = epoll_watches(epoll_id) else { return 0 };
             let mut written = 0usize;
-            for watch_opt in inst.watches.iter() {
+            for watch_opt in watches.iter() {
                 if written >= maxevents { break; }
                 if let Some(watch) = watch_opt {
                     let mut poll_ev: i16 = 0;
@@ -287,6 +301,7 @@ fn deliver_poll_result_phys(waiter: &PollWaiter, phys_offset: u64) -> usize {
                     if rev & POLLIN  != 0 { epoll_rev |= EPOLLIN; }
                     if rev & POLLOUT != 0 { epoll_rev |= EPOLLOUT; }
                     if rev & POLLERR != 0 { epoll_rev |= EPOLLERR; }
+                    if rev & POLLHUP != 0 { epoll_rev |= EPOLLHUP; }
                     if epoll_rev != 0 {
                         let ev = EpollEvent { events: epoll_rev, data: watch.data };
                         let dst = (base + written as u64 * 12) as *mut EpollEvent;
@@ -302,35 +317,6 @@ fn deliver_poll_result_phys(waiter: &PollWaiter, phys_offset: u64) -> usize {
 
 // ── Waiter-scan helpers ────────────────────────────────────────────────────
 
-/// Check if a poll waiter is watching fd=0 (stdin) for POLLIN.
-/// Called while POLL_WAITERS is held (poll_waiter is borrowed from it).
-fn poll_waiter_watches_stdin(waiter: &PollWaiter, phys_offset: u64) -> bool {
-    match waiter.kind {
-        PollWaiterKind::Poll { nfds } => {
-            let base = (phys_offset + waiter.phys_buf) as *const PollFd;
-            for i in 0..nfds as usize {
-                let pfd = unsafe { *base.add(i) };
-                if pfd.fd == 0 && (pfd.events & POLLIN) != 0 {
-                    return true;
-                }
-            }
-            false
-        }
-        PollWaiterKind::EpollWait { epoll_id, .. } => {
-            // POLL_WAITERS → EPOLL_INSTANCES is the allowed nesting
-            let instances = EPOLL_INSTANCES.lock();
-            if let Some(inst) = instances.get(epoll_id) {
-                for watch in inst.watches.iter().flatten() {
-                    if watch.fd == 0 && (watch.events & EPOLLIN) != 0 {
-                        return true;
-                    }
-                }
-            }
-            false
-        }
-    }
-}
-
 /// Check if a poll waiter is watching a specific IPC channel for POLLIN.
 /// Called while POLL_WAITERS is held.
 fn poll_waiter_watches_channel(
@@ -339,15 +325,14 @@ fn poll_waiter_watches_channel(
     phys_offset: u64,
 ) -> bool {
     let pid = waiter.pid;
-    if pid >= MAX_PROCS { return false; }
     match waiter.kind {
         PollWaiterKind::Poll { nfds } => {
             let map = FD_CHANNEL_MAP.lock();
             let base = (phys_offset + waiter.phys_buf) as *const PollFd;
             for i in 0..nfds as usize {
                 let pfd = unsafe { *base.add(i) };
-                if pfd.fd >= 0 && (pfd.fd as usize) < MAX_FILES_PER_PROC {
-                    if map[pid][pfd.fd as usize] == channel_id && (pfd.events & POLLIN) != 0 {
+                if pfd.fd >= 0 {
+                    if channel_of(&map, pid, pfd.fd as usize) == channel_id && (pfd.events & POLLIN) != 0 {
                         return true;
                     }
                 }
@@ -360,8 +345,8 @@ fn poll_waiter_watches_channel(
             let map = FD_CHANNEL_MAP.lock();
             if let Some(inst) = instances.get(epoll_id) {
                 for watch in inst.watches.iter().flatten() {
-                    if watch.fd >= 0 && (watch.fd as usize) < MAX_FILES_PER_PROC {
-                        if map[pid][watch.fd as usize] == channel_id
+                    if watch.fd >= 0 {
+                        if channel_of(&map, pid, watch.fd as usize) == channel_id
                             && (watch.events & EPOLLIN) != 0
                         {
                             return true;
@@ -376,83 +361,68 @@ fn poll_waiter_watches_channel(
 
 // ── Wakeup hooks ───────────────────────────────────────────────────────────
 
-/// Called by the keyboard ISR (after stdin_wakeup) with IF=0.
+/// Re-check every blocked poll/epoll_wait caller and wake the ones with
+/// something ready now. Called with IF=0 and no locks held — by the
+/// keyboard and serial ISRs (after stdin_wakeup) and by pipe ends whenever
+/// data arrives, space frees up or an end closes (see `process::pipe`).
+/// Readiness is level-triggered (`fd_check_ready` asks each handle), so
+/// this doesn't need to know what changed, only that something might have.
 ///
-/// Delivers POLLIN on fd=0 to any process blocked in poll/epoll_wait that
-/// is watching stdin.
-///
-/// Unlike the serial ISR (which only calls this when `tty::feed_input` says
-/// a byte was really queued), the PS/2 keyboard ISR calls this on *every*
-/// raw scancode — including key-release codes and modifier presses, which
-/// push nothing into `KEYBOARD_BUFFER` (see `keyboard::process_scancode`).
-/// A real keypress is always followed by its release scancode shortly
-/// after; if that release lands while a process is already blocked in a
-/// *fresh* `poll()` call (e.g. waiting for the *next* keystroke), this must
-/// not wake it with a spurious "0 fds ready" — that's indistinguishable
-/// from a real timeout to the caller (confirmed root cause of BusyBox
-/// ash's line editor exiting after ~2 keystrokes: `poll()` returning 0 is
-/// read as EOF by `libbb/read_key.c`). So: only actually wake the process
-/// once `deliver_poll_result_phys` finds something genuinely ready; put an
-/// otherwise-untouched waiter back so a real future event or its own
-/// timeout still wakes it normally.
-pub(crate) fn poll_wakeup_for_fd0() {
+/// A waiter with nothing ready is put back untouched. This matters for
+/// the keyboard: the PS/2 ISR calls this on *every* raw scancode —
+/// including key-release codes and modifier presses, which push nothing
+/// into `KEYBOARD_BUFFER` (see `keyboard::process_scancode`). A real
+/// keypress is always followed by its release scancode shortly after; if
+/// that release lands while a process is already blocked in a *fresh*
+/// `poll()` call (e.g. waiting for the *next* keystroke), this must not
+/// wake it with a spurious "0 fds ready" — that's indistinguishable from
+/// a real timeout to the caller (confirmed root cause of BusyBox ash's
+/// line editor exiting after ~2 keystrokes: `poll()` returning 0 is read
+/// as EOF by `libbb/read_key.c`). Leaving it blocked means a real future
+/// event or its own timeout still wakes it normally.
+pub(crate) fn poll_wakeup_ready() {
     let phys_offset = crate::memory::physical_memory_offset().as_u64();
 
-    // Take the waiter (if any) watching fd=0 for POLLIN.
-    let waiter = {
-        let mut waiters = POLL_WAITERS.lock();
-        let mut found = None;
-        for (i, slot) in waiters.iter().enumerate() {
-            if let Some(w) = slot {
-                if poll_waiter_watches_stdin(w, phys_offset) {
-                    found = Some(i);
-                    break;
-                }
-            }
+    let mut next_pid = 0;
+    loop {
+        // Copy the next waiter out so POLL_WAITERS isn't held across the
+        // readiness check (which takes SCHEDULER) — same order as the other
+        // hooks. Its entry stays put until something is ready, so the
+        // common nothing-ready case (every keyboard scancode) never touches
+        // the heap from the ISR.
+        let Some(waiter) = POLL_WAITERS.lock().range(next_pid..).next().map(|(_, w)| *w) else { break; };
+        next_pid = waiter.pid + 1;
+
+        let count = deliver_poll_result_phys(&waiter, phys_offset);
+        if count == 0 {
+            continue;
         }
-        found.and_then(|i| waiters[i].take())
-    };
+        POLL_WAITERS.lock().remove(&waiter.pid);
 
-    let Some(waiter) = waiter else { return; };
-
-    let count = deliver_poll_result_phys(&waiter, phys_offset);
-    if count == 0 {
-        if waiter.pid < MAX_PROCS {
-            POLL_WAITERS.lock()[waiter.pid] = Some(waiter);
+        // Cancel timeout timer (if any)
+        if let Some(tid) = waiter.timer_id {
+            crate::time::hrtimer::cancel(tid);
         }
-        return;
-    }
 
-    // Cancel timeout timer (if any)
-    if let Some(tid) = waiter.timer_id {
-        crate::time::hrtimer::cancel(tid);
+        let mut sched = crate::process::scheduler::local_scheduler();
+        sched.wake_with_retval(waiter.pid, count as u64);
     }
-
-    let mut sched = crate::process::scheduler::local_scheduler();
-    sched.wake_with_retval(waiter.pid, count as u64);
-    // sched guard dropped; caller (keyboard ISR) still holds IF=0
 }
 
 /// Called from sys_sendmsg after enqueuing a message (CHANNELS released).
 ///
 /// Wakes any process blocked in poll/epoll_wait watching `channel_id` for POLLIN.
 pub(crate) fn poll_wakeup_for_channel(channel_id: ChannelId) {
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
 
     let phys_offset = crate::memory::physical_memory_offset().as_u64();
 
     let waiter = {
         let mut waiters = POLL_WAITERS.lock();
-        let mut found = None;
-        for (i, slot) in waiters.iter().enumerate() {
-            if let Some(w) = slot {
-                if poll_waiter_watches_channel(w, channel_id, phys_offset) {
-                    found = Some(i);
-                    break;
-                }
-            }
-        }
-        found.and_then(|i| waiters[i].take())
+        let found = waiters.values()
+            .find(|w| poll_waiter_watches_channel(w, channel_id, phys_offset))
+            .map(|w| w.pid);
+        found.and_then(|pid| waiters.remove(&pid))
     };
 
     let Some(waiter) = waiter else { return; };
@@ -468,11 +438,7 @@ pub(crate) fn poll_wakeup_for_channel(channel_id: ChannelId) {
 
 /// Cancel a pending poll/epoll waiter for a process (called on exit).
 pub(super) fn poll_cancel_waiter(pid: usize) {
-    if pid >= MAX_PROCS { return; }
-    let waiter = {
-        let mut waiters = POLL_WAITERS.lock();
-        waiters[pid].take()
-    };
+    let waiter = POLL_WAITERS.lock().remove(&pid);
     if let Some(w) = waiter {
         if let Some(tid) = w.timer_id {
             crate::time::hrtimer::cancel(tid);
@@ -486,8 +452,7 @@ pub(super) fn poll_cancel_waiter(pid: usize) {
 /// released, satisfying the lock order: POLL_WAITERS → SCHEDULER.
 /// The timer has already fired so there is nothing to cancel.
 pub(crate) fn poll_clear_on_timeout(pid: usize) {
-    if pid >= MAX_PROCS { return; }
-    POLL_WAITERS.lock()[pid] = None;
+    POLL_WAITERS.lock().remove(&pid);
 }
 
 // ── Helper: translate user VA → phys + page-boundary check ────────────────
@@ -516,13 +481,10 @@ fn check_epoll_ready_uva(
     events_ptr: u64,
     maxevents: usize,
 ) -> usize {
-    let instances = EPOLL_INSTANCES.lock();
-    let inst = match instances.get(epoll_id) {
-        Some(i) => i,
-        None    => return 0,
-    };
+    // Copied out for the same reason as in `deliver_poll_result_phys`.
+    let Some(watches) = epoll_watches(epoll_id) else { return 0 };
     let mut written = 0usize;
-    for watch_opt in inst.watches.iter() {
+    for watch_opt in watches.iter() {
         if written >= maxevents { break; }
         if let Some(watch) = watch_opt {
             let mut poll_ev: i16 = 0;
@@ -533,6 +495,7 @@ fn check_epoll_ready_uva(
             if rev & POLLIN  != 0 { epoll_rev |= EPOLLIN; }
             if rev & POLLOUT != 0 { epoll_rev |= EPOLLOUT; }
             if rev & POLLERR != 0 { epoll_rev |= EPOLLERR; }
+            if rev & POLLHUP != 0 { epoll_rev |= EPOLLHUP; }
             if epoll_rev != 0 {
                 let ev = EpollEvent { events: epoll_rev, data: watch.data };
                 unsafe {
@@ -571,7 +534,7 @@ pub(super) fn sys_poll(fds_ptr: u64, nfds: u32, timeout_ms: i32) -> SyscallResul
     // `irq` is deliberately never dropped on the slow (blocking) path below
     // — it ends in `jump_to_user` (`-> !`), so interrupts intentionally
     // stay off across that jump; see `sys_read`'s WouldBlock arm.
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
 
     let pid = crate::process::scheduler::current_pid().unwrap_or(0);
 
@@ -616,15 +579,13 @@ pub(super) fn sys_poll(fds_ptr: u64, nfds: u32, timeout_ms: i32) -> SyscallResul
     };
 
     // Store waiter
-    if pid < MAX_PROCS {
-        POLL_WAITERS.lock()[pid] = Some(PollWaiter {
-            pid,
-            phys_buf,
-            phys_len: buf_size,
-            kind: PollWaiterKind::Poll { nfds },
-            timer_id,
-        });
-    }
+    POLL_WAITERS.lock().insert(pid, PollWaiter {
+        pid,
+        phys_buf,
+        phys_len: buf_size,
+        kind: PollWaiterKind::Poll { nfds },
+        timer_id,
+    });
 
     let next_tf = {
         let mut sched = crate::process::scheduler::local_scheduler();
@@ -651,7 +612,7 @@ pub(super) fn sys_epoll_create(_size: i32) -> SyscallResult {
 
     let handle = alloc::boxed::Box::new(EpollHandle { epoll_id });
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     let mut sched = crate::process::scheduler::local_scheduler();
     match sched.running_mut() {
         Some(proc) => {
@@ -665,10 +626,10 @@ pub(super) fn sys_epoll_create(_size: i32) -> SyscallResult {
                     set_epoll_fd(pid, fd, epoll_id);
                     fd as i64
                 }
-                Err(_) => {
+                Err(e) => {
                     drop(sched);
                     EPOLL_INSTANCES.lock().free(epoll_id);
-                    errno::EINVAL
+                    e.into()
                 }
             }
         }
@@ -685,7 +646,6 @@ pub(super) fn sys_epoll_create(_size: i32) -> SyscallResult {
 /// epoll_ctl(233) — modify an epoll instance's interest list.
 pub(super) fn sys_epoll_ctl(epfd: i32, op: i32, fd: i32, event_ptr: u64) -> SyscallResult {
     let pid = crate::process::scheduler::current_pid().unwrap_or(0);
-    if pid >= MAX_PROCS { return errno::ESRCH; }
     if epfd < 0 || (epfd as usize) >= MAX_FILES_PER_PROC { return errno::EBADF; }
 
     let epoll_id = get_epoll_fd(pid, epfd as usize);
@@ -760,7 +720,6 @@ pub(super) fn sys_epoll_wait(epfd: i32, events_ptr: u64, maxevents: i32, timeout
     if let Err(e) = validate_user_buffer(events_ptr, buf_size) { return e; }
 
     let pid = crate::process::scheduler::current_pid().unwrap_or(0);
-    if pid >= MAX_PROCS { return errno::ESRCH; }
     if epfd < 0 || (epfd as usize) >= MAX_FILES_PER_PROC { return errno::EBADF; }
 
     let epoll_id = get_epoll_fd(pid, epfd as usize);
@@ -769,7 +728,7 @@ pub(super) fn sys_epoll_wait(epfd: i32, events_ptr: u64, maxevents: i32, timeout
     // `irq` is deliberately never dropped on the slow (blocking) path below
     // — it ends in `jump_to_user` (`-> !`), so interrupts intentionally
     // stay off across that jump; see `sys_read`'s WouldBlock arm.
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
 
     // Fast path: check readiness now
     let ready = check_epoll_ready_uva(epoll_id, pid, events_ptr, maxevents as usize);
@@ -800,15 +759,13 @@ pub(super) fn sys_epoll_wait(epfd: i32, events_ptr: u64, maxevents: i32, timeout
         None
     };
 
-    if pid < MAX_PROCS {
-        POLL_WAITERS.lock()[pid] = Some(PollWaiter {
-            pid,
-            phys_buf,
-            phys_len: buf_size,
-            kind: PollWaiterKind::EpollWait { epoll_id, maxevents: maxevents as usize },
-            timer_id,
-        });
-    }
+    POLL_WAITERS.lock().insert(pid, PollWaiter {
+        pid,
+        phys_buf,
+        phys_len: buf_size,
+        kind: PollWaiterKind::EpollWait { epoll_id, maxevents: maxevents as usize },
+        timer_id,
+    });
 
     let next_tf = {
         let mut sched = crate::process::scheduler::local_scheduler();
@@ -817,3 +774,32 @@ pub(super) fn sys_epoll_wait(epfd: i32, events_ptr: u64, maxevents: i32, timeout
     unsafe { crate::process::trapframe::jump_to_user(next_tf) }
 }
 
+
+// ── Test hooks ─────────────────────────────────────────────────────────────
+
+/// Register `pid` in every pid-keyed side table the way `sys_socket`,
+/// `sys_epoll_create` and `sys_poll` do: channel `id` and epoll instance
+/// `id` on `fd`, plus a timer-less poll waiter with nothing to check.
+/// Only for `hw_tests.rs`, which has no user process to make real calls.
+#[cfg(test)]
+pub(crate) fn register_side_tables(pid: usize, fd: usize, id: usize) {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    super::ipc::set_fd_channel(pid, fd, id);
+    set_epoll_fd(pid, fd, id);
+    POLL_WAITERS.lock().insert(pid, PollWaiter {
+        pid,
+        phys_buf: 0,
+        phys_len: 0,
+        kind: PollWaiterKind::Poll { nfds: 0 },
+        timer_id: None,
+    });
+}
+
+/// What the side tables hold for `pid`: its channel and epoll ids on `fd`
+/// (0 = none) and whether it has a poll waiter.
+#[cfg(test)]
+pub(crate) fn side_tables(pid: usize, fd: usize) -> (ChannelId, EpollInstanceId, bool) {
+    let _irq = crate::interrupts::InterruptGuard::new();
+    let channel = channel_of(&FD_CHANNEL_MAP.lock(), pid, fd);
+    (channel, get_epoll_fd(pid, fd), POLL_WAITERS.lock().contains_key(&pid))
+}
@@ -1,11 +1,11 @@
 // kernel/src/process/syscall/process_ctl.rs
 //
 // Process lifecycle + control syscalls: fork/clone/exec/exit/waitpid/kill/
-// getpid/setpgid/getpgid/setsid/yield/nanosleep/arch_prctl/set_tid_address.
+// getpid/setpgid/getpgid/setsid/yield/nanosleep/arch_prctl/set_tid_address/
+// times.
 
 use spin::Mutex;
 use core::sync::atomic::Ordering;
-use crate::serial_println;
 use crate::process::TrapFrame;
 use super::{
     errno, SyscallResult, with_scheduler, validate_user_buffer, resolve_path,
@@ -66,32 +66,70 @@ pub(super) fn sys_arch_prctl(code: i32, addr: u64) -> SyscallResult {
 /// set_tid_address(218): pid_t set_tid_address(int *tidptr)
 ///
 /// Used by mlibc during thread startup to register a clear-child-tid pointer.
-/// In our single-threaded model we just return the current PID.
+/// The pointer is ignored; returns the caller's tid, as Linux does.
 pub(super) fn sys_set_tid_address(_tidptr: u64) -> SyscallResult {
-    sys_getpid()
+    sys_gettid()
 }
 
 /// sys_yield — voluntary context switch.
 ///
-/// Reuses the same `switch_to_next` the timer ISR uses for preemption: puts
-/// the caller back at the tail of its run queue (as Ready) and switches to
-/// the next Ready process. If nothing else is Ready, `switch_to_next`
-/// returns the caller's own TrapFrame unchanged and this is a no-op.
+/// `Scheduler::yield_current` — the same switch the timer ISR's
+/// `switch_to_next` does for preemption, counted as voluntary: puts the
+/// caller back at the tail of its run queue (as Ready) and switches to
+/// the next Ready process. If nothing else is Ready, the caller's own
+/// TrapFrame comes back unchanged and this is a no-op.
 pub(super) fn sys_yield() -> SyscallResult {
     let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
 
     // `_irq` is deliberately never dropped: this always ends in
     // `jump_to_user` (`-> !`), so interrupts intentionally stay off across
     // the jump — see `sys_read`'s WouldBlock arm for the same reasoning.
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
 
     let next_tf = {
         let mut scheduler = crate::process::scheduler::local_scheduler();
-        // Pre-set rax=0 in the on-stack frame *before* switch_to_next copies
+        // Pre-set rax=0 in the on-stack frame *before* yield_current copies
         // it into the process's saved TrapFrame, so that whenever this
         // process runs again, the syscall returns 0.
         unsafe { (*(tf_ptr as *mut TrapFrame)).rax = 0; }
-        scheduler.switch_to_next(tf_ptr)
+        scheduler.yield_current(tf_ptr)
+    };
+
+    unsafe { crate::process::trapframe::jump_to_user(next_tf) }
+}
+
+/// sys_sched_yield_to (custom #405): long sched_yield_to(pid_t pid)
+///
+/// Directed `sched_yield`: hand the CPU straight to `pid` if it's Ready
+/// (`Scheduler::yield_to`), for a producer or lock releaser that just woke
+/// it. Returns 0 once the caller runs again after a handoff. If `pid` is
+/// alive but not Ready (blocked, stopped, already running — i.e. the
+/// caller itself), falls back to a plain yield and returns 1 instead, so
+/// the caller can tell. `ESRCH` if no such process, without yielding.
+pub(super) fn sys_sched_yield_to(pid: i64) -> SyscallResult {
+    if pid <= 0 {
+        return errno::EINVAL;
+    }
+    let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
+
+    // `_irq` is deliberately never dropped — see sys_yield above.
+    let _irq = crate::interrupts::InterruptGuard::new();
+
+    let next_tf = {
+        let mut scheduler = crate::process::scheduler::local_scheduler();
+        if !scheduler.iter_all().any(|p| p.pid.0 == pid as usize) {
+            return errno::ESRCH;
+        }
+        // Pre-set the caller's return value, as sys_yield does; `yield_to`
+        // leaves it untouched if it declines.
+        unsafe { (*(tf_ptr as *mut TrapFrame)).rax = 0; }
+        match scheduler.yield_to(pid as usize, tf_ptr) {
+            Some(tf) => tf,
+            None => {
+                unsafe { (*(tf_ptr as *mut TrapFrame)).rax = 1; }
+                scheduler.yield_current(tf_ptr)
+            }
+        }
     };
 
     unsafe { crate::process::trapframe::jump_to_user(next_tf) }
@@ -115,7 +153,7 @@ pub(super) fn sys_nanosleep(ns: u64) -> SyscallResult {
     let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
 
     // `_irq` is deliberately never dropped — see sys_yield above.
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
 
     let next_tf = {
         let mut scheduler = crate::process::scheduler::local_scheduler();
@@ -127,7 +165,7 @@ pub(super) fn sys_nanosleep(ns: u64) -> SyscallResult {
         }
 
         let pid = scheduler.current_pid().map(|p| p.0).unwrap_or(0);
-        serial_println!("[DBG] nanosleep PID {} for {} ns (expiry={})", pid, ns, expiry);
+        crate::debug!("[DBG] nanosleep PID {} for {} ns (expiry={})", pid, ns, expiry);
 
         // Register the hrtimer.  QUEUE lock is acquired and released inside
         // start(); we still hold the scheduler lock, which is safe because
@@ -142,12 +180,63 @@ pub(super) fn sys_nanosleep(ns: u64) -> SyscallResult {
     unsafe { crate::process::trapframe::jump_to_user(next_tf) }
 }
 
+/// getpid(39): the caller's thread group id (`Process::tgid`) — the same
+/// for every thread of one program.
 pub(super) fn sys_getpid() -> SyscallResult {
+    with_scheduler(|scheduler| {
+        scheduler.running_ref().map(|p| p.tgid.0 as SyscallResult).unwrap_or(0)
+    })
+}
+
+/// gettid(186): the caller's own id (`Process::pid`). Equal to `getpid()`
+/// except in a thread created by `clone()`.
+pub(super) fn sys_gettid() -> SyscallResult {
     with_scheduler(|scheduler| {
         scheduler.current_pid().map(|pid| pid.0 as SyscallResult).unwrap_or(0)
     })
 }
 
+/// times(100): clock_t times(struct tms *buf)
+///
+/// `struct tms { clock_t tms_utime, tms_stime, tms_cutime, tms_cstime; }`
+/// (4 × i64), all in `_SC_CLK_TCK` units (`clockevent::USER_HZ`, scaled
+/// from `TICK_HZ` timer ticks by `ticks_to_clock_t`). `tms_utime` is
+/// `Process::cpu_ticks` — this kernel doesn't split user from system
+/// time, so `tms_stime`/`tms_cstime` are always 0 and every tick lands in
+/// the user column. A NULL `buf` is allowed (Linux accepts it too) and
+/// just skips the write. Returns time since boot (`scheduler::
+/// total_ticks()`, same units) — the denominator for a CPU-usage %.
+pub(super) fn sys_times(buf_ptr: u64) -> SyscallResult {
+    if buf_ptr != 0 {
+        if let Err(e) = validate_user_buffer(buf_ptr, 32) {
+            return e;
+        }
+    }
+
+    let (utime, cutime) = {
+        let scheduler = crate::process::irq_guard::SchedGuard::lock();
+        match scheduler.running_ref() {
+            Some(proc) => (proc.cpu_ticks, proc.children_cpu_ticks),
+            None => return errno::ESRCH,
+        }
+    };
+    let to_clock_t = crate::time::clockevent::ticks_to_clock_t;
+
+    if buf_ptr != 0 {
+        // Same direct-write reasoning as `sys_clock_gettime`: the caller's
+        // own page table is active, and an unmapped page demand-faults in.
+        unsafe {
+            let ptr = buf_ptr as *mut i64;
+            ptr.write_unaligned(to_clock_t(utime) as i64);
+            ptr.add(1).write_unaligned(0);
+            ptr.add(2).write_unaligned(to_clock_t(cutime) as i64);
+            ptr.add(3).write_unaligned(0);
+        }
+    }
+
+    to_clock_t(crate::process::scheduler::total_ticks()) as SyscallResult
+}
+
 /// sys_exit — terminate the calling process and switch immediately.
 ///
 /// Performs an immediate full context switch via kill_and_switch_tf +
@@ -158,7 +247,12 @@ pub(super) fn sys_exit(status: i32) -> SyscallResult {
 
     let reason = format!("exit({})", status);
 
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    // Still live, on our own stack: free any zombies collected since the
+    // last waitpid() here, so a parent that never waits again doesn't
+    // leave them parked until the idle task gets to them.
+    crate::process::scheduler::reap_pending();
+
+    let irq = crate::interrupts::InterruptGuard::new();
 
     let (dead_pid, parent_to_notify, tf_ptr, old_files) = {
         let mut scheduler = crate::process::scheduler::local_scheduler();
@@ -175,7 +269,7 @@ pub(super) fn sys_exit(status: i32) -> SyscallResult {
         // via SIGCHLD — matches POSIX (individual thread exits are not a
         // waitpid()/SIGCHLD event, only the process as a whole exiting is).
         let (old_files, parent_to_notify) = if let Some(proc) = scheduler.running_mut() {
-            proc.exit_status = status;
+            proc.exit_status = crate::process::wait_status::ExitReason::from_exit_code(status);
             let parent = if proc.is_thread { None } else { proc.parent_pid };
             let old = core::mem::replace(
                 &mut proc.files,
@@ -187,7 +281,7 @@ pub(super) fn sys_exit(status: i32) -> SyscallResult {
         };
         let dead_pid = scheduler.current_pid().map(|p| p.0).unwrap_or(0);
         let ptr = scheduler.kill_and_switch_tf(&reason);
-        serial_println!("  → Process exited, switching immediately (full TrapFrame restore)");
+        crate::debug!("  → Process exited, switching immediately (full TrapFrame restore)");
         (dead_pid, parent_to_notify, ptr, old_files)
     };
 
@@ -204,25 +298,26 @@ pub(super) fn sys_exit(status: i32) -> SyscallResult {
     // see its doc comment); the parent may already *be* `running` here if
     // `kill_and_switch_tf` above just picked it as the next process to
     // schedule, so `notify_child_death` checks that case itself.
-    crate::process::scheduler::local_scheduler().notify_child_death(dead_pid, parent_to_notify);
-    drop(irq); // interrupts back on from here — matches pre-refactor sti() placement
-
-    // Cancel any pending poll/epoll wait and clear side tables
-    cancel_all_waiters(dead_pid);
+    {
+        let mut scheduler = crate::process::scheduler::local_scheduler();
+        scheduler.notify_child_death(dead_pid, parent_to_notify);
+        // Cancel any pending poll/epoll wait and clear side tables
+        cancel_all_waiters(dead_pid, &mut scheduler);
+    }
+    drop(irq); // back to the entry IF state — off, for a syscall (SFMASK)
 
-    // Explicit terminal `sti` (redundant with the target trapframe's own
-    // saved RFLAGS, which `iretq` restores regardless — kept only to
-    // preserve pre-refactor behavior exactly). Not wrapped in a guard on
-    // purpose: this is a one-shot action immediately followed by a
-    // diverging call, not a scope with multiple exit paths to protect.
+    // No `sti` before the jump: `jump_to_user` starts with `cli` anyway,
+    // and the `iretq` at the end loads the next process's own saved RFLAGS
+    // (IF included). The explicit `sti` that used to sit here only opened
+    // a window for a tick to land between the two.
     unsafe {
-        core::arch::asm!("sti");
         crate::process::trapframe::jump_to_user(tf_ptr);
     }
 }
 
 /// Cancel every side-table registration a dying process might be holding
-/// (pending poll/epoll waits, futex waiters). Must run for *every* death
+/// (pending poll/epoll waits, its epoll and socket fd rows, futex waiters,
+/// sleep/timeout hrtimers). Must run for *every* death
 /// path, not just `sys_exit`'s: `resolve_signals`'s uncaught-signal
 /// Terminate path (`Scheduler::kill_and_switch_tf`, driven by hardware
 /// faults and now routinely by job-control signals like `kill(-pgid,
@@ -236,16 +331,21 @@ pub(super) fn sys_exit(status: i32) -> SyscallResult {
 /// nanosleep loop (`jobctl_test.c`'s group-kill test) followed immediately
 /// by starting an interactive `ash` — its own `poll()`-based input loop
 /// intermittently died after 1-2 characters, traced back to exactly this.
-pub(crate) fn cancel_all_waiters(pid: usize) {
+///
+/// `sched` is the caller's SCHEDULER guard: a PI futex the dead process
+/// still owned is handed to its top waiter through it.
+pub(crate) fn cancel_all_waiters(pid: usize, sched: &mut crate::process::scheduler::Scheduler) {
     super::poll::poll_cancel_waiter(pid);
     super::poll::clear_epoll_fd_all(pid);
-    super::sync::futex_cancel_waiter(pid);
+    super::ipc::clear_fd_channels(pid);
+    super::sync::futex_cancel_waiter(pid, sched);
+    crate::time::hrtimer::cancel_pid(pid);
 }
 
 pub(super) fn sys_fork() -> SyscallResult {
     let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
 
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
 
     // Real fork() semantics: the child gets a copy of the parent's *live*
     // FPU/SSE registers, not whatever was last stashed in the parent's own
@@ -256,47 +356,67 @@ pub(super) fn sys_fork() -> SyscallResult {
     let mut parent_fpu_state = crate::process::fpu::default_state();
     unsafe { crate::process::fpu::save(&mut parent_fpu_state); }
 
-    // Collect what we need from the running process
-    let (child_as, parent_pid, parent_fs_base, files, child_tf, parent_cwd, parent_pgid, parent_exe_name) = {
+    // Collect what we need from the running process. The address space
+    // and fd table are only *referenced* here and copied once the lock is
+    // dropped: both copies allocate heavily (page tables straight from
+    // BUDDY), and SCHEDULER is the lock the timer ISR needs — see
+    // `lock_order`.
+    let (parent_as, parent_files, parent_pid, parent_fs_base, child_tf, parent_cwd, parent_pgid, parent_exe_name, parent_affinity) = {
         let scheduler = crate::process::scheduler::local_scheduler();
+        // Cheap early-out before copying the whole address space;
+        // `add_process` below is still the authoritative check.
+        if scheduler.process_count() >= crate::process::scheduler::MAX_PROCESSES {
+            return errno::EAGAIN;
+        }
         match scheduler.running_ref() {
             Some(proc) => {
                 // Build child TrapFrame: same as parent but rax=0 (fork returns 0 in child)
                 let mut tf_copy = unsafe { *tf_ptr };
                 tf_copy.rax = 0;
-
-                match unsafe { proc.address_space.fork() } {
-                    Ok(child_as) => (child_as, proc.pid, proc.fs_base, proc.files.lock().clone(), tf_copy, proc.cwd.clone(), proc.pgid, proc.exe_name.clone()),
-                    Err(e) => {
-                        serial_println!("fork: address_space.fork() failed: {}", e);
-                        return errno::ENOMEM;
-                    }
-                }
+                (proc.address_space.clone(), proc.files.clone(), proc.pid, proc.fs_base, tf_copy, proc.cwd.clone(), proc.pgid, proc.exe_name.clone(), proc.affinity)
             }
             None => return errno::ESRCH,
         }
     };
 
+    // Interrupts are still off (`_irq`), so the parent can't run — or
+    // change its mappings — between the snapshot above and this copy.
+    let child_as = match unsafe { parent_as.fork() } {
+        Ok(child_as) => child_as,
+        Err(e) => {
+            crate::warn!("fork: address_space.fork() failed: {}", e);
+            return errno::ENOMEM;
+        }
+    };
+    let files = parent_files.lock().fork();
+
     let kernel_stack = crate::init::processes::allocate_kernel_stack();
+    let pid = crate::process::scheduler::local_scheduler().allocate_pid();
 
-    let child_pid = {
-        let mut scheduler = crate::process::scheduler::local_scheduler();
-        let pid = scheduler.allocate_pid();
-
-        let mut child = alloc::boxed::Box::new(
-            crate::process::Process::new_user_from_fork(
-                pid, parent_pid, alloc::boxed::Box::new(child_tf),
-                kernel_stack, child_as, files, parent_cwd, parent_pgid, parent_exe_name,
-                alloc::boxed::Box::new(parent_fpu_state),
-            )
-        );
-        child.fs_base = parent_fs_base; // inherit TLS base from parent
-        child.set_name("child");
-        scheduler.add_process(child);
-        pid.0 as SyscallResult
-    };
+    let mut child = alloc::boxed::Box::new(
+        crate::process::Process::new_user_from_fork(
+            pid, parent_pid, alloc::boxed::Box::new(child_tf),
+            kernel_stack, child_as, files, parent_cwd, parent_pgid, parent_exe_name,
+            alloc::boxed::Box::new(parent_fpu_state),
+        )
+    );
+    child.fs_base = parent_fs_base; // inherit TLS base from parent
+    child.affinity = parent_affinity;
+    child.set_name("child");
+
+    // The guard is a temporary, so a refused `child` comes back with the
+    // lock already released — dropping it (and its copy of the address
+    // space and fd table) here is safe.
+    let added = crate::process::scheduler::local_scheduler().add_process(child);
+    if let Err(refused) = added {
+        drop(refused);
+        // The pid and kernel stack are still ours to return.
+        crate::process::scheduler::local_scheduler().release_pid(pid);
+        crate::init::processes::free_kernel_stack(kernel_stack);
+        return errno::EAGAIN;
+    }
 
-    child_pid  // parent sees child PID
+    pid.0 as SyscallResult  // parent sees child PID
 }
 
 // ── clone(56) ──────────────────────────────────────────────────────────────
@@ -325,11 +445,27 @@ pub(super) fn sys_fork() -> SyscallResult {
 /// `pthread_join()` never calls `waitpid()` on a tid, so the kernel reaps a
 /// thread's `Process` immediately instead of waiting for a collector that
 /// will never come).
+///
+/// There is no `flags` argument: every call is what Linux would spell
+/// `CLONE_VM | CLONE_FILES | CLONE_THREAD` — shared address space (the
+/// `Arc` is the refcount; `AddressSpace`'s `Drop` frees frames and page
+/// tables only when the last sharer is torn down), shared fd table, same
+/// thread group (`Process::tgid`). `fork()` is the only way to get the
+/// opposite.
+///
+/// `entry` and `stack` must be user addresses (`EFAULT` otherwise): they go
+/// straight into the new thread's TrapFrame, and a non-canonical `rip`/
+/// `rsp` would fault on the kernel's own `iretq` into it rather than in the
+/// thread.
 pub(super) fn sys_clone(entry: u64, stack: u64, _tcb: u64) -> SyscallResult {
-    let (parent_pid, address_space, files, parent_cwd, parent_pgid, parent_exe_name) = {
+    if validate_user_buffer(entry, 1).is_err() || validate_user_buffer(stack, 0).is_err() {
+        return errno::EFAULT;
+    }
+
+    let (parent_pid, tgid, address_space, files, parent_cwd, parent_pgid, parent_exe_name, parent_affinity) = {
         let sched = crate::process::scheduler::local_scheduler();
         match sched.running_ref() {
-            Some(proc) => (proc.pid, proc.address_space.clone(), proc.files.clone(), proc.cwd.clone(), proc.pgid, proc.exe_name.clone()),
+            Some(proc) => (proc.pid, proc.tgid, proc.address_space.clone(), proc.files.clone(), proc.cwd.clone(), proc.pgid, proc.exe_name.clone(), proc.affinity),
             None => return errno::ESRCH,
         }
     };
@@ -349,20 +485,35 @@ pub(super) fn sys_clone(entry: u64, stack: u64, _tcb: u64) -> SyscallResult {
         }
     });
 
+    // Everything that allocates happens outside the scheduler lock, as in
+    // `sys_fork`: it's held only for the pid and the final queueing.
     let kernel_stack = crate::init::processes::allocate_kernel_stack();
-
-    let mut scheduler = crate::process::irq_guard::SchedGuard::lock();
-    let pid = scheduler.allocate_pid();
+    let pid = crate::process::irq_guard::SchedGuard::lock().allocate_pid();
 
     let mut thread = alloc::boxed::Box::new(
-        crate::process::Process::new_thread(
-            pid, parent_pid,
-            x86_64::VirtAddr::new(entry), x86_64::VirtAddr::new(stack),
-            kernel_stack, address_space, files, owned_stack_vma, parent_cwd, parent_pgid, parent_exe_name,
-        )
+        crate::process::Process::new_thread(pid, tgid, parent_pid, crate::process::ThreadInit {
+            entry: x86_64::VirtAddr::new(entry),
+            stack: x86_64::VirtAddr::new(stack),
+            kernel_stack,
+            address_space,
+            files,
+            owned_stack_vma,
+            cwd: parent_cwd,
+            pgid: parent_pgid,
+            exe_name: parent_exe_name,
+        })
     );
+    thread.affinity = parent_affinity;
     thread.set_name("thread");
-    scheduler.add_process(thread);
+
+    let added = crate::process::irq_guard::SchedGuard::lock().add_process(thread);
+    if let Err(refused) = added {
+        // Same cleanup as `sys_fork`'s.
+        drop(refused);
+        crate::process::irq_guard::SchedGuard::lock().release_pid(pid);
+        crate::init::processes::free_kernel_stack(kernel_stack);
+        return errno::EAGAIN;
+    }
     pid.0 as SyscallResult
 }
 
@@ -449,22 +600,22 @@ pub(super) fn sys_exec(path_ptr: usize, argv_ptr: usize, envp_ptr: usize) -> Sys
         Err(e) => return e,
     };
 
-    serial_println!("sys_exec: loading '{}' (argc={}, envc={})", name, argv.len(), envp.len());
+    crate::debug!("sys_exec: loading '{}' (argc={}, envc={})", name, argv.len(), envp.len());
 
     let resolved_path = match resolve_exec_path(name) {
         Ok(p) => p,
         Err(e) => {
-            serial_println!("sys_exec: '{}' not found", name);
+            crate::debug!("sys_exec: '{}' not found", name);
             return e;
         }
     };
-    serial_println!("sys_exec: resolved '{}' -> '{}'", name, resolved_path);
+    crate::debug!("sys_exec: resolved '{}' -> '{}'", name, resolved_path);
 
     let elf_owned = {
         let mut handle = match crate::fs::vfs::open(&resolved_path, crate::fs::types::OpenFlags::RDONLY) {
             Ok(h) => h,
             Err(e) => {
-                serial_println!("sys_exec: '{}' not found", name);
+                crate::debug!("sys_exec: '{}' not found", name);
                 return e.as_i64();
             }
         };
@@ -482,12 +633,13 @@ pub(super) fn sys_exec(path_ptr: usize, argv_ptr: usize, envp_ptr: usize) -> Sys
 
     // Load ELF without any lock — may take time and allocates frames.
     // Every process's stack starts small and grows on demand (see
-    // memory::elf_loader::STACK_PAGES/STACK_MAX_PAGES) — no per-program
-    // size to pass in.
-    let loaded = match unsafe { crate::memory::elf_loader::load_elf(&elf_owned, 0, &argv, &envp) } {
+    // memory::elf_loader::STACK_PAGES/STACK_MAX_PAGES) — the default
+    // layout is right for anything exec'd.
+    let layout = crate::memory::elf_loader::UserLayout::default();
+    let loaded = match unsafe { crate::memory::elf_loader::load_elf(&elf_owned, 0, &layout, &argv, &envp) } {
         Ok(l) => l,
         Err(e) => {
-            serial_println!("sys_exec: load_elf failed: {}", e);
+            crate::warn!("sys_exec: load_elf failed: {}", e);
             return if e == "ELF loader: argv/envp too large for the initial stack page" {
                 errno::E2BIG
             } else {
@@ -510,6 +662,18 @@ pub(super) fn sys_exec(path_ptr: usize, argv_ptr: usize, envp_ptr: usize) -> Sys
     // instrumentation this fix was diagnosed with).
     drop(elf_owned);
 
+    // Past the last failure point, so close FD_CLOEXEC fds now — and
+    // outside SCHEDULER, for `sys_close`'s reason: closing a pipe end can
+    // wake its peer, which takes the scheduler lock itself.
+    let files = {
+        let guard = crate::process::irq_guard::SchedGuard::lock();
+        guard.running_ref().map(|proc| proc.files.clone())
+    };
+    if let Some(files) = files {
+        let _irq = crate::interrupts::InterruptGuard::new();
+        files.lock().close_on_exec();
+    }
+
     crate::ktrace!(crate::debug::SCHED, "exec: load_elf done, going cli");
     // `_irq` is deliberately never dropped on the success path — this
     // function always ends in `jump_to_user` (`-> !`), so interrupts
@@ -517,7 +681,7 @@ pub(super) fn sys_exec(path_ptr: usize, argv_ptr: usize, envp_ptr: usize) -> Sys
     // arm for the same reasoning. On the `None` early-return below, the
     // guard drops automatically and does re-enable interrupts, same as
     // before this was RAII.
-    let _irq = crate::process::irq_guard::InterruptGuard::new();
+    let _irq = crate::interrupts::InterruptGuard::new();
     crate::ktrace!(crate::debug::SCHED, "exec: cli done, taking scheduler lock");
 
     let next_tf = {
@@ -580,6 +744,10 @@ pub(super) fn sys_exec(path_ptr: usize, argv_ptr: usize, envp_ptr: usize) -> Sys
                 *proc.fpu_state = crate::process::fpu::default_state();
                 unsafe { crate::process::fpu::restore(&proc.fpu_state); }
 
+                // Breakpoint addresses mean nothing in the new image.
+                proc.hw_breaks = crate::process::hw_break::HwBreakpoints::new();
+                unsafe { crate::process::hw_break::load(&proc.hw_breaks); }
+
                 crate::ktrace!(crate::debug::SCHED, "exec: activating new CR3");
                 unsafe { proc.address_space.activate(); }
                 crate::ktrace!(crate::debug::SCHED, "exec: CR3 active, jumping to entry={:#x}", proc.trapframe.rip);
@@ -661,9 +829,15 @@ pub(super) fn sys_waitpid(pid_arg: i64, status_ptr: usize, options: i32) -> Sysc
         if let Err(e) = validate_user_buffer(status_ptr as u64, 4) { return e; }
     }
 
+    // A pid whose slot has been recycled names a child that was already
+    // reaped — never whichever process holds that slot now.
+    if pid_arg > 0 && crate::process::irq_guard::SchedGuard::lock().pid_is_stale(pid_arg as usize) {
+        return errno::ECHILD;
+    }
+
     let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
 
-    let irq = crate::process::irq_guard::InterruptGuard::new();
+    let irq = crate::interrupts::InterruptGuard::new();
 
     enum Outcome {
         Return(SyscallResult),
@@ -677,8 +851,15 @@ pub(super) fn sys_waitpid(pid_arg: i64, status_ptr: usize, options: i32) -> Sysc
     // since the only thing that could release it (this call, mid-return)
     // can't resume until that spin gives up, which it never does. Same bug
     // class as `sys_kill`'s doc comment describes.
+    // The reaped zombie, if any, handed out of the locked block below so
+    // `Process::teardown` runs with SCHEDULER released (see its doc
+    // comment) — plus any zombies `notify_child_death` already collected
+    // on a blocked parent's behalf since the last waitpid().
+    let mut reaped: alloc::vec::Vec<crate::process::Process> = alloc::vec::Vec::new();
+
     let outcome = {
         let mut scheduler = crate::process::scheduler::local_scheduler();
+        reaped.append(&mut scheduler.take_pending_reaps());
 
         let caller_pid = scheduler.current_pid();
         let caller_pgid = scheduler.running_ref().map(|p| p.pgid).unwrap_or(0);
@@ -707,14 +888,19 @@ pub(super) fn sys_waitpid(pid_arg: i64, status_ptr: usize, options: i32) -> Sysc
         };
 
         if let Some(pos) = zombie_pos {
-            // Safe to free the zombie's kernel stack and write the status
-            // straight into `status_ptr` right here: we're running on the
-            // *parent's* stack in the parent's own address space (this is
-            // its own waitpid() syscall), never the dead child's.
+            // Safe to tear the zombie down and write the status straight
+            // into `status_ptr`: we're running on the *parent's* stack in
+            // the parent's own address space (this is its own waitpid()
+            // syscall), never the dead child's.
             let proc = scheduler.wait_queue.remove(pos).unwrap();
             let status = proc.wait_status_word();
             let pid = proc.pid.0;
-            crate::init::processes::free_kernel_stack(proc.kernel_stack);
+            let child_ticks = proc.cpu_ticks + proc.children_cpu_ticks;
+            if let Some(parent) = scheduler.running_mut() {
+                parent.children_cpu_ticks += child_ticks;
+            }
+            scheduler.release_pid(proc.pid);
+            reaped.push(*proc);
             crate::debug::inc_reaps();
             if status_ptr != 0 {
                 // write_unaligned, not write: `validate_user_buffer` only
@@ -763,6 +949,11 @@ pub(super) fn sys_waitpid(pid_arg: i64, status_ptr: usize, options: i32) -> Sysc
         }
     };
 
+    // SCHEDULER is released; interrupts are still off (`irq`).
+    for mut proc in reaped {
+        proc.teardown();
+    }
+
     match outcome {
         Outcome::Return(v) => {
             drop(irq);
@@ -837,6 +1028,9 @@ pub(super) fn sys_kill(target_pid: i64, sig: u32) -> SyscallResult {
             0
         } else {
             let target_pid = target_pid as usize;
+            if sched.pid_is_stale(target_pid) {
+                return errno::ESRCH;
+            }
             let is_self = sched.current_pid().map(|p| p.0) == Some(target_pid);
             if is_self {
                 if let Some(proc) = sched.running_mut() {
@@ -925,6 +1119,80 @@ pub(super) fn sys_getpgid(pid: i64) -> SyscallResult {
     })
 }
 
+/// sched_setaffinity(203): int sched_setaffinity(pid_t pid, size_t len,
+/// const cpu_set_t *mask)
+///
+/// `pid` 0 is the caller. Only the first `unsigned long` of `mask` is
+/// read — CPUs past 63 don't exist here, and on this uniprocessor kernel
+/// neither does anything past CPU 0 (`cpuset::NR_CPUS`). A mask with no
+/// existing CPU in it (including `len` 0) is `EINVAL`, same as Linux;
+/// otherwise the restricted mask is stored on the target.
+pub(super) fn sys_sched_setaffinity(pid: i64, len: usize, mask_ptr: u64) -> SyscallResult {
+    if pid < 0 {
+        return errno::EINVAL;
+    }
+    let n = len.min(8);
+    let mut bytes = [0u8; 8];
+    if n > 0 {
+        if let Err(e) = validate_user_buffer(mask_ptr, n) {
+            return e;
+        }
+        unsafe { core::ptr::copy_nonoverlapping(mask_ptr as *const u8, bytes.as_mut_ptr(), n); }
+    }
+    let Some(set) = crate::process::cpuset::CpuSet::from_user(u64::from_le_bytes(bytes)) else {
+        return errno::EINVAL;
+    };
+
+    with_scheduler(|sched| {
+        let caller_pid = sched.current_pid().map(|p| p.0).unwrap_or(0);
+        let target_pid = if pid == 0 { caller_pid } else { pid as usize };
+        let target = if target_pid == caller_pid {
+            sched.running_mut()
+        } else {
+            sched.find_process_mut(target_pid)
+        };
+        match target {
+            Some(p) => {
+                p.affinity = set;
+                0
+            }
+            None => errno::ESRCH,
+        }
+    })
+}
+
+/// sched_getaffinity(204): int sched_getaffinity(pid_t pid, size_t len,
+/// cpu_set_t *mask)
+///
+/// Writes the target's mask as one `unsigned long` and returns its size
+/// (8) — the raw syscall's contract; libc zeroes the rest of the caller's
+/// `cpu_set_t`. `len` must be a non-zero multiple of 8, as on Linux.
+pub(super) fn sys_sched_getaffinity(pid: i64, len: usize, mask_ptr: u64) -> SyscallResult {
+    if pid < 0 || len < 8 || !len.is_multiple_of(8) {
+        return errno::EINVAL;
+    }
+    if let Err(e) = validate_user_buffer(mask_ptr, 8) {
+        return e;
+    }
+
+    // The mask as a non-negative result, so ESRCH can share the return.
+    let bits = with_scheduler(|sched| {
+        let caller_pid = sched.current_pid().map(|p| p.0).unwrap_or(0);
+        let target_pid = if pid == 0 { caller_pid } else { pid as usize };
+        let target = if target_pid == caller_pid {
+            sched.running_ref().map(|p| p.affinity)
+        } else {
+            sched.find_process_mut(target_pid).map(|p| p.affinity)
+        };
+        target.map(|set| set.bits() as SyscallResult).unwrap_or(errno::ESRCH)
+    });
+    if bits < 0 {
+        return bits;
+    }
+    unsafe { core::ptr::write_unaligned(mask_ptr as *mut u64, bits as u64); }
+    8
+}
+
 /// setsid(112): pid_t setsid(void)
 ///
 /// No real session tracking exists — approximated as "become your own
@@ -1,12 +1,14 @@
 // kernel/src/process/syscall/sync.rs
 //
-// futex(202) — wait/wake, backs mlibc mutexes/condvars.
+// futex(202) — wait/wake, backs mlibc mutexes/condvars; plus the
+// priority-inheriting LOCK_PI/UNLOCK_PI pair.
 
+use alloc::{collections::{BTreeMap, VecDeque}, vec::Vec};
 use spin::Mutex;
 use core::sync::atomic::Ordering;
+use x86_64::VirtAddr;
 use crate::process::TrapFrame;
 use super::{errno, SyscallResult, validate_user_buffer, CURRENT_SYSCALL_TF};
-use super::ipc::MAX_PROCS;
 
 // ── futex(202) ─────────────────────────────────────────────────────────────
 
@@ -15,30 +17,48 @@ use super::ipc::MAX_PROCS;
 ///
 /// WAIT blocks the caller if `*uaddr == val` until a matching WAKE (timeouts
 /// are not supported — `_timeout` is ignored, matching the previous stub).
-/// WAKE wakes up to `val` waiters registered on the same `uaddr`.
+/// WAKE wakes up to `val` waiters registered on the same futex, oldest
+/// first, and returns how many it woke. `uaddr` must be 4-byte aligned
+/// (`EINVAL`) and mapped (`EFAULT`).
 ///
-/// Waiters are scoped by (uaddr, address space) — not raw uaddr alone —
-/// because every process's anonymous mmap region starts at the same fixed
-/// base (see USER_MMAP_BASE), so two unrelated processes can easily end up
-/// with numerically identical uaddrs for e.g. mlibc's internal malloc lock.
-/// Without this scoping a WAKE in one process could wake a waiter in a
-/// completely unrelated one. There is no real thread-sharing yet (sys_clone
-/// is ENOSYS), so today this is one-waiter-per-address-space in practice,
-/// but the scoping is what makes it correct once real threads land.
+/// Which waiters count as "the same futex" is `FutexKey`'s job — see there.
+///
+/// THE LOST-WAKEUP RACE. The waker's protocol is "store the new value, then
+/// WAKE"; the waiter's is "check the value, then sleep". If the check and
+/// the registration weren't atomic with respect to the waker, a WAKE could
+/// land in between, find nobody registered, and the waiter would then sleep
+/// on a value that has already changed — forever. So the authoritative
+/// compare happens with interrupts off *and* `FUTEX_WAITERS` held, re-read
+/// through the physmap (a kernel address, so it can't fault with the lock
+/// held), and the caller is registered before either is released. A WAKE
+/// can only run before that (and the compare then sees the new value:
+/// `EAGAIN`) or after it (and finds the waiter).
+///
+/// Spurious wakeups are allowed by the futex contract — WAIT returning 0
+/// only means "look again", never "the value changed". mlibc's callers
+/// already loop on it.
+///
+/// LOCK_PI/UNLOCK_PI are Linux's priority-inheritance mutex ops — see
+/// `futex_lock_pi`.
 pub(super) fn sys_futex(uaddr: u64, futex_op: i32, val: i32, _timeout: u64) -> SyscallResult {
     const FUTEX_WAIT: i32 = 0;
     const FUTEX_WAKE: i32 = 1;
+    const FUTEX_LOCK_PI: i32 = 6;
+    const FUTEX_UNLOCK_PI: i32 = 7;
     const FUTEX_PRIVATE_FLAG: i32 = 128;
 
     let op = futex_op & !FUTEX_PRIVATE_FLAG;
+    let private = futex_op & FUTEX_PRIVATE_FLAG != 0;
+
+    if validate_user_buffer(uaddr, 4).is_err() { return errno::EFAULT; }
+    if !uaddr.is_multiple_of(4) { return errno::EINVAL; }
 
     match op {
         FUTEX_WAIT => {
-            if validate_user_buffer(uaddr, 4).is_err() { return errno::EFAULT; }
-            let current = unsafe { *(uaddr as *const i32) };
-            if current != val {
-                return errno::EAGAIN;
-            }
+            // Plain user read first, with interrupts still on: if the page
+            // isn't populated yet this is where demand paging brings it in,
+            // so the locked re-read below finds it mapped.
+            let _ = unsafe { core::ptr::read_volatile(uaddr as *const i32) };
 
             let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
 
@@ -46,18 +66,24 @@ pub(super) fn sys_futex(uaddr: u64, futex_op: i32, val: i32, _timeout: u64) -> S
             // ends in `jump_to_user` (`-> !`), so interrupts intentionally
             // stay off across that jump; see `sys_read`'s WouldBlock arm
             // for the same reasoning.
-            let _irq = crate::process::irq_guard::InterruptGuard::new();
+            let _irq = crate::interrupts::InterruptGuard::new();
 
-            let (pid, as_id) = {
+            let (pid, key, phys) = {
                 let sched = crate::process::scheduler::local_scheduler();
-                match sched.running_ref() {
-                    Some(proc) => (proc.pid.0, proc.address_space.root_frame().start_address().as_u64()),
-                    None => return errno::ESRCH,
-                }
+                let Some(proc) = sched.running_ref() else { return errno::ESRCH };
+                let Some(phys) = (unsafe { proc.address_space.translate_addr(VirtAddr::new(uaddr)) }) else {
+                    return errno::EFAULT;
+                };
+                (proc.pid.0, FutexKey::new(proc, uaddr, private, phys), phys)
             };
 
-            if pid < MAX_PROCS {
-                FUTEX_WAITERS.lock()[pid] = Some(FutexWaiter { uaddr, as_id });
+            {
+                let mut waiters = FUTEX_WAITERS.lock();
+                let word = (crate::memory::physical_memory_offset() + phys.as_u64()).as_ptr::<i32>();
+                if unsafe { core::ptr::read_volatile(word) } != val {
+                    return errno::EAGAIN;
+                }
+                waiters.entry(key).or_default().push_back(pid);
             }
 
             let next_tf = {
@@ -68,60 +94,395 @@ pub(super) fn sys_futex(uaddr: u64, futex_op: i32, val: i32, _timeout: u64) -> S
             unsafe { crate::process::trapframe::jump_to_user(next_tf) }
         }
         FUTEX_WAKE => {
-            let _irq = crate::process::irq_guard::InterruptGuard::new();
+            let _irq = crate::interrupts::InterruptGuard::new();
 
-            let as_id = {
+            let key = {
                 let sched = crate::process::scheduler::local_scheduler();
-                match sched.running_ref() {
-                    Some(proc) => proc.address_space.root_frame().start_address().as_u64(),
-                    None => return errno::ESRCH,
-                }
+                let Some(proc) = sched.running_ref() else { return errno::ESRCH };
+                // Nothing mapped means nobody can be waiting on it either.
+                let Some(phys) = (unsafe { proc.address_space.translate_addr(VirtAddr::new(uaddr)) }) else {
+                    return 0;
+                };
+                FutexKey::new(proc, uaddr, private, phys)
             };
 
-            let max_wake = if val <= 0 { i32::MAX } else { val };
-            let mut woken_pids = [0usize; 8];
-            let mut woken_count = 0usize;
-            {
+            let max_wake = if val <= 0 { usize::MAX } else { val as usize };
+            let woken: Vec<usize> = {
                 let mut waiters = FUTEX_WAITERS.lock();
-                for (pid, slot) in waiters.iter_mut().enumerate() {
-                    if woken_count >= woken_pids.len() || woken_count as i32 >= max_wake {
-                        break;
-                    }
-                    if let Some(w) = slot {
-                        if w.uaddr == uaddr && w.as_id == as_id {
-                            woken_pids[woken_count] = pid;
-                            woken_count += 1;
-                            *slot = None;
-                        }
-                    }
+                let Some(queue) = waiters.get_mut(&key) else { return 0 };
+                let n = max_wake.min(queue.len());
+                let woken = queue.drain(..n).collect();
+                if queue.is_empty() {
+                    waiters.remove(&key);
                 }
-            }
+                woken
+            };
 
-            if woken_count > 0 {
+            if !woken.is_empty() {
                 let mut sched = crate::process::scheduler::local_scheduler();
-                for &pid in &woken_pids[..woken_count] {
+                for &pid in &woken {
                     sched.wake_with_retval(pid, 0);
                 }
             }
-            woken_count as i64
+            woken.len() as i64
         }
+        FUTEX_LOCK_PI => futex_lock_pi(uaddr, private),
+        FUTEX_UNLOCK_PI => futex_unlock_pi(uaddr, private),
         _ => errno::ENOSYS,
     }
 }
 
-#[derive(Clone, Copy)]
-struct FutexWaiter {
-    uaddr: u64,
-    as_id: u64,
+// ── Priority-inheritance futexes ───────────────────────────────────────────
+
+/// Set in a PI futex word while anyone is blocked on it, so the owner's
+/// unlock fast path (`cmpxchg(tid → 0)`) fails and it calls UNLOCK_PI.
+const FUTEX_WAITERS_BIT: u32 = 0x8000_0000;
+/// Set in a PI futex word handed over because its owner died holding it
+/// (`futex_cancel_waiter`): the new owner has the lock, but whatever it
+/// protected may be half-updated.
+const FUTEX_OWNER_DIED: u32 = 0x4000_0000;
+/// The owner's tid in a PI futex word; 0 = unlocked.
+const FUTEX_TID_MASK: u32 = 0x3fff_ffff;
+
+/// One contended PI futex: its owner, and the LOCK_PI callers blocked on it
+/// as `(pid, effective priority when it blocked)`.
+struct PiFutex {
+    owner: usize,
+    waiters: Vec<(usize, u8)>,
+}
+
+impl PiFutex {
+    /// Unlink the waiter the lock goes to next: the highest priority,
+    /// oldest first among equals.
+    fn take_next(&mut self) -> Option<usize> {
+        // `max_by_key` keeps the *last* maximum; reversed, that's the
+        // oldest of the highest-priority waiters.
+        let i = self.waiters.iter().enumerate().rev().max_by_key(|(_, &(_, pri))| pri)?.0;
+        Some(self.waiters.remove(i).0)
+    }
+}
+
+/// Every PI futex someone is blocked on. Kept apart from `FUTEX_WAITERS`:
+/// these are woken by priority, not FIFO, each one is handed the lock as it
+/// wakes, and the owner is tracked so inheritance can be worked out per
+/// futex (`inherited_floor`). Keys with no waiters are removed.
+static PI_WAITERS: Mutex<BTreeMap<FutexKey, PiFutex>> = Mutex::new(BTreeMap::new());
+
+/// The priority `pid` inherits: the best one blocked on any PI futex it
+/// owns, or 0 if none. Recomputed (and applied with `Process::set_pi_floor`)
+/// whenever a futex changes hands or a waiter comes or goes, so releasing
+/// one of two contended mutexes keeps the other's boost.
+fn inherited_floor(futexes: &BTreeMap<FutexKey, PiFutex>, pid: usize) -> u8 {
+    futexes.values()
+        .filter(|f| f.owner == pid)
+        .flat_map(|f| f.waiters.iter().map(|&(_, pri)| pri))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Everything LOCK_PI/UNLOCK_PI need about the caller and the word.
+struct PiTarget {
+    pid: usize,
+    priority: u8,
+    key: FutexKey,
+    word: *const core::sync::atomic::AtomicU32,
+}
+
+/// Touch the PI word at `uaddr` with a write — a no-op `fetch_or(0)` — so a
+/// COW page is broken (and a demand-zero one faulted in) before the kernel
+/// starts storing tids into the frame through the physmap. Like FUTEX_WAIT's
+/// plain read, this runs before interrupts go off.
+fn pi_prefault(uaddr: u64) {
+    unsafe { (*(uaddr as *const core::sync::atomic::AtomicU32)).fetch_or(0, Ordering::SeqCst) };
+}
+
+/// Resolve `uaddr` for a PI op. Must run with interrupts off, after
+/// `pi_prefault`.
+fn pi_target(uaddr: u64, private: bool) -> Result<PiTarget, SyscallResult> {
+    let sched = crate::process::scheduler::local_scheduler();
+    let Some(proc) = sched.running_ref() else { return Err(errno::ESRCH) };
+    if proc.pid.0 as u64 & !(FUTEX_TID_MASK as u64) != 0 {
+        // A recycled slot's generation pushed the pid past what the word
+        // can hold; see `Pid`.
+        return Err(errno::EINVAL);
+    }
+    let Some(phys) = (unsafe { proc.address_space.translate_addr(VirtAddr::new(uaddr)) }) else {
+        return Err(errno::EFAULT);
+    };
+    Ok(PiTarget {
+        pid: proc.pid.0,
+        priority: proc.effective_priority,
+        key: FutexKey::new(proc, uaddr, private, phys),
+        word: (crate::memory::physical_memory_offset() + phys.as_u64()).as_ptr(),
+    })
+}
+
+/// FUTEX_LOCK_PI: take the PI mutex at `uaddr` (Linux's protocol — the word
+/// is the owner's tid, or 0 when free, plus `FUTEX_WAITERS_BIT`).
+/// Userspace only calls this after its own `cmpxchg(0 → tid)` failed.
+///
+/// Free: the caller takes it and returns 0 at once. Owned by the caller:
+/// `EDEADLK`. Owned by a pid that doesn't exist: `ESRCH`. Otherwise the
+/// owner inherits the caller's effective priority (`Process::set_pi_floor`,
+/// re-queued by `Scheduler::reprioritize`) so a medium-priority process
+/// can't keep it — and therefore the caller — off the CPU, and the caller
+/// blocks until `futex_unlock_pi` hands it the lock (or the owner dies
+/// holding it — see `futex_cancel_waiter`); it returns 0 as the new owner.
+///
+/// Interrupts stay off from the first read of the word to
+/// `block_current`, so on this single CPU nothing can unlock in between;
+/// the two locks (`PI_WAITERS`, then `SCHEDULER`) are never held together.
+fn futex_lock_pi(uaddr: u64, private: bool) -> SyscallResult {
+    pi_prefault(uaddr);
+    let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
+    // Never dropped on the blocking path — see FUTEX_WAIT.
+    let _irq = crate::interrupts::InterruptGuard::new();
+
+    let t = match pi_target(uaddr, private) {
+        Ok(t) => t,
+        Err(e) => return e,
+    };
+    let word = unsafe { &*t.word };
+
+    let (owner, floor) = {
+        let futexes = PI_WAITERS.lock();
+        let cur = word.load(Ordering::SeqCst);
+        if cur & FUTEX_TID_MASK == 0 {
+            word.store(t.pid as u32 | (cur & FUTEX_WAITERS_BIT), Ordering::SeqCst);
+            return 0;
+        }
+        let owner = (cur & FUTEX_TID_MASK) as usize;
+        (owner, inherited_floor(&futexes, owner).max(t.priority))
+    };
+    if owner == t.pid {
+        return errno::EDEADLK;
+    }
+
+    let boosted = crate::process::scheduler::local_scheduler()
+        .reprioritize(owner, |p| p.set_pi_floor(floor));
+    if !boosted {
+        return errno::ESRCH;
+    }
+
+    {
+        let mut futexes = PI_WAITERS.lock();
+        word.fetch_or(FUTEX_WAITERS_BIT, Ordering::SeqCst);
+        // The word is authoritative: an uncontended lock (userspace's own
+        // cmpxchg) or a handoff may have changed hands since the entry
+        // was made.
+        let futex = futexes.entry(t.key).or_insert_with(|| PiFutex { owner, waiters: Vec::new() });
+        futex.owner = owner;
+        futex.waiters.push((t.pid, t.priority));
+    }
+
+    let next_tf = {
+        let mut scheduler = crate::process::scheduler::local_scheduler();
+        unsafe { (*(tf_ptr as *mut TrapFrame)).rax = 0; }
+        scheduler.block_current(tf_ptr)
+    };
+    unsafe { crate::process::trapframe::jump_to_user(next_tf) }
+}
+
+/// FUTEX_UNLOCK_PI: release the PI mutex at `uaddr`, which the caller must
+/// own (`EPERM` otherwise).
+///
+/// The caller keeps only what it inherits through the PI mutexes it still
+/// owns. With waiters, the lock goes straight to the highest-priority one
+/// (oldest first among equals): its tid is written into the word — with
+/// `FUTEX_WAITERS_BIT` if others are still queued, and then it inherits
+/// the best of *their* priorities in turn — and it's woken already owning
+/// the lock. If it now outranks the caller, the CPU is handed to it
+/// directly (`Scheduler::yield_to`) rather than waiting for the next tick;
+/// the caller's 0 is returned whenever it next runs.
+fn futex_unlock_pi(uaddr: u64, private: bool) -> SyscallResult {
+    pi_prefault(uaddr);
+    let tf_ptr = CURRENT_SYSCALL_TF.load(Ordering::Relaxed) as *const TrapFrame;
+    let _irq = crate::interrupts::InterruptGuard::new();
+
+    let t = match pi_target(uaddr, private) {
+        Ok(t) => t,
+        Err(e) => return e,
+    };
+    let word = unsafe { &*t.word };
+
+    let (handoff, my_floor) = {
+        let mut futexes = PI_WAITERS.lock();
+        if (word.load(Ordering::SeqCst) & FUTEX_TID_MASK) as usize != t.pid {
+            return errno::EPERM;
+        }
+        let next = futexes.get_mut(&t.key).and_then(|futex| {
+            let pid = futex.take_next()?;
+            futex.owner = pid;
+            Some((pid, !futex.waiters.is_empty()))
+        });
+        let handoff = match next {
+            Some((pid, more)) => {
+                let bit = if more { FUTEX_WAITERS_BIT } else { 0 };
+                word.store(pid as u32 | bit, Ordering::SeqCst);
+                if !more {
+                    futexes.remove(&t.key);
+                }
+                Some((pid, inherited_floor(&futexes, pid)))
+            }
+            None => {
+                word.store(0, Ordering::SeqCst);
+                None
+            }
+        };
+        (handoff, inherited_floor(&futexes, t.pid))
+    };
+
+    let mut scheduler = crate::process::scheduler::local_scheduler();
+    scheduler.reprioritize(t.pid, |p| p.set_pi_floor(my_floor));
+    let Some((next, floor)) = handoff else { return 0 };
+
+    scheduler.wake_with_retval(next, 0);
+    scheduler.reprioritize(next, |p| p.set_pi_floor(floor));
+
+    let mine = scheduler.running_ref().map_or(0, |p| p.effective_priority);
+    let theirs = scheduler.iter_all().find(|p| p.pid.0 == next).map_or(0, |p| p.effective_priority);
+    if theirs <= mine {
+        return 0;
+    }
+    unsafe { (*(tf_ptr as *mut TrapFrame)).rax = 0; }
+    let Some(next_tf) = scheduler.yield_to(next, tf_ptr) else { return 0 };
+    drop(scheduler);
+    unsafe { crate::process::trapframe::jump_to_user(next_tf) }
 }
 
-/// One outstanding FUTEX_WAIT per PID — mirrors POLL_WAITERS/RECV_WAITER.
-static FUTEX_WAITERS: Mutex<[Option<FutexWaiter>; MAX_PROCS]> = Mutex::new([None; MAX_PROCS]);
+/// The PI word `key` names, reached through `proc` — a waiter on it, so
+/// for a `Private` key an address in the same address space. `None` if
+/// that address is no longer mapped.
+fn pi_word(key: FutexKey, proc: &crate::process::Process) -> Option<&'static core::sync::atomic::AtomicU32> {
+    let phys = match key {
+        FutexKey::Shared { phys } => phys,
+        FutexKey::Private { uaddr, .. } => {
+            unsafe { proc.address_space.translate_addr(VirtAddr::new(uaddr)) }?.as_u64()
+        }
+    };
+    let word = (crate::memory::physical_memory_offset() + phys).as_ptr::<core::sync::atomic::AtomicU32>();
+    Some(unsafe { &*word })
+}
 
-/// Clear a pending futex wait for a process (called on exit).
-pub(super) fn futex_cancel_waiter(pid: usize) {
-    if pid < MAX_PROCS {
-        FUTEX_WAITERS.lock()[pid] = None;
+/// Identifies one futex across WAIT/WAKE calls.
+///
+/// `Private` — (address space, virtual address) — for anything in a
+/// private mapping, not the physical address behind `uaddr`: after
+/// `fork()` the parent's pages are copy-on-write too, so a futex word a
+/// thread is waiting on can move to a fresh frame the moment a sibling
+/// thread stores to it — and that sibling's WAKE, keyed by the *new*
+/// frame, would miss the waiter. Linux keys private mappings the same way,
+/// for the same reason. The address-space half matters because every
+/// process's anonymous mmap region starts at the same `USER_MMAP_BASE`, so
+/// unrelated processes routinely have numerically identical futex
+/// addresses (e.g. mlibc's internal malloc lock); threads share an
+/// `AddressSpace` (see `sys_clone`) and so share keys, as they must.
+///
+/// `Shared` — the physical address — for a word inside a `MAP_SHARED`
+/// mapping (`VmaKind::Shared`), unless the caller passed
+/// `FUTEX_PRIVATE_FLAG`. Those frames never move (no COW, mapped for the
+/// VMA's whole life), and they're what two forked processes sharing the
+/// mapping actually have in common — their address spaces (and so their
+/// `Private` keys) differ.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FutexKey {
+    /// `as_id` is the PML4 physical address — stable and unique per live
+    /// address space.
+    Private { as_id: u64, uaddr: u64 },
+    Shared { phys: u64 },
+}
+
+impl FutexKey {
+    fn new(proc: &crate::process::Process, uaddr: u64, private: bool, phys: x86_64::PhysAddr) -> Self {
+        let shared_mapping = proc.address_space.find_vma(uaddr)
+            .is_some_and(|vma| vma.kind == crate::memory::vma::VmaKind::Shared);
+        if shared_mapping && !private {
+            FutexKey::Shared { phys: phys.as_u64() }
+        } else {
+            FutexKey::Private { as_id: proc.address_space.root_frame().start_address().as_u64(), uaddr }
+        }
     }
 }
 
+/// Waiting pids per futex, FIFO. Keys with no waiters are removed, so this
+/// only ever holds futexes someone is actually blocked on. Full pids, not
+/// slot indices — the wake goes through `wake_with_retval`, which matches
+/// on the full pid.
+static FUTEX_WAITERS: Mutex<BTreeMap<FutexKey, VecDeque<usize>>> = Mutex::new(BTreeMap::new());
+
+/// Clear a pending futex wait for a process (called on exit), and settle
+/// the PI futexes it was involved in:
+/// - one it was blocked on loses it as a waiter, and its owner drops
+///   whatever priority it was inheriting from it;
+/// - one it owned goes to its top waiter, exactly like `futex_unlock_pi`,
+///   but with `FUTEX_OWNER_DIED` set in the word, so the new owner (woken
+///   with 0) can tell the state it protects may be inconsistent — rather
+///   than leaving every waiter blocked on a word naming a dead tid.
+///
+/// Takes `sched` because every caller but `sys_exit` already holds
+/// SCHEDULER; `PI_WAITERS` is released before it's used.
+pub(crate) fn futex_cancel_waiter(pid: usize, sched: &mut crate::process::scheduler::Scheduler) {
+    FUTEX_WAITERS.lock().retain(|_, queue| {
+        queue.retain(|&p| p != pid);
+        !queue.is_empty()
+    });
+
+    let mut handoffs: Vec<(FutexKey, usize, bool)> = Vec::new();
+    let mut floors: Vec<(usize, u8)> = Vec::new();
+    {
+        let mut futexes = PI_WAITERS.lock();
+        for (&key, futex) in futexes.iter_mut() {
+            let before = futex.waiters.len();
+            futex.waiters.retain(|&(p, _)| p != pid);
+            if futex.waiters.len() != before {
+                floors.push((futex.owner, 0));
+            }
+            if futex.owner == pid {
+                if let Some(next) = futex.take_next() {
+                    futex.owner = next;
+                    handoffs.push((key, next, !futex.waiters.is_empty()));
+                    floors.push((next, 0));
+                }
+            }
+        }
+        futexes.retain(|_, futex| !futex.waiters.is_empty());
+        for (owner, floor) in floors.iter_mut() {
+            *floor = inherited_floor(&futexes, *owner);
+        }
+    }
+
+    for (key, next, more) in handoffs {
+        let bit = if more { FUTEX_WAITERS_BIT } else { 0 };
+        let word = sched.iter_all().find(|p| p.pid.0 == next).and_then(|p| pi_word(key, p));
+        if let Some(word) = word {
+            word.store(next as u32 | FUTEX_OWNER_DIED | bit, Ordering::SeqCst);
+        }
+        sched.wake_with_retval(next, 0);
+    }
+    for (owner, floor) in floors {
+        if owner != pid {
+            sched.reprioritize(owner, |p| p.set_pi_floor(floor));
+        }
+    }
+}
+
+/// Only for `hw_tests.rs`: what `futex_lock_pi` records when `waiter`
+/// blocks on the `MAP_SHARED` word at physical address `phys`, owned by
+/// `owner` — the queue entry and the owner's new floor — without touching
+/// the word or blocking anyone.
+#[cfg(test)]
+pub(crate) fn pi_block(
+    phys: u64,
+    owner: usize,
+    waiter: usize,
+    priority: u8,
+    sched: &mut crate::process::scheduler::Scheduler,
+) {
+    let floor = {
+        let mut futexes = PI_WAITERS.lock();
+        let futex = futexes.entry(FutexKey::Shared { phys }).or_insert_with(|| PiFutex { owner, waiters: Vec::new() });
+        futex.waiters.push((waiter, priority));
+        inherited_floor(&futexes, owner)
+    };
+    sched.reprioritize(owner, |p| p.set_pi_floor(floor));
+}
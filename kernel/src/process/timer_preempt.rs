@@ -4,19 +4,64 @@
 //
 // PREVIOUS DESIGN:
 //   Context switch every N ticks (modulo counter).  No concept of
-//   time slices — just a fixed throttle.
+//   time slices — just a fixed throttle.  The handler also rotated its
+//   own round-robin over a flat process list, duplicating (and
+//   disagreeing with) the scheduler's selection logic.
 //
 // CURRENT DESIGN:
 //   Every tick: send EOI, call scheduler.tick() which decrements the
 //   running process's remaining time slice and handles aging.
-//   When tick() returns true (slice exhausted): do full context switch.
+//   When tick() returns true (slice exhausted): do full context switch
+//   via `Scheduler::switch_to_next` — the only place that picks the next
+//   process, activates its address space, and sets its kernel stack.
 //   Otherwise: return immediately (same process continues).
+//
+//   There is deliberately no tick counter of its own here: how many ticks
+//   a process runs before being preempted is `Scheduler::quantum_for`'s
+//   decision alone (per-priority, tunable via BASE_QUANTUM /
+//   PRIORITY_QUANTUM_BONUS in scheduler.rs), not a fixed throttle.
+//
+// LOCK ALREADY HELD:
+//   The scheduler lock is only taken with IF=0, so in a correct kernel the
+//   tick can never land while it's held. If something breaks that rule
+//   (an `sti` while a guard is still alive), a blocking `lock()` here spins
+//   forever: the holder can't run again until this ISR returns. So the
+//   tick uses `try_local_scheduler` and, when the lock is busy, just
+//   resumes the interrupted code — no accounting, no preemption, counted
+//   in `sched_lock_busy_ticks` (/proc/kdebug) so the broken rule still
+//   shows up. hrtimer wakeups already drained for that tick can't be
+//   dropped, so they're parked in `DEFERRED_WAKES` for the next tick.
 
 use core::arch::global_asm;
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use super::trapframe::TrapFrame;
 
-static TICK_COUNT: AtomicU64 = AtomicU64::new(0);
+/// hrtimer PIDs a lock-busy tick couldn't wake; 0 = empty slot (PID 0 is
+/// idle, never an hrtimer target). Only the timer ISR touches these, and
+/// it doesn't nest, so plain loads/stores would do — atomics just avoid
+/// `static mut`.
+static DEFERRED_WAKES: [AtomicUsize; 8] = [const { AtomicUsize::new(0) }; 8];
+
+fn defer_wakes(pids: &[usize]) {
+    for &pid in pids {
+        match DEFERRED_WAKES.iter().find(|slot| slot.load(Ordering::Relaxed) == 0) {
+            Some(slot) => slot.store(pid, Ordering::Relaxed),
+            None => crate::warn!("timer: deferred-wake table full, PID {} misses its hrtimer wakeup", pid),
+        }
+    }
+}
+
+/// Move every parked PID into `out[count..]`, returning the new count.
+fn take_deferred_wakes(out: &mut [usize; 16], mut count: usize) -> usize {
+    for slot in &DEFERRED_WAKES {
+        let pid = slot.swap(0, Ordering::Relaxed);
+        if pid != 0 && count < out.len() {
+            out[count] = pid;
+            count += 1;
+        }
+    }
+    count
+}
 
 global_asm!(
     ".global timer_interrupt_entry",
@@ -75,40 +120,52 @@ extern "C" {
 #[no_mangle]
 pub extern "C" fn timer_preempt_handler(current_tf: *const TrapFrame) -> *const TrapFrame {
     // ── 1. EOI (must be first — acknowledge interrupt) ────────────────
-    unsafe {
-        use x86_64::instructions::port::PortWriteOnly;
-        PortWriteOnly::<u8>::new(0x20).write(0x20);
-    }
+    // PIC or LAPIC, whichever one `lapic::init_timer` left driving the tick.
+    crate::lapic::timer_eoi();
 
     // ── 2. Advance jiffies counter ────────────────────────────────────
     // crate::time::clockevent::tick();
 
-    // let tick_n = TICK_COUNT.fetch_add(1, Ordering::Relaxed);
-    // if tick_n % 50 == 0 {
-    //     crate::serial_println!("[TICK] {}", tick_n);
-    // }
-
     // ── 3. Fire expired hrtimers ──────────────────────────────────────
     //
     // tick() acquires QUEUE, drains expired timers, releases QUEUE, then
     // returns a list of PIDs to wake.  QUEUE is always released before we
     // acquire the scheduler lock below (ABBA-deadlock prevention).
-    let mut wake_pids = [0usize; 8];
-    let wake_count = {
+    let mut expired = [0usize; 8];
+    let expired_count = {
         let now_ns = crate::time::ktime_get();
-        crate::time::hrtimer::tick(now_ns, &mut wake_pids)
+        crate::time::hrtimer::tick(now_ns, &mut expired)
     };
 
+    // ── 3b. Console cursor blink ──────────────────────────────────────
+    //
+    // One relaxed load and a compare on all but one tick per half-second;
+    // the toggle itself only try-locks the framebuffer locks.
+    crate::drivers::framebuffer_console::blink_tick();
+
     // ── 4. Scheduler: wake hrtimer PIDs + tick time slice ────────────
     //
     // Acquire scheduler lock once for all wakeups + the tick decision.
     // Release it before clearing POLL_WAITERS to obey lock order:
     //   POLL_WAITERS → SCHEDULER (never the reverse).
+    //
+    // `try_local_scheduler`, not `local_scheduler` — see LOCK ALREADY HELD
+    // at the top of this file.
+    let mut wake_pids = [0usize; 16];
+    let wake_count;
     let next_tf = {
-        let mut scheduler = super::scheduler::local_scheduler();
+        let Some(mut scheduler) = super::scheduler::try_local_scheduler() else {
+            defer_wakes(&expired[..expired_count]);
+            crate::debug::inc_sched_lock_busy_ticks();
+            return current_tf;
+        };
+
+        // This tick's expirations plus any a lock-busy tick parked.
+        wake_pids[..expired_count].copy_from_slice(&expired[..expired_count]);
+        wake_count = take_deferred_wakes(&mut wake_pids, expired_count);
 
         for &pid in &wake_pids[..wake_count] {
-            crate::serial_println!("[ISR] hrtimer waking PID {}", pid);
+            crate::trace!("[ISR] hrtimer waking PID {}", pid);
             scheduler.wake(pid);
         }
 
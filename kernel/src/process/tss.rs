@@ -6,7 +6,24 @@ use x86_64::structures::tss::TaskStateSegment;
 use x86_64::structures::gdt::{GlobalDescriptorTable, Descriptor, SegmentSelector};
 use spin::Once;
 
+// IST slots (0-based `interrupt_stack_table` indices; the IDT entry
+// takes index + 1). Each handler using one must be non-reentrant — see
+// `InterruptDescriptorTable::add_handler_with_ist`.
 pub const DOUBLE_FAULT_IST_INDEX: u16 = 0;
+/// Page faults: a kernel stack overflowing into its guard page used to
+/// take the #PF on that same exhausted stack and escalate to a double
+/// fault, losing the faulting address. On its own stack the handler
+/// runs normally and panics with CR2/RIP.
+pub const PAGE_FAULT_IST_INDEX: u16 = 1;
+/// NMI can arrive at any instruction, including mid-way through a stack
+/// switch in `syscall_entry_fast`, where RSP isn't usable yet.
+pub const NMI_IST_INDEX: u16 = 2;
+/// #MC is as asynchronous as an NMI, and the state it reports may be the
+/// reason the current stack can't be trusted.
+pub const MACHINE_CHECK_IST_INDEX: u16 = 3;
+
+/// Size of each IST stack `init` installs, by slot.
+const IST_STACK_SIZES: [usize; 4] = [4096 * 5, 4096 * 16, 4096 * 5, 4096 * 5];
 
 struct Selectors {
     code_selector: SegmentSelector,
@@ -29,78 +46,124 @@ pub static mut KERNEL_RSP0: u64 = 0;
 // GDT se inicializa una vez
 static GDT: Once<(GlobalDescriptorTable, Selectors)> = Once::new();
 
+/// Build (once) the GDT and its selectors, without loading it. Split out
+/// of `init` so `kernel_code_selector` can answer before `init` runs —
+/// the IDT is built first thing in boot, long before step 9.
+fn gdt() -> &'static (GlobalDescriptorTable, Selectors) {
+    GDT.call_once(|| {
+        let mut gdt = GlobalDescriptorTable::new();
+
+        // Segmentos de kernel (Ring 0)
+        let code_selector = gdt.append(Descriptor::kernel_code_segment());
+        let data_selector = gdt.append(Descriptor::kernel_data_segment());
+
+        // Segmentos de user (Ring 3)
+        let user_data_selector = gdt.append(Descriptor::user_data_segment());
+        let user_code_selector = gdt.append(Descriptor::user_code_segment());
+
+        // TSS - la GDT apunta directamente a la ubicación estática
+        // (solo la dirección; `init` llena los stacks antes de `load_tss`)
+        let tss: *const TaskStateSegment = &raw const TSS;
+        let tss_selector = gdt.append(Descriptor::tss_segment(unsafe { &*tss }));
+
+        (gdt, Selectors {
+            code_selector,
+            data_selector,
+            user_code_selector,
+            user_data_selector,
+            tss_selector,
+        })
+    })
+}
+
+/// Kernel CS in this GDT — what every IDT gate must name
+/// (`interrupts::idt::InterruptDescriptorTable::new`).
+pub fn kernel_code_selector() -> SegmentSelector {
+    gdt().1.code_selector
+}
+
 /// Inicializa el TSS y GDT
 pub fn init() {
     // Inicializar TSS con stacks
     unsafe {
         // Stack para double fault (IST)
         TSS.interrupt_stack_table[DOUBLE_FAULT_IST_INDEX as usize] = {
-            const STACK_SIZE: usize = 4096 * 5;
+            const STACK_SIZE: usize = IST_STACK_SIZES[DOUBLE_FAULT_IST_INDEX as usize];
             static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
             
-            let stack_start = VirtAddr::from_ptr(&raw const STACK);
-            let stack_end = stack_start + STACK_SIZE as u64;
-            stack_end
+            VirtAddr::from_ptr(&raw const STACK) + STACK_SIZE as u64
         };
         
+        // Stack para page faults (IST). Same 64 KiB as a process kernel
+        // stack: the #PF path can go as deep as `kill_current_user_process`
+        // tearing the process down, which used to run on a kernel stack.
+        TSS.interrupt_stack_table[PAGE_FAULT_IST_INDEX as usize] = {
+            const STACK_SIZE: usize = IST_STACK_SIZES[PAGE_FAULT_IST_INDEX as usize];
+            static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
+
+            VirtAddr::from_ptr(&raw const STACK) + STACK_SIZE as u64
+        };
+
+        // Stack para NMI (IST)
+        TSS.interrupt_stack_table[NMI_IST_INDEX as usize] = {
+            const STACK_SIZE: usize = IST_STACK_SIZES[NMI_IST_INDEX as usize];
+            static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
+
+            VirtAddr::from_ptr(&raw const STACK) + STACK_SIZE as u64
+        };
+
+        // Stack para machine check (IST)
+        TSS.interrupt_stack_table[MACHINE_CHECK_IST_INDEX as usize] = {
+            const STACK_SIZE: usize = IST_STACK_SIZES[MACHINE_CHECK_IST_INDEX as usize];
+            static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
+
+            VirtAddr::from_ptr(&raw const STACK) + STACK_SIZE as u64
+        };
+
         // Stack de kernel inicial para syscalls (RSP0)
         TSS.privilege_stack_table[0] = {
             const STACK_SIZE: usize = 4096 * 5;
             static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
             
-            let stack_start = VirtAddr::from_ptr(&raw const STACK);
-            let stack_end = stack_start + STACK_SIZE as u64;
-            stack_end
+            VirtAddr::from_ptr(&raw const STACK) + STACK_SIZE as u64
         };
     }
     
-    // Crear GDT
-    GDT.call_once(|| {
-        let mut gdt = GlobalDescriptorTable::new();
-        
-        // Segmentos de kernel (Ring 0)
-        let code_selector = gdt.append(Descriptor::kernel_code_segment());
-        let data_selector = gdt.append(Descriptor::kernel_data_segment());
-        
-        // Segmentos de user (Ring 3)
-        let user_data_selector = gdt.append(Descriptor::user_data_segment());
-        let user_code_selector = gdt.append(Descriptor::user_code_segment());
-        
-        // TSS - la GDT apunta directamente a la ubicación estática
-        let tss_selector = unsafe {
-            gdt.append(Descriptor::tss_segment(&TSS))
-        };
-        
-        (gdt, Selectors {
-            code_selector,
-            data_selector,
-            user_code_selector,
-            user_data_selector,
-            tss_selector,
-        })
-    });
-    
-    // Cargar GDT
-    GDT.get().unwrap().0.load();
-    
+    // Crear (si hace falta) y cargar GDT
+    let (gdt, selectors) = gdt();
+    gdt.load();
+
     unsafe {
         use x86_64::instructions::tables::load_tss;
         use x86_64::instructions::segmentation::{CS, DS, Segment};
-        
+
         // Cargar segmentos de kernel
-        CS::set_reg(GDT.get().unwrap().1.code_selector);
-        DS::set_reg(GDT.get().unwrap().1.data_selector);
-        
+        CS::set_reg(selectors.code_selector);
+        DS::set_reg(selectors.data_selector);
+
         // Cargar TSS
-        load_tss(GDT.get().unwrap().1.tss_selector);
+        load_tss(selectors.tss_selector);
     }
 
-    crate::serial_println!("TSS and GDT initialized");
+    crate::debug!("TSS and GDT initialized");
+}
+
+/// Which IST slot's stack `addr` lies on, if any — so a backtrace can
+/// tell an exception handler's hop back to the interrupted stack from a
+/// corrupt chain (`backtrace::capture`). Lock-free; `None` for every
+/// address before `init` has installed the stacks.
+pub fn ist_slot_of(addr: u64) -> Option<usize> {
+    // By value: `TaskStateSegment` is packed, so no reference into it.
+    let ist = unsafe { TSS.interrupt_stack_table };
+    IST_STACK_SIZES.iter().enumerate().find_map(|(slot, &size)| {
+        let top = ist[slot].as_u64();
+        (top != 0 && addr < top && addr >= top - size as u64).then_some(slot)
+    })
 }
 
 /// Obtiene los selectores de segmento para user space
 pub fn get_user_selectors() -> (SegmentSelector, SegmentSelector) {
-    let selectors = &GDT.get().unwrap().1;
+    let selectors = &gdt().1;
     (selectors.user_code_selector, selectors.user_data_selector)
 }
 
@@ -125,7 +188,9 @@ pub fn set_kernel_stack(stack_top: VirtAddr) {
 /// STAR[47:32] = 0x0008 → syscall sets CS=0x08, SS=0x10
 /// STAR[63:48] = 0x0010 → sysretq would set CS=0x23, SS=0x1b  (we use iretq)
 /// LSTAR       = address of syscall_entry_fast
-/// SFMASK      = clear IF (bit 9) so we enter with interrupts disabled
+/// SFMASK      = clear IF (bit 9) so we enter with interrupts disabled,
+///               and TF (bit 8) so a single-stepped process
+///               (`process::single_step`) doesn't trap on the entry stub
 pub fn init_syscall_msrs() {
     extern "C" { fn syscall_entry_fast(); }
 
@@ -145,11 +210,11 @@ pub fn init_syscall_msrs() {
         // LSTAR: 64-bit kernel entry point
         wrmsr(IA32_LSTAR, syscall_entry_fast as u64);
 
-        // SFMASK: clear IF on entry (bit 9)
-        wrmsr(IA32_FMASK, 1 << 9);
+        // SFMASK: clear IF (bit 9) and TF (bit 8) on entry
+        wrmsr(IA32_FMASK, (1 << 9) | (1 << 8));
     }
 
-    crate::serial_println!("syscall MSRs configured (LSTAR={:#x})", syscall_entry_fast as u64);
+    crate::debug!("syscall MSRs configured (LSTAR={:#x})", syscall_entry_fast as u64);
 }
 
 #[inline]
@@ -39,11 +39,12 @@ pub fn list_programs() -> &'static [(&'static str, ProgramSource)] {
 pub enum ProgramSource {
     /// Raw ELF bytes (from include_bytes! or a filesystem read).
     Elf(&'static [u8]),
-    /// Legacy: raw code pointer + size for inline assembly tests.
+    /// Legacy: raw machine code for inline assembly tests, e.g.
+    /// `|| user_test_fileio::test_code("fb").unwrap()` — the whole test,
+    /// bounded by its `_end` marker, so the loader maps every page of it.
     /// Used as a fallback until ELF userspace is ready.
     RawCode {
-        code_ptr: fn() -> *const u8,
-        code_size: usize,
+        code: fn() -> &'static [u8],
     },
 }
 
@@ -102,8 +103,8 @@ pub fn print_available() {
     for (name, source) in PROGRAMS.iter() {
         let kind = match source {
             ProgramSource::Elf(data) => alloc::format!("ELF ({} bytes)", data.len()),
-            ProgramSource::RawCode { code_size, .. } => {
-                alloc::format!("raw asm ({} bytes)", code_size)
+            ProgramSource::RawCode { code } => {
+                alloc::format!("raw asm ({} bytes)", code().len())
             }
         };
         crate::serial_println!("  '{}' — {}", name, kind);
@@ -46,6 +46,7 @@ global_asm!(
 global_asm!(
     ".section .text.user",
     ".global user_test_write",
+    ".type user_test_write, @function",
     "user_test_write:",
     
     // Preparar mensaje en el stack
@@ -78,6 +79,10 @@ global_asm!(
     // Limpiar stack y repetir
     "    add rsp, 32",
     "    jmp user_test_write",
+
+    ".global user_test_write_end",
+    "user_test_write_end:",
+    ".size user_test_write, user_test_write_end - user_test_write",
 );
 
 // ============================================================================
@@ -87,6 +92,7 @@ global_asm!(
 global_asm!(
     ".section .text.user",
     ".global user_test_devnull",
+    ".type user_test_devnull, @function",
     "user_test_devnull:",
     
     // Path en el stack: "/dev/null\0"
@@ -131,6 +137,10 @@ global_asm!(
     // Repetir
     "    add rsp, 16",
     "    jmp user_test_devnull",
+
+    ".global user_test_devnull_end",
+    "user_test_devnull_end:",
+    ".size user_test_devnull, user_test_devnull_end - user_test_devnull",
 );
 
 // ============================================================================
@@ -140,6 +150,7 @@ global_asm!(
 global_asm!(
     ".section .text.user",
     ".global user_test_fb",
+    ".type user_test_fb, @function",
     "user_test_fb:",
     
     // Path: "/dev/fb\0"
@@ -187,30 +198,154 @@ global_asm!(
     
     "    add rsp, 32",
     "    jmp user_test_fb",
+
+    ".global user_test_fb_end",
+    "user_test_fb_end:",
+    ".size user_test_fb, user_test_fb_end - user_test_fb",
+);
+
+// ============================================================================
+// TEST 4: sched_yield — alternancia cooperativa
+// ============================================================================
+//
+// Escribe "Y<pid % 10> " en stdout y llama a sched_yield(24), en bucle, sin
+// busy-wait. Con dos copias corriendo y nada más Ready, el log serial debe
+// alternar estrictamente ("Y3 Y4 Y3 Y4 ..."): cada yield cede el turno a la
+// otra copia antes de que el timer llegue a expulsarla. Dos marcas seguidas
+// del mismo pid significan que el yield volvió sin cambiar de proceso.
+//
+// 16 vueltas y exit(0): con la feature `boot-yield-pair` el arranque lanza
+// dos copias (`init::processes::spawn_yield_pair`) y no deben inundar el
+// log ni quitarle la CPU a la shell para siempre.
+//
+// Usa la instrucción `syscall` (no `int 0x80` como los tests de arriba): es
+// el único punto de entrada que el kernel cablea (IA32_LSTAR), y este test
+// existe justamente para ejercitar ese camino + `switch_to_next`. `syscall`
+// pisa rcx/r11, por eso el puntero al mensaje vive en r12.
+
+global_asm!(
+    ".section .text.user",
+    ".global user_test_yield",
+    ".type user_test_yield, @function",
+    "user_test_yield:",
+
+    // pid % 10 -> dígito ASCII
+    "    mov rax, 39",          // SYS_GETPID
+    "    syscall",
+    "    xor edx, edx",
+    "    mov rcx, 10",
+    "    div rcx",
+    "    add dl, 0x30",         // '0' + resto
+
+    // Mensaje en el stack: "Y<d> "
+    "    sub rsp, 16",
+    "    mov byte ptr [rsp], 0x59",     // 'Y'
+    "    mov [rsp+1], dl",
+    "    mov byte ptr [rsp+2], 0x20",   // ' '
+    "    mov r12, rsp",
+    "    mov r13, 16",          // vueltas
+
+    "2:",
+    // sys_write(1, mensaje, 3)
+    "    mov rdi, 1",
+    "    mov rsi, r12",
+    "    mov rdx, 3",
+    "    mov rax, 1",           // SYS_WRITE
+    "    syscall",
+
+    // sched_yield()
+    "    mov rax, 24",          // SYS_SCHED_YIELD
+    "    syscall",
+    "    dec r13",
+    "    jnz 2b",
+
+    // sys_exit(0)
+    "    xor edi, edi",
+    "    mov rax, 60",          // SYS_EXIT
+    "    syscall",
+
+    ".global user_test_yield_end",
+    "user_test_yield_end:",
+    ".size user_test_yield, user_test_yield_end - user_test_yield",
 );
 
 // ============================================================================
 // EXPORTS
 // ============================================================================
+//
+// Cada test cierra con un símbolo `<test>_end` (y un `.size` para que el ELF
+// del kernel también lo refleje). `USER_TESTS` se genera a partir de esos
+// pares de símbolos: `(nombre, ptr, len)` con `len = _end - inicio`, y
+// `spawn_user` copia exactamente esos bytes. Un test se copia solo, sin los
+// wrappers de arriba, así que no puede llamarlos — sólo saltos a etiquetas
+// propias. `validate_layout` comprueba la tabla en el arranque.
+//
+// Para añadir un test: su bloque `global_asm!` con `.type`/`_end`/`.size`
+// como los de arriba, y una línea en `user_tests!`.
 
-extern "C" {
-    pub fn user_test_write() -> !;
-    pub fn user_test_devnull() -> !;
-    pub fn user_test_fb() -> !;
+/// Una entrada de la tabla de tests: nombre, inicio y longitud exacta.
+#[derive(Clone, Copy)]
+pub struct UserTest {
+    pub name: &'static str,
+    pub ptr: *const u8,
+    pub len: usize,
 }
 
-pub fn get_test_ptr(test_name: &str) -> *const u8 {
-    match test_name {
-        "write" => user_test_write as *const u8,
-        "devnull" => user_test_devnull as *const u8,
-        "fb" => user_test_fb as *const u8,
-        _ => user_test_write as *const u8,
+impl UserTest {
+    /// El código máquina completo del test.
+    pub fn code(&self) -> &'static [u8] {
+        // SAFETY: `ptr` y su marcador `_end` están en el mismo bloque
+        // `global_asm!` de `.text.user`, mapeado para toda la vida del kernel.
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
     }
 }
 
-pub fn print_available_tests() {
-    crate::serial_println!("📋 File I/O tests disponibles:");
-    crate::serial_println!("  'write'    - Write to stdout");
-    crate::serial_println!("  'devnull'  - Open /dev/null y escribir");
-    crate::serial_println!("  'fb'       - Open /dev/fb y escribir en pantalla");
-}
\ No newline at end of file
+macro_rules! user_tests {
+    ($($name:literal => $start:ident, $end:ident;)*) => {
+        extern "C" {
+            $(
+                pub fn $start() -> !;
+                static $end: u8;
+            )*
+        }
+
+        /// Tabla `(nombre, ptr, len)` de todos los tests, en orden.
+        pub fn user_tests() -> [UserTest; [$($name),*].len()] {
+            [$({
+                let ptr = $start as *const u8;
+                let end = &raw const $end;
+                UserTest {
+                    name: $name,
+                    ptr,
+                    len: (end as usize).saturating_sub(ptr as usize),
+                }
+            }),*]
+        }
+    };
+}
+
+user_tests! {
+    "write"   => user_test_write,   user_test_write_end;
+    "devnull" => user_test_devnull, user_test_devnull_end;
+    "fb"      => user_test_fb,      user_test_fb_end;
+    "yield"   => user_test_yield,   user_test_yield_end;
+}
+
+/// Código máquina completo del test `test_name`; `None` si no existe.
+pub fn test_code(test_name: &str) -> Option<&'static [u8]> {
+    user_tests().iter().find(|t| t.name == test_name).map(UserTest::code)
+}
+
+/// Comprobación de arranque: cada test ocupa algo (`len > 0`) y cabe en
+/// la ventana de código reservada (`len < USER_CODE_SIZE`, 64 KiB). Un
+/// fallo aquí es un `_end` mal puesto o un test que creció demasiado —
+/// mejor un panic al arrancar que un #UD en usuario por código cortado.
+pub fn validate_layout() {
+    for test in user_tests() {
+        assert!(
+            test.len > 0 && test.len < crate::memory::user_code::USER_CODE_SIZE,
+            "user test '{}': bad code length {} (must be 1..{})",
+            test.name, test.len, crate::memory::user_code::USER_CODE_SIZE,
+        );
+    }
+}
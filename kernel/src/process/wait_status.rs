@@ -0,0 +1,88 @@
+// kernel/src/process/wait_status.rs
+//
+// How a process ended (`ExitReason`), and the wait(2) status word that
+// `waitpid()` hands its parent for it.
+//
+// The layout is Linux's (mlibc's `abis/linux/wait.h`), which is also what
+// `constanos-sysdeps/include/abi-bits/wait.h` decodes:
+//
+//   normal exit     (code & 0xFF) << 8          low 7 bits == 0
+//   killed by sig   sig & 0x7F  (| 0x80 core)   low 7 bits in 1..=0x7E
+//   stopped by sig  (sig << 8) | 0x7F           low 8 bits == 0x7F
+//   continued       0xFFFF                      (never produced: no WCONTINUED)
+//
+// Before this, the kernel used a dripos-style word (`WIFEXITED` = bit 0x200,
+// `WIFSIGNALED` = bit 0x400, signal in bits 24-31) matching the copied-in
+// header — correct only as long as every binary was built against that
+// exact header, and unreadable by anything that assumes the POSIX layout.
+// `decode` below holds the kernel-side twins of the header's macros, so
+// both directions of the encoding live in one place.
+
+/// Why a process stopped running for good. Stored in
+/// `Process::exit_status`, set exactly once on the way to `Zombie`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    /// `exit(code)` / `exit_group(code)` — only the low 8 bits of the
+    /// caller's value survive, as on every Unix.
+    Exited(u8),
+    /// Killed by an uncaught signal, or by a hardware fault (reported as
+    /// `SIGSEGV` — this kernel doesn't distinguish fault kinds at the
+    /// signal level). `core_dumped` is always `false` today: there are no
+    /// core dumps, but the bit is part of the encoding.
+    Signaled { sig: u32, core_dumped: bool },
+}
+
+impl ExitReason {
+    /// What a process that hasn't exited yet reports (threads, which are
+    /// reaped without ever being `waitpid()`ed, keep it forever).
+    pub const RUNNING: ExitReason = ExitReason::Exited(0);
+
+    /// `exit(status)`: truncates to the low 8 bits.
+    pub const fn from_exit_code(status: i32) -> Self {
+        ExitReason::Exited(status as u8)
+    }
+
+    pub const fn killed_by(sig: u32) -> Self {
+        ExitReason::Signaled { sig, core_dumped: false }
+    }
+
+    /// Serialize into the wait(2) status word written to `waitpid()`'s
+    /// `status` pointer.
+    pub const fn to_wait_status(self) -> i32 {
+        match self {
+            ExitReason::Exited(code) => (code as i32) << 8,
+            ExitReason::Signaled { sig, core_dumped } => {
+                ((sig & 0x7F) as i32) | if core_dumped { WCOREFLAG } else { 0 }
+            }
+        }
+    }
+}
+
+pub const WCOREFLAG: i32 = 0x80;
+
+/// Status word for a child stopped by `sig` (`WUNTRACED` report).
+pub const fn stopped_status(sig: u32) -> i32 {
+    (((sig & 0xFF) as i32) << 8) | 0x7F
+}
+
+/// The decoding side: the same predicates `abi-bits/wait.h` defines as
+/// `W*` macros, for anything in the kernel that has to read a status word
+/// back (and as the reference a libc port should match). `wifcontinued`
+/// is here for completeness — `waitpid()` doesn't implement `WCONTINUED`,
+/// so the kernel never produces that word.
+#[allow(dead_code)]
+pub mod decode {
+    use super::WCOREFLAG;
+
+    pub const CONTINUED_STATUS: i32 = 0xFFFF;
+
+    pub const fn wexitstatus(status: i32) -> i32 { (status & 0xFF00) >> 8 }
+    pub const fn wtermsig(status: i32) -> i32 { status & 0x7F }
+    pub const fn wstopsig(status: i32) -> i32 { wexitstatus(status) }
+    pub const fn wifexited(status: i32) -> bool { wtermsig(status) == 0 }
+    /// Low 7 bits neither 0 (exited) nor 0x7F (stopped/continued).
+    pub const fn wifsignaled(status: i32) -> bool { (((status & 0x7F) + 1) as i8 >> 1) > 0 }
+    pub const fn wifstopped(status: i32) -> bool { status & 0xFF == 0x7F }
+    pub const fn wifcontinued(status: i32) -> bool { status == CONTINUED_STATUS }
+    pub const fn wcoredump(status: i32) -> bool { status & WCOREFLAG != 0 }
+}
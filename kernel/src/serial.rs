@@ -1,10 +1,36 @@
 // kernel/src/serial.rs
 //
-// Two writers for COM1 (0x3F8):
+// Two UARTs, two roles, two writers.
 //
-//   1. `Serial` — behind a Mutex, used by serial_print!/serial_println!.
-//      Safe for general kernel code.  Do NOT use from inside allocators
-//      or interrupt handlers (risk of deadlock).
+// Roles: kernel diagnostics (`serial_print!` and the raw writer) go to the
+// *log port*; user console output (`/dev/console` writes and the
+// framebuffer console's `[fb] ` mirror) goes to the *console port*. Both
+// default to COM1 (0x3F8), which is exactly the old single-port behaviour.
+// If COM2 (0x2F8) passed its loopback self-test at boot, either role can be
+// moved to it at runtime (`set_log_port`/`set_console_port`, exposed via
+// `kdebug console|logport <1|2>`), so CI can capture machine logs and
+// program output as two separate files (QEMU: a second `-serial`). Each
+// UART has its own lock, so when the roles are split a user write never
+// waits on kernel logging. RX (IRQ4) stays COM1-only — console *input*
+// comes from the shared keyboard/UART ring buffer regardless.
+//
+// Writers:
+//
+// Both also feed the kernel message ring (`crate::log::record`, readable
+// via /dev/kmsg) — from the macros, not the writers themselves, so each
+// message is recorded once, whole.
+//
+//   1. `Serial` — one per UART behind a Mutex (COM1_SERIAL/COM2_SERIAL),
+//      used by serial_print!/serial_println! (`with_serial`, log port) and
+//      by every user-visible write (`console_write`/`with_console`,
+//      console port). Holding the port's lock for a whole call is what
+//      keeps a user `write()` and a kernel `serial_println!` from
+//      interleaving byte-by-byte when they share a UART. The lock is always
+//      taken under
+//      `interrupts::without_interrupts`, so an ISR can never spin on a
+//      lock its own CPU's interrupted code holds — but ISRs should still
+//      prefer the raw writer. Do NOT use from inside allocators or
+//      interrupt handlers (risk of deadlock).
 //
 //   2. `RawSerialWriter` — NO lock, NO allocation.  Implements fmt::Write
 //      so it supports full formatting ({}, {:#x}, {:?}, etc.) via
@@ -14,30 +40,45 @@
 //
 //      Trade-off: concurrent writers may interleave at the byte level.
 //      In practice this is fine — serial output is for debugging, and
-//      interleaving only happens if an interrupt fires mid-write.
+//      interleaving only happens if an interrupt fires mid-write. Keep it
+//      to panic/IRQ/allocator contexts; anything user-visible goes through
+//      the locked writer. Writes to the log port.
 
 use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use x86_64::instructions::port::Port;
 use spin::Mutex;
 
+pub const COM1: u16 = 0x3F8;
+pub const COM2: u16 = 0x2F8;
+
+/// Base port of the UART carrying kernel diagnostics.
+static LOG_PORT: AtomicU16 = AtomicU16::new(COM1);
+/// Base port of the UART carrying `/dev/console` and the `[fb] ` mirror.
+static CONSOLE_PORT: AtomicU16 = AtomicU16::new(COM1);
+/// Set by `init()` if COM2 echoed its loopback pattern. Until then (and
+/// forever, on a machine without one) neither role can move to COM2.
+static COM2_PRESENT: AtomicBool = AtomicBool::new(false);
+
 // ============================================================================
 // Locked writer (general use)
 // ============================================================================
 
-static SERIAL: Mutex<Serial> = Mutex::new(Serial::new());
+static COM1_SERIAL: Mutex<Serial> = Mutex::new(Serial::new(COM1));
+static COM2_SERIAL: Mutex<Serial> = Mutex::new(Serial::new(COM2));
 
-struct Serial {
+pub struct Serial {
     port: Port<u8>,
 }
 
 impl Serial {
-    const fn new() -> Self {
+    const fn new(base: u16) -> Self {
         Self {
-            port: Port::new(0x3F8),
+            port: Port::new(base),
         }
     }
 
-    fn write_byte(&mut self, byte: u8) {
+    pub fn write_byte(&mut self, byte: u8) {
         unsafe {
             self.port.write(byte);
         }
@@ -53,10 +94,71 @@ impl fmt::Write for Serial {
     }
 }
 
+fn lock_for(base: u16) -> &'static Mutex<Serial> {
+    if base == COM2 { &COM2_SERIAL } else { &COM1_SERIAL }
+}
+
+/// Run `f` with UART `base`'s lock held (and interrupts masked), so
+/// everything it writes reaches that UART as one uninterrupted run.
+fn with_port<R>(base: u16, f: impl FnOnce(&mut Serial) -> R) -> R {
+    crate::interrupts::without_interrupts(|| f(&mut lock_for(base).lock()))
+}
+
+/// `with_port` on the log port.
+pub fn with_serial<R>(f: impl FnOnce(&mut Serial) -> R) -> R {
+    with_port(LOG_PORT.load(Ordering::Relaxed), f)
+}
+
+/// `with_port` on the console port.
+pub fn with_console<R>(f: impl FnOnce(&mut Serial) -> R) -> R {
+    with_port(CONSOLE_PORT.load(Ordering::Relaxed), f)
+}
+
+/// Write `buf` to the console port as one atomic run with respect to
+/// `serial_print!` (when they share a UART) and to other console writes.
+pub fn console_write(buf: &[u8]) {
+    with_console(|serial| {
+        for &byte in buf {
+            serial.write_byte(byte);
+        }
+    });
+}
+
+/// Which COM port (1 or 2) currently carries each role.
+pub fn log_port() -> u8 { port_number(LOG_PORT.load(Ordering::Relaxed)) }
+pub fn console_port() -> u8 { port_number(CONSOLE_PORT.load(Ordering::Relaxed)) }
+
+fn port_number(base: u16) -> u8 {
+    if base == COM2 { 2 } else { 1 }
+}
+
+/// Resolve a COM port number to its base, refusing COM2 if it isn't there.
+fn port_base(n: u8) -> Option<u16> {
+    match n {
+        1 => Some(COM1),
+        2 if COM2_PRESENT.load(Ordering::Relaxed) => Some(COM2),
+        _ => None,
+    }
+}
+
+/// Move kernel diagnostics to COM `n` (1 or 2). Returns the previous port
+/// number, or `None` if `n` isn't a present UART.
+pub fn set_log_port(n: u8) -> Option<u8> {
+    let base = port_base(n)?;
+    Some(port_number(LOG_PORT.swap(base, Ordering::Relaxed)))
+}
+
+/// Move console output to COM `n` (1 or 2). Same contract as `set_log_port`.
+pub fn set_console_port(n: u8) -> Option<u8> {
+    let base = port_base(n)?;
+    Some(port_number(CONSOLE_PORT.swap(base, Ordering::Relaxed)))
+}
+
 #[doc(hidden)]
 pub fn _print(args: fmt::Arguments) {
     use fmt::Write;
-    SERIAL.lock().write_fmt(args).unwrap();
+    with_serial(|serial| serial.write_fmt(args).unwrap());
+    crate::log::record(args);
 }
 
 #[macro_export]
@@ -97,17 +199,24 @@ impl fmt::Write for RawSerialWriter {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         for byte in s.bytes() {
             unsafe {
-                Port::<u8>::new(0x3F8).write(byte);
+                Port::<u8>::new(LOG_PORT.load(Ordering::Relaxed)).write(byte);
             }
         }
         Ok(())
     }
 }
 
+#[doc(hidden)]
+pub fn _print_raw(args: fmt::Arguments) {
+    use fmt::Write;
+    let _ = RawSerialWriter.write_fmt(args);
+    crate::log::record(args);
+}
+
 /// Lock-free print with full formatting support.
 ///
 /// Use this instead of `serial_print!` inside allocators, interrupt
-/// handlers, or any context where the `SERIAL` Mutex might be held.
+/// handlers, or any context where a UART's Mutex might be held.
 ///
 /// ```ignore
 /// serial_print_raw!("Buddy: OOM for order {}", order);
@@ -116,8 +225,7 @@ impl fmt::Write for RawSerialWriter {
 #[macro_export]
 macro_rules! serial_print_raw {
     ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let _ = write!($crate::serial::RawSerialWriter, $($arg)*);
+        $crate::serial::_print_raw(format_args!($($arg)*))
     }};
 }
 
@@ -126,31 +234,68 @@ macro_rules! serial_print_raw {
 macro_rules! serial_println_raw {
     () => ($crate::serial_print_raw!("\n"));
     ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let _ = writeln!($crate::serial::RawSerialWriter, $($arg)*);
+        $crate::serial::_print_raw(format_args!("{}\n", format_args!($($arg)*)))
     }};
 }
 
 // ============================================================================
-// UART RX interrupt setup (COM1 → IRQ4)
+// UART setup (COM1 → IRQ4; COM2 output only)
 // ============================================================================
 
-/// Program the 16550 UART for interrupt-driven receive and enable the
-/// "data available" interrupt.  Must be called before the IRQ4 line is
-/// unmasked at the PIC (see `init::devices::init_hardware_interrupts`).
+// Register offsets from a UART's base port.
+const REG_DATA: u16 = 0;  // RBR/THR (DLAB=0) / divisor lo (DLAB=1)
+const REG_IER: u16 = 1;   // interrupt enable (DLAB=0) / divisor hi (DLAB=1)
+const REG_FCR: u16 = 2;   // FIFO control
+const REG_LCR: u16 = 3;   // line control (holds DLAB)
+const REG_MCR: u16 = 4;   // modem control
+const REG_LSR: u16 = 5;   // line status
+
+const MCR_NORMAL: u8 = 0x0B;    // DTR, RTS, OUT2 (OUT2 gates the IRQ line on a real 8250/16550)
+const MCR_LOOPBACK: u8 = 0x1E;  // LOOP, RTS, OUT1, OUT2 — TX wired straight back to RX
+const LSR_DATA_READY: u8 = 0x01;
+const LSR_TX_EMPTY: u8 = 0x40;  // TEMT: holding register *and* shift register empty
+const LOOPBACK_PATTERN: u8 = 0xAE;
+
+/// Program both 16550s from scratch: 115200 baud, 8N1, FIFOs on and
+/// cleared, modem control lines up, every UART interrupt off. Then run
+/// each one's loopback self-test and log the results; COM2 only becomes
+/// selectable if its test passed.
 ///
-/// Only configures the RX side. TX (`write_byte` / `RawSerialWriter`)
-/// already works against QEMU's default post-reset UART state without
-/// any setup, so it's left untouched here to avoid regressing
-/// `serial_println!` output that every other subsystem depends on for
-/// diagnostics.
-pub fn init_interrupts() {
+/// Called first thing in `init::boot` (and `boot_for_tests`) — before
+/// this, TX only worked because firmware and QEMU happen to leave the
+/// UART in a usable post-reset state, which real firmware doesn't
+/// promise and which `init_interrupts`' RX setup was silently relying on.
+/// Nothing is logged until after the UART is programmed, so the first
+/// line of boot output already goes out at a known baud rate.
+pub fn init() {
+    program_uart(COM1);
+    program_uart(COM2);
+    let com1_ok = loopback_self_test(COM1);
+    let com2_ok = loopback_self_test(COM2);
+    COM2_PRESENT.store(com2_ok, Ordering::Relaxed);
+
+    if com1_ok {
+        crate::info!("[serial] COM1 16550 115200 8N1, loopback self-test passed");
+    } else {
+        // Still worth trying to log: on a UART that's present but has a
+        // broken loopback path, TX may well work. On no UART at all the
+        // writes go nowhere, which is no worse than before.
+        crate::error!("[serial] COM1 loopback self-test FAILED — no UART, or a broken one");
+    }
+    if com2_ok {
+        crate::info!("[serial] COM2 16550 115200 8N1, loopback self-test passed (selectable via `kdebug console 2`)");
+    } else {
+        crate::info!("[serial] COM2 not present, log and console both stay on COM1");
+    }
+}
+
+fn program_uart(base: u16) {
     unsafe {
-        let mut ier: Port<u8> = Port::new(0x3F9); // interrupt enable (DLAB=0) / divisor hi (DLAB=1)
-        let mut lcr: Port<u8> = Port::new(0x3FB); // line control (holds DLAB)
-        let mut dll: Port<u8> = Port::new(0x3F8); // divisor latch low (DLAB=1)
-        let mut fcr: Port<u8> = Port::new(0x3FA); // FIFO control
-        let mut mcr: Port<u8> = Port::new(0x3FC); // modem control
+        let mut ier: Port<u8> = Port::new(base + REG_IER);
+        let mut lcr: Port<u8> = Port::new(base + REG_LCR);
+        let mut dll: Port<u8> = Port::new(base + REG_DATA);
+        let mut fcr: Port<u8> = Port::new(base + REG_FCR);
+        let mut mcr: Port<u8> = Port::new(base + REG_MCR);
 
         ier.write(0x00); // disable interrupts while reconfiguring
         lcr.write(0x80); // DLAB=1, expose the baud rate divisor latch
@@ -158,7 +303,62 @@ pub fn init_interrupts() {
         ier.write(0x00); // divisor hi byte = 0 (aliases IER while DLAB=1)
         lcr.write(0x03); // DLAB=0, 8 bits / no parity / 1 stop bit
         fcr.write(0xC7); // enable FIFO, clear RX+TX, 14-byte trigger level
-        mcr.write(0x0B); // DTR, RTS, OUT2 (OUT2 gates the IRQ line on a real 8250/16550)
-        ier.write(0x01); // enable "received data available" interrupt
+        mcr.write(MCR_NORMAL);
     }
-}
\ No newline at end of file
+}
+
+/// Put UART `base` in loopback mode, send one byte, and check it comes
+/// back on the RX side. Leaves the UART in normal mode with the RX FIFO
+/// empty.
+///
+/// Must run with nothing else writing to that UART — in
+/// loopback mode TX never reaches the wire, so anything printed meanwhile
+/// would be both lost and mistaken for the echoed byte. Interrupts are
+/// masked for the duration for the same reason.
+pub fn loopback_self_test(base: u16) -> bool {
+    crate::interrupts::without_interrupts(|| unsafe {
+        let mut data: Port<u8> = Port::new(base + REG_DATA);
+        let mut mcr: Port<u8> = Port::new(base + REG_MCR);
+        let mut lsr: Port<u8> = Port::new(base + REG_LSR);
+
+        // Let anything already queued finish going out on the wire first:
+        // bytes still in the TX FIFO when LOOP is set would be echoed back
+        // (and lost) instead of the test pattern.
+        for _ in 0..100_000 {
+            if lsr.read() & LSR_TX_EMPTY != 0 {
+                break;
+            }
+            core::hint::spin_loop();
+        }
+        mcr.write(MCR_LOOPBACK);
+        // Drain anything already sitting in the RX FIFO (bounded: a
+        // missing UART reads back 0xFF, i.e. "data ready" forever).
+        for _ in 0..16 {
+            if lsr.read() & LSR_DATA_READY == 0 {
+                break;
+            }
+            let _ = data.read();
+        }
+        data.write(LOOPBACK_PATTERN);
+
+        let mut received = None;
+        for _ in 0..100_000 {
+            if lsr.read() & LSR_DATA_READY != 0 {
+                received = Some(data.read());
+                break;
+            }
+            core::hint::spin_loop();
+        }
+        mcr.write(MCR_NORMAL);
+        received == Some(LOOPBACK_PATTERN)
+    })
+}
+
+/// Enable COM1's "data available" interrupt. Must be called after `init()`
+/// (which programmed line settings, FIFOs and OUT2) and before the IRQ4
+/// line is unmasked at the PIC (see `init::devices::init_hardware_interrupts`).
+pub fn init_interrupts() {
+    unsafe {
+        Port::<u8>::new(COM1 + REG_IER).write(0x01);
+    }
+}
@@ -0,0 +1,94 @@
+// kernel/src/symbols.rs
+//
+// Kernel symbol lookup for backtraces and fault reports: `function+0xoff`
+// instead of a bare address. Optional — build with `--features ksyms`
+// (root crate; it forwards the feature to this one). Without it `resolve`
+// always says `None` and everything prints raw addresses as before.
+//
+// WHERE THE TABLE COMES FROM:
+//   The kernel can't know its own symbols until after it's linked, so the
+//   image carries a fixed-size, mostly-empty `.ksymtab` section (`KSYMTAB`,
+//   just the magic) and the root `build.rs` fills it in post-link
+//   (`embed_symbols`): every sized STT_FUNC from the ELF `.symtab`,
+//   demangled, written over the section's bytes in a copy of the ELF that
+//   the boot image is then built from. A kernel ELF that skipped that step
+//   (`scripts/run-kernel-tests.sh` boots the raw test binary) just has an
+//   empty table.
+//
+// LAYOUT:
+//   A header (magic, link-time address of the section, count, offset of
+//   the name area), then `{ addr, size, name }` entries sorted by address,
+//   then length-prefixed names — see `hal::ksymtab`, which holds the
+//   format and the lookup so they're host-tested.
+//
+// Addresses are link-time ones. The bootloader places the (PIE) kernel at
+// a base of its choosing, so the load offset is recovered by comparing
+// where `KSYMTAB` actually is against the header's link-time address.
+//
+// `resolve` bounds-checks every read and never allocates or locks: it runs
+// from the panic handler and from NMI/#MC.
+
+/// Bytes reserved for the table. `embed_symbols` fails the build if the
+/// kernel's functions don't fit — raise it then.
+#[cfg(feature = "ksyms")]
+const KSYMTAB_SIZE: usize = 2 * 1024 * 1024;
+
+#[cfg(feature = "ksyms")]
+use hal::ksymtab::MAGIC;
+
+/// The section `build.rs` patches. Starts out as the magic (which also
+/// keeps it out of a NOBITS section) and zeros, i.e. an empty table.
+#[cfg(feature = "ksyms")]
+#[used]
+#[link_section = ".ksymtab"]
+static KSYMTAB: [u8; KSYMTAB_SIZE] = {
+    let mut table = [0; KSYMTAB_SIZE];
+    let mut i = 0;
+    while i < MAGIC.len() {
+        table[i] = MAGIC[i];
+        i += 1;
+    }
+    table
+};
+
+/// The table as the linker laid it out — through `black_box`, since the
+/// compiler would otherwise fold reads of an immutable static down to its
+/// all-zero initializer and never see what `build.rs` wrote there.
+#[cfg(feature = "ksyms")]
+fn table() -> &'static [u8] {
+    core::hint::black_box(&KSYMTAB[..])
+}
+
+#[cfg(not(feature = "ksyms"))]
+fn table() -> &'static [u8] {
+    &[]
+}
+
+/// Number of symbols in the embedded table (0 if the feature is off or
+/// `build.rs` never filled it in). Only for `hw_tests.rs`, which skips
+/// its lookup checks on an unsymbolized image.
+#[cfg(test)]
+pub(crate) fn count() -> usize {
+    hal::ksymtab::count(table())
+}
+
+/// The function containing `addr` and `addr`'s offset into it.
+pub fn resolve(addr: u64) -> Option<(&'static str, usize)> {
+    let table = table();
+    hal::ksymtab::resolve(table, table.as_ptr() as u64, addr)
+}
+
+/// `{}`-formats an address as `0x...`, followed by ` <function+0xoff>`
+/// when `resolve` knows it.
+#[derive(Clone, Copy)]
+pub struct Sym(pub u64);
+
+impl core::fmt::Display for Sym {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:#x}", self.0)?;
+        if let Some((name, off)) = resolve(self.0) {
+            write!(f, " <{}+{:#x}>", name, off)?;
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,54 @@
 // kernel/src/time/clockevent.rs
 //
-// Clockevent: jiffies counter driven by the PIT at 100 Hz.
+// Clockevent: jiffies counter driven by the timer tick at `TICK_HZ` — the
+// LAPIC timer when `lapic::init_timer` found one, the PIT otherwise.
 //
-// JIFFIES is incremented once per timer interrupt (every 10 ms).
+// JIFFIES is incremented once per timer interrupt (every `PERIOD_NS`).
 // Atomic operations keep it ISR-safe without a lock.
+//
+// `TICK_HZ` is the one place the tick rate is chosen: the PIT and LAPIC
+// are programmed with it, TSC/LAPIC calibration measures one of its
+// periods (`cpu::tsc::pit_hz`), and the scheduler's quantum, aging and
+// watchdog intervals are written in milliseconds and converted with
+// `ms_to_ticks`. Sleeps and poll timeouts are nanosecond deadlines
+// checked every tick, so they wake at most one period late at any rate.
 
 use core::sync::atomic::{AtomicU64, Ordering};
 
-/// Global jiffy counter. Each PIT interrupt increments this by 1.
+/// Timer interrupts per second.
+pub const TICK_HZ: u32 = 100;
+
+// The PIT must be able to produce it: a divisor of 1193182 / TICK_HZ that
+// fits the 16-bit reload register (so roughly 19..=1193182 Hz), and that
+// gives back TICK_HZ to the nearest Hz — otherwise jiffies would drift
+// against `PERIOD_NS` on a PIT-driven tick.
+const _: () = assert!(
+    matches!(hal::pit::divisor_for_hz(TICK_HZ),
+        Ok(d) if d <= u16::MAX as u32 && hal::pit::achieved_hz(d) == TICK_HZ),
+    "TICK_HZ needs a PIT divisor that fits in 16 bits and hits it to the Hz",
+);
+
+/// Global jiffy counter. Each timer interrupt increments this by 1.
 static JIFFIES: AtomicU64 = AtomicU64::new(0);
 
-/// PIT tick period in nanoseconds (10 ms at 100 Hz).
-pub const PERIOD_NS: u64 = 10_000_000;
+/// Tick period in nanoseconds (10 ms at 100 Hz).
+pub const PERIOD_NS: u64 = 1_000_000_000 / TICK_HZ as u64;
+
+/// Ticks covering `ms` milliseconds, rounded up — so a nonzero interval
+/// is never zero ticks.
+pub const fn ms_to_ticks(ms: u32) -> u32 {
+    (ms as u64 * TICK_HZ as u64).div_ceil(1000) as u32
+}
+
+/// The unit userspace expects `clock_t` values in (`times()`, `utime` in
+/// `/proc/<pid>/stat`): `sysconf(_SC_CLK_TCK)`, which libc hardcodes.
+pub const USER_HZ: u64 = 100;
+
+/// Convert a tick count (`Process::cpu_ticks`, `total_ticks()`) to
+/// `USER_HZ` units.
+pub const fn ticks_to_clock_t(ticks: u64) -> u64 {
+    ticks * USER_HZ / TICK_HZ as u64
+}
 
 /// Called from the timer ISR. Increments the jiffy counter and returns the
 /// new value. Using Relaxed ordering: the ISR is single-CPU and the atomic
@@ -21,7 +58,7 @@ pub fn tick() -> u64 {
     JIFFIES.fetch_add(1, Ordering::Relaxed) + 1
 }
 
-/// Returns the current jiffy count (ticks since boot, 10 ms each).
+/// Returns the current jiffy count (ticks since boot, `PERIOD_NS` each).
 #[inline]
 pub fn jiffies() -> u64 {
     JIFFIES.load(Ordering::Relaxed)
@@ -34,7 +71,7 @@ pub fn jiffies_to_ns(j: u64) -> u64 {
 }
 
 /// Stub for future one-shot clockevent programming.
-/// Currently the PIT runs in periodic mode at 100 Hz; this is a no-op.
+/// Currently the tick is periodic at `TICK_HZ`; this is a no-op.
 #[allow(dead_code)]
 #[inline]
 pub fn set_next_ns(_ns: u64) {}
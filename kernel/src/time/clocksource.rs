@@ -10,7 +10,6 @@
 // so no lock is needed for ktime_get().
 
 use core::sync::atomic::{AtomicUsize, Ordering};
-use crate::serial_println;
 
 pub struct ClockSourceInfo {
     pub name: &'static str,
@@ -46,7 +45,7 @@ pub fn select_best() {
         .unwrap_or(0);
 
     ACTIVE_IDX.store(best, Ordering::Relaxed);
-    serial_println!(
+    crate::info!(
         "clocksource: selected '{}' (rating {})",
         SOURCES[best].name,
         SOURCES[best].rating
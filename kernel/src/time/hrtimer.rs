@@ -8,6 +8,9 @@
 //     calls KernelFn actions inline, and fills a small fixed-size array
 //     with PIDs to wake (no alloc in ISR path).
 //   - start()/cancel() acquire QUEUE lock; safe under cli.
+//   - This is the one deadline queue for every "wake PID at T" user —
+//     nanosleep, poll and epoll_wait timeouts — so the ISR scans a single
+//     list per tick. `cancel_pid` drops whatever a dying process left here.
 //
 // LOCKING DISCIPLINE (see timer_preempt.rs for full analysis):
 //   ISR path:  QUEUE lock (brief) → release → scheduler lock
@@ -79,6 +82,38 @@ pub fn cancel(id: u32) -> bool {
     }
 }
 
+/// Cancel every `WakePid(pid)` timer, whatever registered it.
+///
+/// Called from `cancel_all_waiters` on every death path, so a process
+/// killed mid-nanosleep doesn't leave its timer queued until expiry.
+/// Returns the number of timers removed.
+pub fn cancel_pid(pid: usize) -> usize {
+    let mut q = QUEUE.lock();
+    let before = q.timers.len();
+    q.timers.retain(|t| !matches!(t.action, HrTimerAction::WakePid(p) if p == pid));
+    before - q.timers.len()
+}
+
+/// `/proc/timers`: one `id expiry_ns remaining_ms action` line per
+/// pending timer, soonest first.
+pub fn render_timers() -> alloc::string::String {
+    use core::fmt::Write;
+
+    let now = crate::time::ktime_get();
+    let mut out = alloc::string::String::new();
+    let q = QUEUE.lock();
+    for t in &q.timers {
+        let remaining_ms = t.expiry_ns.saturating_sub(now) / 1_000_000;
+        let _ = match t.action {
+            HrTimerAction::WakePid(pid) => writeln!(out, "{:>6} {:>16} {:>8} ms wake pid {}",
+                t.id, t.expiry_ns, remaining_ms, pid),
+            HrTimerAction::KernelFn(f) => writeln!(out, "{:>6} {:>16} {:>8} ms call {:p}",
+                t.id, t.expiry_ns, remaining_ms, f as *const ()),
+        };
+    }
+    out
+}
+
 /// Called from the timer ISR once per tick.
 ///
 /// Drains all timers whose `expiry_ns <= now_ns`.
@@ -86,8 +121,8 @@ pub fn cancel(id: u32) -> bool {
 ///   - `WakePid` PIDs are collected into `pids_out`; QUEUE is released first.
 ///
 /// Returns the number of PIDs written into `pids_out`.
-/// If more than 8 timers with WakePid fire in the same tick, extras are
-/// silently dropped — they will be processed in the next tick at most 10 ms later.
+/// If more than 8 timers with WakePid fire in the same tick, the rest stay
+/// queued and are processed on the next tick, at most 10 ms later.
 pub fn tick(now_ns: u64, pids_out: &mut [usize; 8]) -> usize {
     let mut count = 0usize;
 
@@ -98,6 +133,11 @@ pub fn tick(now_ns: u64, pids_out: &mut [usize; 8]) -> usize {
         if t.expiry_ns > now_ns {
             break; // remaining timers are in the future
         }
+        if matches!(t.action, HrTimerAction::WakePid(_)) && count == pids_out.len() {
+            // Out of room — leave it (and everything after it) for next tick
+            // rather than removing a wakeup nobody would ever deliver.
+            break;
+        }
         let timer = q.timers.remove(0);
         match timer.action {
             HrTimerAction::KernelFn(f) => {
@@ -105,11 +145,8 @@ pub fn tick(now_ns: u64, pids_out: &mut [usize; 8]) -> usize {
                 f();
             }
             HrTimerAction::WakePid(pid) => {
-                if count < 8 {
-                    pids_out[count] = pid;
-                    count += 1;
-                }
-                // If count == 8, drop the PID; it will be retried next tick.
+                pids_out[count] = pid;
+                count += 1;
             }
         }
     }
@@ -30,10 +30,10 @@ pub fn init() {
     match crate::rtc::read_unix_time() {
         Some(secs) => {
             BOOT_UNIX_SECS.store(secs, Ordering::Relaxed);
-            crate::serial_println!("rtc: read {} (unix epoch seconds)", secs);
+            crate::info!("rtc: read {} (unix epoch seconds)", secs);
         }
         None => {
-            crate::serial_println!("rtc: no response — real-time clock unavailable, falling back to boot=epoch");
+            crate::warn!("rtc: no response — real-time clock unavailable, falling back to boot=epoch");
         }
     }
 }
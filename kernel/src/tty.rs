@@ -14,11 +14,34 @@
 // ISIG is set and the byte matches VINTR/VQUIT/VSUSP, it's turned into a
 // real signal delivered to the foreground process group instead of being
 // queued as input — the same job a real Unix tty driver's line discipline
-// does. ICANON/ECHO are stored (so tcgetattr/tcsetattr round-trip
-// correctly and nothing errors out) but not actually implemented in the
-// kernel: line editing and echo stay userspace's job, same as before this
-// existed (see userspace/src/bin/shell.rs) — ash's own line editor does
-// the same once it puts the tty in raw mode via tcsetattr.
+// does.
+//
+// ICANON/ECHO line discipline (cooked mode): with ICANON set (the default),
+// `feed_input` doesn't queue bytes as they arrive — it collects them in
+// `LINE`, handles VERASE/backspace (drop the last byte, echo `\b \b`) and
+// VKILL (drop the whole line), and only moves the line into
+// `KEYBOARD_BUFFER` — where fd 0's `sys_read` and its ISR-side
+// `stdin_wakeup` pick it up — once Enter (or VEOL/VEOF) completes it. With
+// ECHO set, every accepted byte is echoed to the framebuffer console (which
+// mirrors to serial). This is the console's only "tty": fd 0 of every
+// process is bound to it, so nothing needs installing per process.
+//
+// The line is edited at a caret, not just appended to: the Left/Right/
+// Home/End escape sequences the keyboard decoder (and a serial terminal)
+// send move it, typing inserts at it, VERASE deletes before it and
+// `ESC[3~` (a serial terminal's Delete) at it. The echo redraws the rest
+// of the line and backs the terminal cursor up to the caret with `\b`,
+// which both the framebuffer console and a serial terminal understand.
+// Other escape sequences (Up/Down, PgUp/PgDn) are swallowed — there's no
+// history here; that's ash's line editor's job.
+//
+// Raw mode is the usual tcsetattr() toggle (TCSETS clearing ICANON, via
+// `set_termios`): bytes then go straight into `KEYBOARD_BUFFER`, as
+// before this existed. ash's own line editor does exactly that while it
+// reads a command line and restores cooked mode before running one, so a
+// plain `cat` gets line editing and echo from here. Programs that want
+// every keystroke as it happens — `snake` via `/dev/kbd`, which reads the
+// same buffer — must switch to raw mode themselves, same as on Unix.
 
 use core::sync::atomic::{AtomicU32, Ordering};
 use spin::Mutex;
@@ -37,7 +60,17 @@ pub const VSTOP: usize = 8;
 pub const VSUSP: usize = 9;
 pub const VTIME: usize = 10;
 
+// c_lflag / c_iflag bits — `abi-bits/termios.h`'s values, not Linux's.
+pub const ECHO: u32 = 0x0001;
+pub const ECHOE: u32 = 0x0002;
+pub const ECHOK: u32 = 0x0004;
+pub const ICANON: u32 = 0x0010;
 pub const ISIG: u32 = 0x0040;
+pub const ICRNL: u32 = 0x0002;
+
+/// Longest line cooked mode will hold; further bytes are dropped (not
+/// echoed) until Enter or an erase makes room — POSIX's MAX_CANON.
+const MAX_CANON: usize = 255;
 
 /// Matches `mlibc-port/constanos-sysdeps/include/abi-bits/termios.h`'s
 /// `struct termios` byte-for-byte (`cc_t`/`tcflag_t`/`speed_t` are all
@@ -88,33 +121,243 @@ pub static TERMIOS: Mutex<Termios> = Mutex::new(default_termios());
 /// e.g. around running a foreground job).
 pub static FOREGROUND_PGID: AtomicU32 = AtomicU32::new(0);
 
-/// Feed one raw input byte through the tty's line discipline. Returns
-/// `true` if it should be queued as ordinary input (push into
-/// `keyboard_buffer::KEYBOARD_BUFFER` as before), `false` if it was
-/// consumed here as a signal.
+/// Cooked-mode line being edited — not yet visible to any reader. Only
+/// touched from the input ISRs (via `feed_input`) and `set_termios`, which
+/// runs with interrupts off, so the lock is never contended across an
+/// interrupt on this core.
+struct LineBuf {
+    buf: [u8; MAX_CANON],
+    len: usize,
+    /// Edit position, `0..=len`.
+    caret: usize,
+    esc: EscState,
+}
+
+/// Progress through an input escape sequence (`ESC [ <digits> <final>`).
+#[derive(Clone, Copy)]
+enum EscState {
+    None,
+    Esc,
+    /// Inside the CSI; holds the numeric parameter so far (`ESC[3~`).
+    Csi(u8),
+}
+
+static LINE: Mutex<LineBuf> = Mutex::new(LineBuf {
+    buf: [0; MAX_CANON],
+    len: 0,
+    caret: 0,
+    esc: EscState::None,
+});
+
+/// Enough `\b`s to back up over a whole line plus the blank that erases
+/// its old last cell.
+static BACKSPACES: [u8; MAX_CANON + 1] = [0x08; MAX_CANON + 1];
+
+impl LineBuf {
+    /// Hand the line to readers, leaving it empty.
+    fn flush(&mut self) {
+        for &b in &self.buf[..self.len] {
+            crate::keyboard_buffer::KEYBOARD_BUFFER.push(b as char);
+        }
+        self.clear();
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+        self.caret = 0;
+        self.esc = EscState::None;
+    }
+
+    /// Insert `byte` at the caret. With `echo_on`, redraw from the caret
+    /// to the end of the line and back up to just past the new byte.
+    fn insert(&mut self, byte: u8, echo_on: bool) {
+        if self.len == MAX_CANON {
+            return;
+        }
+        let at = self.caret;
+        self.buf.copy_within(at..self.len, at + 1);
+        self.buf[at] = byte;
+        self.len += 1;
+        self.caret += 1;
+        if echo_on {
+            echo(&self.buf[at..self.len]);
+            echo(&BACKSPACES[..self.len - self.caret]);
+        }
+    }
+
+    /// Delete the byte at `at` (< `len`), with the terminal cursor already
+    /// on its cell; redraws the shifted tail, blanks the old last cell and
+    /// leaves the cursor back on `at`.
+    fn delete_at(&mut self, at: usize, echo_on: bool) {
+        self.buf.copy_within(at + 1..self.len, at);
+        self.len -= 1;
+        self.caret = at;
+        if echo_on {
+            echo(&self.buf[at..self.len]);
+            echo(b" ");
+            echo(&BACKSPACES[..self.len - at + 1]);
+        }
+    }
+
+    /// Move the caret to `to`, echoing the cursor motion.
+    fn move_caret(&mut self, to: usize, echo_on: bool) {
+        if echo_on {
+            if to < self.caret {
+                echo(&BACKSPACES[..self.caret - to]);
+            } else {
+                // Re-echoing what's already there is the one rightward
+                // motion every terminal understands.
+                echo(&self.buf[self.caret..to]);
+            }
+        }
+        self.caret = to;
+    }
+
+    /// Act on a completed `ESC [ <param> <final>` sequence.
+    fn csi(&mut self, param: u8, final_byte: u8, echo_on: bool) {
+        match (final_byte, param) {
+            (b'D', _) if self.caret > 0 => self.move_caret(self.caret - 1, echo_on),
+            (b'C', _) if self.caret < self.len => self.move_caret(self.caret + 1, echo_on),
+            (b'H', _) | (b'~', 1) => self.move_caret(0, echo_on),
+            (b'F', _) | (b'~', 4) => self.move_caret(self.len, echo_on),
+            (b'~', 3) if self.caret < self.len => self.delete_at(self.caret, echo_on),
+            _ => {}
+        }
+    }
+}
+
+fn echo(bytes: &[u8]) {
+    if !bytes.is_empty() {
+        crate::drivers::framebuffer_console::try_echo(bytes);
+    }
+}
+
+/// Feed one raw input byte through the tty's line discipline (ISIG, then
+/// ICANON/ECHO — see the module comment). Called from the keyboard and
+/// serial ISRs. Returns `true` if new input became readable in
+/// `keyboard_buffer::KEYBOARD_BUFFER` (pushed here), so the caller knows
+/// whether to wake stdin/poll waiters; `false` if the byte was consumed as
+/// a signal or is still sitting in an unfinished cooked-mode line.
 pub fn feed_input(c: char) -> bool {
-    let byte = c as u32;
-    let (isig, intr, quit, susp) = {
-        let t = TERMIOS.lock();
-        (t.c_lflag & ISIG != 0, t.c_cc[VINTR], t.c_cc[VQUIT], t.c_cc[VSUSP])
-    };
-    if !isig {
-        return true;
+    let t = *TERMIOS.lock();
+    let mut byte = c as u32;
+
+    if t.c_lflag & ISIG != 0 {
+        let sig = if byte == t.c_cc[VINTR] {
+            Some(crate::process::signal::SIGINT)
+        } else if byte == t.c_cc[VQUIT] {
+            Some(crate::process::signal::SIGQUIT)
+        } else if byte == t.c_cc[VSUSP] {
+            Some(crate::process::signal::SIGTSTP)
+        } else {
+            None
+        };
+        if let Some(sig) = sig {
+            // A signal discards the half-typed line, like a real tty
+            // without NOFLSH.
+            LINE.lock().clear();
+            let pgid = FOREGROUND_PGID.load(Ordering::Relaxed);
+            if pgid != 0 {
+                crate::process::syscall::send_to_group(pgid, sig);
+            }
+            return false;
+        }
     }
 
-    let sig = if byte == intr {
-        crate::process::signal::SIGINT
-    } else if byte == quit {
-        crate::process::signal::SIGQUIT
-    } else if byte == susp {
-        crate::process::signal::SIGTSTP
-    } else {
+    if t.c_iflag & ICRNL != 0 && byte == b'\r' as u32 {
+        byte = b'\n' as u32;
+    }
+    let echo_on = t.c_lflag & ECHO != 0;
+
+    if t.c_lflag & ICANON == 0 {
+        if echo_on {
+            echo(&[byte as u8]);
+        }
+        crate::keyboard_buffer::KEYBOARD_BUFFER.push(byte as u8 as char);
         return true;
-    };
+    }
 
-    let pgid = FOREGROUND_PGID.load(Ordering::Relaxed);
-    if pgid != 0 {
-        crate::process::syscall::send_to_group(pgid, sig);
+    let mut line = LINE.lock();
+    match line.esc {
+        EscState::Esc => {
+            line.esc = if byte == b'[' as u32 { EscState::Csi(0) } else { EscState::None };
+            return false;
+        }
+        EscState::Csi(param) => {
+            line.esc = EscState::None;
+            match byte {
+                0x30..=0x39 => {
+                    line.esc = EscState::Csi(param.saturating_mul(10).saturating_add(byte as u8 - b'0'));
+                }
+                0x40..=0x7e => line.csi(param, byte as u8, echo_on),
+                _ => {}
+            }
+            return false;
+        }
+        EscState::None if byte == 0x1b => {
+            line.esc = EscState::Esc;
+            return false;
+        }
+        EscState::None => {}
     }
-    false
+
+    if byte == t.c_cc[VERASE] || byte == 0x08 {
+        if line.caret > 0 {
+            let at = line.caret - 1;
+            let echo_erase = echo_on && t.c_lflag & ECHOE != 0;
+            if echo_erase {
+                echo(b"\x08");
+            }
+            line.delete_at(at, echo_erase);
+        }
+        false
+    } else if byte == t.c_cc[VKILL] {
+        if echo_on && t.c_lflag & ECHOK != 0 {
+            let len = line.len;
+            line.move_caret(len, true);
+            for _ in 0..len {
+                echo(b"\x08 \x08");
+            }
+        }
+        line.clear();
+        false
+    } else if byte == b'\n' as u32 || (t.c_cc[VEOL] != 0 && byte == t.c_cc[VEOL]) || byte == t.c_cc[VEOF] {
+        // Enter/VEOL end the line and stay part of it; VEOF (Ctrl-D) just
+        // releases what's there. There's no way to signal a 0-byte read
+        // through `KEYBOARD_BUFFER`, so a Ctrl-D on an empty line does
+        // nothing rather than reporting EOF.
+        // The terminator goes at the end of the line wherever the caret is.
+        if byte != t.c_cc[VEOF] {
+            let len = line.len;
+            line.move_caret(len, echo_on);
+            if len < MAX_CANON {
+                line.insert(byte as u8, echo_on);
+            } else if echo_on {
+                echo(&[byte as u8]);
+            }
+        }
+        let any = line.len > 0;
+        line.flush();
+        any
+    } else {
+        line.insert(byte as u8, echo_on);
+        false
+    }
+}
+
+/// TCSETS/TCSETSW/TCSETSF: install new settings. Leaving cooked mode makes
+/// any half-typed line readable as-is, the way a real tty does, so
+/// type-ahead isn't lost when a program switches to raw mode.
+pub fn set_termios(t: Termios) {
+    crate::interrupts::without_interrupts(|| {
+        let was_canonical = {
+            let mut cur = TERMIOS.lock();
+            let was = cur.c_lflag & ICANON != 0;
+            *cur = t;
+            was
+        };
+        if was_canonical && t.c_lflag & ICANON == 0 {
+            LINE.lock().flush();
+        }
+    });
 }
@@ -20,6 +20,7 @@
 #include <fcntl.h>
 #include <limits.h>
 #include <poll.h>
+#include <sched.h>
 #include <signal.h>
 #include <stdarg.h>
 #include <stdint.h>
@@ -29,6 +30,8 @@
 #include <sys/stat.h>
 #include <sys/statvfs.h>
 #include <sys/sysinfo.h>
+#include <sys/times.h>
+#include <sys/uio.h>
 #include <sys/utsname.h>
 #include <termios.h>
 
@@ -53,6 +56,10 @@ constexpr long SYS_open = 2;
 constexpr long SYS_close = 3;
 constexpr long SYS_stat = 4;
 constexpr long SYS_fstat = 5;
+constexpr long SYS_pread64 = 17;
+constexpr long SYS_pwrite64 = 18;
+constexpr long SYS_readv = 19;
+constexpr long SYS_writev = 20;
 constexpr long SYS_getdents64 = 217;
 constexpr long SYS_sigaction = 13;
 constexpr long SYS_sigprocmask = 14;
@@ -86,12 +93,14 @@ constexpr long SYS_munmap = 11;
 constexpr long SYS_ioctl = 16;
 constexpr long SYS_nanosleep = 35;
 constexpr long SYS_getpid = 39;
+constexpr long SYS_gettid = 186;
 constexpr long SYS_clone = 56;
 constexpr long SYS_fork = 57;
 constexpr long SYS_execve = 59;
 constexpr long SYS_exit = 60;
 constexpr long SYS_waitpid = 61;
 constexpr long SYS_kill = 62;
+constexpr long SYS_times = 100;
 constexpr long SYS_setpgid = 109;
 constexpr long SYS_setsid = 112;
 constexpr long SYS_getpgid = 121;
@@ -105,7 +114,10 @@ constexpr long TCSETS_REQ = 0x5402;
 constexpr long TCSETSW_REQ = 0x5403;
 constexpr long TCSETSF_REQ = 0x5404;
 constexpr long SYS_futex = 202;
+constexpr long SYS_sched_setaffinity = 203;
+constexpr long SYS_sched_getaffinity = 204;
 constexpr long SYS_clock_gettime = 228;
+constexpr long SYS_getrandom = 318;
 
 constexpr long ARCH_SET_FS = 0x1002;
 constexpr long FUTEX_WAIT = 0;
@@ -170,6 +182,15 @@ int sys_clock_get(int clock, time_t *secs, long *nanos) {
 }
 #endif
 
+#ifndef MLIBC_BUILDING_RTLD
+int sys_getentropy(void *buffer, size_t length) {
+	long ret = raw_syscall(SYS_getrandom, (long)buffer, (long)length, 0);
+	if (ret < 0)
+		return (int)-ret;
+	return 0;
+}
+#endif
+
 int sys_open(const char *path, int flags, mode_t, int *fd) {
 	long ret = raw_syscall(SYS_open, (long)path, flags);
 	if (ret < 0)
@@ -452,6 +473,38 @@ int sys_write(int fd, const void *buf, size_t count, ssize_t *bytes_written) {
 }
 #endif
 
+int sys_pread(int fd, void *buf, size_t n, off_t off, ssize_t *bytes_read) {
+	long ret = raw_syscall(SYS_pread64, fd, (long)buf, (long)n, (long)off);
+	if (ret < 0)
+		return (int)-ret;
+	*bytes_read = ret;
+	return 0;
+}
+
+int sys_pwrite(int fd, const void *buf, size_t n, off_t off, ssize_t *bytes_written) {
+	long ret = raw_syscall(SYS_pwrite64, fd, (long)buf, (long)n, (long)off);
+	if (ret < 0)
+		return (int)-ret;
+	*bytes_written = ret;
+	return 0;
+}
+
+int sys_readv(int fd, const struct iovec *iovs, int iovc, ssize_t *bytes_read) {
+	long ret = raw_syscall(SYS_readv, fd, (long)iovs, iovc);
+	if (ret < 0)
+		return (int)-ret;
+	*bytes_read = ret;
+	return 0;
+}
+
+int sys_writev(int fd, const struct iovec *iovs, int iovc, ssize_t *bytes_written) {
+	long ret = raw_syscall(SYS_writev, fd, (long)iovs, iovc);
+	if (ret < 0)
+		return (int)-ret;
+	*bytes_written = ret;
+	return 0;
+}
+
 // TCGETS (0x5401): our kernel's sys_ioctl returns 0 for fd 0/1/2 (the
 // console), ENOTTY otherwise — exactly the check isatty() needs.
 int sys_isatty(int fd) {
@@ -525,6 +578,25 @@ int sys_setsid(pid_t *sid) {
 	return 0;
 }
 
+// The raw syscall writes only the CPUs it knows about and returns how many
+// bytes that was; like glibc, clear the rest so callers can CPU_ISSET
+// anywhere in their set.
+int sys_getaffinity(pid_t pid, size_t cpusetsize, cpu_set_t *mask) {
+	long ret = raw_syscall(SYS_sched_getaffinity, pid, (long)cpusetsize, (long)mask);
+	if (ret < 0)
+		return (int)-ret;
+	if ((size_t)ret < cpusetsize)
+		__builtin_memset((char *)mask + ret, 0, cpusetsize - ret);
+	return 0;
+}
+
+int sys_setaffinity(pid_t pid, size_t cpusetsize, const cpu_set_t *mask) {
+	long ret = raw_syscall(SYS_sched_setaffinity, pid, (long)cpusetsize, (long)mask);
+	if (ret < 0)
+		return (int)-ret;
+	return 0;
+}
+
 int sys_seek(int fd, off_t offset, int whence, off_t *new_offset) {
 	long ret = raw_syscall(SYS_lseek, fd, offset, whence);
 	if (ret < 0)
@@ -551,8 +623,10 @@ int sys_vm_map(void *hint, size_t size, int prot, int flags,
 		int fd, off_t, void **window) {
 	__ensure(flags & MAP_ANONYMOUS);
 	(void)fd;
+	// MAP_SHARED is the only other flag the kernel looks at (shared across
+	// fork instead of COW, see kernel/src/memory/vma.rs VmaKind::Shared).
 	long ret = raw_syscall(SYS_mmap, (long)hint, (long)size, prot,
-			MAP_ANONYMOUS, -1);
+			MAP_ANONYMOUS | (flags & MAP_SHARED), -1);
 	if (ret < 0)
 		return (int)-ret;
 	*window = (void *)ret;
@@ -659,6 +733,21 @@ pid_t sys_getpid() {
 	return (pid_t)raw_syscall(SYS_getpid);
 }
 
+// getpid() is the thread group id (same in every thread), gettid() the
+// calling thread's own id — see Process::tgid in kernel/src/process/mod.rs.
+pid_t sys_gettid() {
+	return (pid_t)raw_syscall(SYS_gettid);
+}
+
+// Kernel fills all four `struct tms` fields (ticks at 100 Hz, `stime`/
+// `cstime` always 0 — no user/system split) and returns ticks since boot.
+int sys_times(struct tms *tms, clock_t *out) {
+	long ret = raw_syscall(SYS_times, (long)tms);
+	if (ret < 0) return (int)-ret;
+	*out = (clock_t)ret;
+	return 0;
+}
+
 pid_t sys_getppid() {
 	// Not tracked by this kernel; harmless placeholder.
 	return 1;
@@ -19,13 +19,17 @@
 
 #define WCOREFLAG 0x80
 
-#define WEXITSTATUS(x) ((x) & 0x000000FF)
-#define WIFCONTINUED(x) ((x) & 0x00000100)
-#define WIFEXITED(x) ((x) & 0x00000200)
-#define WIFSIGNALED(x) ((x) & 0x00000400)
-#define WIFSTOPPED(x) ((x) & 0x00000800)
-#define WSTOPSIG(x) (((x) & 0x00FF0000) >> 16)
-#define WTERMSIG(x) (((x) & 0xFF000000) >> 24)
+/* Status-word layout is Linux's (mlibc abis/linux/wait.h), matching what
+ * the kernel's waitpid() writes (kernel/src/process/wait_status.rs). The
+ * option flags above are still this header's own values — the kernel's
+ * sys_waitpid parses those, not Linux's. */
+#define WEXITSTATUS(x) (((x) & 0xff00) >> 8)
+#define WTERMSIG(x) ((x) & 0x7f)
+#define WSTOPSIG(x) WEXITSTATUS(x)
+#define WIFEXITED(x) (WTERMSIG(x) == 0)
+#define WIFSIGNALED(x) (((signed char) (((x) & 0x7f) + 1) >> 1) > 0)
+#define WIFSTOPPED(x) (((x) & 0xff) == 0x7f)
+#define WIFCONTINUED(x) ((x) == 0xffff)
 #define WCOREDUMP(x) ((x) & WCOREFLAG)
 
 #endif /*_ABIBITS_WAIT_H */
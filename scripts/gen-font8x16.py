@@ -0,0 +1,260 @@
+#!/usr/bin/env python3
+# scripts/gen-font8x16.py
+#
+# Regenerates kernel/src/font/dejavu8x16.rs: DejaVu Sans Mono rasterized
+# into 8x16 one-bit cells for code points U+0000-U+00FF (ASCII + Latin-1),
+# in font8x8's row format (one byte per pixel row, bit 0 = leftmost
+# column), so `framebuffer::Font` can index it exactly like BASIC_LEGACY.
+#
+# Usage: scripts/gen-font8x16.py [path/to/DejaVuSansMono.ttf]
+#        (default: /usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf)
+#
+# No dependencies beyond the standard library — a minimal TrueType reader
+# (cmap format 4, loca/glyf with simple and composite glyphs) and a 4x4
+# supersampled nonzero-winding scanline rasterizer. No real hinting, so
+# the output was tuned by eye: the advance width is scaled to exactly 8 px,
+# the baseline sits at row 12 (leaving rows 13-15 for descenders), each
+# glyph is nudged by whichever sub-pixel offset leaves the fewest
+# half-covered pixels (snaps most ~1 px stems onto one column), and a pixel
+# is set at >= 40% coverage. Code points with no glyph in the font (C0/C1
+# controls) come out blank.
+
+import struct
+import sys
+
+FONT = sys.argv[1] if len(sys.argv) > 1 else "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf"
+OUT = "kernel/src/font/dejavu8x16.rs"
+W, H = 8, 16
+BASELINE = 12
+SS = 4
+THRESHOLD = 0.40
+SHIFTS = (-0.375, -0.25, -0.125, 0.0, 0.125, 0.25, 0.375)
+
+
+class Ttf:
+    def __init__(self, data):
+        self.d = data
+        num_tables = struct.unpack(">H", data[4:6])[0]
+        self.tables = {}
+        for i in range(num_tables):
+            tag, _, off, length = struct.unpack(">4sIII", data[12 + 16 * i:28 + 16 * i])
+            self.tables[tag.decode()] = (off, length)
+        head = self.tables["head"][0]
+        self.units_per_em = struct.unpack(">H", data[head + 18:head + 20])[0]
+        self.loca_long = struct.unpack(">h", data[head + 50:head + 52])[0] == 1
+        maxp = self.tables["maxp"][0]
+        self.num_glyphs = struct.unpack(">H", data[maxp + 4:maxp + 6])[0]
+        hmtx = self.tables["hmtx"][0]
+        self.advance = struct.unpack(">H", data[hmtx:hmtx + 2])[0]
+        self.cmap = self._read_cmap()
+
+    def u16(self, o):
+        return struct.unpack(">H", self.d[o:o + 2])[0]
+
+    def i16(self, o):
+        return struct.unpack(">h", self.d[o:o + 2])[0]
+
+    def _read_cmap(self):
+        base = self.tables["cmap"][0]
+        n = self.u16(base + 2)
+        for i in range(n):
+            plat, enc, off = struct.unpack(">HHI", self.d[base + 4 + 8 * i:base + 12 + 8 * i])
+            if plat == 3 and enc == 1 and self.u16(base + off) == 4:
+                return base + off
+        raise SystemExit("no (3,1) format-4 cmap")
+
+    def glyph_index(self, cp):
+        t = self.cmap
+        segx2 = self.u16(t + 6)
+        ends = t + 14
+        starts = ends + segx2 + 2
+        deltas = starts + segx2
+        ranges = deltas + segx2
+        for s in range(segx2 // 2):
+            end = self.u16(ends + 2 * s)
+            if cp > end:
+                continue
+            start = self.u16(starts + 2 * s)
+            if cp < start:
+                return 0
+            delta = self.i16(deltas + 2 * s)
+            ro = self.u16(ranges + 2 * s)
+            if ro == 0:
+                return (cp + delta) & 0xFFFF
+            g = self.u16(ranges + 2 * s + ro + 2 * (cp - start))
+            return (g + delta) & 0xFFFF if g else 0
+        return 0
+
+    def _glyph_range(self, g):
+        loca = self.tables["loca"][0]
+        if self.loca_long:
+            a, b = struct.unpack(">II", self.d[loca + 4 * g:loca + 4 * g + 8])
+        else:
+            a, b = (2 * x for x in struct.unpack(">HH", self.d[loca + 2 * g:loca + 2 * g + 4]))
+        glyf = self.tables["glyf"][0]
+        return glyf + a, b - a
+
+    def contours(self, g):
+        """List of contours, each a list of (x, y, on_curve)."""
+        off, length = self._glyph_range(g)
+        if length == 0:
+            return []
+        ncont = self.i16(off)
+        p = off + 10
+        if ncont >= 0:
+            ends = [self.u16(p + 2 * i) for i in range(ncont)]
+            p += 2 * ncont
+            p += 2 + self.u16(p)  # skip instructions
+            npts = ends[-1] + 1 if ends else 0
+            flags = []
+            while len(flags) < npts:
+                f = self.d[p]; p += 1
+                flags.append(f)
+                if f & 8:
+                    r = self.d[p]; p += 1
+                    flags.extend([f] * r)
+            xs, x = [], 0
+            for f in flags:
+                if f & 2:
+                    dx = self.d[p]; p += 1
+                    x += dx if f & 16 else -dx
+                elif not f & 16:
+                    x += self.i16(p); p += 2
+                xs.append(x)
+            ys, y = [], 0
+            for f in flags:
+                if f & 4:
+                    dy = self.d[p]; p += 1
+                    y += dy if f & 32 else -dy
+                elif not f & 32:
+                    y += self.i16(p); p += 2
+                ys.append(y)
+            out, start = [], 0
+            for e in ends:
+                out.append([(xs[i], ys[i], flags[i] & 1) for i in range(start, e + 1)])
+                start = e + 1
+            return out
+        # Composite glyph.
+        out = []
+        while True:
+            flags, comp = self.u16(p), self.u16(p + 2)
+            p += 4
+            if flags & 1:
+                a1, a2 = self.i16(p), self.i16(p + 2); p += 4
+            else:
+                a1, a2 = struct.unpack(">bb", self.d[p:p + 2]); p += 2
+            xx, xy, yx, yy = 1.0, 0.0, 0.0, 1.0
+            if flags & 8:
+                xx = yy = self.i16(p) / 16384; p += 2
+            elif flags & 0x40:
+                xx, yy = self.i16(p) / 16384, self.i16(p + 2) / 16384; p += 4
+            elif flags & 0x80:
+                xx, xy, yx, yy = (self.i16(p + 2 * i) / 16384 for i in range(4)); p += 8
+            if not flags & 2:
+                raise SystemExit("point-matched composite components unsupported")
+            for c in self.contours(comp):
+                out.append([(xx * x + yx * y + a1, xy * x + yy * y + a2, on) for x, y, on in c])
+            if not flags & 0x20:
+                return out
+
+
+def flatten(contour, steps=8):
+    """Quadratic B-spline contour -> closed polyline."""
+    n = len(contour)
+    pts = []
+    for i in range(n):
+        x, y, on = contour[i]
+        if not on:
+            px, py, pon = contour[i - 1]
+            if not pon:
+                px, py = (px + x) / 2, (py + y) / 2
+            pts.append((px, py, True))
+            pts.append((x, y, False))
+            nx, ny, non = contour[(i + 1) % n]
+            if not non:
+                pts.append(((x + nx) / 2, (y + ny) / 2, True))
+        else:
+            pts.append((x, y, True))
+    poly = []
+    i = 0
+    while i < len(pts):
+        x, y, on = pts[i]
+        if on:
+            poly.append((x, y))
+            i += 1
+            continue
+        (x0, y0) = poly[-1]
+        x2, y2, _ = pts[(i + 1) % len(pts)]
+        for s in range(1, steps):
+            t = s / steps
+            poly.append(((1 - t) ** 2 * x0 + 2 * t * (1 - t) * x + t * t * x2,
+                         (1 - t) ** 2 * y0 + 2 * t * (1 - t) * y + t * t * y2))
+        i += 1
+    return poly
+
+
+def coverage(edges, dx, dy):
+    """Per-pixel coverage (0..1) of the outline shifted by (dx, dy) px."""
+    cov = []
+    for row in range(H):
+        line = []
+        for sy in range(SS):
+            py = row + (sy + 0.5) / SS - dy
+            crossings = []
+            for (x0, y0), (x1, y1) in edges:
+                if (y0 <= py) != (y1 <= py):
+                    crossings.append((x0 + (py - y0) * (x1 - x0) / (y1 - y0), 1 if y1 > y0 else -1))
+            samples = []
+            for col in range(W):
+                hits = 0
+                for sx in range(SS):
+                    px = col + (sx + 0.5) / SS - dx
+                    if sum(d for x, d in crossings if x < px) != 0:
+                        hits += 1
+                samples.append(hits)
+            line.append(samples)
+        cov.append([sum(line[sy][col] for sy in range(SS)) / (SS * SS) for col in range(W)])
+    return cov
+
+
+def rasterize(font, cp):
+    g = font.glyph_index(cp)
+    if g == 0:
+        return [0] * H
+    scale = W / font.advance
+    edges = []
+    for c in font.contours(g):
+        poly = [(x * scale, BASELINE - y * scale) for x, y in flatten(c)]
+        for i in range(len(poly)):
+            edges.append((poly[i - 1], poly[i]))
+    # Poor man's hinting: of a few sub-pixel nudges, keep the one whose
+    # pixels are most decisively in or out (fewest half-covered ones).
+    best = max((coverage(edges, dx, dy) for dx in SHIFTS for dy in SHIFTS),
+               key=lambda cov: sum(abs(c - 0.5) for row in cov for c in row))
+    return [sum(1 << col for col in range(W) if row[col] >= THRESHOLD) for row in best]
+
+
+def main():
+    font = Ttf(open(FONT, "rb").read())
+    glyphs = [rasterize(font, cp) for cp in range(256)]
+    with open(OUT, "w") as f:
+        f.write("// kernel/src/font/dejavu8x16.rs\n")
+        f.write("//\n")
+        f.write("// GENERATED by scripts/gen-font8x16.py from DejaVu Sans Mono — do not\n")
+        f.write("// edit by hand. 8x16 cells for U+0000-U+00FF, one byte per row, bit 0 =\n")
+        f.write("// leftmost column (font8x8's layout). DejaVu is derived from Bitstream\n")
+        f.write("// Vera, whose license (DejaVu's LICENSE file) permits redistributing\n")
+        f.write("// modified versions, including rasterized bitmaps like these, with\n")
+        f.write("// its notice: Copyright (c) 2003 by Bitstream, Inc. All Rights\n")
+        f.write("// Reserved. Bitstream Vera is a trademark of Bitstream, Inc. DejaVu\n")
+        f.write("// changes are in the public domain.\n\n")
+        f.write("pub const DEJAVU_8X16: [[u8; 16]; 256] = [\n")
+        for cp, rows in enumerate(glyphs):
+            body = ", ".join("0x%02X" % r for r in rows)
+            label = chr(cp) if 0x20 < cp < 0x7F or 0xA0 < cp else "U+%04X" % cp
+            f.write("    [%s], // %s\n" % (body, label))
+        f.write("];\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -34,6 +34,10 @@
 #                                                    # screendump/pixel-sampling needed just to
 #                                                    # confirm what SGR codes got emitted.
 #   scripts/qemu-debug.sh dlog [N]                  # tail -n N debug.log (-d int trace)
+#   scripts/qemu-debug.sh clog [N]                  # tail -n N console.log (COM2) — empty
+#                                                    # unless the guest ran `kdebug console 2`,
+#                                                    # which moves /dev/console and the [fb]
+#                                                    # mirror off serial.log (COM1) onto it
 #   scripts/qemu-debug.sh wait-for PATTERN [TIMEOUT_SECS]   # poll serial.log for a regex
 #
 # Common flow:
@@ -52,6 +56,7 @@ REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 STATE_DIR="/tmp/qemu-debug-rust_so_kernel"
 SOCK="$STATE_DIR/monitor.sock"
 SERIAL_LOG="$STATE_DIR/serial.log"
+CONSOLE_LOG="$STATE_DIR/console.log"
 DEBUG_LOG="$STATE_DIR/debug.log"
 QEMU_STDOUT="$STATE_DIR/qemu-stdout.log"
 PID_FILE="$STATE_DIR/qemu.pid"
@@ -113,6 +118,7 @@ cmd_start() {
 
     rm -f "$SOCK"
     : > "$SERIAL_LOG"
+    : > "$CONSOLE_LOG"
     : > "$DEBUG_LOG"
 
     # AC97 audiodev backend: "none" by default (no host audio needed,
@@ -128,6 +134,7 @@ cmd_start() {
         -m 512M
         -cpu max
         -serial "file:$SERIAL_LOG"
+        -serial "file:$CONSOLE_LOG"
         -monitor "unix:$SOCK,server,nowait"
         -display none
         -d int,guest_errors -D "$DEBUG_LOG"
@@ -284,6 +291,10 @@ cmd_dlog() {
     tail -n "${1:-100}" "$DEBUG_LOG"
 }
 
+cmd_clog() {
+    tail -n "${1:-100}" "$CONSOLE_LOG"
+}
+
 cmd_wait_for() {
     local pattern="$1"
     local timeout="${2:-15}"
@@ -312,9 +323,10 @@ case "${1:-}" in
     log) cmd_log "${2:-}" ;;
     rawlog) cmd_rawlog "${2:-}" ;;
     dlog) cmd_dlog "${2:-}" ;;
+    clog) cmd_clog "${2:-}" ;;
     wait-for) cmd_wait_for "$2" "${3:-}" ;;
     *)
-        echo "Usage: $0 {start|stop|status|send TEXT|key KEY...|enter|screendump [out]|log [N]|rawlog [N]|dlog [N]|wait-for PATTERN [TIMEOUT]}" >&2
+        echo "Usage: $0 {start|stop|status|send TEXT|key KEY...|enter|screendump [out]|log [N]|rawlog [N]|dlog [N]|clog [N]|wait-for PATTERN [TIMEOUT]}" >&2
         exit 1
         ;;
 esac
@@ -0,0 +1,48 @@
+// Shell front end for kernel/src/pcspeaker.rs: `beep [freq_hz] [ms]`
+// sounds the PC speaker (PIT channel 2) for `ms` milliseconds, defaulting
+// to 750 Hz for 100 ms. Goes through the Linux console's KDMKTONE ioctl
+// on stdout, so stdout has to be the console (serial or framebuffer) —
+// anything else gets ENOTTY, same as on Linux.
+//
+// KDMKTONE returns at once and the kernel stops the tone from a timer,
+// as on Linux, so this sleeps for the duration itself — the prompt comes
+// back when the beep ends. Uses a local raw_syscall() for the same reason
+// kdebug.c does — no dependency on which ioctl request macros mlibc's
+// headers happen to ship.
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#define SYS_IOCTL 16
+#define KDMKTONE 0x4B30
+#define PIT_FREQUENCY_HZ 1193182
+
+static long raw_syscall(long nr, long a1, long a2, long a3) {
+    long ret;
+    register long r10 asm("r10") = 0;
+    register long r8  asm("r8")  = 0;
+    asm volatile ("syscall"
+            : "=a"(ret)
+            : "a"(nr), "D"(a1), "S"(a2), "d"(a3), "r"(r10), "r"(r8)
+            : "rcx", "r11", "memory");
+    return ret;
+}
+
+int main(int argc, char **argv) {
+    long freq = argc > 1 ? strtol(argv[1], NULL, 10) : 750;
+    long ms = argc > 2 ? strtol(argv[2], NULL, 10) : 100;
+    if (argc > 3 || freq < 19 || freq > PIT_FREQUENCY_HZ || ms <= 0 || ms > 0xFFFF) {
+        printf("usage: beep [freq_hz 19-%d] [ms 1-65535]\n", PIT_FREQUENCY_HZ);
+        return 1;
+    }
+
+    long count = PIT_FREQUENCY_HZ / freq;
+    long ret = raw_syscall(SYS_IOCTL, 1, KDMKTONE, (ms << 16) | count);
+    if (ret < 0) {
+        printf("beep: ioctl(KDMKTONE) failed: %ld\n", ret);
+        return 1;
+    }
+    struct timespec ts = { ms / 1000, (ms % 1000) * 1000000L };
+    nanosleep(&ts, NULL);
+    return 0;
+}
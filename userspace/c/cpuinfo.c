@@ -0,0 +1,21 @@
+// `cpuinfo`: prints /proc/cpuinfo (kernel/src/cpu/features.rs's
+// render_cpuinfo) — CPU vendor and brand string from CPUID, the calibrated
+// TSC frequency, and which of the features the kernel checks for (fpu,
+// tsc, apic, sse, sse2, x2apic, rdrand, nx, constant_tsc) this CPU has.
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main(void) {
+    int fd = open("/proc/cpuinfo", O_RDONLY);
+    if (fd < 0) {
+        printf("cpuinfo: can't open /proc/cpuinfo\n");
+        return 1;
+    }
+    char buf[512];
+    ssize_t n;
+    while ((n = read(fd, buf, sizeof buf)) > 0)
+        write(1, buf, n);
+    close(fd);
+    return 0;
+}
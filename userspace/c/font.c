@@ -0,0 +1,57 @@
+// Shell front end for the framebuffer console's fonts (kernel/src/font/):
+// `font 8x16` switches to the DejaVu Sans Mono 8x16 font, `font 8x8` back
+// to the original font8x8 one. Goes through the custom FBIO_SETFONT ioctl
+// on stdout, so stdout has to be /dev/fb — the serial console has no font
+// and gets ENOTTY.
+//
+// The switch clears the screen (the text grid changes size with the font).
+// Full-screen programs started before it keep the old TIOCGWINSZ size
+// until they ask again. Uses a local raw_syscall() for the same reason
+// beep.c does.
+#include <stdio.h>
+#include <string.h>
+
+#define SYS_IOCTL 16
+#define FBIO_SETFONT 0x46420002
+
+// Index = FBIO_SETFONT argument = position in kernel/src/font's FONTS.
+static const char *const fonts[] = { "8x8", "8x16" };
+#define NFONTS (sizeof fonts / sizeof fonts[0])
+
+static long raw_syscall(long nr, long a1, long a2, long a3) {
+    long ret;
+    register long r10 asm("r10") = 0;
+    register long r8  asm("r8")  = 0;
+    asm volatile ("syscall"
+            : "=a"(ret)
+            : "a"(nr), "D"(a1), "S"(a2), "d"(a3), "r"(r10), "r"(r8)
+            : "rcx", "r11", "memory");
+    return ret;
+}
+
+static void usage(void) {
+    printf("usage: font <");
+    for (unsigned i = 0; i < NFONTS; i++)
+        printf(i ? "|%s" : "%s", fonts[i]);
+    printf(">\n");
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        usage();
+        return 1;
+    }
+    for (unsigned i = 0; i < NFONTS; i++) {
+        if (strcmp(argv[1], fonts[i]) != 0)
+            continue;
+        fflush(stdout);
+        long ret = raw_syscall(SYS_IOCTL, 1, FBIO_SETFONT, i);
+        if (ret < 0) {
+            printf("font: ioctl(FBIO_SETFONT) failed: %ld\n", ret);
+            return 1;
+        }
+        return 0;
+    }
+    usage();
+    return 1;
+}
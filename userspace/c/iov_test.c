@@ -0,0 +1,56 @@
+// readv(19)/writev(20) (kernel/src/process/syscall/fs.rs's iov_loop):
+// writes a header + body pair through a pipe in one writev, reads it back
+// split across two differently sized buffers with one readv, and finally
+// writev's a two-part line to stdout.
+#include <stdio.h>
+#include <string.h>
+#include <sys/uio.h>
+#include <unistd.h>
+
+int main(void) {
+    int fds[2];
+    if (pipe(fds) != 0) {
+        printf("iov_test: pipe FAILED\n");
+        return 1;
+    }
+
+    char header[] = "HDR:";
+    char body[] = "scatter-gather";
+    struct iovec out[2] = {
+        { header, strlen(header) },
+        { body, strlen(body) },
+    };
+    size_t want = out[0].iov_len + out[1].iov_len;
+    ssize_t n = writev(fds[1], out, 2);
+    if (n != (ssize_t)want) {
+        printf("iov_test: writev returned %ld, expected %lu\n", (long)n, (unsigned long)want);
+        return 1;
+    }
+
+    char a[6] = {0}, b[32] = {0};
+    struct iovec in[2] = {
+        { a, sizeof a - 1 },
+        { b, sizeof b - 1 },
+    };
+    n = readv(fds[0], in, 2);
+    if (n != (ssize_t)want) {
+        printf("iov_test: readv returned %ld, expected %lu\n", (long)n, (unsigned long)want);
+        return 1;
+    }
+    if (strcmp(a, "HDR:sc") != 0 || strcmp(b, "atter-gather") != 0) {
+        printf("iov_test: readv split wrong: '%s' + '%s'\n", a, b);
+        return 1;
+    }
+    close(fds[0]);
+    close(fds[1]);
+
+    char part1[] = "iov_test: ";
+    char part2[] = "OK\n";
+    struct iovec line[2] = {
+        { part1, strlen(part1) },
+        { part2, strlen(part2) },
+    };
+    fflush(stdout);
+    writev(1, line, 2);
+    return 0;
+}
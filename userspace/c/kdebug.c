@@ -3,13 +3,32 @@
 // the custom kdebug_ctl syscall (403). See kernel::debug's doc comment for
 // why this exists: tracepoints stay in the code permanently instead of
 // being hand-added and stripped out per bug, gated so they're silent by
-// default.
+// default. `kdebug loglevel [n]` reads/sets kernel::log's severity
+// filter (0 = silent, 1 = error .. 5 = trace; boot default 3 = info).
+// `kdebug faultaround [n]` reads/sets kernel::memory::demand_paging's
+// fault-around cluster (pages mapped per anonymous write fault; a power
+// of two up to 16, boot default 1 = single-page). `kdebug console [1|2]`
+// and `kdebug logport [1|2]` read/move kernel::serial's console and log
+// roles between COM1 and COM2 (COM2 only if it passed its boot self-test).
+// `kdebug pagewalk <hexaddr>` prints each page-table level's entry for
+// that address in kdebug's own address space, and where the walk ended.
+// `kdebug schedstat` prints the scheduler's context-switch counters —
+// voluntary vs. preempted, queue migrations — and each process's own.
+// `kdebug trace <pid> [steps]` toggles single-stepping that process: each
+// user instruction it runs logs its registers to serial, up to `steps`
+// (kernel default 256, at most 4096), then tracing turns itself off.
+// `kdebug tty [fb|serial|both]` shows/sets where /dev/tty output goes —
+// every process's stdout and stderr (kernel::drivers::dev_tty).
+// `kdebug run <test>` starts one of the kernel's built-in raw-asm user
+// tests (kernel::process::user_test_fileio: write, devnull, fb, yield) as
+// a new process and prints its pid.
 //
 // Talks straight to the syscall instruction (no mlibc wrapper exists for
 // this kernel-specific syscall) using the exact same rax=nr,
 // rdi/rsi/rdx/r10/r8=args convention as mlibc-port/constanos-sysdeps's own
 // internal raw_syscall() (kernel/src/process/syscall.rs's ABI doc comment).
 #include <stdio.h>
+#include <stdlib.h>
 #include <string.h>
 
 #define SYS_KDEBUG_CTL 403
@@ -25,19 +44,200 @@ static long raw_syscall(long nr, long a1, long a2, long a3) {
     return ret;
 }
 
+// One `kdebug <name> [n]` subcommand: `get`/`set` are its kdebug_ctl cmd
+// pair, `run` prints the value in its own format. Dispatch and `usage()`
+// both read this table, so adding a knob is one row.
+struct command {
+    const char *name;
+    const char *args;
+    const char *help;
+    int (*run)(const struct command *cmd, int argc, char **argv);
+    long get, set;
+};
+
+static void usage(void);
+
+// Parse `kdebug <name> <n>`'s argument; 0 on success.
+static int parse_value(int argc, char **argv, long *n) {
+    char *end;
+    *n = strtol(argv[2], &end, 10);
+    return (argc != 3 || *end != '\0') ? -1 : 0;
+}
+
+// `kdebug loglevel|faultaround [n]`: plain numbers.
+static int number_cmd(const struct command *cmd, int argc, char **argv) {
+    if (argc == 2) {
+        printf("kdebug: %s=%ld\n", cmd->name, raw_syscall(SYS_KDEBUG_CTL, cmd->get, 0, 0));
+        return 0;
+    }
+    long n;
+    long prev = parse_value(argc, argv, &n) == 0 ? raw_syscall(SYS_KDEBUG_CTL, cmd->set, 0, n) : -1;
+    if (prev < 0) {
+        usage();
+        return 1;
+    }
+    printf("kdebug: %s %ld -> %ld\n", cmd->name, prev, n);
+    return 0;
+}
+
+// `kdebug console|logport [n]`: serial port numbers.
+static int serial_port_cmd(const struct command *cmd, int argc, char **argv) {
+    if (argc == 2) {
+        printf("kdebug: %s=COM%ld\n", cmd->name, raw_syscall(SYS_KDEBUG_CTL, cmd->get, 0, 0));
+        return 0;
+    }
+    long n;
+    if (parse_value(argc, argv, &n) != 0) {
+        usage();
+        return 1;
+    }
+    long prev = raw_syscall(SYS_KDEBUG_CTL, cmd->set, 0, n);
+    if (prev < 0) {
+        printf("kdebug: no COM%ld\n", n);
+        return 1;
+    }
+    printf("kdebug: %s COM%ld -> COM%ld\n", cmd->name, prev, n);
+    return 0;
+}
+
+// `kdebug pagewalk <hexaddr>`: `get` is the walk cmd, the report comes
+// back as text.
+static int pagewalk_cmd(const struct command *cmd, int argc, char **argv) {
+    char report[1024];
+    char *end;
+    if (argc != 3) {
+        usage();
+        return 1;
+    }
+    unsigned long addr = strtoul(argv[2], &end, 16);
+    if (*end != '\0' || raw_syscall(SYS_KDEBUG_CTL, cmd->get, (long)report, (long)addr) < 0) {
+        printf("kdebug: bad address '%s'\n", argv[2]);
+        return 1;
+    }
+    fputs(report, stdout);
+    return 0;
+}
+
+// `kdebug schedstat`: like pagewalk, a text report with no argument.
+static int schedstat_cmd(const struct command *cmd, int argc, char **argv) {
+    static char report[4096];
+    (void)argv;
+    if (argc != 2) {
+        usage();
+        return 1;
+    }
+    if (raw_syscall(SYS_KDEBUG_CTL, cmd->get, (long)report, 0) < 0) {
+        printf("kdebug: schedstat failed\n");
+        return 1;
+    }
+    fputs(report, stdout);
+    return 0;
+}
+
+// `kdebug trace <pid> [steps]`: pid rides in the name slot, the step
+// count in `enable`; the kernel toggles and returns the new budget.
+static int trace_cmd(const struct command *cmd, int argc, char **argv) {
+    char *end;
+    if (argc != 3 && argc != 4) {
+        usage();
+        return 1;
+    }
+    long pid = strtol(argv[2], &end, 10);
+    long steps = 0;
+    if (*end == '\0' && argc == 4)
+        steps = strtol(argv[3], &end, 10);
+    if (*end != '\0' || pid <= 0 || steps < 0) {
+        usage();
+        return 1;
+    }
+    long left = raw_syscall(SYS_KDEBUG_CTL, cmd->set, pid, steps);
+    if (left < 0) {
+        printf("kdebug: no process %ld\n", pid);
+        return 1;
+    }
+    if (left == 0)
+        printf("kdebug: trace %ld off\n", pid);
+    else
+        printf("kdebug: trace %ld on (%ld steps, logged to serial)\n", pid, left);
+    return 0;
+}
+
+// `kdebug tty [fb|serial|both]`: /dev/tty's output bits, by name.
+static const char *const TTY_OUTPUTS[] = { "?", "fb", "serial", "both" };
+
+static int tty_cmd(const struct command *cmd, int argc, char **argv) {
+    if (argc == 2) {
+        long cur = raw_syscall(SYS_KDEBUG_CTL, cmd->get, 0, 0);
+        printf("kdebug: tty=%s\n", TTY_OUTPUTS[cur & 3]);
+        return 0;
+    }
+    long n = 0;
+    for (long i = 1; i <= 3; i++) {
+        if (argc == 3 && strcmp(argv[2], TTY_OUTPUTS[i]) == 0)
+            n = i;
+    }
+    long prev = n ? raw_syscall(SYS_KDEBUG_CTL, cmd->set, 0, n) : -1;
+    if (prev < 0) {
+        usage();
+        return 1;
+    }
+    printf("kdebug: tty %s -> %s\n", TTY_OUTPUTS[prev & 3], TTY_OUTPUTS[n]);
+    return 0;
+}
+
+// `kdebug run <test>`: the test name rides in the name slot; the kernel
+// returns the new process's pid.
+static int run_cmd(const struct command *cmd, int argc, char **argv) {
+    if (argc != 3) {
+        usage();
+        return 1;
+    }
+    long pid = raw_syscall(SYS_KDEBUG_CTL, cmd->set, (long)argv[2], 0);
+    if (pid < 0) {
+        printf("kdebug: run %s failed (%ld)\n", argv[2], pid);
+        return 1;
+    }
+    printf("kdebug: started '%s' as pid %ld\n", argv[2], pid);
+    return 0;
+}
+
+static const struct command COMMANDS[] = {
+    { "loglevel",    "[0-5]",     "show/set log level (3 = info)",            number_cmd,      2, 3 },
+    { "faultaround", "[n]",       "show/set fault-around pages (1 = off)",    number_cmd,      4, 5 },
+    { "console",     "[1|2]",     "show/set serial console port (COM1/COM2)", serial_port_cmd, 6, 7 },
+    { "logport",     "[1|2]",     "show/set kernel log serial port",          serial_port_cmd, 8, 9 },
+    { "pagewalk",    "<hexaddr>", "show the page-table walk for an address",  pagewalk_cmd,    10, 0 },
+    { "schedstat",   "",          "show context-switch counters",             schedstat_cmd,   11, 0 },
+    { "trace",       "<pid> [n]", "toggle single-stepping a process",         trace_cmd,       0, 12 },
+    { "tty",         "[fb|serial|both]", "show/set where /dev/tty output goes", tty_cmd,         13, 14 },
+    { "run",         "<test>",    "start a built-in user test process",       run_cmd,         0, 15 },
+};
+static const size_t NCOMMANDS = sizeof(COMMANDS) / sizeof(COMMANDS[0]);
+
 static void usage(void) {
     printf("usage: kdebug                    show current mask\n");
     printf("       kdebug <subsystem> <on|off>  (mm, sched, fs, proc)\n");
+    for (size_t i = 0; i < NCOMMANDS; i++) {
+        char left[32];
+        snprintf(left, sizeof(left), "%s %s", COMMANDS[i].name, COMMANDS[i].args);
+        printf("       kdebug %-18s %s\n", left, COMMANDS[i].help);
+    }
 }
 
 int main(int argc, char **argv) {
     if (argc == 1) {
         long mask = raw_syscall(SYS_KDEBUG_CTL, 0, 0, 0);
-        printf("kdebug: mask=0x%lx\n", mask);
+        long level = raw_syscall(SYS_KDEBUG_CTL, 2, 0, 0);
+        printf("kdebug: mask=0x%lx loglevel=%ld\n", mask, level);
         usage();
         return 0;
     }
 
+    for (size_t i = 0; i < NCOMMANDS; i++) {
+        if (strcmp(argv[1], COMMANDS[i].name) == 0)
+            return COMMANDS[i].run(&COMMANDS[i], argc, argv);
+    }
+
     if (argc != 3) {
         usage();
         return 1;
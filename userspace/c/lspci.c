@@ -0,0 +1,22 @@
+// `lspci`: prints /proc/pci (kernel/src/pci.rs's enumerate + render_list)
+// — every PCI function with its vendor:device ID, class code, IRQ line and
+// decoded BARs. busybox's own lspci applet stays disabled
+// (busybox-config/minimal.config): it walks /sys/bus/pci, which this
+// kernel doesn't have.
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main(void) {
+    int fd = open("/proc/pci", O_RDONLY);
+    if (fd < 0) {
+        printf("lspci: can't open /proc/pci\n");
+        return 1;
+    }
+    char buf[512];
+    ssize_t n;
+    while ((n = read(fd, buf, sizeof buf)) > 0)
+        write(1, buf, n);
+    close(fd);
+    return 0;
+}
@@ -0,0 +1,21 @@
+// `memmap`: prints /proc/memmap (kernel/src/memory/memmap.rs's
+// render_memmap) — the physical memory regions the bootloader handed the
+// kernel (usable, bootloader-owned, firmware-reserved), each with its size,
+// then the per-kind totals.
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main(void) {
+    int fd = open("/proc/memmap", O_RDONLY);
+    if (fd < 0) {
+        printf("memmap: can't open /proc/memmap\n");
+        return 1;
+    }
+    char buf[512];
+    ssize_t n;
+    while ((n = read(fd, buf, sizeof buf)) > 0)
+        write(1, buf, n);
+    close(fd);
+    return 0;
+}
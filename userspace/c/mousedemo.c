@@ -0,0 +1,80 @@
+// Interactive check for /dev/mouse (kernel/src/drivers/dev_mouse.rs):
+// moves a small square around the framebuffer as the PS/2 mouse moves.
+// The square turns red while the left button is held; the right button
+// exits. Reads raw 3-byte PS/2 packets (buttons + 9-bit signed dx/dy, Y
+// positive-up), and draws into a 320x200 offscreen buffer handed to /dev/fb
+// via FBIO_BLIT, which the kernel scales up to the real screen — the same
+// path DOOM uses, see doom-port/doomgeneric_constanos.c.
+#include <fcntl.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <time.h>
+#include <unistd.h>
+
+#define FBIO_BLIT 0x46420001UL
+
+#define W 320
+#define H 200
+#define SIZE 8
+
+struct fb_blit_args {
+    uint64_t ptr;
+    uint32_t width;
+    uint32_t height;
+};
+
+static uint32_t frame[W * H];
+
+static void draw(int fb, int x, int y, int left) {
+    for (int i = 0; i < W * H; i++)
+        frame[i] = 0x00202040;
+    uint32_t color = left ? 0x00FF3030 : 0x00FFFFFF;
+    for (int row = y; row < y + SIZE; row++)
+        for (int col = x; col < x + SIZE; col++)
+            frame[row * W + col] = color;
+    struct fb_blit_args args = { (uint64_t)(uintptr_t)frame, W, H };
+    ioctl(fb, FBIO_BLIT, &args);
+}
+
+static int clamp(int v, int lo, int hi) {
+    return v < lo ? lo : v > hi ? hi : v;
+}
+
+int main(void) {
+    int mouse = open("/dev/mouse", O_RDONLY);
+    int fb = open("/dev/fb", O_WRONLY);
+    if (mouse < 0 || fb < 0) {
+        printf("mousedemo: can't open /dev/mouse or /dev/fb\n");
+        return 1;
+    }
+
+    int x = (W - SIZE) / 2, y = (H - SIZE) / 2, left = 0;
+    draw(fb, x, y, left);
+
+    const struct timespec tick = { 0, 10 * 1000 * 1000 };
+    uint8_t pkt[3 * 16];
+    for (;;) {
+        ssize_t n = read(mouse, pkt, sizeof pkt);
+        if (n <= 0) {
+            nanosleep(&tick, NULL);
+            continue;
+        }
+        for (ssize_t i = 0; i + 3 <= n; i += 3) {
+            uint8_t b0 = pkt[i];
+            if (b0 & 0x02)
+                goto out;
+            int dx = pkt[i + 1] - ((b0 & 0x10) ? 256 : 0);
+            int dy = pkt[i + 2] - ((b0 & 0x20) ? 256 : 0);
+            x = clamp(x + dx, 0, W - SIZE);
+            y = clamp(y - dy, 0, H - SIZE);
+            left = b0 & 0x01;
+        }
+        draw(fb, x, y, left);
+    }
+out:
+    close(mouse);
+    close(fb);
+    return 0;
+}
@@ -0,0 +1,33 @@
+// `reboot`: reset the machine via reboot(2) with LINUX_REBOOT_CMD_RESTART
+// (kernel/src/power.rs), which pulses the 8042 keyboard controller's
+// reset line — QEMU and most real PCs honour it; if not, the kernel logs
+// that the reset was ignored and the syscall fails with EIO. busybox's
+// own reboot applet is disabled (busybox-config/minimal.config): it signals
+// an init process we don't run instead of calling reboot(2) itself.
+//
+// Raw syscall for the same reason as kdebug.c/beep.c: no dependency on
+// whether mlibc's headers ship <sys/reboot.h>.
+#include <stdio.h>
+
+#define SYS_REBOOT 169
+#define LINUX_REBOOT_MAGIC1 0xfee1deadL
+#define LINUX_REBOOT_MAGIC2 672274793L
+#define LINUX_REBOOT_CMD_RESTART 0x01234567L
+
+static long raw_syscall(long nr, long a1, long a2, long a3) {
+    long ret;
+    register long r10 asm("r10") = 0;
+    register long r8  asm("r8")  = 0;
+    asm volatile ("syscall"
+            : "=a"(ret)
+            : "a"(nr), "D"(a1), "S"(a2), "d"(a3), "r"(r10), "r"(r8)
+            : "rcx", "r11", "memory");
+    return ret;
+}
+
+int main(void) {
+    long ret = raw_syscall(SYS_REBOOT, LINUX_REBOOT_MAGIC1, LINUX_REBOOT_MAGIC2,
+                           LINUX_REBOOT_CMD_RESTART);
+    printf("reboot: failed (%ld)\n", ret);
+    return 1;
+}
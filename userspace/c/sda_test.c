@@ -0,0 +1,73 @@
+// Sector round-trip check for /dev/sda (kernel/src/drivers/dev_sda.rs):
+// saves the disk's last sector, overwrites it with a pattern, reads it back
+// through a fresh lseek, compares, then restores the original and checks
+// that too. Also checks that unaligned transfers are refused with EINVAL.
+//
+// /dev/sda is the same disk ext2 has mounted at /mnt, so this only ever
+// touches the last sector and always puts it back — but it IS writing
+// under a live filesystem; don't run it while something else is writing
+// to /mnt.
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define SECTOR 512
+
+static int fail(const char *what) {
+    printf("sda_test: FAIL: %s (errno %d)\n", what, errno);
+    return 1;
+}
+
+static int read_at(int fd, off_t off, unsigned char *buf) {
+    return lseek(fd, off, SEEK_SET) == off && read(fd, buf, SECTOR) == SECTOR;
+}
+
+static int write_at(int fd, off_t off, const unsigned char *buf) {
+    return lseek(fd, off, SEEK_SET) == off && write(fd, buf, SECTOR) == SECTOR;
+}
+
+int main(void) {
+    int fd = open("/dev/sda", O_RDWR);
+    if (fd < 0)
+        return fail("open /dev/sda");
+
+    struct stat st;
+    if (fstat(fd, &st) < 0 || st.st_size < SECTOR)
+        return fail("no disk (fstat size < 1 sector)");
+    off_t last = st.st_size - SECTOR;
+    printf("sda_test: %lld sectors, testing lba %lld\n",
+           (long long)(st.st_size / SECTOR), (long long)(last / SECTOR));
+
+    unsigned char orig[SECTOR], pattern[SECTOR], back[SECTOR];
+    if (!read_at(fd, last, orig))
+        return fail("read original sector");
+
+    for (int i = 0; i < SECTOR; i++)
+        pattern[i] = (unsigned char)(i * 7 + 0x5A);
+    if (!write_at(fd, last, pattern))
+        return fail("write pattern");
+    if (!read_at(fd, last, back) || memcmp(back, pattern, SECTOR) != 0) {
+        write_at(fd, last, orig);
+        return fail("pattern didn't read back");
+    }
+
+    if (!write_at(fd, last, orig))
+        return fail("restore original sector");
+    if (!read_at(fd, last, back) || memcmp(back, orig, SECTOR) != 0)
+        return fail("original didn't read back");
+
+    lseek(fd, 1, SEEK_SET);
+    errno = 0;
+    if (read(fd, back, SECTOR) != -1 || errno != EINVAL)
+        return fail("unaligned read wasn't EINVAL");
+
+    if (read_at(fd, st.st_size, back))
+        return fail("read past end of disk returned data");
+
+    printf("sda_test: PASS\n");
+    close(fd);
+    return 0;
+}
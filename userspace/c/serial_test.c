@@ -0,0 +1,61 @@
+// Two processes writing to the serial console at once (kernel/src/serial.rs's
+// per-UART lock): parent and a fork()ed child each write ROUNDS whole lines
+// to /dev/console, one write() per line, the parent all 'A's and the child
+// all 'B's, while the kernel logs to the same UART. Every write goes out
+// under that lock, so each line must arrive intact — never mixed letters,
+// never split by a kernel message.
+//
+// The UART is write-only from here, so the check is on the host side:
+//   grep '^serial_test [AB]' serial.log | grep -Ev '^serial_test (A+|B+)$'
+// must print nothing. The program itself only checks that every write()
+// was complete.
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define ROUNDS   200
+#define LINE_LEN 72
+
+static int write_lines(int fd, char letter) {
+    char line[LINE_LEN];
+    memcpy(line, "serial_test ", 12);
+    memset(line + 12, letter, LINE_LEN - 13);
+    line[LINE_LEN - 1] = '\n';
+    for (int i = 0; i < ROUNDS; i++) {
+        if (write(fd, line, LINE_LEN) != LINE_LEN)
+            return 1;
+    }
+    return 0;
+}
+
+int main(void) {
+    int fd = open("/dev/console", O_WRONLY);
+    if (fd < 0) {
+        printf("serial_test: FAIL: open /dev/console\n");
+        return 1;
+    }
+
+    pid_t pid = fork();
+    if (pid < 0) {
+        printf("serial_test: FAIL: fork\n");
+        return 1;
+    }
+    if (pid == 0)
+        _exit(write_lines(fd, 'B'));
+
+    int failed = write_lines(fd, 'A');
+    int status = 0;
+    waitpid(pid, &status, 0);
+    if (!WIFEXITED(status) || WEXITSTATUS(status) != 0)
+        failed = 1;
+    close(fd);
+
+    if (failed) {
+        printf("serial_test: FAIL: short write\n");
+        return 1;
+    }
+    printf("serial_test: PASS: %d lines per process; check serial.log for mixed lines\n", ROUNDS);
+    return 0;
+}
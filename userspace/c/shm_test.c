@@ -0,0 +1,101 @@
+// MAP_SHARED | MAP_ANONYMOUS across fork (kernel/src/memory/vma.rs's
+// VmaKind::Shared): parent maps one shared page, forks, and the two pass a
+// message back and forth through it, each side sleeping in futex(WAIT) on
+// a turn counter until the other bumps it and futex(WAKE)s. No
+// FUTEX_PRIVATE_FLAG — the two processes have different address spaces,
+// so only the shared (physical-address) futex key can match them up.
+//
+// Also checks a MAP_PRIVATE page stays copy-on-write: the child's store to
+// it must not show up in the parent.
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define SYS_FUTEX 202
+#define FUTEX_WAIT 0
+#define FUTEX_WAKE 1
+#define ROUNDS 5
+
+struct shared {
+    volatile int32_t turn; // even: parent's move, odd: child's
+    int32_t value;
+    char msg[64];
+};
+
+static long raw_syscall(long nr, long a1, long a2, long a3) {
+    long ret;
+    register long r10 asm("r10") = 0;
+    register long r8  asm("r8")  = 0;
+    asm volatile ("syscall"
+            : "=a"(ret)
+            : "a"(nr), "D"(a1), "S"(a2), "d"(a3), "r"(r10), "r"(r8)
+            : "rcx", "r11", "memory");
+    return ret;
+}
+
+// Sleep until `s->turn` is no longer `seen`. Loops: WAIT may return early
+// (EAGAIN if the turn already moved, or a spurious wakeup).
+static void wait_turn_change(struct shared *s, int32_t seen) {
+    while (s->turn == seen)
+        raw_syscall(SYS_FUTEX, (long)&s->turn, FUTEX_WAIT, seen);
+}
+
+static void pass_turn(struct shared *s) {
+    __atomic_add_fetch(&s->turn, 1, __ATOMIC_SEQ_CST);
+    raw_syscall(SYS_FUTEX, (long)&s->turn, FUTEX_WAKE, 1);
+}
+
+int main(void) {
+    struct shared *s = mmap(NULL, 4096, PROT_READ | PROT_WRITE,
+                            MAP_SHARED | MAP_ANONYMOUS, -1, 0);
+    int *priv = mmap(NULL, 4096, PROT_READ | PROT_WRITE,
+                     MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    if (s == MAP_FAILED || priv == MAP_FAILED) {
+        printf("shm_test: FAIL: mmap\n");
+        return 1;
+    }
+    *priv = 1;
+
+    pid_t pid = fork();
+    if (pid < 0) {
+        printf("shm_test: FAIL: fork\n");
+        return 1;
+    }
+
+    if (pid == 0) {
+        *priv = 2; // private: must stay invisible to the parent
+        for (int i = 0; i < ROUNDS; i++) {
+            wait_turn_change(s, 2 * i);
+            s->value *= 2;
+            snprintf(s->msg, sizeof s->msg, "child doubled to %d", s->value);
+            pass_turn(s);
+        }
+        _exit(0);
+    }
+
+    int ok = 1;
+    s->value = 1;
+    for (int i = 0; i < ROUNDS; i++) {
+        pass_turn(s);                   // turn 2i -> 2i+1: child's move
+        wait_turn_change(s, 2 * i + 1); // back to us at 2i+2
+        printf("shm_test: round %d: %s\n", i, s->msg);
+        if (s->value != 1 << (i + 1))
+            ok = 0;
+    }
+
+    int status = 0;
+    waitpid(pid, &status, 0);
+    if (*priv != 1) {
+        printf("shm_test: FAIL: MAP_PRIVATE store leaked from child (%d)\n", *priv);
+        return 1;
+    }
+    if (!ok || !WIFEXITED(status) || WEXITSTATUS(status) != 0) {
+        printf("shm_test: FAIL\n");
+        return 1;
+    }
+    printf("shm_test: PASS\n");
+    return 0;
+}
@@ -0,0 +1,33 @@
+// `shutdown`: power the machine off via reboot(2) with LINUX_REBOOT_CMD_POWER_OFF
+// (kernel/src/power.rs). Only works under QEMU (see hal/src/power.rs for
+// why) — anywhere else the kernel logs that the port write was ignored and
+// the syscall fails with EIO. busybox's own poweroff/halt applets are
+// disabled (busybox-config/minimal.config): they signal an init process we
+// don't run instead of calling reboot(2) themselves.
+//
+// Raw syscall for the same reason as kdebug.c/beep.c: no dependency on
+// whether mlibc's headers ship <sys/reboot.h>.
+#include <stdio.h>
+
+#define SYS_REBOOT 169
+#define LINUX_REBOOT_MAGIC1 0xfee1deadL
+#define LINUX_REBOOT_MAGIC2 672274793L
+#define LINUX_REBOOT_CMD_POWER_OFF 0x4321fedcL
+
+static long raw_syscall(long nr, long a1, long a2, long a3) {
+    long ret;
+    register long r10 asm("r10") = 0;
+    register long r8  asm("r8")  = 0;
+    asm volatile ("syscall"
+            : "=a"(ret)
+            : "a"(nr), "D"(a1), "S"(a2), "d"(a3), "r"(r10), "r"(r8)
+            : "rcx", "r11", "memory");
+    return ret;
+}
+
+int main(void) {
+    long ret = raw_syscall(SYS_REBOOT, LINUX_REBOOT_MAGIC1, LINUX_REBOOT_MAGIC2,
+                           LINUX_REBOOT_CMD_POWER_OFF);
+    printf("shutdown: failed (%ld) — not running under QEMU?\n", ret);
+    return 1;
+}
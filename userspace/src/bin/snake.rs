@@ -290,6 +290,15 @@ extern "C" fn _start() -> ! {
         syscall::exit(1);
     }
 
+    // /dev/kbd reads the same buffer the tty's cooked mode holds keys
+    // back from until Enter — switch to raw mode for the game, restored
+    // by `end_game`.
+    let saved_termios = syscall::tcgetattr(0).ok();
+    if let Some(mut raw) = saved_termios {
+        raw.c_lflag &= !(syscall::ICANON | syscall::ECHO);
+        syscall::tcsetattr(0, &raw);
+    }
+
     let seed = syscall::uptime_ms() as u32;
     let mut game = Game::new(seed);
 
@@ -303,7 +312,7 @@ extern "C" fn _start() -> ! {
                 b'a' | b'A' => Some(Dir::Left),
                 b'd' | b'D' => Some(Dir::Right),
                 b'q' | b'Q' => {
-                    end_game(fb_fd, &game, false);
+                    end_game(fb_fd, &game, false, saved_termios.as_ref());
                 }
                 _ => None,
             };
@@ -315,7 +324,7 @@ extern "C" fn _start() -> ! {
         }
 
         if !game.step() {
-            end_game(fb_fd, &game, true);
+            end_game(fb_fd, &game, true, saved_termios.as_ref());
         }
 
         draw(fb_fd, &game);
@@ -323,8 +332,12 @@ extern "C" fn _start() -> ! {
     }
 }
 
-/// Draws a final message, closes fds, and exits. Never returns.
-fn end_game(fb_fd: i32, game: &Game, died: bool) -> ! {
+/// Draws a final message, restores the tty, closes fds, and exits. Never
+/// returns.
+fn end_game(fb_fd: i32, game: &Game, died: bool, termios: Option<&syscall::Termios>) -> ! {
+    if let Some(t) = termios {
+        syscall::tcsetattr(0, t);
+    }
     move_cursor(fb_fd, TOP_OFFSET + ROWS as u16 + 2, LEFT_OFFSET);
     syscall::write(fb_fd, b"\x1b[0m");
     if died {
@@ -74,6 +74,7 @@ const SYS_POLL: u64 = 7;
 #[allow(dead_code)]
 const SYS_LSEEK: u64 = 8;
 const SYS_MMAP: u64 = 9;
+const SYS_IOCTL: u64 = 16;
 #[allow(dead_code)]
 const SYS_MUNMAP: u64 = 11;
 #[allow(dead_code)]
@@ -225,6 +226,38 @@ pub fn fstat(fd: i32) -> Result<Stat, i64> {
     if r < 0 { Err(r) } else { Ok(st) }
 }
 
+// ── Terminal ────────────────────────────────────────────────────────────
+
+/// `struct termios` — matches `kernel/src/tty.rs::Termios` (every field a
+/// u32 in this port's ABI, 68 bytes).
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Termios {
+    pub c_iflag: u32,
+    pub c_oflag: u32,
+    pub c_cflag: u32,
+    pub c_lflag: u32,
+    pub c_cc: [u32; 11],
+    pub ibaud: u32,
+    pub obaud: u32,
+}
+
+pub const ECHO: u32 = 0x0001;
+pub const ICANON: u32 = 0x0010;
+
+const TCGETS: u64 = 0x5401;
+const TCSETS: u64 = 0x5402;
+
+pub fn tcgetattr(fd: i32) -> Result<Termios, i64> {
+    let mut t = Termios::default();
+    let r = unsafe { syscall3(SYS_IOCTL, fd as u64, TCGETS, &mut t as *mut Termios as u64) };
+    if r < 0 { Err(r) } else { Ok(t) }
+}
+
+pub fn tcsetattr(fd: i32, t: &Termios) -> i64 {
+    unsafe { syscall3(SYS_IOCTL, fd as u64, TCSETS, t as *const Termios as u64) }
+}
+
 /// `linux_dirent64`-compatible getdents64. Returns bytes written into `buf`.
 pub fn getdents64(fd: i32, buf: &mut [u8]) -> i64 {
     unsafe { syscall3(SYS_GETDENTS64, fd as u64, buf.as_mut_ptr() as u64, buf.len() as u64) }
@@ -396,6 +429,17 @@ pub fn kdebug_set(name_cstr: &[u8], enable: bool) -> i64 {
     unsafe { syscall3(SYS_KDEBUG_CTL, 1, name_cstr.as_ptr() as u64, enable as u64) }
 }
 
+/// Get the kernel log level (0 = silent .. 5 = trace, see `kernel::log`).
+pub fn kdebug_get_loglevel() -> i64 {
+    unsafe { syscall3(SYS_KDEBUG_CTL, 2, 0, 0) }
+}
+
+/// Set the kernel log level. Returns the previous level, or a negative
+/// errno if `level` is out of range.
+pub fn kdebug_set_loglevel(level: u8) -> i64 {
+    unsafe { syscall3(SYS_KDEBUG_CTL, 3, 0, level as u64) }
+}
+
 /// `struct timespec { i64 tv_sec; i64 tv_nsec; }`
 pub fn clock_gettime() -> (i64, i64) {
     let mut ts: [i64; 2] = [0, 0];